@@ -0,0 +1,283 @@
+//! Pending tasks bucketed by when they're due: the Waybar tooltip's
+//! sections and `tasuki agenda`.
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::error::{Result, TasukiError};
+use crate::model::Task;
+use crate::output;
+
+/// Tasks split by due date relative to today, each bucket keeping the
+/// order the tasks were given in.
+#[derive(Debug, Default)]
+pub struct Buckets<'a> {
+    pub overdue: Vec<&'a Task>,
+    pub today: Vec<&'a Task>,
+    pub tomorrow: Vec<&'a Task>,
+    /// Days from the day after tomorrow up to `upcoming_days` ahead, oldest
+    /// first; days without tasks are left out
+    pub upcoming: Vec<(NaiveDate, Vec<&'a Task>)>,
+    /// Due after the upcoming days
+    pub later: Vec<&'a Task>,
+    pub no_due: Vec<&'a Task>,
+}
+
+impl Buckets<'_> {
+    /// Tasks across the upcoming days.
+    pub fn upcoming_count(&self) -> usize {
+        self.upcoming.iter().map(|(_, tasks)| tasks.len()).sum()
+    }
+}
+
+/// Split `tasks` into buckets around `today`, with `upcoming_days` days
+/// (counting from today) before "later" starts.
+pub fn bucketize(tasks: &[Task], today: NaiveDate, upcoming_days: u32) -> Buckets<'_> {
+    let tomorrow = today + chrono::Duration::days(1);
+    let last_upcoming = today + chrono::Duration::days(i64::from(upcoming_days));
+    let mut buckets = Buckets::default();
+
+    for task in tasks {
+        match task.due {
+            None => buckets.no_due.push(task),
+            Some(d) if d < today => buckets.overdue.push(task),
+            Some(d) if d == today => buckets.today.push(task),
+            Some(d) if d == tomorrow && upcoming_days >= 1 => buckets.tomorrow.push(task),
+            Some(d) if d <= last_upcoming => {
+                match buckets.upcoming.iter_mut().find(|(day, _)| *day == d) {
+                    Some((_, day_tasks)) => day_tasks.push(task),
+                    None => buckets.upcoming.push((d, vec![task])),
+                }
+            }
+            Some(_) => buckets.later.push(task),
+        }
+    }
+
+    buckets.upcoming.sort_by_key(|(day, _)| *day);
+    buckets
+}
+
+/// One part of the agenda: a header and its tasks, in the order given.
+#[derive(Debug)]
+pub struct Section<'a> {
+    pub title: String,
+    pub tasks: Vec<&'a Task>,
+    /// Whether the tasks may be due on different days, so each needs its
+    /// date shown
+    pub dated: bool,
+    /// The overdue section, for formats that style it
+    pub overdue: bool,
+}
+
+/// The agenda for `day`: overdue tasks, `day`'s, a section per day up to
+/// `days` days ahead, then later and undated ones. Empty sections are left
+/// out; headers read relative to `today`.
+pub fn sections<'a>(
+    tasks: &'a [Task],
+    day: NaiveDate,
+    days: u32,
+    config: &Config,
+    today: NaiveDate,
+) -> Vec<Section<'a>> {
+    let buckets = bucketize(tasks, day, days);
+    let section = |title: String, tasks: Vec<&'a Task>, dated: bool| Section {
+        title,
+        tasks,
+        dated,
+        overdue: false,
+    };
+
+    let mut sections = vec![
+        Section {
+            overdue: true,
+            ..section("Overdue".to_string(), buckets.overdue, true)
+        },
+        section(day_label(day, config, today), buckets.today, false),
+        section(
+            day_label(day + chrono::Duration::days(1), config, today),
+            buckets.tomorrow,
+            false,
+        ),
+    ];
+    for (upcoming_day, day_tasks) in buckets.upcoming {
+        sections.push(section(
+            day_label(upcoming_day, config, today),
+            day_tasks,
+            false,
+        ));
+    }
+    sections.push(section("Later".to_string(), buckets.later, true));
+    sections.push(section("No due date".to_string(), buckets.no_due, false));
+
+    sections.retain(|s| !s.tasks.is_empty());
+    sections
+}
+
+/// "Today", "Tomorrow", "Tuesday 2025-03-04", and for past days just the
+/// weekday and date rather than `due_label`'s "Overdue - ...".
+fn day_label(day: NaiveDate, config: &Config, today: NaiveDate) -> String {
+    if day >= today {
+        output::due_label(Some(day), config, today)
+    } else {
+        format!(
+            "{} {}",
+            day.format("%A"),
+            output::format_date(day, config, today)
+        )
+    }
+}
+
+/// The day `tasuki agenda --date` names: "today", "tomorrow" or
+/// YYYY-MM-DD.
+pub fn parse_day(text: &str, today: NaiveDate) -> Result<NaiveDate> {
+    match text.trim().to_lowercase().as_str() {
+        "today" => Ok(today),
+        "tomorrow" => Ok(today + chrono::Duration::days(1)),
+        other => NaiveDate::parse_from_str(other, "%Y-%m-%d").map_err(|_| {
+            TasukiError::Parse(format!(
+                "Invalid date '{}' (expected today, tomorrow or YYYY-MM-DD)",
+                text
+            ))
+        }),
+    }
+}
+
+/// `tasuki agenda`: the `sections` as plain text.
+pub fn render(
+    tasks: &[Task],
+    day: NaiveDate,
+    days: u32,
+    config: &Config,
+    today: NaiveDate,
+) -> String {
+    let sections = sections(tasks, day, days, config, today);
+    if sections.is_empty() {
+        return "Nothing to do.\n".to_string();
+    }
+
+    let mut text = String::new();
+    for (i, section) in sections.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        text.push_str(&format!("{} ({})\n", section.title, section.tasks.len()));
+        for task in &section.tasks {
+            match task.due.filter(|_| section.dated) {
+                Some(due) => text.push_str(&format!(
+                    "  ☐ {} ({})\n",
+                    task.title,
+                    output::format_date(due, config, today)
+                )),
+                None => text.push_str(&format!("  ☐ {}\n", task.title)),
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str, due: Option<&str>) -> Task {
+        Task::build(title).with_due(due.map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()))
+    }
+
+    fn titles(tasks: &[&Task]) -> Vec<String> {
+        tasks.iter().map(|t| t.title.clone()).collect()
+    }
+
+    #[test]
+    fn test_bucketize_by_due_date() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let tasks = vec![
+            task("Sunday B", Some("2025-03-02")),
+            task("Friday", Some("2025-03-07")),
+            task("Pay rent", Some("2025-02-27")),
+            task("Read book", None),
+            task("Tuesday", Some("2025-03-04")),
+            task("Call Sam", Some("2025-03-01")),
+            task("Sunday A", Some("2025-03-02")),
+            task("Next week", Some("2025-03-09")),
+            task("Saturday", Some("2025-03-08")),
+        ];
+
+        let buckets = bucketize(&tasks, today, 7);
+        assert_eq!(titles(&buckets.overdue), ["Pay rent"]);
+        assert_eq!(titles(&buckets.today), ["Call Sam"]);
+        // Given order within a bucket, not sorted
+        assert_eq!(titles(&buckets.tomorrow), ["Sunday B", "Sunday A"]);
+        let days: Vec<(String, Vec<String>)> = buckets
+            .upcoming
+            .iter()
+            .map(|(day, tasks)| (day.to_string(), titles(tasks)))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                ("2025-03-04".to_string(), vec!["Tuesday".to_string()]),
+                ("2025-03-07".to_string(), vec!["Friday".to_string()]),
+                ("2025-03-08".to_string(), vec!["Saturday".to_string()]),
+            ]
+        );
+        assert_eq!(buckets.upcoming_count(), 3);
+        assert_eq!(titles(&buckets.later), ["Next week"]);
+        assert_eq!(titles(&buckets.no_due), ["Read book"]);
+
+        // No days ahead: even tomorrow is later
+        let buckets = bucketize(&tasks, today, 0);
+        assert!(buckets.tomorrow.is_empty());
+        assert!(buckets.upcoming.is_empty());
+        assert_eq!(buckets.later.len(), 6);
+        assert_eq!(titles(&buckets.today), ["Call Sam"]);
+    }
+
+    #[test]
+    fn test_render_groups_under_date_headers() {
+        // Saturday
+        let today = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let tasks = vec![
+            task("Pay rent", Some("2025-02-27")),
+            task("Call Sam", Some("2025-03-01")),
+            task("Gym", Some("2025-03-04")),
+            task("Dentist", Some("2025-03-02")),
+            task("Laundry", Some("2025-03-04")),
+            task("Taxes", Some("2025-04-15")),
+            task("Read book", None),
+        ];
+
+        assert_eq!(
+            render(&tasks, today, 7, &Config::default(), today),
+            "\
+Overdue (1)
+  ☐ Pay rent (2025-02-27)
+
+Today (1)
+  ☐ Call Sam
+
+Tomorrow (1)
+  ☐ Dentist
+
+Tuesday 2025-03-04 (2)
+  ☐ Gym
+  ☐ Laundry
+
+Later (1)
+  ☐ Taxes (2025-04-15)
+
+No due date (1)
+  ☐ Read book
+"
+        );
+
+        // Past the window, Tuesday's tasks move to "Later"
+        let short = render(&tasks, today, 2, &Config::default(), today);
+        assert!(!short.contains("Tuesday"));
+        assert!(short.contains("Later (3)\n  ☐ Gym (2025-03-04)\n  ☐ Laundry (2025-03-04)\n"));
+
+        assert_eq!(
+            render(&[], today, 7, &Config::default(), today),
+            "Nothing to do.\n"
+        );
+    }
+}
@@ -0,0 +1,570 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, TimeZone};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::backends::{
+    open_bounded, truncate_title, BackendCapabilities, FileLines, TaskBackend,
+    DEFAULT_MAX_FILE_SIZE_MB,
+};
+use crate::error::{Result, TasukiError};
+use crate::model::{
+    BackendSource, NewTask, Priority, Task, TaskFilter, TaskId, TaskStatus, TaskUpdate,
+};
+use crate::paths;
+
+/// Default for `[backends.ical] refresh_minutes`.
+pub const DEFAULT_REFRESH_MINUTES: u64 = 60;
+
+/// Settings for the read-only iCalendar backend, `[backends.ical]`.
+pub struct IcalConfig {
+    /// The `.ics` file tasks are read from; the download cache when `url` is set
+    pub path: PathBuf,
+    /// Calendar to download into `path` when the copy there is stale
+    pub url: Option<String>,
+    /// How old the downloaded copy may get before it's fetched again
+    pub refresh: Duration,
+    /// Also list upcoming VEVENTs, due on the day they start
+    pub include_events: bool,
+}
+
+impl IcalConfig {
+    /// Read `[backends.ical]`: either `path` to a local `.ics` file or `url`
+    /// to one that's downloaded into the cache directory.
+    pub fn from_table(table: &toml::Table) -> Result<Self> {
+        let url = table.get("url").and_then(|v| v.as_str()).map(String::from);
+
+        let path = match (table.get("path").and_then(|v| v.as_str()), &url) {
+            (Some(path), _) => paths::expand_path(path)?,
+            (None, Some(_)) => paths::cache_dir()
+                .map(|d| d.join("calendar.ics"))
+                .ok_or_else(|| {
+                    TasukiError::Config(
+                        "Could not find a cache directory for ical.url; set backends.ical.path"
+                            .into(),
+                    )
+                })?,
+            (None, None) => {
+                return Err(TasukiError::Config("ical needs either path or url".into()))
+            }
+        };
+
+        let refresh_minutes = table
+            .get("refresh_minutes")
+            .and_then(|v| v.as_integer())
+            .map_or(DEFAULT_REFRESH_MINUTES, |m| m.max(0) as u64);
+
+        let include_events = table
+            .get("include_events")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        Ok(Self {
+            path,
+            url,
+            refresh: Duration::from_secs(refresh_minutes * 60),
+            include_events,
+        })
+    }
+}
+
+/// Tasks read from an iCalendar file: VTODOs, and optionally VEVENTs.
+/// Nothing is ever written back, so every mutation fails.
+pub struct IcalBackend {
+    config: IcalConfig,
+    max_file_bytes: u64,
+}
+
+impl IcalBackend {
+    /// A backend over `config.path`.
+    pub fn new(config: IcalConfig) -> Self {
+        Self {
+            config,
+            max_file_bytes: DEFAULT_MAX_FILE_SIZE_MB * 1024 * 1024,
+        }
+    }
+
+    /// Skip reading the calendar when it's larger than this; see `open_bounded`.
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    /// Download `url` into `path` with curl if the copy there is missing or
+    /// older than `refresh`. A failed download keeps the old copy.
+    async fn refresh_cache(&self, url: &str) -> Result<()> {
+        let path = &self.config.path;
+        let fresh = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age < self.config.refresh);
+        if fresh {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let partial = path.with_extension("ics.part");
+        let status = tokio::process::Command::new("curl")
+            .args(["-fsSL", "--max-time", "30", "-o"])
+            .arg(&partial)
+            .arg(url)
+            .status()
+            .await;
+
+        match status {
+            Ok(status) if status.success() => {
+                std::fs::rename(&partial, path)?;
+                Ok(())
+            }
+            result => {
+                let _ = std::fs::remove_file(&partial);
+                let reason = match result {
+                    Ok(status) => format!("curl exited with {}", status),
+                    Err(e) => format!("could not run curl: {}", e),
+                };
+                if path.exists() {
+                    tracing::warn!(
+                        "Could not refresh {}: {}; using the cached copy",
+                        url,
+                        reason
+                    );
+                    Ok(())
+                } else {
+                    Err(TasukiError::Backend {
+                        backend: "ical".to_string(),
+                        message: format!("could not download {}: {}", url, reason),
+                    })
+                }
+            }
+        }
+    }
+
+    async fn read_tasks(&self) -> Result<Vec<Task>> {
+        if let Some(ref url) = self.config.url {
+            self.refresh_cache(url).await?;
+        }
+
+        let path = &self.config.path;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let Some(reader) = open_bounded(path, self.max_file_bytes)? else {
+            return Ok(Vec::new());
+        };
+        let mut lines = FileLines::new(reader);
+        let tasks = parse_calendar(
+            &mut lines,
+            &path.to_string_lossy(),
+            self.config.include_events,
+            crate::clock::today(),
+        );
+        lines.finish()?;
+
+        Ok(tasks)
+    }
+
+    fn read_only(&self) -> TasukiError {
+        TasukiError::Backend {
+            backend: "ical".to_string(),
+            message: "is read-only".to_string(),
+        }
+    }
+}
+
+/// A content line after unfolding, `NAME;PARAM=x:VALUE`, without its
+/// parameters, and the physical line it started on.
+struct Property {
+    line: usize,
+    name: String,
+    value: String,
+}
+
+/// Join folded lines (a continuation starts with a space or tab) and split
+/// each into name and value.
+fn unfold(lines: impl Iterator<Item = String>) -> Vec<Property> {
+    let mut joined: Vec<(usize, String)> = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        match (line.strip_prefix([' ', '\t']), joined.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ if line.is_empty() => {}
+            _ => joined.push((i + 1, line.to_string())),
+        }
+    }
+
+    joined
+        .into_iter()
+        .filter_map(|(line, text)| {
+            let (head, value) = text.split_once(':')?;
+            let name = head.split(';').next().unwrap_or(head);
+            Some(Property {
+                line,
+                name: name.to_ascii_uppercase(),
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Undo TEXT escaping: `\,`, `\;`, `\\` and `\n`.
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push(' '),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Split a list value on the commas that aren't escaped as `\,`, leaving
+/// each part escaped.
+fn split_list(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// A DATE (`20250301`) or DATE-TIME (`20250301T170000`, with a trailing `Z`
+/// for UTC, which is converted to local time).
+fn parse_date_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(chrono::Local.from_utc_datetime(&naive).naive_local());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })
+}
+
+/// RFC 5545 PRIORITY: 1–4 high, 5 medium, 6–9 low, 0 undefined.
+fn priority_of(value: &str) -> Priority {
+    match value.trim().parse::<u8>() {
+        Ok(1..=4) => Priority::High,
+        Ok(5) => Priority::Medium,
+        Ok(6..=9) => Priority::Low,
+        _ => Priority::None,
+    }
+}
+
+/// The component being read and what has been gathered from it so far.
+struct Component {
+    kind: &'static str,
+    line: usize,
+    summary: Option<String>,
+    due: Option<NaiveDate>,
+    priority: Priority,
+    done: bool,
+    cancelled: bool,
+    completed_at: Option<NaiveDateTime>,
+    created_at: Option<NaiveDateTime>,
+    tags: Vec<String>,
+}
+
+/// Tasks from the lines of an `.ics` file. VTODOs become tasks (cancelled
+/// ones are left out); with `include_events`, VEVENTs starting `today` or
+/// later become pending tasks due the day they start.
+pub fn parse_calendar(
+    lines: impl Iterator<Item = String>,
+    path: &str,
+    include_events: bool,
+    today: NaiveDate,
+) -> Vec<Task> {
+    let mut tasks = Vec::new();
+    let mut calendar_name = None;
+    let mut current: Option<Component> = None;
+    // Nested components (VALARM) whose properties belong to neither
+    let mut nested = 0usize;
+
+    for prop in unfold(lines) {
+        let value = prop.value.as_str();
+        match prop.name.as_str() {
+            "BEGIN" if current.is_some() => nested += 1,
+            "BEGIN" => {
+                let kind = match value.trim().to_ascii_uppercase().as_str() {
+                    "VTODO" => "VTODO",
+                    "VEVENT" if include_events => "VEVENT",
+                    _ => continue,
+                };
+                current = Some(Component {
+                    kind,
+                    line: prop.line,
+                    summary: None,
+                    due: None,
+                    priority: Priority::None,
+                    done: false,
+                    cancelled: false,
+                    completed_at: None,
+                    created_at: None,
+                    tags: Vec::new(),
+                });
+            }
+            "END" if nested > 0 => nested -= 1,
+            "END" => {
+                let Some(c) = current.take() else {
+                    continue;
+                };
+                let Some(title) = c.summary.filter(|s| !s.is_empty()) else {
+                    continue;
+                };
+                if c.cancelled || (c.kind == "VEVENT" && c.due.map_or(true, |d| d < today)) {
+                    continue;
+                }
+                tasks.push(Task {
+                    id: format!("ical:{}", c.line),
+                    title: truncate_title(title),
+                    status: if c.done {
+                        TaskStatus::Done
+                    } else {
+                        TaskStatus::Pending
+                    },
+                    priority: c.priority,
+                    due: c.due,
+                    tags: c.tags,
+                    source: BackendSource::ICal,
+                    source_line: Some(c.line),
+                    source_path: Some(path.to_string()),
+                    created_at: c.created_at,
+                    completed_at: c.completed_at,
+                    heading_context: None,
+                    project: calendar_name.clone(),
+                });
+            }
+            "X-WR-CALNAME" if current.is_none() => calendar_name = Some(unescape(value)),
+            _ if nested > 0 => {}
+            name => {
+                let Some(c) = current.as_mut() else {
+                    continue;
+                };
+                match (c.kind, name) {
+                    (_, "SUMMARY") => c.summary = Some(unescape(value).trim().to_string()),
+                    ("VTODO", "DUE") | ("VEVENT", "DTSTART") => {
+                        c.due = parse_date_time(value).map(|dt| dt.date())
+                    }
+                    (_, "PRIORITY") => c.priority = priority_of(value),
+                    (_, "STATUS") => match value.trim().to_ascii_uppercase().as_str() {
+                        "COMPLETED" => c.done = true,
+                        "CANCELLED" => c.cancelled = true,
+                        _ => {}
+                    },
+                    (_, "COMPLETED") => {
+                        c.done = true;
+                        c.completed_at = parse_date_time(value);
+                    }
+                    (_, "CREATED") => c.created_at = parse_date_time(value),
+                    (_, "CATEGORIES") => c.tags.extend(
+                        split_list(value)
+                            .into_iter()
+                            .map(|t| unescape(t).split_whitespace().collect::<Vec<_>>().join("-"))
+                            .filter(|t| !t.is_empty()),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    tasks
+}
+
+#[async_trait]
+impl TaskBackend for IcalBackend {
+    fn name(&self) -> &str {
+        "ical"
+    }
+
+    fn source(&self) -> BackendSource {
+        BackendSource::ICal
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            can_create: false,
+            can_update: false,
+            can_delete: false,
+            supports_priority: true,
+            supports_due_time: false,
+            supports_description: false,
+            supports_recurrence: false,
+        }
+    }
+
+    async fn fetch_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
+        let mut tasks = self.read_tasks().await?;
+
+        filter.retain(&mut tasks)?;
+        Ok(tasks)
+    }
+
+    async fn create_task(&self, _task: &NewTask) -> Result<Task> {
+        Err(self.read_only())
+    }
+
+    async fn update_task(&self, _id: &TaskId, _update: &TaskUpdate) -> Result<Task> {
+        Err(self.read_only())
+    }
+
+    async fn complete_task(&self, _id: &TaskId, _title: Option<&str>) -> Result<TaskId> {
+        Err(self.read_only())
+    }
+
+    async fn uncomplete_task(&self, _id: &TaskId, _title: Option<&str>) -> Result<TaskId> {
+        Err(self.read_only())
+    }
+
+    async fn delete_task(&self, _id: &TaskId) -> Result<()> {
+        Err(self.read_only())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = "\
+BEGIN:VCALENDAR\r
+VERSION:2.0\r
+X-WR-CALNAME:Team\r
+BEGIN:VTODO\r
+UID:1@example.com\r
+SUMMARY:Ship the release notes\\, v2\r
+DUE;VALUE=DATE:20250310\r
+PRIORITY:1\r
+CATEGORIES:Work,Release Eng,Q1\\,Q2\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+SUMMARY:Alarm text\r
+END:VALARM\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:2@example.com\r
+SUMMARY:Review the long folded\r
+  summary line\r
+DUE;TZID=Europe/Berlin:20250312T170000\r
+PRIORITY:5\r
+STATUS:COMPLETED\r
+COMPLETED:20250311T090000\r
+END:VTODO\r
+BEGIN:VTODO\r
+SUMMARY:Dropped idea\r
+STATUS:CANCELLED\r
+END:VTODO\r
+BEGIN:VTODO\r
+SUMMARY:Someday\r
+PRIORITY:9\r
+END:VTODO\r
+BEGIN:VEVENT\r
+SUMMARY:Planning\r
+DTSTART:20250305T100000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Retro\r
+DTSTART;VALUE=DATE:20250301\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn parse(include_events: bool) -> Vec<Task> {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        parse_calendar(
+            FIXTURE.lines().map(String::from),
+            "team.ics",
+            include_events,
+            today,
+        )
+    }
+
+    #[test]
+    fn test_parse_vtodos() {
+        let tasks = parse(false);
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Ship the release notes, v2",
+                "Review the long folded summary line",
+                "Someday"
+            ]
+        );
+
+        let first = &tasks[0];
+        assert_eq!(first.id, "ical:4");
+        assert_eq!(first.source, BackendSource::ICal);
+        assert_eq!(first.status, TaskStatus::Pending);
+        assert_eq!(first.priority, Priority::High);
+        assert_eq!(first.due, NaiveDate::from_ymd_opt(2025, 3, 10));
+        assert_eq!(first.tags, vec!["Work", "Release-Eng", "Q1,Q2"]);
+        assert_eq!(first.project.as_deref(), Some("Team"));
+
+        let done = &tasks[1];
+        assert_eq!(done.status, TaskStatus::Done);
+        assert_eq!(done.priority, Priority::Medium);
+        assert_eq!(done.due, NaiveDate::from_ymd_opt(2025, 3, 12));
+        assert_eq!(
+            done.completed_at.map(|d| d.to_string()).as_deref(),
+            Some("2025-03-11 09:00:00")
+        );
+
+        assert_eq!(tasks[2].priority, Priority::Low);
+        assert_eq!(tasks[2].due, None);
+    }
+
+    #[test]
+    fn test_parse_upcoming_events() {
+        let tasks = parse(true);
+        let events: Vec<&Task> = tasks.iter().filter(|t| t.title == "Planning").collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].due, NaiveDate::from_ymd_opt(2025, 3, 5));
+        assert_eq!(events[0].status, TaskStatus::Pending);
+        // Already over
+        assert!(!tasks.iter().any(|t| t.title == "Retro"));
+    }
+
+    #[tokio::test]
+    async fn test_backend_is_read_only() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("team.ics");
+        std::fs::write(&path, FIXTURE).unwrap();
+        let backend = IcalBackend::new(IcalConfig {
+            path,
+            url: None,
+            refresh: Duration::from_secs(3600),
+            include_events: false,
+        });
+
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        assert_eq!(tasks.len(), 3);
+
+        let caps = backend.capabilities();
+        assert!(!caps.can_create && !caps.can_update && !caps.can_delete);
+        assert!(backend.complete_task(&tasks[0].id, None).await.is_err());
+    }
+}
@@ -1,32 +1,61 @@
 use async_trait::async_trait;
 use chrono::NaiveDate;
-use std::fs;
 use std::path::PathBuf;
 
-use crate::backends::TaskBackend;
+use crate::backends::retry::{self, RetryPolicy};
+use crate::backends::{
+    cap_line, open_bounded, truncate_title, BackendCapabilities, FileLines, TaskBackend,
+    DEFAULT_MAX_FILE_SIZE_MB,
+};
 use crate::error::{Result, TasukiError};
-use crate::model::{BackendSource, NewTask, Task, TaskFilter, TaskId, TaskStatus, TaskUpdate, Priority};
+use crate::paths;
+use crate::model::{
+    dedupe_tags, normalize_tag, BackendSource, BangPriorities, NewTask, Priority, Task, TaskFilter,
+    TaskId, TaskStatus, TaskUpdate,
+};
 
+/// Settings for the todo.txt-style local file backend, `[backends.local]`.
 pub struct LocalFileConfig {
+    /// The todo.txt file tasks are read from and written to
     pub path: PathBuf,
 }
 
 impl LocalFileConfig {
-    pub fn default_dir() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("~"))
-            .join(".tasuki")
+    /// `data_dir()/todo.txt`, unless a pre-XDG `~/.tasuki/todo.txt` exists.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(legacy) = paths::legacy_dir().map(|d| d.join("todo.txt")) {
+            if legacy.exists() {
+                if let Some(data_dir) = paths::data_dir() {
+                    tracing::info!(
+                        "Using legacy {}; move it to {} to follow XDG",
+                        legacy.display(),
+                        data_dir.join("todo.txt").display()
+                    );
+                }
+                return Some(legacy);
+            }
+        }
+
+        paths::data_dir().map(|d| d.join("todo.txt"))
     }
 
-    pub fn from_table(table: &toml::Table) -> Result<Self> {
-        let default_dir = Self::default_dir();
+    /// The configured todo.txt path, without touching the filesystem.
+    pub fn resolve_path(table: &toml::Table) -> Result<PathBuf> {
+        match table.get("path").and_then(|v| v.as_str()) {
+            Some(s) => paths::expand_path(s),
+            None => Self::default_path().ok_or_else(|| {
+                TasukiError::Config(
+                    "Could not find a home directory (or $XDG_DATA_HOME) for todo.txt; \
+                     set backends.local.path explicitly"
+                        .into(),
+                )
+            }),
+        }
+    }
 
-        let path = table
-            .get("path")
-            .and_then(|v| v.as_str())
-            .map(|s| shellexpand::tilde(s).into_owned())
-            .map(PathBuf::from)
-            .unwrap_or_else(|| default_dir.join("todo.txt"));
+    /// Read `[backends.local]`, creating the file's directory if needed.
+    pub fn from_table(table: &toml::Table) -> Result<Self> {
+        let path = Self::resolve_path(table)?;
 
         if let Some(parent) = path.parent() {
             if !parent.exists() {
@@ -40,13 +69,41 @@ impl LocalFileConfig {
     }
 }
 
+/// Tasks in a single todo.txt-style file, one per line.
 pub struct LocalFileBackend {
     config: LocalFileConfig,
+    max_file_bytes: u64,
+    retry: RetryPolicy,
+    bangs: BangPriorities,
 }
 
 impl LocalFileBackend {
+    /// A backend over `config.path`.
     pub fn new(config: LocalFileConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            max_file_bytes: DEFAULT_MAX_FILE_SIZE_MB * 1024 * 1024,
+            retry: RetryPolicy::default(),
+            bangs: BangPriorities::default(),
+        }
+    }
+
+    /// Skip reading todo.txt when it's larger than this; see `open_bounded`.
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    /// How reads and writes of todo.txt retry transient errors.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Priorities meant by a leading `!`, `!!` and `!!!`.
+    pub fn with_bangs(mut self, bangs: BangPriorities) -> Self {
+        self.bangs = bangs;
+        self
     }
 
     fn parse_line(&self, line: &str, line_num: usize) -> Option<Task> {
@@ -55,38 +112,40 @@ impl LocalFileBackend {
             return None;
         }
 
-        let (status, rest) = if line.starts_with("x ") {
-            (TaskStatus::Done, &line[2..])
-        } else {
-            (TaskStatus::Pending, line)
+        let (status, rest) = match line.strip_prefix("x ") {
+            Some(rest) => (TaskStatus::Done, rest),
+            None => (TaskStatus::Pending, line),
         };
 
         let mut rest = rest.trim_start();
-        
-        // Priority
-        let priority = if rest.starts_with("(p1)") {
-            rest = &rest[4..].trim_start();
-            Priority::High
-        } else if rest.starts_with("(p2)") {
-            rest = &rest[4..].trim_start();
-            Priority::Medium
-        } else if rest.starts_with("(p3)") {
-            rest = &rest[4..].trim_start();
-            Priority::Low
-        } else {
-            Priority::None
-        };
 
-        let (completed_at, rest) = if status == TaskStatus::Done {
-            if let Some((date_str, remaining)) = Self::parse_date_prefix(rest) {
-                (date_str, remaining)
-            } else {
-                (None, rest)
+        // A done task's completion date comes right after the `x`, as
+        // `update_task` writes it, though `x (p1) 2025-02-20` is read too
+        let mut completed_at = None;
+        if status == TaskStatus::Done {
+            if let Some((date, remaining)) = Self::parse_date_prefix(rest) {
+                completed_at = date;
+                rest = remaining;
             }
-        } else {
-            (None, rest)
+        }
+
+        // Priority: a leading `(p1)`, `p1` or `!`/`!!`/`!!!` token
+        let first = rest.split_whitespace().next().unwrap_or("");
+        let priority = match Priority::from_token(first, &self.bangs) {
+            Some(p) => {
+                rest = rest[first.len()..].trim_start();
+                p
+            }
+            None => Priority::None,
         };
 
+        if status == TaskStatus::Done && completed_at.is_none() {
+            if let Some((date, remaining)) = Self::parse_date_prefix(rest) {
+                completed_at = date;
+                rest = remaining;
+            }
+        }
+
         let (created_at, rest) = if let Some((date_str, remaining)) = Self::parse_date_prefix(rest) {
             (date_str, remaining)
         } else {
@@ -98,8 +157,8 @@ impl LocalFileBackend {
         let mut title_parts = Vec::new();
 
         for word in rest.split_whitespace() {
-            if word.starts_with('#') {
-                tags.push(word[1..].to_string());
+            if let Some(tag) = word.strip_prefix('#').and_then(|t| normalize_tag(t, false)) {
+                tags.push(tag);
             } else if word.starts_with("due:") {
                 if let Some(date_str) = word.strip_prefix("due:") {
                     due = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok();
@@ -120,134 +179,67 @@ impl LocalFileBackend {
             status,
             priority,
             due,
-            tags,
+            tags: dedupe_tags(tags),
             source: BackendSource::LocalFile,
             source_line: Some(line_num),
             source_path: Some(self.config.path.to_string_lossy().into_owned()),
             created_at: created_at.map(|d| d.and_hms_opt(0, 0, 0).unwrap()),
             completed_at: completed_at.map(|d| d.and_hms_opt(0, 0, 0).unwrap()),
             heading_context: None,
+            project: None,
         })
     }
 
+    /// A leading `YYYY-MM-DD` word and what follows it. A date run into
+    /// more text (`2025-06-01x`) is part of the title.
     fn parse_date_prefix(s: &str) -> Option<(Option<NaiveDate>, &str)> {
         let s = s.trim_start();
-        if s.len() >= 10 {
-            let date_part = &s[..10];
-            if let Ok(date) = NaiveDate::parse_from_str(date_part, "%Y-%m-%d") {
-                return Some((Some(date), &s[10..].trim_start()));
-            }
+        let date_part = s.get(..10)?;
+        let after = &s[10..];
+        if !after.is_empty() && !after.starts_with(char::is_whitespace) {
+            return None;
         }
-        None
+        let date = NaiveDate::parse_from_str(date_part, "%Y-%m-%d").ok()?;
+        Some((Some(date), after.trim_start()))
     }
 
-    fn read_tasks(&self) -> Result<Vec<Task>> {
+    async fn read_tasks(&self) -> Result<Vec<Task>> {
         if !self.config.path.exists() {
             return Ok(Vec::new());
         }
 
-        let content = fs::read_to_string(&self.config.path)?;
-        let tasks: Vec<Task> = content
-            .lines()
-            .enumerate()
-            .filter_map(|(i, line)| self.parse_line(line, i + 1))
-            .collect();
-
-        Ok(tasks)
-    }
-}
-
-#[async_trait]
-impl TaskBackend for LocalFileBackend {
-    fn name(&self) -> &str {
-        "local"
-    }
-
-    fn source(&self) -> BackendSource {
-        BackendSource::LocalFile
-    }
-
-    async fn fetch_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
-        let mut tasks = self.read_tasks()?;
-
-        if let Some(ref status) = filter.status {
-            tasks.retain(|t| &t.status == status);
-        }
-
-        if let Some(ref due_before) = filter.due_before {
-            tasks.retain(|t| t.due.map_or(false, |d| d <= *due_before));
-        }
-
-        if let Some(ref due_after) = filter.due_after {
-            tasks.retain(|t| t.due.map_or(false, |d| d >= *due_after));
-        }
-
-        if let Some(ref search) = filter.search {
-            let search_lower = search.to_lowercase();
-            tasks.retain(|t| t.title.to_lowercase().contains(&search_lower));
-        }
-
-        if let Some(has_due) = filter.has_due {
-            tasks.retain(|t| if has_due { t.due.is_some() } else { t.due.is_none() });
-        }
+        // Parsed as it streams in; a retry reads the file again from the start
+        let path = &self.config.path;
+        let what = format!("Reading {}", path.display());
+        let tasks = retry::retry(self.retry, &what, || {
+            let Some(reader) = open_bounded(path, self.max_file_bytes)? else {
+                return Ok(Vec::new());
+            };
+            let mut lines = FileLines::new(reader);
+            let tasks: Vec<Task> = (&mut lines)
+                .enumerate()
+                .filter_map(|(i, line)| self.parse_line(&line, i + 1))
+                .map(|mut task| {
+                    task.title = truncate_title(task.title);
+                    task
+                })
+                .collect();
+            lines.finish()?;
+            Ok(tasks)
+        })
+        .await?;
 
         Ok(tasks)
     }
 
-    async fn create_task(&self, task: &NewTask) -> Result<Task> {
-        let line_num = if self.config.path.exists() {
-            fs::read_to_string(&self.config.path)?.lines().count() + 1
-        } else {
-            1
-        };
-
-        let mut parts = Vec::new();
-
-        match task.priority {
-            Priority::High => parts.push("(p1)".to_string()),
-            Priority::Medium => parts.push("(p2)".to_string()),
-            Priority::Low => parts.push("(p3)".to_string()),
-            Priority::None => {}
-        }
-
-        let today = chrono::Local::now().date_naive();
-        parts.push(today.to_string());
-        parts.push(task.title.clone());
-
-        for tag in &task.tags {
-            parts.push(format!("#{}", tag));
-        }
-
-        if let Some(due) = task.due {
-            parts.push(format!("due:{}", due));
-        }
-
-        let line = parts.join(" ") + "\n";
-
-        use std::io::Write;
-        let mut file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.config.path)?;
-        file.write_all(line.as_bytes())?;
-
-        Ok(Task {
-            id: format!("local:{}", line_num),
-            title: task.title.clone(),
-            status: TaskStatus::Pending,
-            priority: task.priority,
-            due: task.due,
-            tags: task.tags.clone(),
-            source: BackendSource::LocalFile,
-            source_line: Some(line_num),
-            source_path: Some(self.config.path.to_string_lossy().into_owned()),
-            created_at: Some(today.and_hms_opt(0, 0, 0).unwrap()),
-            completed_at: None,
-            heading_context: None,
-        })
-    }
-
-    async fn update_task(&self, id: &TaskId, update: &TaskUpdate) -> Result<Task> {
+    /// `update_task`, refusing if `title` is given and the line holds
+    /// another task now (see `TaskBackend::complete_task`).
+    async fn update_line(
+        &self,
+        id: &TaskId,
+        update: &TaskUpdate,
+        title: Option<&str>,
+    ) -> Result<Task> {
         let line_num: usize = id
             .strip_prefix("local:")
             .ok_or_else(|| TasukiError::Parse(format!("Invalid task ID: {}", id)))?
@@ -261,7 +253,7 @@ impl TaskBackend for LocalFileBackend {
             )));
         }
 
-        let content = fs::read_to_string(&self.config.path)?;
+        let content = retry::read_to_string(self.retry, &self.config.path).await?;
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
         if line_num == 0 || line_num > lines.len() {
@@ -269,8 +261,20 @@ impl TaskBackend for LocalFileBackend {
         }
 
         let current_line = &lines[line_num - 1];
-        let mut task = self.parse_line(current_line, line_num)
+        let mut task = self
+            .parse_line(current_line, line_num)
             .ok_or_else(|| TasukiError::Parse(format!("Could not parse line {}", line_num)))?;
+        if let Some(expected) = title {
+            let listed = self
+                .parse_line(&cap_line(current_line.clone()), line_num)
+                .map(|t| truncate_title(t.title));
+            if listed.as_deref() != Some(expected) {
+                return Err(TasukiError::NotFound(format!(
+                    "line {} no longer holds '{}'",
+                    line_num, expected
+                )));
+            }
+        }
 
         if let Some(ref title) = update.title {
             task.title = title.clone();
@@ -285,7 +289,7 @@ impl TaskBackend for LocalFileBackend {
             task.due = *due;
         }
         if let Some(ref tags) = update.tags {
-            task.tags = tags.clone();
+            task.tags = dedupe_tags(tags.clone());
         }
 
         let mut parts = Vec::new();
@@ -295,7 +299,7 @@ impl TaskBackend for LocalFileBackend {
             if let Some(completed) = task.completed_at {
                 parts.push(completed.date().to_string());
             } else {
-                parts.push(chrono::Local::now().date_naive().to_string());
+                parts.push(crate::clock::today().to_string());
             }
         }
 
@@ -322,27 +326,124 @@ impl TaskBackend for LocalFileBackend {
 
         lines[line_num - 1] = parts.join(" ");
 
-        fs::write(&self.config.path, lines.join("\n") + "\n")?;
+        retry::write(self.retry, &self.config.path, lines.join("\n") + "\n").await?;
 
         Ok(task)
     }
+}
 
-    async fn complete_task(&self, id: &TaskId) -> Result<()> {
+#[async_trait]
+impl TaskBackend for LocalFileBackend {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn source(&self) -> BackendSource {
+        BackendSource::LocalFile
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            can_create: true,
+            can_update: true,
+            can_delete: true,
+            supports_priority: true,
+            supports_due_time: false,
+            supports_description: false,
+            supports_recurrence: false,
+        }
+    }
+
+    async fn fetch_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
+        let mut tasks = self.read_tasks().await?;
+
+        filter.retain(&mut tasks)?;
+        Ok(tasks)
+    }
+
+    async fn create_task(&self, task: &NewTask) -> Result<Task> {
+        let line_num = if self.config.path.exists() {
+            retry::read_to_string(self.retry, &self.config.path)
+                .await?
+                .lines()
+                .count()
+                + 1
+        } else {
+            1
+        };
+
+        let mut parts = Vec::new();
+
+        match task.priority {
+            Priority::High => parts.push("(p1)".to_string()),
+            Priority::Medium => parts.push("(p2)".to_string()),
+            Priority::Low => parts.push("(p3)".to_string()),
+            Priority::None => {}
+        }
+
+        let today = crate::clock::today();
+        parts.push(today.to_string());
+        parts.push(task.title.clone());
+
+        let tags = dedupe_tags(task.tags.clone());
+        for tag in &tags {
+            parts.push(format!("#{}", tag));
+        }
+
+        if let Some(due) = task.due {
+            parts.push(format!("due:{}", due));
+        }
+
+        let line = parts.join(" ") + "\n";
+
+        // Only opening is retried: a second append after a partial one
+        // could add the task twice
+        use std::io::Write;
+        let what = format!("Opening {}", self.config.path.display());
+        let mut file = retry::retry(self.retry, &what, || {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.config.path)
+        })
+        .await?;
+        file.write_all(line.as_bytes())?;
+
+        Ok(Task {
+            id: format!("local:{}", line_num),
+            title: task.title.clone(),
+            status: TaskStatus::Pending,
+            priority: task.priority,
+            due: task.due,
+            tags,
+            source: BackendSource::LocalFile,
+            source_line: Some(line_num),
+            source_path: Some(self.config.path.to_string_lossy().into_owned()),
+            created_at: Some(today.and_hms_opt(0, 0, 0).unwrap()),
+            completed_at: None,
+            heading_context: None,
+            project: None,
+        })
+    }
+
+    async fn update_task(&self, id: &TaskId, update: &TaskUpdate) -> Result<Task> {
+        self.update_line(id, update, None).await
+    }
+
+    async fn complete_task(&self, id: &TaskId, title: Option<&str>) -> Result<TaskId> {
         let update = TaskUpdate {
             status: Some(TaskStatus::Done),
             ..Default::default()
         };
-        self.update_task(id, &update).await?;
-        Ok(())
+        Ok(self.update_line(id, &update, title).await?.id)
     }
 
-    async fn uncomplete_task(&self, id: &TaskId) -> Result<()> {
+    async fn uncomplete_task(&self, id: &TaskId, title: Option<&str>) -> Result<TaskId> {
         let update = TaskUpdate {
             status: Some(TaskStatus::Pending),
             ..Default::default()
         };
-        self.update_task(id, &update).await?;
-        Ok(())
+        Ok(self.update_line(id, &update, title).await?.id)
     }
 
     async fn delete_task(&self, id: &TaskId) -> Result<()> {
@@ -359,7 +460,7 @@ impl TaskBackend for LocalFileBackend {
             )));
         }
 
-        let content = fs::read_to_string(&self.config.path)?;
+        let content = retry::read_to_string(self.retry, &self.config.path).await?;
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
         if line_num == 0 || line_num > lines.len() {
@@ -367,7 +468,7 @@ impl TaskBackend for LocalFileBackend {
         }
 
         lines.remove(line_num - 1);
-        fs::write(&self.config.path, lines.join("\n") + "\n")?;
+        retry::write(self.retry, &self.config.path, lines.join("\n") + "\n").await?;
 
         Ok(())
     }
@@ -402,6 +503,32 @@ mod tests {
         assert_eq!(task.priority, Priority::High);
     }
 
+    #[test]
+    fn test_parse_bare_and_bang_priorities() {
+        let config = LocalFileConfig {
+            path: PathBuf::from("/tmp/test.txt"),
+        };
+        let backend = LocalFileBackend::new(config);
+
+        let task = backend.parse_line("p2 Submit report", 1).unwrap();
+        assert_eq!(task.title, "Submit report");
+        assert_eq!(task.priority, Priority::Medium);
+
+        let task = backend.parse_line("!!! Water plants", 1).unwrap();
+        assert_eq!(task.title, "Water plants");
+        assert_eq!(task.priority, Priority::Low);
+
+        let task = backend.parse_line("Help! the sink leaks", 1).unwrap();
+        assert_eq!(task.title, "Help! the sink leaks");
+        assert_eq!(task.priority, Priority::None);
+
+        let reversed =
+            BangPriorities::from_names(&["low".into(), "medium".into(), "high".into()]).unwrap();
+        let backend = backend.with_bangs(reversed);
+        let task = backend.parse_line("!!! Water plants", 1).unwrap();
+        assert_eq!(task.priority, Priority::High);
+    }
+
     #[test]
     fn test_parse_done_task() {
         let config = LocalFileConfig {
@@ -425,4 +552,129 @@ mod tests {
         assert_eq!(task.title, "Buy groceries");
         assert_eq!(task.due, Some(NaiveDate::from_ymd_opt(2025, 2, 25).unwrap()));
     }
+
+    #[tokio::test]
+    async fn test_oversize_file_skipped_and_long_titles_truncated() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        let long_title = "a".repeat(5000);
+        // The parser only sees the start of a line, so a date past the cap is lost
+        let long_line = format!("{}due:2025-03-01", "b ".repeat(3000));
+        std::fs::write(
+            &path,
+            format!("Short task\n{}\n{}\n", long_title, long_line),
+        )
+        .unwrap();
+
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[1].title.chars().count(), crate::backends::MAX_TITLE_CHARS + 1);
+        assert!(tasks[1].title.ends_with('…'));
+        assert_eq!(tasks[2].due, None);
+        assert!(tasks[2].title.ends_with('…'));
+
+        let backend =
+            LocalFileBackend::new(LocalFileConfig { path }).with_max_file_bytes(1024);
+        assert!(backend.fetch_tasks(&TaskFilter::default()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_nested_tags_round_trip_and_filter() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "Invoice #work/clientA\nStandup #work\nWorkshop #workshop\n").unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+
+        let filter = TaskFilter {
+            tag: Some("work".to_string()),
+            ..Default::default()
+        };
+        let titles: Vec<String> = backend
+            .fetch_tasks(&filter)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        assert_eq!(titles, vec!["Invoice", "Standup"]);
+
+        backend
+            .complete_task(&"local:1".to_string(), None)
+            .await
+            .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.lines().next().unwrap().ends_with("#work/clientA"));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_tags_collapse() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "Ship it #Work. #work #urgent # now\n").unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        assert_eq!(tasks[0].title, "Ship it # now");
+        assert_eq!(tasks[0].tags, vec!["Work", "urgent"]);
+
+        // Adding a tag the task already has doesn't write it twice
+        let update = TaskUpdate {
+            tags: Some(vec!["Work".into(), "urgent".into(), "work".into()]),
+            ..Default::default()
+        };
+        let task = backend.update_task(&"local:1".to_string(), &update).await.unwrap();
+        assert_eq!(task.tags, vec!["Work", "urgent"]);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("#Work").count() + content.matches("#work").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_created_tasks_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+        let today = crate::clock::today();
+        let created = Some(today.and_hms_opt(0, 0, 0).unwrap());
+
+        for priority in [Priority::None, Priority::High] {
+            let new = NewTask {
+                title: "Buy milk".to_string(),
+                priority,
+                due: None,
+                tags: Vec::new(),
+                backend: BackendSource::LocalFile,
+            };
+            let task = backend.create_task(&new).await.unwrap();
+            let read = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+            let read = read.iter().find(|t| t.id == task.id).unwrap();
+            assert_eq!(read.title, "Buy milk");
+            assert_eq!((read.priority, read.created_at), (priority, created));
+
+            // Completing puts the completion date first; the rest stays put
+            backend.complete_task(&task.id, None).await.unwrap();
+            let read = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+            let read = read.iter().find(|t| t.id == task.id).unwrap();
+            assert_eq!(read.title, "Buy milk");
+            assert_eq!((read.priority, read.created_at, read.completed_at), (priority, created, created));
+        }
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, format!("x {0} {0} Buy milk\nx {0} (p1) {0} Buy milk\n", today));
+    }
+
+    #[test]
+    fn test_date_prefix_needs_a_whole_word() {
+        let backend = LocalFileBackend::new(LocalFileConfig {
+            path: PathBuf::from("/tmp/test.txt"),
+        });
+
+        let task = backend.parse_line("2025-06-01x Buy milk", 1).unwrap();
+        assert_eq!((task.title.as_str(), task.created_at), ("2025-06-01x Buy milk", None));
+        let task = backend.parse_line("x (p2) 2025-06-02 2025-06-01 Buy milk", 1).unwrap();
+        assert_eq!(task.priority, Priority::Medium);
+        assert_eq!(task.title, "Buy milk");
+        // Multi-byte text where a date could start doesn't trip the parser
+        let task = backend.parse_line("日本語のタスク", 1).unwrap();
+        assert_eq!(task.title, "日本語のタスク");
+    }
 }
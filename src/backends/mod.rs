@@ -1,209 +1,1577 @@
 use async_trait::async_trait;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 
 use crate::error::Result;
-use crate::model::{BackendSource, NewTask, Task, TaskFilter, TaskId, TaskUpdate};
+use crate::model::{
+    BackendSource, NewTask, Priority, SortKey, Task, TaskFilter, TaskId, TaskStatus, TaskUpdate,
+    Tiebreak,
+};
 
+/// Tasks as checkboxes in the notes of an Obsidian vault.
 pub mod obsidian;
+/// Tasks in a single todo.txt-style file.
 pub mod localfile;
+/// Read-only tasks from an iCalendar (`.ics`) file or URL.
+pub mod ical;
+/// Retrying file reads and writes through transient errors.
+pub mod retry;
 
+/// What a backend can store and which operations it allows, so callers can
+/// avoid offering actions or fields that would fail or be silently dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendCapabilities {
+    /// Can add new tasks
+    pub can_create: bool,
+    /// Can complete, reopen and edit tasks
+    pub can_update: bool,
+    /// Can remove tasks
+    pub can_delete: bool,
+    /// Stores `Task::priority`
+    pub supports_priority: bool,
+    /// Stores a time of day with the due date
+    pub supports_due_time: bool,
+    /// Stores a free-form description
+    pub supports_description: bool,
+    /// Stores a recurrence rule
+    pub supports_recurrence: bool,
+}
+
+impl BackendCapabilities {
+    /// Names of the fields in `task` this backend would not store.
+    pub fn dropped_fields(&self, task: &NewTask) -> Vec<&'static str> {
+        let mut dropped = Vec::new();
+        if !self.supports_priority && task.priority != Priority::None {
+            dropped.push("priority");
+        }
+        dropped
+    }
+}
+
+/// A place tasks are stored. Task IDs are opaque to callers and only
+/// meaningful to the backend that issued them.
 #[async_trait]
 pub trait TaskBackend: Send + Sync {
+    /// Name for messages, and the `@name` quick-add sends tasks here with.
     fn name(&self) -> &str;
+    /// Which source the tasks from this backend report.
     fn source(&self) -> BackendSource;
+    /// What this backend can store; see `BackendCapabilities`.
+    fn capabilities(&self) -> BackendCapabilities;
 
+    /// Whether `id` is one of this backend's task IDs, which routes changes
+    /// to it. By default, IDs starting with its source name and a colon.
+    fn owns_id(&self, id: &str) -> bool {
+        id.split(':').next() == Some(self.source().name())
+    }
+
+    /// Glyph marking this backend's tasks in lists.
+    fn icon(&self) -> &str {
+        self.source().icon()
+    }
+
+    /// Every task matching `filter`.
     async fn fetch_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>>;
+    /// Store a new task and return it with its ID.
     async fn create_task(&self, task: &NewTask) -> Result<Task>;
+    /// Change the fields set in `update`, returning the task as stored.
     async fn update_task(&self, id: &TaskId, update: &TaskUpdate) -> Result<Task>;
-    async fn complete_task(&self, id: &TaskId) -> Result<()>;
-    async fn uncomplete_task(&self, id: &TaskId) -> Result<()>;
+    /// Mark a task done, returning its ID afterwards: the same ID unless
+    /// the backend moved the task (Obsidian's `on_complete`). `title`, when
+    /// given, is the task the caller means: IDs are line numbers, so a line
+    /// now holding another task is refused with `NotFound`.
+    async fn complete_task(&self, id: &TaskId, title: Option<&str>) -> Result<TaskId>;
+    /// Mark a done task pending again, returning its ID afterwards; `title`
+    /// as for `complete_task`.
+    async fn uncomplete_task(&self, id: &TaskId, title: Option<&str>) -> Result<TaskId>;
+    /// Remove a task.
     async fn delete_task(&self, id: &TaskId) -> Result<()>;
+
+    /// Extra lines stored with a task (description, child bullets), read on
+    /// demand for the detail pane. Backends without any return nothing.
+    fn task_details(&self, _task: &Task) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// How many subtasks nested under a task are still open, so completing
+    /// it can ask first. Backends without subtasks return 0.
+    fn pending_subtasks(&self, _task: &Task) -> Result<usize> {
+        Ok(0)
+    }
+}
+
+/// Titles longer than this many characters are cut short when read.
+pub const MAX_TITLE_CHARS: usize = 1000;
+
+/// Lines longer than this many characters are cut before they're parsed,
+/// so a megabyte-long line (say, minified JSON pasted into a note) costs
+/// no more to parse than a long task.
+pub const MAX_LINE_CHARS: usize = 4 * MAX_TITLE_CHARS;
+
+/// Default for `[backends] max_file_size_mb`.
+pub const DEFAULT_MAX_FILE_SIZE_MB: u64 = 10;
+
+/// Open `path` for reading line by line. A file larger than `max_bytes` is
+/// skipped with a warning (`Ok(None)`) so one stray dump can't stall a refresh.
+pub fn open_bounded(path: &Path, max_bytes: u64) -> std::io::Result<Option<BufReader<File>>> {
+    let file = File::open(path)?;
+    let size = file.metadata()?.len();
+    if size > max_bytes {
+        tracing::warn!(
+            "Skipping {}: {} MB is over max_file_size_mb ({} MB)",
+            path.display(),
+            size / (1024 * 1024),
+            max_bytes / (1024 * 1024)
+        );
+        return Ok(None);
+    }
+    Ok(Some(BufReader::new(file)))
+}
+
+/// Lines of a file, read one at a time and cut with `cap_line`. Iteration
+/// ends at the first read error, which `finish` then returns.
+pub struct FileLines<R> {
+    lines: std::io::Lines<R>,
+    error: Option<std::io::Error>,
+}
+
+impl<R: BufRead> FileLines<R> {
+    /// Read lines from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            error: None,
+        }
+    }
+
+    /// The read error that ended iteration early, if any.
+    pub fn finish(self) -> std::io::Result<()> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for FileLines<R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        match self.lines.next()? {
+            Ok(line) => Some(cap_line(line)),
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+/// Cut `line` to `MAX_LINE_CHARS`. Unlike titles the cut isn't marked, so
+/// the parser doesn't read the mark as part of a tag or date.
+pub fn cap_line(mut line: String) -> String {
+    if let Some((end, _)) = line.char_indices().nth(MAX_LINE_CHARS) {
+        line.truncate(end);
+    }
+    line
+}
+
+/// Cut `title` to `MAX_TITLE_CHARS`, marking the cut with "…".
+pub fn truncate_title(title: String) -> String {
+    match title.char_indices().nth(MAX_TITLE_CHARS) {
+        Some((end, _)) => format!("{}…", &title[..end]),
+        None => title,
+    }
 }
 
+/// How one backend's part of the last `all_tasks_timed` went.
+#[derive(Debug, Clone)]
+pub struct FetchReport {
+    /// The backend this report is about
+    pub source: BackendSource,
+    /// Time taken by its `fetch_tasks`
+    pub elapsed: std::time::Duration,
+    /// Number of tasks fetched, or the error message
+    pub result: std::result::Result<usize, String>,
+    /// The fetch failed and the backend's tasks from its last successful
+    /// fetch (with the same filter) were used instead
+    pub stale: bool,
+}
+
+/// The config that shapes `sort_by_urgency`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortOrder {
+    /// See `general.escalate_overdue_after_days`
+    pub escalate_after: Option<u32>,
+    /// Order of tasks within the same due bucket
+    pub tiebreak: Tiebreak,
+}
+
+impl SortOrder {
+    /// From `general.escalate_overdue_after_days` and `general.tiebreak`.
+    pub fn from_config(general: &crate::config::GeneralConfig) -> Self {
+        Self {
+            escalate_after: general.escalate_overdue_after_days,
+            tiebreak: general.tiebreak(),
+        }
+    }
+}
+
+/// Order tasks most urgent first: overdue, due today, later due dates, then
+/// undated; tasks due the same day are ordered by `general.tiebreak`
+/// (effective priority, then title, by default).
+pub fn sort_by_urgency(tasks: &mut [Task], order: &SortOrder) {
+    let today = crate::clock::today();
+    tasks.sort_by(|a, b| urgency_cmp(a, b, today, order));
+}
+
+/// The most urgent pending task, as it would sort first in `all_tasks`.
+pub fn next_task<'a>(tasks: &'a [Task], order: &SortOrder) -> Option<&'a Task> {
+    let today = crate::clock::today();
+    tasks
+        .iter()
+        .filter(|t| t.status == crate::model::TaskStatus::Pending)
+        .min_by(|a, b| urgency_cmp(a, b, today, order))
+}
+
+fn urgency_cmp(
+    a: &Task,
+    b: &Task,
+    today: chrono::NaiveDate,
+    order: &SortOrder,
+) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let bucket = |t: &Task| match t.due {
+        Some(d) if d < today => 0,
+        Some(d) if d == today => 1,
+        Some(_) => 2,
+        None => 3,
+    };
+
+    let date_cmp = match (a.due, b.due) {
+        (Some(da), Some(db)) => da.cmp(&db),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+
+    let key_cmp = |key: &SortKey| match key {
+        SortKey::Priority => b
+            .effective_priority(today, order.escalate_after)
+            .cmp(&a.effective_priority(today, order.escalate_after)),
+        SortKey::Created => match (a.created_at, b.created_at) {
+            (Some(ca), Some(cb)) => ca.cmp(&cb),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        SortKey::Source => a.source.name().cmp(b.source.name()),
+        SortKey::Title => a
+            .title
+            .to_lowercase()
+            .cmp(&b.title.to_lowercase())
+            .then_with(|| a.title.cmp(&b.title)),
+    };
+
+    order
+        .tiebreak
+        .0
+        .iter()
+        .fold(bucket(a).cmp(&bucket(b)).then(date_cmp), |ordering, key| {
+            ordering.then_with(|| key_cmp(key))
+        })
+}
+
+/// A backend's last successful fetch, and the filter it was for.
+type LastFetch = (TaskFilter, Vec<Task>);
+
+/// What `BackendManager::create_task_or_existing` did.
+#[derive(Debug, Clone)]
+pub enum Created {
+    /// The task was added
+    New(Task),
+    /// The same task had just been added, so this one is returned instead
+    Existing(Task),
+}
+
+impl Created {
+    /// The task, whether new or already there.
+    pub fn into_task(self) -> Task {
+        match self {
+            Created::New(task) | Created::Existing(task) => task,
+        }
+    }
+}
+
+/// All enabled backends behind one interface. Reads merge and sort tasks
+/// from every backend; writes go to the backend that owns the task.
 pub struct BackendManager {
     backends: Vec<Box<dyn TaskBackend>>,
+    /// One per backend, held for the duration of every mutation so rapid
+    /// actions never interleave their read-modify-write of the same file.
+    write_locks: Vec<Mutex<()>>,
+    /// One per backend: its last successful fetch and the filter it used,
+    /// served instead when the next fetch fails (e.g. a network mount
+    /// dropping out) so a long-running TUI keeps its list.
+    last_fetched: std::sync::Mutex<Vec<Option<LastFetch>>>,
+    allow_fallback: bool,
+    default_backend: Option<BackendSource>,
+    sort_order: SortOrder,
+    dedupe_window: Option<Duration>,
+    /// Tasks created within `dedupe_window`: when, what was asked for, and
+    /// the task it made
+    recently_created: std::sync::Mutex<Vec<(Instant, NewTask, Task)>>,
 }
 
 impl BackendManager {
+    /// A manager over `backends`, with no fallback and default sort order.
     pub fn new(backends: Vec<Box<dyn TaskBackend>>) -> Self {
-        Self { backends }
+        let write_locks = backends.iter().map(|_| Mutex::new(())).collect();
+        let last_fetched = std::sync::Mutex::new(backends.iter().map(|_| None).collect());
+        Self {
+            backends,
+            write_locks,
+            last_fetched,
+            allow_fallback: false,
+            default_backend: None,
+            sort_order: SortOrder::default(),
+            dedupe_window: None,
+            recently_created: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// How `all_tasks` orders its result.
+    pub fn with_sort_order(mut self, sort_order: SortOrder) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
+    /// Backend for new tasks that don't name one; `None` means the first.
+    pub fn with_default_backend(mut self, default_backend: Option<BackendSource>) -> Self {
+        self.default_backend = default_backend;
+        self
     }
 
+    /// Don't create a task again if the same title was created in the same
+    /// backend less than `window` ago, e.g. from Enter pressed twice.
+    pub fn with_dedupe_window(mut self, window: Option<Duration>) -> Self {
+        self.dedupe_window = window;
+        self
+    }
+
+    /// Create in the first backend when the requested one isn't enabled.
+    pub fn with_fallback(mut self, allow_fallback: bool) -> Self {
+        self.allow_fallback = allow_fallback;
+        self
+    }
+
+    /// The backends enabled in `config`, set up as `config` says.
     pub fn from_config(config: &crate::config::Config) -> Result<Self> {
+        Self::with_backends(config, &config.backends)
+    }
+
+    fn with_backends(
+        config: &crate::config::Config,
+        backends_config: &crate::config::BackendsConfig,
+    ) -> Result<Self> {
         let mut backends: Vec<Box<dyn TaskBackend>> = Vec::new();
 
-        if let Some(ref table) = config.backends.local {
+        let max_file_bytes = backends_config.max_file_size_mb * 1024 * 1024;
+        let retry = backends_config.retry_policy();
+        let bangs = config.general.bangs();
+
+        if let Some(ref table) = backends_config.local {
             if table.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
                 let local_config = localfile::LocalFileConfig::from_table(table)?;
-                backends.push(Box::new(localfile::LocalFileBackend::new(local_config)));
+                backends.push(Box::new(
+                    localfile::LocalFileBackend::new(local_config)
+                        .with_max_file_bytes(max_file_bytes)
+                        .with_retry(retry)
+                        .with_bangs(bangs),
+                ));
             }
         }
 
-        if let Some(ref table) = config.backends.obsidian {
+        if let Some(ref table) = backends_config.obsidian {
             if table.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
                 let obs_config = obsidian::ObsidianConfig::from_table(table)?;
-                backends.push(Box::new(obsidian::ObsidianBackend::new(obs_config)));
+                backends.push(Box::new(
+                    obsidian::ObsidianBackend::new(obs_config)
+                        .with_max_file_bytes(max_file_bytes)
+                        .with_retry(retry)
+                        .with_bangs(bangs),
+                ));
+            }
+        }
+
+        if let Some(ref table) = backends_config.ical {
+            if table.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+                let ical_config = ical::IcalConfig::from_table(table)?;
+                backends.push(Box::new(
+                    ical::IcalBackend::new(ical_config).with_max_file_bytes(max_file_bytes),
+                ));
             }
         }
 
-        Ok(Self::new(backends))
+        let default_backend = match config.general.default_backend {
+            Some(ref name) => Some(BackendSource::from_name(name).ok_or_else(|| {
+                crate::error::TasukiError::Config(format!(
+                    "general.default_backend \"{}\" is not one of {}",
+                    name,
+                    BackendSource::names()
+                ))
+            })?),
+            None => None,
+        };
+
+        Ok(Self::new(backends)
+            .with_fallback(backends_config.allow_fallback)
+            .with_dedupe_window(backends_config.dedupe_window_secs.map(Duration::from_secs))
+            .with_default_backend(default_backend)
+            .with_sort_order(SortOrder::from_config(&config.general)))
     }
 
+    /// Tasks matching `filter` from every backend, sorted by urgency.
     pub async fn all_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
+        self.all_tasks_timed(filter).await.0
+    }
+
+    /// `all_tasks`, plus how long each backend took and whether it failed.
+    /// Reports are returned even when the fetch as a whole fails.
+    ///
+    /// A backend that fails after an earlier fetch with the same filter
+    /// succeeded contributes that fetch's tasks, reported as `stale`.
+    pub async fn all_tasks_timed(
+        &self,
+        filter: &TaskFilter,
+    ) -> (Result<Vec<Task>>, Vec<FetchReport>) {
         use futures::future::join_all;
+        use std::time::Instant;
         use tracing::error;
 
         let futures: Vec<_> = self.backends.iter()
             .map(|backend| async move {
+                let started = Instant::now();
                 let result = backend.fetch_tasks(filter).await;
-                (backend.name(), backend.source(), result)
+                (backend.name(), backend.source(), started.elapsed(), result)
             })
             .collect();
 
         let results = join_all(futures).await;
         let mut all_tasks = Vec::new();
         let mut errors = Vec::new();
+        let mut reports = Vec::new();
+        let mut last_fetched = self.last_fetched.lock().unwrap_or_else(|e| e.into_inner());
 
-        for (name, _source, result) in results {
+        for ((name, source, elapsed, result), last) in results.into_iter().zip(last_fetched.iter_mut()) {
             match result {
                 Ok(tasks) => {
+                    reports.push(FetchReport { source, elapsed, result: Ok(tasks.len()), stale: false });
+                    *last = Some((filter.clone(), tasks.clone()));
                     all_tasks.extend(tasks);
                 }
                 Err(e) => {
                     error!("Backend '{}' error: {}", name, e);
-                    errors.push((name, e));
+                    let stale = match last {
+                        Some((last_filter, tasks)) if last_filter == filter => {
+                            tracing::warn!("Showing the last {} tasks fetched from '{}'", tasks.len(), name);
+                            all_tasks.extend(tasks.iter().cloned());
+                            true
+                        }
+                        _ => false,
+                    };
+                    reports.push(FetchReport { source, elapsed, result: Err(e.to_string()), stale });
+                    if !stale {
+                        errors.push((name, e));
+                    }
                 }
             }
         }
+        drop(last_fetched);
 
         if !errors.is_empty() && all_tasks.is_empty() {
-            return Err(crate::error::TasukiError::Backend {
-                backend: errors[0].0.to_string(),
-                message: format!("{}", errors[0].1),
-            });
+            use crate::error::TasukiError;
+
+            // Kept apart so callers can tell a mount that's reconnecting
+            // from a lasting failure
+            let (name, e) = errors.swap_remove(0);
+            let err = match e {
+                e @ TasukiError::Unavailable { .. } => e,
+                TasukiError::Io(e) if retry::is_transient(&e) => TasukiError::Unavailable {
+                    backend: name.to_string(),
+                    source: e,
+                },
+                e => TasukiError::Backend {
+                    backend: name.to_string(),
+                    message: format!("{}", e),
+                },
+            };
+            return (Err(err), reports);
         }
 
-        // Sort: overdue first, then due date, then priority, then title
-        all_tasks.sort_by(|a, b| {
-            use chrono::Local;
-            let today = Local::now().date_naive();
+        sort_by_urgency(&mut all_tasks, &self.sort_order);
 
-            let score_a = match a.due {
-                Some(d) if d < today => 0,
-                Some(d) if d == today => 1,
-                Some(_) => 2,
-                None => 3,
-            };
-            let score_b = match b.due {
-                Some(d) if d < today => 0,
-                Some(d) if d == today => 1,
-                Some(_) => 2,
-                None => 3,
-            };
+        (Ok(all_tasks), reports)
+    }
 
-            let due_cmp = score_a.cmp(&score_b);
-            if due_cmp != std::cmp::Ordering::Equal {
-                return due_cmp;
-            }
+    /// Where tasks go when quick-add doesn't name a backend:
+    /// `general.default_backend` if it's enabled, else the first enabled one.
+    pub fn default_source(&self) -> Option<BackendSource> {
+        self.default_backend
+            .filter(|source| self.backends.iter().any(|b| b.source() == *source))
+            .or_else(|| self.backends.first().map(|b| b.source()))
+    }
 
-            let date_cmp = match (a.due, b.due) {
-                (Some(da), Some(db)) => da.cmp(&db),
-                (Some(_), None) => std::cmp::Ordering::Less,
-                (None, Some(_)) => std::cmp::Ordering::Greater,
-                (None, None) => std::cmp::Ordering::Equal,
-            };
-            if date_cmp != std::cmp::Ordering::Equal {
-                return date_cmp;
-            }
+    /// Capabilities of the enabled backend for `source`, if any.
+    pub fn capabilities(&self, source: BackendSource) -> Option<BackendCapabilities> {
+        self.backends
+            .iter()
+            .find(|b| b.source() == source)
+            .map(|b| b.capabilities())
+    }
 
-            let priority_cmp = b.priority.cmp(&a.priority);
-            if priority_cmp != std::cmp::Ordering::Equal {
-                return priority_cmp;
-            }
+    /// Capabilities of the backend that owns the task with this ID.
+    pub fn capabilities_for_id(&self, id: &TaskId) -> Option<BackendCapabilities> {
+        self.backends
+            .iter()
+            .find(|b| b.owns_id(id))
+            .map(|b| b.capabilities())
+    }
 
-            a.title.cmp(&b.title)
-        });
+    /// `config`'s quick-add options, where `@name` sends tasks to the
+    /// enabled backend of that `name`, if it creates them.
+    pub fn quick_add_options(&self, config: &crate::config::Config) -> crate::nlp::QuickAddOptions {
+        let mut options = config.quick_add_options();
+        options.targets = self
+            .backends
+            .iter()
+            .filter(|b| b.capabilities().can_create)
+            .map(|b| (b.name().to_string(), b.source()))
+            .collect();
+        options
+    }
 
-        Ok(all_tasks)
+    /// Source of the enabled backend that owns the task with this ID.
+    pub fn source_of(&self, id: &TaskId) -> Option<BackendSource> {
+        self.backends
+            .iter()
+            .find(|b| b.owns_id(id))
+            .map(|b| b.source())
     }
 
+    /// Sources of the enabled backends, in order.
+    pub fn sources(&self) -> Vec<BackendSource> {
+        self.backends.iter().map(|b| b.source()).collect()
+    }
+
+    /// Glyph for tasks from `source`: its enabled backend's `icon`, or the
+    /// source's own.
+    pub fn icon(&self, source: BackendSource) -> &str {
+        self.backends
+            .iter()
+            .find(|b| b.source() == source)
+            .map_or(source.icon(), |b| b.icon())
+    }
+
+    /// Create the task in the backend it asks for. If that backend isn't
+    /// enabled this fails, unless `allow_fallback` is set, in which case the
+    /// first enabled backend is used; check `Task::source` for where it went.
+    ///
+    /// With a dedupe window, a task identical to one created moments ago
+    /// isn't created again; see `create_task_or_existing`.
     pub async fn create_task(&self, task: &NewTask) -> Result<Task> {
-        for backend in &self.backends {
-            if backend.source() == task.backend {
-                return backend.create_task(task).await;
+        self.create_task_or_existing(task).await.map(Created::into_task)
+    }
+
+    /// `create_task`, telling whether the task was added or, within the
+    /// dedupe window, was already there: the same `task` was created by this
+    /// manager less than `dedupe_window` ago and is still pending.
+    pub async fn create_task_or_existing(&self, task: &NewTask) -> Result<Created> {
+        if let Some(existing) = self.recent_duplicate(task).await {
+            tracing::info!("'{}' was added moments ago; not adding it again", task.title);
+            return Ok(Created::Existing(existing));
+        }
+
+        let created = self.create_routed(task).await?;
+        if self.dedupe_window.is_some() {
+            if let Ok(mut recent) = self.recently_created.lock() {
+                recent.push((Instant::now(), task.clone(), created.clone()));
             }
         }
+        Ok(Created::New(created))
+    }
+
+    /// The pending task `task` would duplicate, if one like it was created
+    /// within the dedupe window.
+    async fn recent_duplicate(&self, task: &NewTask) -> Option<Task> {
+        let window = self.dedupe_window?;
+        let created = {
+            let mut recent = self.recently_created.lock().ok()?;
+            recent.retain(|(at, _, _)| at.elapsed() < window);
+            recent
+                .iter()
+                .rev()
+                .find(|(_, asked, _)| asked == task)
+                .map(|(_, _, created)| created.clone())?
+        };
+
+        let backend = self
+            .backends
+            .iter()
+            .find(|b| b.source() == created.source)?;
+        let filter = TaskFilter {
+            status: Some(TaskStatus::Pending),
+            ..Default::default()
+        };
+        let tasks = backend.fetch_tasks(&filter).await.ok()?;
+        tasks
+            .into_iter()
+            .find(|t| t.status == TaskStatus::Pending && t.title == created.title)
+    }
+
+    async fn create_routed(&self, task: &NewTask) -> Result<Task> {
+        if let Some(index) = self.backends.iter().position(|b| b.source() == task.backend) {
+            return self.create_in(index, task).await;
+        }
+
+        let Some(fallback) = self.backends.first() else {
+            return Err(crate::error::TasukiError::Backend {
+                backend: "none".to_string(),
+                message: "No backends configured".to_string(),
+            });
+        };
 
-        if let Some(backend) = self.backends.first() {
-            return backend.create_task(task).await;
+        if !self.allow_fallback {
+            let enabled: Vec<&str> = self.backends.iter().map(|b| b.source().name()).collect();
+            return Err(crate::error::TasukiError::Backend {
+                backend: task.backend.name().to_string(),
+                message: format!(
+                    "not enabled (enabled: {}); set backends.allow_fallback = true to use {} instead",
+                    enabled.join(", "),
+                    fallback.source().name()
+                ),
+            });
         }
 
-        Err(crate::error::TasukiError::Backend {
-            backend: "none".to_string(),
-            message: "No backends configured".to_string(),
-        })
+        tracing::warn!(
+            "Backend '{}' not enabled, creating task in '{}'",
+            task.backend.name(),
+            fallback.source().name()
+        );
+        self.create_in(0, task).await
     }
 
-    pub async fn complete_task(&self, id: &TaskId) -> Result<()> {
-        let prefix = id.split(':').next().unwrap_or("");
-        
-        for backend in &self.backends {
-            if backend.source().name() == prefix {
-                return backend.complete_task(id).await;
-            }
+    async fn create_in(&self, index: usize, task: &NewTask) -> Result<Task> {
+        let backend = &self.backends[index];
+        let caps = backend.capabilities();
+        if !caps.can_create {
+            return Err(crate::error::TasukiError::Backend {
+                backend: backend.name().to_string(),
+                message: "does not support creating tasks".to_string(),
+            });
         }
 
-        Err(crate::error::TasukiError::Parse(format!(
-            "No backend found for task ID: {}",
-            id
-        )))
+        let dropped = caps.dropped_fields(task);
+        if !dropped.is_empty() {
+            tracing::warn!(
+                "Backend '{}' does not support {}; dropping it from '{}'",
+                backend.name(),
+                dropped.join(", "),
+                task.title
+            );
+        }
+
+        let _guard = self.write_locks[index].lock().await;
+        backend.create_task(task).await
     }
 
-    pub async fn uncomplete_task(&self, id: &TaskId) -> Result<()> {
-        let prefix = id.split(':').next().unwrap_or("");
-        
-        for backend in &self.backends {
-            if backend.source().name() == prefix {
-                return backend.uncomplete_task(id).await;
-            }
+    /// The backend owning `id` (see `TaskBackend::owns_id`) and its write lock.
+    fn owner(&self, id: &TaskId) -> Result<(&dyn TaskBackend, &Mutex<()>)> {
+        self.backends
+            .iter()
+            .zip(&self.write_locks)
+            .find(|(backend, _)| backend.owns_id(id))
+            .map(|(backend, lock)| (backend.as_ref(), lock))
+            .ok_or_else(|| {
+                crate::error::TasukiError::Parse(format!("No backend found for task ID: {}", id))
+            })
+    }
+
+    /// The owner of `id`, if it allows changing its tasks.
+    fn updatable_owner(&self, id: &TaskId) -> Result<(&dyn TaskBackend, &Mutex<()>)> {
+        let (backend, lock) = self.owner(id)?;
+        if !backend.capabilities().can_update {
+            return Err(crate::error::TasukiError::Backend {
+                backend: backend.name().to_string(),
+                message: "is read-only".to_string(),
+            });
         }
+        Ok((backend, lock))
+    }
+
+    /// Mark the task with `id` done in the backend that owns it, returning
+    /// its ID afterwards. See `TaskBackend::complete_task` for `title`.
+    pub async fn complete_task(&self, id: &TaskId, title: Option<&str>) -> Result<TaskId> {
+        let (backend, lock) = self.updatable_owner(id)?;
+        let _guard = lock.lock().await;
+        backend.complete_task(id, title).await
+    }
 
-        Err(crate::error::TasukiError::Parse(format!(
-            "No backend found for task ID: {}",
-            id
-        )))
+    /// Mark the task with `id` pending again, returning its ID afterwards.
+    pub async fn uncomplete_task(&self, id: &TaskId, title: Option<&str>) -> Result<TaskId> {
+        let (backend, lock) = self.updatable_owner(id)?;
+        let _guard = lock.lock().await;
+        backend.uncomplete_task(id, title).await
     }
 
+    /// Apply `update` to the task with `id`.
     pub async fn update_task(&self, id: &TaskId, update: &crate::model::TaskUpdate) -> Result<crate::model::Task> {
-        let prefix = id.split(':').next().unwrap_or("");
-        
-        for backend in &self.backends {
-            if backend.source().name() == prefix {
-                return backend.update_task(id, update).await;
+        let (backend, lock) = self.updatable_owner(id)?;
+        let _guard = lock.lock().await;
+        backend.update_task(id, update).await
+    }
+
+    /// `task` as it is in its file now. Line-based IDs shift when lines are
+    /// added above a task, so a task held since the last fetch (or returned
+    /// by `create_task`) may point at the wrong line. Finds the task with
+    /// the same title in the same file, nearest its old line.
+    pub async fn relocate(&self, task: &Task) -> Result<Task> {
+        let (backend, _) = self.owner(&task.id)?;
+        let filter = TaskFilter {
+            search: Some(task.title.clone()),
+            ..Default::default()
+        };
+        let old_line = task.source_line.unwrap_or(0);
+
+        backend
+            .fetch_tasks(&filter)
+            .await?
+            .into_iter()
+            .filter(|t| t.title == task.title && t.source_path == task.source_path)
+            .min_by_key(|t| t.source_line.unwrap_or(0).abs_diff(old_line))
+            .ok_or_else(|| {
+                crate::error::TasukiError::NotFound(format!(
+                    "'{}' is no longer in {}",
+                    task.title,
+                    task.source_path.as_deref().unwrap_or(backend.name())
+                ))
+            })
+    }
+
+    /// Remove the task with `id` from its backend.
+    pub async fn delete_task(&self, id: &TaskId) -> Result<()> {
+        let (backend, lock) = self.owner(id)?;
+        if !backend.capabilities().can_delete {
+            return Err(crate::error::TasukiError::Backend {
+                backend: backend.name().to_string(),
+                message: "does not support deleting tasks".to_string(),
+            });
+        }
+        let _guard = lock.lock().await;
+        backend.delete_task(id).await
+    }
+
+    /// Move `task` into the `to` backend: create a copy there (completed too
+    /// if the original is done), then delete the original. If the delete
+    /// fails after the copy was made, the error names both IDs.
+    pub async fn move_task(&self, task: &Task, to: BackendSource) -> Result<Task> {
+        if task.source == to {
+            return Err(crate::error::TasukiError::Parse(format!(
+                "{} is already in {}",
+                task.id,
+                to.name()
+            )));
+        }
+        let Some(index) = self.backends.iter().position(|b| b.source() == to) else {
+            return Err(crate::error::TasukiError::Backend {
+                backend: to.name().to_string(),
+                message: "not enabled".to_string(),
+            });
+        };
+        // Refuse up front rather than leave a copy behind
+        let (source, _) = self.owner(&task.id)?;
+        if !source.capabilities().can_delete {
+            return Err(crate::error::TasukiError::Backend {
+                backend: source.name().to_string(),
+                message: "does not support deleting tasks, so it can't be moved".to_string(),
+            });
+        }
+
+        let mut created = self
+            .create_in(
+                index,
+                &NewTask {
+                    title: task.title.clone(),
+                    priority: task.priority,
+                    due: task.due,
+                    tags: task.tags.clone(),
+                    backend: to,
+                },
+            )
+            .await?;
+
+        let result = async {
+            if task.status == crate::model::TaskStatus::Done {
+                self.complete_task(&created.id, Some(&created.title))
+                    .await?;
+                created.status = crate::model::TaskStatus::Done;
             }
+            self.delete_task(&task.id).await
+        };
+        if let Err(e) = result.await {
+            return Err(crate::error::TasukiError::Backend {
+                backend: task.source.name().to_string(),
+                message: format!(
+                    "created {} but could not finish moving {}: {}; remove one of them by hand",
+                    created.id, task.id, e
+                ),
+            });
         }
 
-        Err(crate::error::TasukiError::Parse(format!(
-            "No backend found for task ID: {}",
-            id
-        )))
+        Ok(created)
     }
 
-    pub async fn delete_task(&self, id: &TaskId) -> Result<()> {
-        let prefix = id.split(':').next().unwrap_or("");
-        
-        for backend in &self.backends {
-            if backend.source().name() == prefix {
-                return backend.delete_task(id).await;
+    /// The task with exactly this ID, fetched only from the backend that
+    /// owns it. Unlike `find_task` nothing else is tried,
+    /// so a stale ID (the line moved, the task was deleted) is an error
+    /// rather than a guess.
+    pub async fn task_by_id(&self, id: &str) -> Result<Task> {
+        let Some(backend) = self.backends.iter().find(|b| b.owns_id(id)) else {
+            let enabled: Vec<&str> = self.backends.iter().map(|b| b.source().name()).collect();
+            return Err(crate::error::TasukiError::Config(format!(
+                "Task ID '{}' doesn't start with an enabled backend (enabled: {})",
+                id,
+                enabled.join(", ")
+            )));
+        };
+
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await?;
+        tasks.into_iter().find(|t| t.id == id).ok_or_else(|| {
+            crate::error::TasukiError::NotFound(format!(
+                "No task with ID '{}'; it may have moved, run `tasuki list` for current IDs",
+                id
+            ))
+        })
+    }
+
+    /// Resolve a task by exact ID, falling back to a case-insensitive title
+    /// match where every query word must appear in the title.
+    pub async fn find_task(&self, query: &str) -> Result<Task> {
+        let tasks = self.all_tasks(&TaskFilter::default()).await?;
+
+        if let Some(task) = tasks.iter().find(|t| t.id == query) {
+            return Ok(task.clone());
+        }
+
+        let query_lower = query.to_lowercase();
+        if let Some(task) = tasks.iter().find(|t| t.title.to_lowercase() == query_lower) {
+            return Ok(task.clone());
+        }
+
+        let words: Vec<&str> = query_lower.split_whitespace().collect();
+        if words.is_empty() {
+            return Err(crate::error::TasukiError::NotFound(format!(
+                "No task matches '{}'",
+                query
+            )));
+        }
+
+        let matches: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| {
+                let title = t.title.to_lowercase();
+                words.iter().all(|w| title.contains(w))
+            })
+            .collect();
+
+        match matches.as_slice() {
+            [] => Err(crate::error::TasukiError::NotFound(format!(
+                "No task matches '{}'",
+                query
+            ))),
+            [task] => Ok((*task).clone()),
+            many => {
+                let candidates: Vec<String> = many
+                    .iter()
+                    .take(5)
+                    .map(|t| format!("  {}  {}", t.id, t.title))
+                    .collect();
+                Err(crate::error::TasukiError::Parse(format!(
+                    "'{}' matches {} tasks, use an ID:\n{}",
+                    query,
+                    many.len(),
+                    candidates.join("\n")
+                )))
             }
         }
+    }
 
-        Err(crate::error::TasukiError::Parse(format!(
-            "No backend found for task ID: {}",
-            id
-        )))
+    /// Extra lines stored with `task`; see `TaskBackend::task_details`.
+    pub fn task_details(&self, task: &Task) -> Result<Vec<String>> {
+        match self.backends.iter().find(|b| b.owns_id(&task.id)) {
+            Some(backend) => backend.task_details(task),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// `TaskBackend::pending_subtasks` from the backend `task` came from.
+    pub fn pending_subtasks(&self, task: &Task) -> Result<usize> {
+        match self.backends.iter().find(|b| b.owns_id(&task.id)) {
+            Some(backend) => backend.pending_subtasks(task),
+            None => Ok(0),
+        }
+    }
+
+    /// Number of enabled backends.
+    pub fn len(&self) -> usize {
+        self.backends.len()
     }
 
+    /// Whether no backend is enabled.
     pub fn is_empty(&self) -> bool {
         self.backends.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ReadOnlyBackend;
+
+    #[async_trait]
+    impl TaskBackend for ReadOnlyBackend {
+        fn name(&self) -> &str {
+            "read-only"
+        }
+
+        fn source(&self) -> BackendSource {
+            BackendSource::LocalFile
+        }
+
+        fn capabilities(&self) -> BackendCapabilities {
+            BackendCapabilities {
+                can_create: false,
+                can_update: false,
+                can_delete: false,
+                supports_priority: false,
+                supports_due_time: false,
+                supports_description: false,
+                supports_recurrence: false,
+            }
+        }
+
+        async fn fetch_tasks(&self, _filter: &TaskFilter) -> Result<Vec<Task>> {
+            Ok(Vec::new())
+        }
+
+        async fn create_task(&self, _task: &NewTask) -> Result<Task> {
+            unreachable!("create_task called on a read-only backend")
+        }
+
+        async fn update_task(&self, _id: &TaskId, _update: &TaskUpdate) -> Result<Task> {
+            unreachable!("update_task called on a read-only backend")
+        }
+
+        async fn complete_task(&self, _id: &TaskId, _title: Option<&str>) -> Result<TaskId> {
+            unreachable!("complete_task called on a read-only backend")
+        }
+
+        async fn uncomplete_task(&self, _id: &TaskId, _title: Option<&str>) -> Result<TaskId> {
+            unreachable!("uncomplete_task called on a read-only backend")
+        }
+
+        async fn delete_task(&self, _id: &TaskId) -> Result<()> {
+            unreachable!("delete_task called on a read-only backend")
+        }
+    }
+
+    /// Tasks kept in memory under `source`, each with one open subtask;
+    /// `fail_delete` makes deletes error, and with `custom_ids` IDs are
+    /// `#<n>` and the icon is "●".
+    struct MemoryBackend {
+        source: BackendSource,
+        tasks: std::sync::Mutex<Vec<Task>>,
+        fail_delete: bool,
+        custom_ids: bool,
+    }
+
+    impl MemoryBackend {
+        fn new(source: BackendSource) -> Self {
+            Self {
+                source,
+                tasks: std::sync::Mutex::new(Vec::new()),
+                fail_delete: false,
+                custom_ids: false,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TaskBackend for MemoryBackend {
+        fn name(&self) -> &str {
+            self.source.name()
+        }
+
+        fn source(&self) -> BackendSource {
+            self.source
+        }
+
+        fn owns_id(&self, id: &str) -> bool {
+            if self.custom_ids {
+                id.starts_with('#')
+            } else {
+                id.split(':').next() == Some(self.source.name())
+            }
+        }
+
+        fn icon(&self) -> &str {
+            if self.custom_ids {
+                "●"
+            } else {
+                self.source.icon()
+            }
+        }
+
+        fn capabilities(&self) -> BackendCapabilities {
+            BackendCapabilities {
+                can_create: true,
+                can_update: true,
+                can_delete: true,
+                supports_priority: true,
+                supports_due_time: false,
+                supports_description: false,
+                supports_recurrence: false,
+            }
+        }
+
+        async fn fetch_tasks(&self, _filter: &TaskFilter) -> Result<Vec<Task>> {
+            Ok(self.tasks.lock().unwrap().clone())
+        }
+
+        async fn create_task(&self, task: &NewTask) -> Result<Task> {
+            let mut tasks = self.tasks.lock().unwrap();
+            let id = if self.custom_ids {
+                format!("#{}", tasks.len() + 1)
+            } else {
+                format!("{}:{}", self.source.name(), tasks.len() + 1)
+            };
+            let created = Task {
+                tags: task.tags.clone(),
+                ..Task::build(&task.title)
+                    .with_id(&id)
+                    .with_source(self.source)
+                    .with_priority(task.priority)
+                    .with_due(task.due)
+            };
+            tasks.push(created.clone());
+            Ok(created)
+        }
+
+        async fn update_task(&self, _id: &TaskId, _update: &TaskUpdate) -> Result<Task> {
+            unreachable!()
+        }
+
+        async fn complete_task(&self, id: &TaskId, _title: Option<&str>) -> Result<TaskId> {
+            let mut tasks = self.tasks.lock().unwrap();
+            if let Some(task) = tasks.iter_mut().find(|t| &t.id == id) {
+                task.status = crate::model::TaskStatus::Done;
+            }
+            Ok(id.clone())
+        }
+
+        async fn uncomplete_task(&self, _id: &TaskId, _title: Option<&str>) -> Result<TaskId> {
+            unreachable!()
+        }
+
+        fn pending_subtasks(&self, _task: &Task) -> Result<usize> {
+            Ok(1)
+        }
+
+        async fn delete_task(&self, id: &TaskId) -> Result<()> {
+            if self.fail_delete {
+                return Err(crate::error::TasukiError::Backend {
+                    backend: self.name().to_string(),
+                    message: "file is locked".to_string(),
+                });
+            }
+            self.tasks.lock().unwrap().retain(|t| &t.id != id);
+            Ok(())
+        }
+    }
+
+    async fn seeded(source: BackendSource, fail_delete: bool) -> MemoryBackend {
+        let mut backend = MemoryBackend::new(source);
+        backend.fail_delete = fail_delete;
+        let mut new = new_task(Priority::High);
+        new.title = "Renew passport".to_string();
+        new.due = chrono::NaiveDate::from_ymd_opt(2025, 6, 1);
+        new.tags = vec!["admin".to_string()];
+        backend.create_task(&new).await.unwrap();
+        backend
+            .complete_task(&"local:1".to_string(), None)
+            .await
+            .unwrap();
+        backend
+    }
+
+    #[tokio::test]
+    async fn test_move_task_preserves_fields() {
+        let manager = BackendManager::new(vec![
+            Box::new(seeded(BackendSource::LocalFile, false).await),
+            Box::new(MemoryBackend::new(BackendSource::Obsidian)),
+        ]);
+        let task = manager.find_task("local:1").await.unwrap();
+
+        let moved = manager.move_task(&task, BackendSource::Obsidian).await.unwrap();
+        assert_eq!(moved.id, "obsidian:1");
+
+        let tasks = manager.all_tasks(&TaskFilter::default()).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        let task = &tasks[0];
+        assert_eq!(task.source, BackendSource::Obsidian);
+        assert_eq!(task.title, "Renew passport");
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.due, chrono::NaiveDate::from_ymd_opt(2025, 6, 1));
+        assert_eq!(task.tags, vec!["admin"]);
+        assert_eq!(task.status, crate::model::TaskStatus::Done);
+
+        let err = manager.move_task(task, BackendSource::Obsidian).await.unwrap_err();
+        assert!(err.to_string().contains("already in obsidian"));
+    }
+
+    #[tokio::test]
+    async fn test_move_task_reports_both_ids_when_delete_fails() {
+        let manager = BackendManager::new(vec![
+            Box::new(seeded(BackendSource::LocalFile, true).await),
+            Box::new(MemoryBackend::new(BackendSource::Obsidian)),
+        ]);
+        let task = manager.find_task("local:1").await.unwrap();
+
+        let err = manager.move_task(&task, BackendSource::Obsidian).await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("created obsidian:1"), "{}", message);
+        assert!(message.contains("moving local:1"), "{}", message);
+        assert_eq!(manager.all_tasks(&TaskFilter::default()).await.unwrap().len(), 2);
+    }
+
+    fn new_task(priority: Priority) -> NewTask {
+        NewTask {
+            title: "Task".to_string(),
+            priority,
+            due: None,
+            tags: Vec::new(),
+            backend: BackendSource::LocalFile,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_window_returns_task_just_created() {
+        let manager = BackendManager::new(vec![Box::new(MemoryBackend::new(BackendSource::LocalFile))])
+            .with_dedupe_window(Some(Duration::from_secs(60)));
+        let first = manager.create_task_or_existing(&new_task(Priority::None)).await.unwrap();
+        let Created::New(first) = first else {
+            panic!("first create should add the task");
+        };
+        let again = manager.create_task_or_existing(&new_task(Priority::None)).await.unwrap();
+        assert!(matches!(again, Created::Existing(ref t) if t.id == first.id));
+        assert_eq!(manager.all_tasks(&TaskFilter::default()).await.unwrap().len(), 1);
+
+        // Once the first is done, the same title is a new task
+        manager.complete_task(&first.id, None).await.unwrap();
+        let third = manager
+            .create_task_or_existing(&new_task(Priority::None))
+            .await
+            .unwrap();
+        assert!(matches!(third, Created::New(_)));
+
+        // Without a window every create adds one
+        let manager = BackendManager::new(vec![Box::new(MemoryBackend::new(BackendSource::LocalFile))]);
+        manager.create_task(&new_task(Priority::None)).await.unwrap();
+        manager.create_task(&new_task(Priority::None)).await.unwrap();
+        assert_eq!(manager.all_tasks(&TaskFilter::default()).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_routing_asks_backends_for_their_ids() {
+        let mut custom = MemoryBackend::new(BackendSource::Obsidian);
+        custom.custom_ids = true;
+        let manager = BackendManager::new(vec![
+            Box::new(MemoryBackend::new(BackendSource::LocalFile)),
+            Box::new(custom),
+        ]);
+        let mut new = new_task(Priority::None);
+        new.backend = BackendSource::Obsidian;
+        let task = manager.create_task(&new).await.unwrap();
+        assert_eq!(task.id, "#1");
+
+        assert!(manager.capabilities_for_id(&task.id).is_some());
+        manager.complete_task(&task.id, None).await.unwrap();
+        let done = manager.task_by_id("#1").await.unwrap();
+        assert_eq!(done.status, crate::model::TaskStatus::Done);
+        assert_eq!(manager.pending_subtasks(&done).unwrap(), 1);
+        // The source name no longer routes to it
+        assert!(manager
+            .complete_task(&"obsidian:1".to_string(), None)
+            .await
+            .is_err());
+        let unowned = done.with_id("obsidian:1");
+        assert_eq!(manager.pending_subtasks(&unowned).unwrap(), 0);
+
+        assert_eq!(manager.icon(BackendSource::Obsidian), "●");
+        assert_eq!(manager.icon(BackendSource::LocalFile), "■");
+        assert_eq!(manager.icon(BackendSource::ICal), "▲");
+    }
+
+    #[tokio::test]
+    async fn test_mutations_respect_capabilities() {
+        let manager = BackendManager::new(vec![Box::new(ReadOnlyBackend)]);
+        let id = "local:1".to_string();
+
+        let err = manager.create_task(&new_task(Priority::None)).await.unwrap_err();
+        assert!(err.to_string().contains("does not support creating"));
+
+        let err = manager.delete_task(&id).await.unwrap_err();
+        assert!(err.to_string().contains("does not support deleting"));
+
+        let err = manager.complete_task(&id, None).await.unwrap_err();
+        assert!(err.to_string().contains("is read-only"));
+        assert!(manager.uncomplete_task(&id, None).await.is_err());
+        assert!(manager
+            .update_task(&id, &TaskUpdate::default())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_tiebreak_orders_tasks_due_the_same_day() {
+        let due = chrono::NaiveDate::from_ymd_opt(2025, 3, 10);
+        let task = |title: &str, priority, created_day: u32, source: BackendSource| {
+            Task::build(title)
+                .with_source(source)
+                .with_priority(priority)
+                .with_due(due)
+                .with_created(
+                    chrono::NaiveDate::from_ymd_opt(2025, 3, created_day)
+                        .map(|d| d.and_hms_opt(9, 0, 0).unwrap()),
+                )
+        };
+        let mut tasks = vec![
+            task("Alpha", Priority::Low, 3, BackendSource::Obsidian),
+            task("Bravo", Priority::High, 2, BackendSource::LocalFile),
+            task("Charlie", Priority::Low, 1, BackendSource::LocalFile),
+        ];
+        let sorted = |tasks: &mut Vec<Task>, keys: &[&str]| {
+            let names: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+            let order = SortOrder {
+                escalate_after: None,
+                tiebreak: Tiebreak::from_names(&names).unwrap(),
+            };
+            sort_by_urgency(tasks, &order);
+            tasks.iter().map(|t| t.title.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(sorted(&mut tasks, &["priority", "title"]), ["Bravo", "Alpha", "Charlie"]);
+        assert_eq!(sorted(&mut tasks, &["created"]), ["Charlie", "Bravo", "Alpha"]);
+        assert_eq!(sorted(&mut tasks, &["source", "title"]), ["Bravo", "Charlie", "Alpha"]);
+        assert_eq!(sorted(&mut tasks, &["priority", "created"]), ["Bravo", "Charlie", "Alpha"]);
+        assert_eq!(sorted(&mut tasks, &["title"]), ["Alpha", "Bravo", "Charlie"]);
+
+        // Keys never override the due-date sort
+        tasks[2].due = chrono::NaiveDate::from_ymd_opt(2025, 3, 9);
+        assert_eq!(sorted(&mut tasks, &["title"]), ["Charlie", "Alpha", "Bravo"]);
+
+        // Titles ignore case, then fall back to byte order
+        let mut tasks = vec![
+            task("Banana", Priority::None, 1, BackendSource::LocalFile),
+            task("apple", Priority::None, 1, BackendSource::LocalFile),
+            task("Apple", Priority::None, 1, BackendSource::LocalFile),
+        ];
+        assert_eq!(sorted(&mut tasks, &["title"]), ["Apple", "apple", "Banana"]);
+
+        assert!(Tiebreak::from_names(&["urgency".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_escalation_only_changes_sort_order() {
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let task = |title: &str, priority, due_day: u32| {
+            Task::build(title)
+                .with_priority(priority)
+                .with_due(chrono::NaiveDate::from_ymd_opt(2025, 3, due_day))
+        };
+        let sorted = |escalate_after: Option<u32>| {
+            let mut tasks = vec![
+                task("Today", Priority::High, 10),
+                task("Alpha", Priority::Low, 1),
+                task("Zulu", Priority::High, 1),
+            ];
+            let order = SortOrder {
+                escalate_after,
+                ..Default::default()
+            };
+            tasks.sort_by(|a, b| urgency_cmp(a, b, today, &order));
+            tasks.into_iter().map(|t| (t.title, t.priority)).collect::<Vec<_>>()
+        };
+
+        // Overdue always comes before today. Among tasks due the same day,
+        // nine days overdue ranks Alpha with the high-priority Zulu once
+        // past the threshold, leaving the title to decide
+        let titles = |sorted: Vec<(String, Priority)>| {
+            sorted.into_iter().map(|(title, _)| title).collect::<Vec<_>>()
+        };
+        assert_eq!(titles(sorted(None)), ["Zulu", "Alpha", "Today"]);
+        assert_eq!(titles(sorted(Some(10))), ["Zulu", "Alpha", "Today"]);
+        assert_eq!(titles(sorted(Some(7))), ["Alpha", "Zulu", "Today"]);
+
+        // Stored priorities are untouched
+        assert!(sorted(Some(7)).contains(&("Alpha".to_string(), Priority::Low)));
+    }
+
+    #[test]
+    fn test_truncate_title() {
+        assert_eq!(truncate_title("Short".to_string()), "Short");
+        let exact = "é".repeat(MAX_TITLE_CHARS);
+        assert_eq!(truncate_title(exact.clone()), exact);
+        let long = truncate_title("é".repeat(MAX_TITLE_CHARS + 10));
+        assert_eq!(long, format!("{}…", "é".repeat(MAX_TITLE_CHARS)));
+
+        let line = "é".repeat(MAX_LINE_CHARS + 10);
+        assert_eq!(cap_line(line), "é".repeat(MAX_LINE_CHARS));
+        let content = format!("{}\nnext\n", "x".repeat(MAX_LINE_CHARS * 2));
+        let mut lines = FileLines::new(content.as_bytes());
+        assert_eq!(lines.next().unwrap().len(), MAX_LINE_CHARS);
+        assert_eq!(lines.next().as_deref(), Some("next"));
+    }
+
+    #[test]
+    fn test_dropped_fields() {
+        let caps = ReadOnlyBackend.capabilities();
+        assert!(caps.dropped_fields(&new_task(Priority::None)).is_empty());
+        assert_eq!(caps.dropped_fields(&new_task(Priority::High)), vec!["priority"]);
+        assert_eq!(
+            BackendManager::new(vec![Box::new(ReadOnlyBackend)])
+                .capabilities_for_id(&"local:3".to_string()),
+            Some(caps)
+        );
+    }
+
+    #[test]
+    fn test_quick_add_targets_follow_enabled_backends() {
+        let config = crate::config::Config::default();
+        let read_only = BackendManager::new(vec![Box::new(ReadOnlyBackend)]);
+        assert_eq!(read_only.quick_add_options(&config).targets, Vec::new());
+
+        let manager = BackendManager::new(vec![
+            Box::new(ReadOnlyBackend),
+            Box::new(MemoryBackend::new(BackendSource::ICal)),
+        ]);
+        assert_eq!(
+            manager.quick_add_options(&config).targets,
+            vec![("ical".to_string(), BackendSource::ICal)]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_rapid_toggles_do_not_corrupt_file() {
+        use std::sync::Arc;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        let content: String = (1..=50).map(|i| format!("Task {}\n", i)).collect();
+        std::fs::write(&path, content).unwrap();
+
+        let backend = localfile::LocalFileBackend::new(localfile::LocalFileConfig {
+            path: path.clone(),
+        });
+        let manager = Arc::new(BackendManager::new(vec![Box::new(backend)]));
+
+        // Every task is completed; odd ones are then toggled back.
+        let handles: Vec<_> = (1..=50)
+            .map(|i| {
+                let manager = Arc::clone(&manager);
+                tokio::spawn(async move {
+                    let id = format!("local:{}", i);
+                    manager.complete_task(&id, None).await.unwrap();
+                    if i % 2 == 1 {
+                        manager.uncomplete_task(&id, None).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let tasks = manager.all_tasks(&TaskFilter::default()).await.unwrap();
+        assert_eq!(tasks.len(), 50);
+        for i in 1..=50 {
+            let task = tasks.iter().find(|t| t.title == format!("Task {}", i)).unwrap();
+            let expected = if i % 2 == 1 {
+                crate::model::TaskStatus::Pending
+            } else {
+                crate::model::TaskStatus::Done
+            };
+            assert_eq!(task.status, expected, "Task {}", i);
+            assert_eq!(task.id, format!("local:{}", i));
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_relocate_after_insert_above_created_task() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".obsidian")).unwrap();
+        let inbox = dir.path().join("Inbox.md");
+        std::fs::write(&inbox, "- [ ] Older task\n").unwrap();
+
+        let table: toml::Table = toml::from_str(&format!(
+            "vault_path = {:?}\ninbox_file = \"Inbox.md\"",
+            dir.path().to_string_lossy()
+        ))
+        .unwrap();
+        let backend = obsidian::ObsidianBackend::new(
+            obsidian::ObsidianConfig::from_table(&table).unwrap(),
+        );
+        let manager = BackendManager::new(vec![Box::new(backend)]);
+
+        let created = manager
+            .create_task(&NewTask {
+                title: "Fresh task".to_string(),
+                priority: Priority::None,
+                due: None,
+                tags: Vec::new(),
+                backend: BackendSource::Obsidian,
+            })
+            .await
+            .unwrap();
+        assert_eq!(created.source_line, Some(2));
+
+        // Edited elsewhere (another device, the editor) before opening
+        let content = std::fs::read_to_string(&inbox).unwrap();
+        std::fs::write(&inbox, format!("# Inbox\n\n{}", content)).unwrap();
+
+        let current = manager.relocate(&created).await.unwrap();
+        assert_eq!(current.source_line, Some(4));
+        assert_eq!(
+            std::fs::read_to_string(&inbox).unwrap().lines().nth(3),
+            Some("- [ ] Fresh task")
+        );
+
+        std::fs::write(&inbox, "- [ ] Older task\n").unwrap();
+        assert!(matches!(
+            manager.relocate(&created).await,
+            Err(crate::error::TasukiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_task_by_id_is_exact() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".obsidian")).unwrap();
+        std::fs::create_dir(dir.path().join("Daily Notes")).unwrap();
+        let note = dir.path().join("Daily Notes").join("2025-02-25.md");
+        std::fs::write(&note, "# Tuesday\n\n- [ ] Call: the bank\n").unwrap();
+
+        let table: toml::Table = toml::from_str(&format!(
+            "vault_path = {:?}",
+            dir.path().to_string_lossy()
+        ))
+        .unwrap();
+        let backend = obsidian::ObsidianBackend::new(
+            obsidian::ObsidianConfig::from_table(&table).unwrap(),
+        );
+        let manager = BackendManager::new(vec![Box::new(backend)]);
+
+        let task = manager
+            .task_by_id("obsidian:Daily Notes/2025-02-25.md:3")
+            .await
+            .unwrap();
+        assert_eq!(task.title, "Call: the bank");
+
+        // The line moved: no falling back to a title match
+        std::fs::write(&note, "- [ ] Call: the bank\n").unwrap();
+        assert!(matches!(
+            manager.task_by_id("obsidian:Daily Notes/2025-02-25.md:3").await,
+            Err(crate::error::TasukiError::NotFound(_))
+        ));
+        assert!(matches!(
+            manager.task_by_id("local:1").await,
+            Err(crate::error::TasukiError::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_fetch_serves_last_tasks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "Buy milk\n").unwrap();
+        let backend = localfile::LocalFileBackend::new(localfile::LocalFileConfig {
+            path: path.clone(),
+        })
+        .with_retry(retry::RetryPolicy::NONE);
+        let manager = BackendManager::new(vec![Box::new(backend)]);
+
+        // Nothing fetched yet: a failure is just a failure
+        std::fs::remove_file(&path).unwrap();
+        std::fs::create_dir(&path).unwrap();
+        let (result, reports) = manager.all_tasks_timed(&TaskFilter::default()).await;
+        assert!(result.is_err());
+        assert!(!reports[0].stale);
+
+        std::fs::remove_dir(&path).unwrap();
+        std::fs::write(&path, "Buy milk\n").unwrap();
+        manager.all_tasks(&TaskFilter::default()).await.unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::create_dir(&path).unwrap();
+        let (result, reports) = manager.all_tasks_timed(&TaskFilter::default()).await;
+        assert_eq!(result.unwrap()[0].title, "Buy milk");
+        assert!(reports[0].stale);
+        assert!(reports[0].result.is_err());
+
+        // The last fetch was for other tasks, so it can't stand in
+        let pending = TaskFilter {
+            status: Some(crate::model::TaskStatus::Pending),
+            ..Default::default()
+        };
+        assert!(manager.all_tasks(&pending).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_backends_spec() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let todo = dir.path().join("todo.txt");
+        std::fs::write(&todo, "Fixture task due:2025-03-01\n").unwrap();
+
+        // The config's own backends are ignored
+        let mut config = crate::config::Config::default();
+        let mut table = toml::Table::new();
+        table.insert("enabled".into(), true.into());
+        table.insert("vault_path".into(), "/nonexistent".into());
+        config.backends.obsidian = Some(table);
+
+        let spec = format!(" local={} ,", todo.display());
+        let config = config.with_backends_spec(Some(spec)).unwrap();
+        let manager = BackendManager::from_config(&config).unwrap();
+        assert_eq!(manager.len(), 1);
+        assert_eq!(manager.default_source(), Some(BackendSource::LocalFile));
+
+        let tasks = manager.all_tasks(&TaskFilter::default()).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "Fixture task");
+        manager.complete_task(&tasks[0].id, None).await.unwrap();
+        assert!(std::fs::read_to_string(&todo).unwrap().starts_with("x "));
+
+        for bad in ["local", "nope=/tmp/x"] {
+            let err = config.backends.with_spec(bad).err().unwrap();
+            assert!(err.to_string().contains("TASUKI_BACKENDS"), "{}", err);
+        }
+    }
+}
@@ -1,43 +1,115 @@
 use async_trait::async_trait;
-use chrono::{Local, NaiveDate};
+use chrono::NaiveDate;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Once, OnceLock};
 use walkdir::WalkDir;
 
-mod parser;
-use crate::backends::TaskBackend;
+pub(crate) mod parser;
+use crate::backends::retry::{self, RetryPolicy};
+use crate::backends::{
+    cap_line, open_bounded, truncate_title, BackendCapabilities, FileLines, TaskBackend,
+    DEFAULT_MAX_FILE_SIZE_MB,
+};
 use crate::error::{Result, TasukiError};
+use crate::paths;
 use crate::model::{
-    BackendSource, NewTask, Priority, Task, TaskFilter, TaskId, TaskStatus, TaskUpdate,
+    dedupe_tags, BackendSource, BangPriorities, NewTask, Priority, Task, TaskFilter, TaskId,
+    TaskStatus, TaskUpdate,
 };
 
+/// What to put in `Task::project` for tasks from a note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectFrom {
+    /// No project
+    None,
+    /// The note's file name, without `.md`
+    Filename,
+    /// The note's first `# ` heading
+    H1,
+}
+
+/// What completing a task does to its line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnComplete {
+    /// Tick the checkbox where it is
+    InPlace,
+    /// Move it under the note's `done_heading`
+    MoveToDoneSection,
+    /// Move it to the end of `archive_file`
+    MoveToArchive,
+}
+
+/// Settings for the Obsidian backend, `[backends.obsidian]`.
 pub struct ObsidianConfig {
+    /// Root of the vault
     pub vault_path: PathBuf,
+    /// Allow-listed folders, normalized to `/`-separated relative paths
     pub folders: Option<Vec<String>>,
+    /// Match `folders` ignoring case, for case-insensitive filesystems
+    pub folders_ignore_case: bool,
+    /// Folders never scanned, matched by name anywhere in the vault
     pub ignore_folders: Vec<String>,
+    /// Note new tasks are added to, relative to the vault
     pub inbox_file: String,
+    /// What the inbox starts with when tasuki creates it: a template file
+    /// (relative to the vault, or absolute) or else the text itself, with
+    /// `{{date}}` and `{{title}}` filled in. `None` starts it empty
+    pub inbox_template: Option<String>,
+    /// Folder of daily notes, relative to the vault
     pub daily_notes_folder: Option<String>,
+    /// chrono format of daily note file names, without `.md`
     pub daily_notes_format: String,
+    /// Only scan daily notes from the last this many days
     pub daily_notes_lookback: Option<usize>,
+    /// See `ProjectFrom`
+    pub project_from: ProjectFrom,
+    /// Keep checkboxes with only tags (`- [ ] #waiting`), titled by their tags
+    pub keep_tag_only: bool,
+    /// Ignore checkboxes inside fenced code blocks; off for plain markdown
+    /// TODO files that keep tasks in fences
+    pub skip_code_blocks: bool,
+    /// Write `➕ YYYY-MM-DD` on tasks tasuki creates; `None` does so when
+    /// the vault already uses Obsidian Tasks metadata
+    pub add_created_date: Option<bool>,
+    /// Only checkboxes containing this (e.g. `#task`) are tasks, like the
+    /// Tasks plugin's global filter; kept out of titles and tags, and added
+    /// to tasks tasuki creates
+    pub global_filter: Option<String>,
+    /// Never write to the vault: the backend reports no write capabilities
+    /// and every change fails
+    pub read_only: bool,
+    /// See `OnComplete`
+    pub on_complete: OnComplete,
+    /// Heading text of the section `move_to_done_section` moves tasks to
+    pub done_heading: String,
+    /// Note `move_to_archive` moves tasks to, relative to the vault
+    pub archive_file: String,
 }
 
 impl ObsidianConfig {
+    /// Read `[backends.obsidian]`; `vault_path` is required.
     pub fn from_table(table: &toml::Table) -> Result<Self> {
         let vault_path = table
             .get("vault_path")
             .and_then(|v| v.as_str())
-            .map(|s| shellexpand::tilde(s).into_owned())
-            .map(PathBuf::from)
-            .ok_or_else(|| TasukiError::Config("obsidian.vault_path is required".into()))?;
+            .ok_or_else(|| TasukiError::Config("obsidian.vault_path is required".into()))
+            .and_then(paths::expand_path)?;
 
         let folders = table.get("folders").and_then(|v| {
             v.as_array().map(|arr| {
                 arr.iter()
-                    .filter_map(|v| v.as_str().map(String::from))
+                    .filter_map(|v| v.as_str().map(normalize_folder))
                     .collect()
             })
         });
 
+        let folders_ignore_case = table
+            .get("folders_ignore_case")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         let ignore_folders = table
             .get("ignore_folders")
             .and_then(|v| {
@@ -61,6 +133,11 @@ impl ObsidianConfig {
             .unwrap_or("Inbox.md")
             .to_string();
 
+        let inbox_template = table
+            .get("inbox_template")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
         let daily_notes_folder = table
             .get("daily_notes_folder")
             .and_then(|v| v.as_str())
@@ -77,22 +154,177 @@ impl ObsidianConfig {
             .and_then(|v| v.as_integer())
             .map(|v| v as usize);
 
+        let project_from = match table.get("project_from").and_then(|v| v.as_str()) {
+            None | Some("none") => ProjectFrom::None,
+            Some("filename") => ProjectFrom::Filename,
+            Some("h1") => ProjectFrom::H1,
+            Some(other) => {
+                return Err(TasukiError::Config(format!(
+                    "obsidian.project_from must be \"filename\", \"h1\" or \"none\", got \"{}\"",
+                    other
+                )))
+            }
+        };
+
+        let keep_tag_only = table
+            .get("keep_tag_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let skip_code_blocks = table
+            .get("skip_code_blocks")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let add_created_date = table.get("add_created_date").and_then(|v| v.as_bool());
+
+        // Unset means whatever the Tasks plugin uses; "" turns it off
+        let global_filter = match table.get("global_filter").and_then(|v| v.as_str()) {
+            Some(filter) => Some(filter.trim().to_string()),
+            None => tasks_plugin_global_filter(&vault_path),
+        }
+        .filter(|f| !f.is_empty());
+
+        let read_only = table
+            .get("read_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let on_complete = match table.get("on_complete").and_then(|v| v.as_str()) {
+            None | Some("in_place") => OnComplete::InPlace,
+            Some("move_to_done_section") => OnComplete::MoveToDoneSection,
+            Some("move_to_archive") => OnComplete::MoveToArchive,
+            Some(other) => {
+                return Err(TasukiError::Config(format!(
+                    "obsidian.on_complete must be \"in_place\", \"move_to_done_section\" or \"move_to_archive\", got \"{}\"",
+                    other
+                )))
+            }
+        };
+
+        let done_heading = table
+            .get("done_heading")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().trim_start_matches('#').trim())
+            .filter(|s| !s.is_empty())
+            .unwrap_or("Done")
+            .to_string();
+
+        let archive_file = table
+            .get("archive_file")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Archive.md")
+            .to_string();
+
         Ok(Self {
             vault_path,
             folders,
+            folders_ignore_case,
             ignore_folders,
             inbox_file,
+            inbox_template,
             daily_notes_folder,
             daily_notes_format,
             daily_notes_lookback,
+            project_from,
+            keep_tag_only,
+            skip_code_blocks,
+            add_created_date,
+            global_filter,
+            read_only,
+            on_complete,
+            done_heading,
+            archive_file,
+        })
+    }
+
+    /// Whether a checkbox with `tags` passes `global_filter`. A tag filter
+    /// must be one of the tags (not `#tasks` for `#task`), other text just
+    /// has to appear on the line (`line_has_filter`, see `filter_text`).
+    fn passes_global_filter(&self, line_has_filter: bool, tags: &[String]) -> bool {
+        match self.global_filter.as_deref() {
+            None => true,
+            Some(filter) => match filter.strip_prefix('#') {
+                Some(tag) => tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+                None => line_has_filter,
+            },
+        }
+    }
+
+    /// `global_filter` when it's text to find on the line rather than a tag.
+    fn filter_text(&self) -> Option<&str> {
+        self.global_filter
+            .as_deref()
+            .filter(|f| !f.starts_with('#'))
+    }
+
+    /// `title` and `tags` without `global_filter`, which only marks the
+    /// line as a task.
+    fn strip_global_filter(&self, title: &mut String, tags: &mut Vec<String>) {
+        let Some(filter) = self.global_filter.as_deref() else {
+            return;
+        };
+        match filter.strip_prefix('#') {
+            Some(tag) => tags.retain(|t| !t.eq_ignore_ascii_case(tag)),
+            None if title.contains(filter) => {
+                *title = title.replace(filter, " ").split_whitespace().collect::<Vec<_>>().join(" ");
+            }
+            None => {}
+        }
+    }
+
+    /// Whether `rel_path` (relative to the vault) is inside an allow-listed
+    /// folder, or there is no allow-list. Matches whole path components, so
+    /// `Work` doesn't take in `Workouts/`.
+    pub fn in_allowed_folder(&self, rel_path: &Path) -> bool {
+        match self.folders {
+            Some(ref folders) => folders.iter().any(|f| self.folder_contains(f, rel_path)),
+            None => true,
+        }
+    }
+
+    fn folder_contains(&self, folder: &str, rel_path: &Path) -> bool {
+        let mut components = rel_path.components().map(|c| c.as_os_str().to_string_lossy());
+        folder.split('/').all(|part| {
+            components.next().is_some_and(|component| {
+                if self.folders_ignore_case {
+                    part.to_lowercase() == component.to_lowercase()
+                } else {
+                    part == component
+                }
+            })
         })
     }
 
-    pub fn is_obsidian_vault(&self) -> bool {
+    /// Allow-listed folders with no matching directory in the vault.
+    pub fn missing_folders(&self) -> Vec<&str> {
+        let Some(ref folders) = self.folders else {
+            return Vec::new();
+        };
+        folders
+            .iter()
+            .filter(|folder| {
+                let depth = folder.split('/').count();
+                !WalkDir::new(&self.vault_path)
+                    .min_depth(depth)
+                    .max_depth(depth)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_dir())
+                    .any(|e| {
+                        let rel = e.path().strip_prefix(&self.vault_path).unwrap_or(e.path());
+                        self.folder_contains(folder, rel)
+                    })
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub(crate) fn is_obsidian_vault(&self) -> bool {
         self.vault_path.join(".obsidian").exists()
     }
 
-    pub fn obsidian_app_installed() -> bool {
+    pub(crate) fn obsidian_app_installed() -> bool {
         if std::process::Command::new("which")
             .arg("obsidian")
             .output()
@@ -103,7 +335,7 @@ impl ObsidianConfig {
         }
 
         let desktop_dirs = [
-            dirs::home_dir().map(|h| h.join(".local/share/applications")),
+            paths::home_dir().map(|h| h.join(".local/share/applications")),
             Some(PathBuf::from("/usr/share/applications")),
         ];
 
@@ -116,7 +348,7 @@ impl ObsidianConfig {
         false
     }
 
-    pub fn vault_name(&self) -> String {
+    pub(crate) fn vault_name(&self) -> String {
         self.vault_path
             .file_name()
             .map(|n| n.to_string_lossy().into_owned())
@@ -124,13 +356,63 @@ impl ObsidianConfig {
     }
 }
 
+/// `globalFilter` from the Tasks plugin's settings in `vault_path`, if set.
+fn tasks_plugin_global_filter(vault_path: &Path) -> Option<String> {
+    let settings = vault_path.join(".obsidian/plugins/obsidian-tasks-plugin/data.json");
+    let content = fs::read_to_string(settings).ok()?;
+    let settings: serde_json::Value = serde_json::from_str(&content).ok()?;
+    settings["globalFilter"].as_str().map(|f| f.trim().to_string())
+}
+
+/// One note's checkboxes, read by `ObsidianBackend::read_file_tasks`.
+struct ParsedFile {
+    tasks: Vec<(usize, parser::ParsedTask)>,
+    h1: Option<String>,
+    with_filter: HashSet<usize>,
+}
+
+/// Tasks as `- [ ]` checkboxes across the notes of a vault.
 pub struct ObsidianBackend {
     config: ObsidianConfig,
+    max_file_bytes: u64,
+    retry: RetryPolicy,
+    bangs: BangPriorities,
+    /// `adds_created_date`, worked out on the first create
+    adds_created_date: OnceLock<bool>,
+    /// Set once `missing_folders` has been warned about, on the first scan
+    checked_folders: Once,
 }
 
 impl ObsidianBackend {
+    /// A backend over the vault in `config`.
     pub fn new(config: ObsidianConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            max_file_bytes: DEFAULT_MAX_FILE_SIZE_MB * 1024 * 1024,
+            retry: RetryPolicy::default(),
+            bangs: BangPriorities::default(),
+            adds_created_date: OnceLock::new(),
+            checked_folders: Once::new(),
+        }
+    }
+
+    /// Skip notes larger than this when scanning; see `open_bounded`.
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    /// How reads and writes of notes retry transient errors, e.g. from a
+    /// vault on a network mount.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Priorities meant by `!`, `!!` and `!!!` before a task's title.
+    pub fn with_bangs(mut self, bangs: BangPriorities) -> Self {
+        self.bangs = bangs;
+        self
     }
 
     fn is_daily_note(&self, path: &Path) -> Option<NaiveDate> {
@@ -148,7 +430,7 @@ impl ObsidianBackend {
     fn within_lookback(&self, note_date: NaiveDate) -> bool {
         match self.config.daily_notes_lookback {
             Some(days) => {
-                let cutoff = Local::now().date_naive() - chrono::Duration::days(days as i64);
+                let cutoff = crate::clock::today() - chrono::Duration::days(days as i64);
                 note_date >= cutoff
             }
             None => true,
@@ -156,6 +438,11 @@ impl ObsidianBackend {
     }
 
     fn markdown_files(&self) -> Vec<PathBuf> {
+        self.checked_folders.call_once(|| {
+            for folder in self.config.missing_folders() {
+                tracing::warn!("obsidian.folders: \"{}\" not found in the vault", folder);
+            }
+        });
         let mut files = Vec::new();
 
         let walker = WalkDir::new(&self.config.vault_path)
@@ -182,18 +469,11 @@ impl ObsidianBackend {
                 continue;
             }
 
-            if let Some(ref folders) = self.config.folders {
-                let rel_path = path
-                    .strip_prefix(&self.config.vault_path)
-                    .unwrap_or(path);
-
-                let in_allowed_folder = folders.iter().any(|folder| {
-                    rel_path.starts_with(folder)
-                });
-
-                if !in_allowed_folder {
-                    continue;
-                }
+            let rel_path = path
+                .strip_prefix(&self.config.vault_path)
+                .unwrap_or(path);
+            if !self.config.in_allowed_folder(rel_path) {
+                continue;
             }
 
             if let Some(note_date) = self.is_daily_note(path) {
@@ -208,11 +488,112 @@ impl ObsidianBackend {
         files
     }
 
-    fn parse_file_tasks(&self, path: &Path) -> Result<Vec<Task>> {
-        let content = fs::read_to_string(path).map_err(|e| TasukiError::Backend {
-            backend: "obsidian".to_string(),
-            message: format!("Failed to read {}: {}", path.display(), e),
-        })?;
+    /// Whether new tasks get a `➕` date: as configured, else when the Tasks
+    /// plugin is installed or some task in the vault carries its emoji
+    /// metadata. The vault is only searched once per backend.
+    fn adds_created_date(&self) -> bool {
+        if let Some(add) = self.config.add_created_date {
+            return add;
+        }
+        *self.adds_created_date.get_or_init(|| {
+            let plugin = self
+                .config
+                .vault_path
+                .join(".obsidian/plugins/obsidian-tasks-plugin");
+            plugin.is_dir()
+                || self.markdown_files().iter().any(|path| {
+                    match open_bounded(path, self.max_file_bytes) {
+                        Ok(Some(reader)) => {
+                            FileLines::new(reader).any(|line| parser::has_tasks_metadata(&line))
+                        }
+                        _ => false,
+                    }
+                })
+        })
+    }
+
+    /// An error when `read_only` is set, before anything is written.
+    fn check_writable(&self) -> Result<()> {
+        if self.config.read_only {
+            return Err(TasukiError::Backend {
+                backend: "obsidian".to_string(),
+                message: "is read-only (read_only = true in [backends.obsidian])".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Text for a note tasuki is about to create at `path`: `inbox_template`
+    /// filled in, or nothing.
+    fn new_note_content(&self, path: &Path) -> String {
+        let Some(ref template) = self.config.inbox_template else {
+            return String::new();
+        };
+        // A readable file is a template file; anything else is the template
+        let file = paths::expand_path(template)
+            .ok()
+            .map(|p| self.config.vault_path.join(p))
+            .filter(|p| p.is_file());
+        let text = match file {
+            Some(file) => fs::read_to_string(&file).unwrap_or_else(|e| {
+                tracing::warn!("Could not read inbox_template {}: {}", file.display(), e);
+                String::new()
+            }),
+            None => template.clone(),
+        };
+        let title = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+        text.replace("{{date}}", &crate::clock::today().format("%Y-%m-%d").to_string())
+            .replace("{{title}}", &title)
+    }
+
+    /// The checkboxes in `path` as it streams in, its first heading if
+    /// `project_from` wants it, and the lines holding the global filter's
+    /// text; `None` if it's over the size limit.
+    fn read_file_tasks(&self, path: &Path) -> std::io::Result<Option<ParsedFile>> {
+        let Some(reader) = open_bounded(path, self.max_file_bytes)? else {
+            return Ok(None);
+        };
+        let h1 = match self.config.project_from {
+            // A second pass, but it stops at the first heading
+            ProjectFrom::H1 => open_bounded(path, self.max_file_bytes)?
+                .and_then(|r| parser::first_h1(FileLines::new(r))),
+            _ => None,
+        };
+
+        let filter_text = self.config.filter_text();
+        let mut with_filter = HashSet::new();
+        let mut lines = FileLines::new(reader);
+        let parsed = parser::parse_file(
+            (&mut lines).enumerate().map(|(i, line)| {
+                if filter_text.is_some_and(|f| line.contains(f)) {
+                    with_filter.insert(i + 1);
+                }
+                line
+            }),
+            self.config.keep_tag_only,
+            self.config.skip_code_blocks,
+            &self.bangs,
+        );
+        lines.finish()?;
+        Ok(Some(ParsedFile {
+            tasks: parsed,
+            h1,
+            with_filter,
+        }))
+    }
+
+    async fn parse_file_tasks(&self, path: &Path) -> Result<Vec<Task>> {
+        // A retry reads the file again from the start
+        let what = format!("Reading {}", path.display());
+        let file = retry::retry(self.retry, &what, || self.read_file_tasks(path))
+            .await
+            .map_err(|e| TasukiError::Backend {
+                backend: "obsidian".to_string(),
+                message: format!("Failed to read {}: {}", path.display(), e),
+            })?;
+        let Some(file) = file else {
+            return Ok(Vec::new());
+        };
 
         let rel_path = path
             .strip_prefix(&self.config.vault_path)
@@ -221,19 +602,41 @@ impl ObsidianBackend {
             .into_owned();
 
         let daily_note_date = self.is_daily_note(path);
-        let parsed = parser::parse_file(&content);
+        let project = match self.config.project_from {
+            ProjectFrom::None => None,
+            ProjectFrom::Filename => path.file_stem().map(|s| s.to_string_lossy().into_owned()),
+            ProjectFrom::H1 => file.h1,
+        };
 
-        let tasks = parsed
+        let tasks = file
+            .tasks
             .into_iter()
+            .filter(|(line_num, parsed)| {
+                self.config
+                    .passes_global_filter(file.with_filter.contains(line_num), &parsed.tags)
+            })
+            .map(|(line_num, mut parsed)| {
+                self.config.strip_global_filter(&mut parsed.title, &mut parsed.tags);
+                (line_num, parsed)
+            })
+            // Nothing left once the filter is gone, e.g. `- [ ] #task`
+            .filter(|(_, parsed)| !parsed.title.is_empty() || !parsed.tags.is_empty())
             .map(|(line_num, parsed)| {
                 let created_at = parsed
                     .created_at
                     .or(daily_note_date)
                     .map(|d| d.and_hms_opt(0, 0, 0).unwrap());
 
+                // Tag-only tasks (see `keep_tag_only`) are titled by their tags
+                let title = if parsed.title.is_empty() {
+                    parsed.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" ")
+                } else {
+                    truncate_title(parsed.title)
+                };
+
                 Task {
                     id: format!("obsidian:{}:{}", rel_path, line_num),
-                    title: parsed.title,
+                    title,
                     status: parsed.status,
                     priority: parsed.priority,
                     due: parsed.due,
@@ -246,6 +649,7 @@ impl ObsidianBackend {
                         .completed_at
                         .map(|d| d.and_hms_opt(0, 0, 0).unwrap()),
                     heading_context: parsed.heading_context,
+                    project: project.clone(),
                 }
             })
             .collect();
@@ -254,14 +658,16 @@ impl ObsidianBackend {
     }
     
     // Use 1-indexing for lines
-    fn modify_line<F>(&self, path: &str, line_num: usize, modify: F) -> Result<()>
+    async fn modify_line<F>(&self, path: &str, line_num: usize, modify: F) -> Result<()>
     where
-        F: FnOnce(&str) -> String,
+        F: FnOnce(&str) -> Result<String>,
     {
-        let content = fs::read_to_string(path).map_err(|e| TasukiError::Backend {
-            backend: "obsidian".to_string(),
-            message: format!("Failed to read {}: {}", path, e),
-        })?;
+        let content = retry::read_to_string(self.retry, Path::new(path))
+            .await
+            .map_err(|e| TasukiError::Backend {
+                backend: "obsidian".to_string(),
+                message: format!("Failed to read {}: {}", path, e),
+            })?;
 
         let mut lines: Vec<String> = content.lines().map(String::from).collect();
 
@@ -281,7 +687,7 @@ impl ObsidianBackend {
             });
         }
 
-        lines[idx] = modify(&lines[idx]);
+        lines[idx] = modify(&lines[idx])?;
 
         // Preserve trailing newline if original had one
         let mut output = lines.join("\n");
@@ -289,16 +695,136 @@ impl ObsidianBackend {
             output.push('\n');
         }
 
-        fs::write(path, output).map_err(|e| TasukiError::Backend {
-            backend: "obsidian".to_string(),
-            message: format!("Failed to write {}: {}", path, e),
-        })?;
+        retry::write(self.retry, Path::new(path), output)
+            .await
+            .map_err(|e| TasukiError::Backend {
+                backend: "obsidian".to_string(),
+                message: format!("Failed to write {}: {}", path, e),
+            })?;
 
         Ok(())
     }
 
+    /// A note's lines, and whether it ends in a newline (an empty note
+    /// counts, so what's added to it does).
+    async fn read_note(&self, path: &Path) -> Result<(Vec<String>, bool)> {
+        let content =
+            retry::read_to_string(self.retry, path)
+                .await
+                .map_err(|e| TasukiError::Backend {
+                    backend: "obsidian".to_string(),
+                    message: format!("Failed to read {}: {}", path.display(), e),
+                })?;
+        let trailing_newline = content.is_empty() || content.ends_with('\n');
+        Ok((content.lines().map(String::from).collect(), trailing_newline))
+    }
+
+    async fn write_note(
+        &self,
+        path: &Path,
+        lines: &[String],
+        trailing_newline: bool,
+    ) -> Result<()> {
+        let mut output = lines.join("\n");
+        if trailing_newline && !output.is_empty() {
+            output.push('\n');
+        }
+        retry::write(self.retry, path, output)
+            .await
+            .map_err(|e| TasukiError::Backend {
+                backend: "obsidian".to_string(),
+                message: format!("Failed to write {}: {}", path.display(), e),
+            })
+    }
+
+    /// Refuse with `NotFound` if `title` is given and `line` (number
+    /// `line_num`) no longer holds that task, titled as `fetch_tasks` would.
+    fn check_title(&self, line: &str, line_num: usize, title: Option<&str>) -> Result<()> {
+        let Some(expected) = title else {
+            return Ok(());
+        };
+        let line = cap_line(line.to_string());
+        let found = parser::parse_checkbox_line_with(&line, true, &self.bangs).map(|mut parsed| {
+            self.config
+                .strip_global_filter(&mut parsed.title, &mut parsed.tags);
+            if parsed.title.is_empty() {
+                parsed
+                    .tags
+                    .iter()
+                    .map(|t| format!("#{}", t))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            } else {
+                truncate_title(parsed.title)
+            }
+        });
+        if found.as_deref() == Some(expected) {
+            Ok(())
+        } else {
+            Err(TasukiError::NotFound(format!(
+                "line {} no longer holds '{}'",
+                line_num, expected
+            )))
+        }
+    }
+
+    /// Complete the task on `line_num` of `rel_path` by moving it, with its
+    /// child bullets, to the done section or the archive. The moved line
+    /// notes where it came from, for `uncomplete_task`. Returns its new ID.
+    async fn complete_by_moving(
+        &self,
+        rel_path: &str,
+        line_num: usize,
+        title: Option<&str>,
+    ) -> Result<TaskId> {
+        let abs_path = self.resolve_path(rel_path);
+        let (mut lines, trailing_newline) = self.read_note(&abs_path).await?;
+        let range = task_block(&lines, line_num)?;
+        self.check_title(&lines[range.start], line_num, title)?;
+
+        let (first, _) = parser::Origin::split(&lines[range.start]);
+        let origin = parser::Origin {
+            path: rel_path.to_string(),
+            line: line_num,
+        };
+        let first = format!("{} {}", mark_done(&first, line_num)?, origin.comment());
+        let mut block: Vec<String> = lines.drain(range).collect();
+        block[0] = first;
+
+        let archive_path = self.resolve_path(&self.config.archive_file);
+        if self.config.on_complete == OnComplete::MoveToDoneSection || archive_path == abs_path {
+            let at = match self.config.on_complete {
+                OnComplete::MoveToDoneSection => done_section_end(&mut lines, &self.config.done_heading),
+                _ => lines.len(),
+            };
+            lines.splice(at..at, block);
+            self.write_note(&abs_path, &lines, trailing_newline).await?;
+            return Ok(format!("obsidian:{}:{}", rel_path, at + 1));
+        }
+
+        let (mut archive, archive_newline) = if archive_path.exists() {
+            self.read_note(&archive_path).await?
+        } else {
+            if let Some(parent) = archive_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| TasukiError::Backend {
+                    backend: "obsidian".to_string(),
+                    message: format!("Failed to create archive folder: {}", e),
+                })?;
+            }
+            (Vec::new(), true)
+        };
+        let at = archive.len();
+        archive.extend(block);
+        // The archive first: if the note can't be written after, the task
+        // is in both rather than neither
+        self.write_note(&archive_path, &archive, archive_newline)
+            .await?;
+        self.write_note(&abs_path, &lines, trailing_newline).await?;
+        Ok(format!("obsidian:{}:{}", self.config.archive_file, at + 1))
+    }
+
     // ID format: obsidian:{relative_path}:{line_number}
-    fn parse_task_id(id: &TaskId) -> Result<(String, usize)> {
+    pub(crate) fn parse_task_id(id: &TaskId) -> Result<(String, usize)> {
         let rest = id.strip_prefix("obsidian:").ok_or_else(|| {
             TasukiError::Parse(format!("Invalid Obsidian task ID: {}", id))
         })?;
@@ -319,11 +845,11 @@ impl ObsidianBackend {
         self.config.vault_path.join(rel_path)
     }
 
+    /// Command to open `task` in the Obsidian app, if the vault is one and
+    /// the app is installed. Callers fall back to the configured editor.
     pub fn open_command(&self, task: &Task) -> Option<Vec<String>> {
         let source_path = task.source_path.as_ref()?;
-        let line_num = task.source_line.unwrap_or(1);
 
-        // Try Obsidian app first
         if self.config.is_obsidian_vault() && ObsidianConfig::obsidian_app_installed() {
             let rel_path = Path::new(source_path)
                 .strip_prefix(&self.config.vault_path)
@@ -342,19 +868,97 @@ impl ObsidianBackend {
             return Some(vec!["xdg-open".to_string(), uri]);
         }
 
-        // Fall back to $EDITOR
-        if let Ok(editor) = std::env::var("EDITOR") {
-            return Some(vec![
-                editor,
-                format!("+{}", line_num),
-                source_path.clone(),
-            ]);
-        }
-
         None
     }
 }
 
+/// A configured folder as a relative `/`-separated path: backslashes become
+/// `/`, and leading `./` and surrounding separators are dropped.
+fn normalize_folder(raw: &str) -> String {
+    raw.replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The line a task ID points at no longer holds a task (the note was
+/// edited since it was read).
+fn not_a_checkbox(line_num: usize) -> TasukiError {
+    TasukiError::Backend {
+        backend: "obsidian".to_string(),
+        message: format!("Line {} is not a checkbox", line_num),
+    }
+}
+
+/// `line` ticked, with today's done date as Obsidian Tasks records it, so
+/// completions count towards the daily goal.
+fn mark_done(line: &str, line_num: usize) -> Result<String> {
+    let line = parser::set_checkbox_status(line, TaskStatus::Done)
+        .ok_or_else(|| not_a_checkbox(line_num))?;
+    if line.contains('✅') {
+        Ok(line)
+    } else {
+        Ok(format!("{} ✅ {}", line, crate::clock::today().format("%Y-%m-%d")))
+    }
+}
+
+/// Indexes in `lines` of the task on `line_num` and its child bullets.
+fn task_block(lines: &[String], line_num: usize) -> Result<std::ops::Range<usize>> {
+    let idx = line_num
+        .checked_sub(1)
+        .filter(|idx| *idx < lines.len())
+        .ok_or_else(|| TasukiError::Backend {
+            backend: "obsidian".to_string(),
+            message: format!("Line {} out of range", line_num),
+        })?;
+    let children = parser::child_lines(&lines.join("\n"), line_num).len();
+    Ok(idx..idx + 1 + children)
+}
+
+/// Where a task joins the `heading` section of `lines`: after the last
+/// non-blank line before the next heading of the same or a higher level.
+/// A note without the section gets it added at the end.
+fn done_section_end(lines: &mut Vec<String>, heading: &str) -> usize {
+    let level = |line: &str| {
+        let hashes = line.chars().take_while(|c| *c == '#').count();
+        ((1..=6).contains(&hashes) && line[hashes..].starts_with(' ')).then_some(hashes)
+    };
+    let found = lines.iter().position(|line| {
+        level(line).is_some() && line.trim_start_matches('#').trim().eq_ignore_ascii_case(heading)
+    });
+
+    let Some(at) = found else {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(format!("## {}", heading));
+        return lines.len();
+    };
+    let section_level = level(&lines[at]).unwrap_or(1);
+    let mut end = lines[at + 1..]
+        .iter()
+        .position(|line| level(line).is_some_and(|l| l <= section_level))
+        .map_or(lines.len(), |i| at + 1 + i);
+    while end > at + 1 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    end
+}
+
+/// `line` without its Obsidian Tasks done date (`✅ 2025-03-01`).
+fn strip_done_date(line: &str) -> String {
+    let Some(pos) = line.find('✅') else {
+        return line.to_string();
+    };
+    let after = line[pos + '✅'.len_utf8()..].trim_start();
+    let date_len = after
+        .get(..10)
+        .filter(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok())
+        .map_or(0, str::len);
+    format!("{}{}", line[..pos].trim_end(), &after[date_len..])
+}
+
 fn urlencoding_simple(s: &str) -> String {
     s.replace(' ', "%20")
         .replace('/', "%2F")
@@ -364,19 +968,51 @@ fn urlencoding_simple(s: &str) -> String {
 #[async_trait]
 impl TaskBackend for ObsidianBackend {
     fn name(&self) -> &str {
-        "Obsidian"
+        "obsidian"
     }
 
     fn source(&self) -> BackendSource {
         BackendSource::Obsidian
     }
 
+    fn capabilities(&self) -> BackendCapabilities {
+        let writable = !self.config.read_only;
+        BackendCapabilities {
+            can_create: writable,
+            can_update: writable,
+            can_delete: writable,
+            supports_priority: true,
+            supports_due_time: false,
+            supports_description: false,
+            supports_recurrence: false,
+        }
+    }
+
     async fn fetch_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
+        // An unmounted drive would otherwise look like an empty vault
+        let vault = &self.config.vault_path;
+        let what = format!("Reading {}", vault.display());
+        match retry::retry(self.retry, &what, || fs::metadata(vault)).await {
+            Ok(meta) if meta.is_dir() => {}
+            Err(e) if retry::is_transient(&e) => {
+                return Err(TasukiError::Unavailable {
+                    backend: "obsidian".to_string(),
+                    source: e,
+                });
+            }
+            _ => {
+                return Err(TasukiError::Backend {
+                    backend: "obsidian".to_string(),
+                    message: format!("Obsidian vault not found at {}", vault.display()),
+                });
+            }
+        }
+
         let files = self.markdown_files();
         let mut all_tasks = Vec::new();
 
         for file in files {
-            match self.parse_file_tasks(&file) {
+            match self.parse_file_tasks(&file).await {
                 Ok(tasks) => all_tasks.extend(tasks),
                 Err(e) => {
                     tracing::warn!("Failed to parse {}: {}", file.display(), e);
@@ -384,52 +1020,23 @@ impl TaskBackend for ObsidianBackend {
             }
         }
 
-        let filtered: Vec<Task> = all_tasks
-            .into_iter()
-            .filter(|task| {
-                if let Some(ref status) = filter.status {
-                    if task.status != *status {
-                        return false;
-                    }
-                }
-                if let Some(ref due_before) = filter.due_before {
-                    match task.due {
-                        Some(d) if d > *due_before => return false,
-                        _ => {}
-                    }
-                }
-                if let Some(ref due_after) = filter.due_after {
-                    match task.due {
-                        Some(d) if d < *due_after => return false,
-                        None => return false,
-                        _ => {}
-                    }
-                }
-                if let Some(ref search) = filter.search {
-                    let search_lower = search.to_lowercase();
-                    if !task.title.to_lowercase().contains(&search_lower) {
-                        return false;
-                    }
-                }
-                if let Some(has_due) = filter.has_due {
-                    if has_due && task.due.is_none() {
-                        return false;
-                    }
-                    if !has_due && task.due.is_some() {
-                        return false;
-                    }
-                }
-                true
-            })
-            .collect();
-
-        Ok(filtered)
+        filter.retain(&mut all_tasks)?;
+        Ok(all_tasks)
     }
 
     async fn create_task(&self, task: &NewTask) -> Result<Task> {
+        self.check_writable()?;
         let inbox_path = self.config.vault_path.join(&self.config.inbox_file);
 
-        let mut line = format!("- [ ] {}", task.title);
+        let mut title = task.title.clone();
+        let mut tags = dedupe_tags(task.tags.clone());
+        self.config.strip_global_filter(&mut title, &mut tags);
+
+        // The global filter first, where the Tasks plugin puts it
+        let mut line = match self.config.global_filter {
+            Some(ref filter) => format!("- [ ] {} {}", filter, title),
+            None => format!("- [ ] {}", title),
+        };
 
         // Priority
         match task.priority {
@@ -439,27 +1046,45 @@ impl TaskBackend for ObsidianBackend {
             Priority::None => {}
         }
 
+        // Created date, before the due date as Obsidian Tasks orders them
+        let created = self.adds_created_date().then(crate::clock::today);
+        if let Some(created) = created {
+            line.push_str(&format!(" ➕ {}", created.format("%Y-%m-%d")));
+        }
+
         // Due date
         if let Some(due) = task.due {
             line.push_str(&format!(" 📅 {}", due.format("%Y-%m-%d")));
         }
 
         // Tags
-        for tag in &task.tags {
+        for tag in &tags {
             line.push_str(&format!(" #{}", tag));
         }
 
         if !inbox_path.exists() {
-            fs::write(&inbox_path, "").map_err(|e| TasukiError::Backend {
-                backend: "obsidian".to_string(),
-                message: format!("Failed to create inbox file: {}", e),
-            })?;
+            // e.g. `inbox_file = "00 Inbox/Tasks.md"` before the folder exists
+            if let Some(parent) = inbox_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| TasukiError::Backend {
+                    backend: "obsidian".to_string(),
+                    message: format!("Failed to create inbox folder: {}", e),
+                })?;
+            }
+            let initial = self.new_note_content(&inbox_path);
+            retry::write(self.retry, &inbox_path, initial)
+                .await
+                .map_err(|e| TasukiError::Backend {
+                    backend: "obsidian".to_string(),
+                    message: format!("Failed to create inbox file: {}", e),
+                })?;
         }
 
-        let mut content = fs::read_to_string(&inbox_path).map_err(|e| TasukiError::Backend {
-            backend: "obsidian".to_string(),
-            message: format!("Failed to read inbox file: {}", e),
-        })?;
+        let mut content = retry::read_to_string(self.retry, &inbox_path)
+            .await
+            .map_err(|e| TasukiError::Backend {
+                backend: "obsidian".to_string(),
+                message: format!("Failed to read inbox file: {}", e),
+            })?;
 
         if !content.is_empty() && !content.ends_with('\n') {
             content.push('\n');
@@ -469,36 +1094,41 @@ impl TaskBackend for ObsidianBackend {
 
         let line_count = content.lines().count();
 
-        fs::write(&inbox_path, &content).map_err(|e| TasukiError::Backend {
-            backend: "obsidian".to_string(),
-            message: format!("Failed to write inbox file: {}", e),
-        })?;
+        retry::write(self.retry, &inbox_path, &content)
+            .await
+            .map_err(|e| TasukiError::Backend {
+                backend: "obsidian".to_string(),
+                message: format!("Failed to write inbox file: {}", e),
+            })?;
 
         let rel_path = self.config.inbox_file.clone();
 
         Ok(Task {
             id: format!("obsidian:{}:{}", rel_path, line_count),
-            title: task.title.clone(),
+            title,
             status: TaskStatus::Pending,
             priority: task.priority,
             due: task.due,
-            tags: task.tags.clone(),
+            tags,
             source: BackendSource::Obsidian,
             source_line: Some(line_count),
             source_path: Some(inbox_path.to_string_lossy().into_owned()),
-            created_at: None,
+            created_at: created.map(|d| d.and_hms_opt(0, 0, 0).unwrap()),
             completed_at: None,
             heading_context: None,
+            project: None,
         })
     }
 
     async fn update_task(&self, id: &TaskId, update: &TaskUpdate) -> Result<Task> {
+        self.check_writable()?;
         let (rel_path, line_num) = Self::parse_task_id(id)?;
         let abs_path = self.resolve_path(&rel_path);
         let abs_path_str = abs_path.to_string_lossy().into_owned();
 
-        let content =
-            fs::read_to_string(&abs_path).map_err(|e| TasukiError::Backend {
+        let content = retry::read_to_string(self.retry, &abs_path)
+            .await
+            .map_err(|e| TasukiError::Backend {
                 backend: "obsidian".to_string(),
                 message: format!("Failed to read {}: {}", abs_path.display(), e),
             })?;
@@ -516,28 +1146,32 @@ impl TaskBackend for ObsidianBackend {
             });
         }
 
-        let current = parser::parse_checkbox_line(lines[idx]).ok_or_else(|| {
-            TasukiError::Backend {
-                backend: "obsidian".to_string(),
-                message: format!("Line {} is not a checkbox", line_num),
-            }
-        })?;
+        let current = parser::parse_checkbox_line_with(lines[idx], true, &self.bangs)
+            .ok_or_else(|| not_a_checkbox(line_num))?;
 
-        let title = update.title.clone().unwrap_or(current.title);
-        let status = update.status.clone().unwrap_or(current.status);
+        let mut title = update.title.clone().unwrap_or(current.title);
+        let status = update.status.unwrap_or(current.status);
         let priority = update.priority.unwrap_or(current.priority);
         let due = match &update.due {
             Some(d) => *d,
             None => current.due,
         };
-        let tags = update.tags.clone().unwrap_or(current.tags);
+        let mut tags = dedupe_tags(update.tags.clone().unwrap_or(current.tags));
+        // Written once, up front, wherever it was before
+        self.config.strip_global_filter(&mut title, &mut tags);
 
         let checkbox = match status {
             TaskStatus::Pending => "- [ ]",
             TaskStatus::Done => "- [x]",
         };
 
-        let mut new_line = format!("{} {}", checkbox, title);
+        let mut new_line = format!("{}{}", current.prefix, checkbox);
+        if let Some(ref filter) = self.config.global_filter {
+            new_line.push_str(&format!(" {}", filter));
+        }
+        if !title.is_empty() {
+            new_line.push_str(&format!(" {}", title));
+        }
 
         match priority {
             Priority::High => new_line.push_str(" ⏫"),
@@ -546,6 +1180,10 @@ impl TaskBackend for ObsidianBackend {
             Priority::None => {}
         }
 
+        if let Some(created) = current.created_at {
+            new_line.push_str(&format!(" ➕ {}", created.format("%Y-%m-%d")));
+        }
+
         if let Some(due) = due {
             new_line.push_str(&format!(" 📅 {}", due.format("%Y-%m-%d")));
         }
@@ -554,55 +1192,116 @@ impl TaskBackend for ObsidianBackend {
             new_line.push_str(&format!(" #{}", tag));
         }
 
-        self.modify_line(&abs_path_str, line_num, |_| new_line.clone())?;
+        if status == TaskStatus::Done {
+            let done = current.completed_at.unwrap_or_else(crate::clock::today);
+            new_line.push_str(&format!(" ✅ {}", done.format("%Y-%m-%d")));
+        }
+
+        // Still knows where to go back to when reopened
+        if let (_, Some(origin)) = parser::Origin::split(lines[idx]) {
+            new_line.push_str(&format!(" {}", origin.comment()));
+        }
+
+        self.modify_line(&abs_path_str, line_num, |_| Ok(new_line.clone()))
+            .await?;
+
+        // Completing or reopening here moves the task as `complete_task` and
+        // `uncomplete_task` would, so `edit` and the server agree with them
+        let new_id = match (current.status, status) {
+            (TaskStatus::Pending, TaskStatus::Done)
+                if self.config.on_complete != OnComplete::InPlace =>
+            {
+                self.complete_by_moving(&rel_path, line_num, None).await?
+            }
+            (TaskStatus::Done, TaskStatus::Pending) => self.uncomplete_task(id, None).await?,
+            _ => id.clone(),
+        };
+        let (new_path, new_line_num) = Self::parse_task_id(&new_id)?;
 
         Ok(Task {
-            id: id.clone(),
+            id: new_id,
             title,
             status,
             priority,
             due,
             tags,
             source: BackendSource::Obsidian,
-            source_line: Some(line_num),
-            source_path: Some(abs_path_str),
-            created_at: None,
+            source_line: Some(new_line_num),
+            source_path: Some(self.resolve_path(&new_path).to_string_lossy().into_owned()),
+            created_at: current.created_at.map(|d| d.and_hms_opt(0, 0, 0).unwrap()),
             completed_at: None,
             heading_context: None,
+            project: None,
         })
     }
 
-    async fn complete_task(&self, id: &TaskId) -> Result<()> {
+    async fn complete_task(&self, id: &TaskId, title: Option<&str>) -> Result<TaskId> {
+        self.check_writable()?;
         let (rel_path, line_num) = Self::parse_task_id(id)?;
+        if self.config.on_complete != OnComplete::InPlace {
+            return self.complete_by_moving(&rel_path, line_num, title).await;
+        }
         let abs_path = self.resolve_path(&rel_path);
         let abs_path_str = abs_path.to_string_lossy().into_owned();
 
         self.modify_line(&abs_path_str, line_num, |line| {
-            line.replacen("- [ ]", "- [x]", 1)
-        })?;
+            self.check_title(line, line_num, title)?;
+            mark_done(line, line_num)
+        })
+        .await?;
 
-        Ok(())
+        Ok(id.clone())
     }
 
-    async fn uncomplete_task(&self, id: &TaskId) -> Result<()> {
+    async fn uncomplete_task(&self, id: &TaskId, title: Option<&str>) -> Result<TaskId> {
+        self.check_writable()?;
         let (rel_path, line_num) = Self::parse_task_id(id)?;
         let abs_path = self.resolve_path(&rel_path);
-        let abs_path_str = abs_path.to_string_lossy().into_owned();
-
-        self.modify_line(&abs_path_str, line_num, |line| {
-            line.replacen("- [x]", "- [ ]", 1)
-                .replacen("- [X]", "- [ ]", 1)
-        })?;
-
-        Ok(())
+        let (mut lines, trailing_newline) = self.read_note(&abs_path).await?;
+        let range = task_block(&lines, line_num)?;
+        self.check_title(&lines[range.start], line_num, title)?;
+
+        let (line, origin) = parser::Origin::split(&lines[range.start]);
+        lines[range.start] = parser::set_checkbox_status(&line, TaskStatus::Pending)
+            .map(|line| strip_done_date(&line))
+            .ok_or_else(|| not_a_checkbox(line_num))?;
+
+        // Moved when it was completed: back where it came from, if that
+        // note is still there
+        match origin {
+            Some(origin) if origin.path == rel_path => {
+                let block: Vec<String> = lines.drain(range).collect();
+                let at = (origin.line - 1).min(lines.len());
+                lines.splice(at..at, block);
+                self.write_note(&abs_path, &lines, trailing_newline).await?;
+                Ok(format!("obsidian:{}:{}", rel_path, at + 1))
+            }
+            Some(origin) if self.resolve_path(&origin.path).is_file() => {
+                let origin_path = self.resolve_path(&origin.path);
+                let (mut origin_lines, origin_newline) = self.read_note(&origin_path).await?;
+                let block: Vec<String> = lines.drain(range).collect();
+                let at = (origin.line - 1).min(origin_lines.len());
+                origin_lines.splice(at..at, block);
+                self.write_note(&origin_path, &origin_lines, origin_newline)
+                    .await?;
+                self.write_note(&abs_path, &lines, trailing_newline).await?;
+                Ok(format!("obsidian:{}:{}", origin.path, at + 1))
+            }
+            _ => {
+                self.write_note(&abs_path, &lines, trailing_newline).await?;
+                Ok(id.clone())
+            }
+        }
     }
 
     async fn delete_task(&self, id: &TaskId) -> Result<()> {
+        self.check_writable()?;
         let (rel_path, line_num) = Self::parse_task_id(id)?;
         let abs_path = self.resolve_path(&rel_path);
 
-        let content =
-            fs::read_to_string(&abs_path).map_err(|e| TasukiError::Backend {
+        let content = retry::read_to_string(self.retry, &abs_path)
+            .await
+            .map_err(|e| TasukiError::Backend {
                 backend: "obsidian".to_string(),
                 message: format!("Failed to read {}: {}", abs_path.display(), e),
             })?;
@@ -628,12 +1327,32 @@ impl TaskBackend for ObsidianBackend {
             output.push('\n');
         }
 
-        fs::write(&abs_path, output).map_err(|e| TasukiError::Backend {
+        retry::write(self.retry, &abs_path, output)
+            .await
+            .map_err(|e| TasukiError::Backend {
+                backend: "obsidian".to_string(),
+                message: format!("Failed to write {}: {}", abs_path.display(), e),
+            })?;
+
+        Ok(())
+    }
+
+    fn task_details(&self, task: &Task) -> Result<Vec<String>> {
+        let (rel_path, line_num) = Self::parse_task_id(&task.id)?;
+        let abs_path = self.resolve_path(&rel_path);
+
+        // Not retried: this is a sync call, where waiting between tries
+        // would hold up the runtime, and a failed read only empties a pane
+        let content = fs::read_to_string(&abs_path).map_err(|e| TasukiError::Backend {
             backend: "obsidian".to_string(),
-            message: format!("Failed to write {}: {}", abs_path.display(), e),
+            message: format!("Failed to read {}: {}", abs_path.display(), e),
         })?;
 
-        Ok(())
+        Ok(parser::child_lines(&content, line_num))
+    }
+
+    fn pending_subtasks(&self, task: &Task) -> Result<usize> {
+        Ok(parser::pending_checkboxes(&self.task_details(task)?))
     }
 }
 
@@ -684,15 +1403,26 @@ mod tests {
         let config = ObsidianConfig {
             vault_path,
             folders: None,
+            folders_ignore_case: false,
             ignore_folders: vec![
                 ".obsidian".to_string(),
                 ".trash".to_string(),
                 ".git".to_string(),
             ],
             inbox_file: "Inbox.md".to_string(),
+            inbox_template: None,
             daily_notes_folder: Some("Daily Notes".to_string()),
             daily_notes_format: "%Y-%m-%d".to_string(),
             daily_notes_lookback: None,
+            project_from: ProjectFrom::None,
+            keep_tag_only: false,
+            skip_code_blocks: true,
+            add_created_date: None,
+            global_filter: None,
+            read_only: false,
+            on_complete: OnComplete::InPlace,
+            done_heading: "Done".to_string(),
+            archive_file: "Archive.md".to_string(),
         };
 
         (dir, config)
@@ -708,6 +1438,118 @@ mod tests {
         assert_eq!(tasks.len(), 5);
     }
 
+    #[tokio::test]
+    async fn test_missing_vault_errors_but_empty_vault_does_not() {
+        let dir = TempDir::new().unwrap();
+
+        let (_vault_dir, mut config) = create_test_vault();
+        config.vault_path = dir.path().join("unmounted");
+        let backend = ObsidianBackend::new(config);
+        let err = backend.fetch_tasks(&TaskFilter::default()).await.unwrap_err();
+        assert!(err.to_string().contains("Obsidian vault not found at"));
+
+        let (_vault_dir, mut config) = create_test_vault();
+        config.vault_path = dir.path().to_path_buf();
+        let backend = ObsidianBackend::new(config);
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        assert!(tasks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_oversize_note_skipped() {
+        let (_dir, config) = create_test_vault();
+        let dump: String = (0..2000).map(|i| format!("- [ ] Dumped {}\n", i)).collect();
+        fs::write(config.vault_path.join("Dump.md"), dump).unwrap();
+
+        let filter = TaskFilter {
+            search: Some("Dumped".to_string()),
+            ..Default::default()
+        };
+        let backend = ObsidianBackend::new(config).with_max_file_bytes(16 * 1024);
+        assert!(backend.fetch_tasks(&filter).await.unwrap().is_empty());
+        // Smaller notes are still read
+        let all = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        assert_eq!(all.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_skip_code_blocks_off_reads_fenced_tasks() {
+        let filter = TaskFilter {
+            search: Some("fenced".to_string()),
+            ..Default::default()
+        };
+        let note = "# TODO\n```\n- [ ] Fenced chore\n```\n";
+
+        let (_dir, config) = create_test_vault();
+        fs::write(config.vault_path.join("TODO.md"), note).unwrap();
+        let backend = ObsidianBackend::new(config);
+        assert!(backend.fetch_tasks(&filter).await.unwrap().is_empty());
+
+        let (_dir, mut config) = create_test_vault();
+        let path = config.vault_path.join("TODO.md");
+        fs::write(&path, note).unwrap();
+        config.skip_code_blocks = false;
+        let backend = ObsidianBackend::new(config);
+        let tasks = backend.fetch_tasks(&filter).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].source_line, Some(3));
+
+        backend.complete_task(&tasks[0].id, None).await.unwrap();
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("- [x] Fenced chore"));
+    }
+
+    #[tokio::test]
+    async fn test_keep_tag_only_tasks() {
+        let filter = TaskFilter {
+            search: Some("waiting".to_string()),
+            ..Default::default()
+        };
+
+        let (_dir, config) = create_test_vault();
+        fs::write(config.vault_path.join("Waiting.md"), "- [ ] #waiting\n").unwrap();
+        let backend = ObsidianBackend::new(config);
+        assert!(backend.fetch_tasks(&filter).await.unwrap().is_empty());
+
+        let (_dir, mut config) = create_test_vault();
+        let path = config.vault_path.join("Waiting.md");
+        fs::write(&path, "- [ ] #waiting\n").unwrap();
+        config.keep_tag_only = true;
+        let backend = ObsidianBackend::new(config);
+        let tasks = backend.fetch_tasks(&filter).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "#waiting");
+
+        let update = TaskUpdate {
+            priority: Some(Priority::High),
+            ..Default::default()
+        };
+        backend.update_task(&tasks[0].id, &update).await.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "- [ ] ⏫ #waiting\n");
+    }
+
+    #[tokio::test]
+    async fn test_task_details_reads_child_lines() {
+        let (_dir, config) = create_test_vault();
+        fs::write(
+            config.vault_path.join("Trip.md"),
+            "- [ ] Plan trip\n  - [ ] Flights\n  - Hotel near the station\n- [ ] Pack\n",
+        )
+        .unwrap();
+        let backend = ObsidianBackend::new(config);
+
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        let plan = tasks.iter().find(|t| t.title == "Plan trip").unwrap();
+        assert_eq!(
+            backend.task_details(plan).unwrap(),
+            vec!["- [ ] Flights", "- Hotel near the station"]
+        );
+
+        let pack = tasks.iter().find(|t| t.title == "Pack").unwrap();
+        assert!(backend.task_details(pack).unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_fetch_pending_only() {
         let (_dir, config) = create_test_vault();
@@ -754,11 +1596,19 @@ mod tests {
             .find(|t| t.title == "Call dentist")
             .expect("Should find 'Call dentist' task");
 
-        backend.complete_task(&task.id).await.unwrap();
+        backend.complete_task(&task.id, None).await.unwrap();
 
         let content =
             fs::read_to_string(vault_path.join("Daily Notes/2025-02-25.md")).unwrap();
-        assert!(content.contains("- [x] Call dentist"));
+        assert!(content.contains(&format!("- [x] Call dentist ✅ {}", crate::clock::today())));
+
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        let task = tasks.iter().find(|t| t.title == "Call dentist").unwrap();
+        assert_eq!(task.completed_at.map(|d| d.date()), Some(crate::clock::today()));
+
+        backend.uncomplete_task(&task.id, None).await.unwrap();
+        let content = fs::read_to_string(vault_path.join("Daily Notes/2025-02-25.md")).unwrap();
+        assert!(content.contains("- [ ] Call dentist\n"));
     }
 
     #[tokio::test]
@@ -777,13 +1627,287 @@ mod tests {
             .find(|t| t.title == "Morning workout")
             .expect("Should find 'Morning workout' task");
 
-        backend.uncomplete_task(&task.id).await.unwrap();
+        backend.uncomplete_task(&task.id, None).await.unwrap();
 
         let content =
             fs::read_to_string(vault_path.join("Daily Notes/2025-02-25.md")).unwrap();
         assert!(content.contains("- [ ] Morning workout"));
     }
 
+    #[tokio::test]
+    async fn test_complete_odd_spacing_and_stale_lines() {
+        let (_dir, config) = create_test_vault();
+        let path = config.vault_path.join("Odd.md");
+        fs::write(
+            &path,
+            "-  [ ] Two spaces\n  -\t[ ] Tab after dash\n- [ ] Explain - [ ] syntax\nJust text\n",
+        )
+        .unwrap();
+        let backend = ObsidianBackend::new(config);
+        let done = format!(" ✅ {}", crate::clock::today());
+
+        for line in 1..=3 {
+            let id = format!("obsidian:Odd.md:{}", line);
+            backend.complete_task(&id, None).await.unwrap();
+        }
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            format!(
+                "-  [x] Two spaces{d}\n  -\t[x] Tab after dash{d}\n- [x] Explain - [ ] syntax{d}\nJust text\n",
+                d = done
+            )
+        );
+
+        backend
+            .uncomplete_task(&"obsidian:Odd.md:3".to_string(), None)
+            .await
+            .unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\n- [ ] Explain - [ ] syntax\n"));
+
+        // The line changed since it was listed: an error, not a silent no-op
+        let err = backend
+            .complete_task(&"obsidian:Odd.md:4".to_string(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Line 4 is not a checkbox"));
+        assert!(backend
+            .uncomplete_task(&"obsidian:Odd.md:4".to_string(), None)
+            .await
+            .is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_complete_moves_to_done_section_and_back() {
+        let (_dir, mut config) = create_test_vault();
+        config.on_complete = OnComplete::MoveToDoneSection;
+        let path = config.vault_path.join("Work.md");
+        let original = "\
+# Work
+
+- [ ] Write report
+    - [ ] Outline
+    notes line
+- [ ] Call Bob
+
+Some text
+";
+        fs::write(&path, original).unwrap();
+        let backend = ObsidianBackend::new(config);
+        let done = format!("✅ {}", crate::clock::today());
+
+        // No section yet: it's added at the end, children and all
+        let report = backend
+            .complete_task(&"obsidian:Work.md:3".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(report, "obsidian:Work.md:8");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!(
+                "# Work\n\n- [ ] Call Bob\n\nSome text\n\n## Done\n\
+                 - [x] Write report {} %%tasuki-from: Work.md:3%%\n    - [ ] Outline\n    notes line\n",
+                done
+            )
+        );
+
+        let bob = backend
+            .complete_task(&"obsidian:Work.md:3".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(bob, "obsidian:Work.md:10");
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        let task = tasks.iter().find(|t| t.id == bob).unwrap();
+        assert_eq!(task.title, "Call Bob");
+        assert_eq!(task.status, TaskStatus::Done);
+        // Moving Bob out from above shifted it up a line
+        let task = tasks.iter().find(|t| t.title == "Write report").unwrap();
+        assert_ne!(task.id, report);
+        assert_eq!(task.id, "obsidian:Work.md:7");
+
+        // Reopened in reverse order, both land where they were
+        let bob = backend.uncomplete_task(&bob, None).await.unwrap();
+        assert_eq!(bob, "obsidian:Work.md:3");
+        let report = backend
+            .uncomplete_task(&"obsidian:Work.md:8".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(report, "obsidian:Work.md:3");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n## Done\n", original)
+        );
+
+        // A line holding some other task by now is left alone
+        let err = backend
+            .complete_task(&"obsidian:Work.md:3".to_string(), Some("Call Bob"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TasukiError::NotFound(_)), "{}", err);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n## Done\n", original)
+        );
+
+        // Completing through an update moves it the same way
+        let update = TaskUpdate {
+            status: Some(TaskStatus::Done),
+            ..Default::default()
+        };
+        let task = backend
+            .update_task(&"obsidian:Work.md:6".to_string(), &update)
+            .await
+            .unwrap();
+        assert_eq!(task.title, "Call Bob");
+        assert_eq!(task.id, "obsidian:Work.md:10");
+        let task = backend
+            .update_task(
+                &task.id,
+                &TaskUpdate {
+                    status: Some(TaskStatus::Pending),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(task.id, "obsidian:Work.md:6");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n## Done\n", original)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_complete_into_existing_done_section() {
+        let (_dir, mut config) = create_test_vault();
+        config.on_complete = OnComplete::MoveToDoneSection;
+        let path = config.vault_path.join("Plan.md");
+        fs::write(
+            &path,
+            "# Plan\n- [ ] Ship it\n\n## done\n- [x] Old thing\n### Older\n- [x] Older thing\n\n## Notes\nKeep this\n",
+        )
+        .unwrap();
+        let backend = ObsidianBackend::new(config);
+
+        // After the section's last line, subsections included, before the
+        // next heading at its level
+        let id = backend
+            .complete_task(&"obsidian:Plan.md:2".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(id, "obsidian:Plan.md:7");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!(
+                "# Plan\n\n## done\n- [x] Old thing\n### Older\n- [x] Older thing\n\
+                 - [x] Ship it ✅ {} %%tasuki-from: Plan.md:2%%\n\n## Notes\nKeep this\n",
+                crate::clock::today()
+            )
+        );
+
+        // A stale ID is refused before anything moves
+        let before = fs::read_to_string(&path).unwrap();
+        let err = backend
+            .complete_task(&"obsidian:Plan.md:9".to_string(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Line 9 is not a checkbox"));
+        assert!(backend
+            .complete_task(&"obsidian:Plan.md:40".to_string(), None)
+            .await
+            .is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    }
+
+    #[tokio::test]
+    async fn test_complete_moves_to_archive_and_back() {
+        let (_dir, mut config) = create_test_vault();
+        config.on_complete = OnComplete::MoveToArchive;
+        config.archive_file = "Archive/Done.md".to_string();
+        let vault = config.vault_path.clone();
+        let home = vault.join("Home.md");
+        let archive = vault.join("Archive/Done.md");
+        fs::write(&home, "- [ ] Fix sink\n  - [x] Buy washer\n- [ ] Paint\n").unwrap();
+        let backend = ObsidianBackend::new(config);
+        let done = format!("✅ {}", crate::clock::today());
+
+        // The archive and its folder are made on first use
+        let sink = backend
+            .complete_task(&"obsidian:Home.md:1".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(sink, "obsidian:Archive/Done.md:1");
+        let paint = backend
+            .complete_task(&"obsidian:Home.md:1".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(paint, "obsidian:Archive/Done.md:3");
+        assert_eq!(fs::read_to_string(&home).unwrap(), "");
+        assert_eq!(
+            fs::read_to_string(&archive).unwrap(),
+            format!(
+                "- [x] Fix sink {d} %%tasuki-from: Home.md:1%%\n  - [x] Buy washer\n\
+                 - [x] Paint {d} %%tasuki-from: Home.md:1%%\n",
+                d = done
+            )
+        );
+
+        let paint = backend.uncomplete_task(&paint, None).await.unwrap();
+        assert_eq!(paint, "obsidian:Home.md:1");
+        let sink = backend.uncomplete_task(&sink, None).await.unwrap();
+        assert_eq!(sink, "obsidian:Home.md:1");
+        assert_eq!(
+            fs::read_to_string(&home).unwrap(),
+            "- [ ] Fix sink\n  - [x] Buy washer\n- [ ] Paint\n"
+        );
+        assert_eq!(fs::read_to_string(&archive).unwrap(), "");
+
+        // Edits keep the note of where it came from
+        let paint = backend
+            .complete_task(&"obsidian:Home.md:3".to_string(), None)
+            .await
+            .unwrap();
+        let update = TaskUpdate {
+            title: Some("Paint fence".to_string()),
+            ..Default::default()
+        };
+        backend.update_task(&paint, &update).await.unwrap();
+        assert!(fs::read_to_string(&archive)
+            .unwrap()
+            .contains("- [x] Paint fence ✅ "));
+        assert!(fs::read_to_string(&archive)
+            .unwrap()
+            .ends_with(" %%tasuki-from: Home.md:3%%\n"));
+
+        // With the note gone there's nowhere to go back to: reopened in place
+        fs::remove_file(&home).unwrap();
+        assert_eq!(backend.uncomplete_task(&paint, None).await.unwrap(), paint);
+        assert_eq!(fs::read_to_string(&archive).unwrap(), "- [ ] Paint fence\n");
+    }
+
+    #[test]
+    fn test_on_complete_from_table() {
+        let table = |extra: &str| -> toml::Table {
+            toml::from_str(&format!("vault_path = \"/tmp/vault\"\n{}", extra)).unwrap()
+        };
+        let config = ObsidianConfig::from_table(&table("")).unwrap();
+        assert_eq!(config.on_complete, OnComplete::InPlace);
+        assert_eq!(config.done_heading, "Done");
+        assert_eq!(config.archive_file, "Archive.md");
+
+        let config = ObsidianConfig::from_table(&table(
+            "on_complete = \"move_to_done_section\"\ndone_heading = \"## Finished\"",
+        ))
+        .unwrap();
+        assert_eq!(config.on_complete, OnComplete::MoveToDoneSection);
+        assert_eq!(config.done_heading, "Finished");
+
+        let err = ObsidianConfig::from_table(&table("on_complete = \"delete\"")).err().unwrap();
+        assert!(err.to_string().contains("obsidian.on_complete"));
+    }
+
     #[tokio::test]
     async fn test_create_task() {
         let (_dir, config) = create_test_vault();
@@ -806,6 +1930,265 @@ mod tests {
         assert!(content.contains("- [ ] New task from tasuki ⏫ 📅 2025-04-01 #work"));
     }
 
+    #[tokio::test]
+    async fn test_create_task_in_missing_folder() {
+        let (_dir, mut config) = create_test_vault();
+        config.inbox_file = "00 Inbox/Tasks.md".to_string();
+        let vault_path = config.vault_path.clone();
+        let backend = ObsidianBackend::new(config);
+
+        let new_task = NewTask {
+            title: "Nested inbox".to_string(),
+            priority: Priority::None,
+            due: None,
+            tags: vec![],
+            backend: BackendSource::Obsidian,
+        };
+        let task = backend.create_task(&new_task).await.unwrap();
+        assert_eq!(task.id, "obsidian:00 Inbox/Tasks.md:1");
+
+        let content = fs::read_to_string(vault_path.join("00 Inbox").join("Tasks.md")).unwrap();
+        assert_eq!(content, "- [ ] Nested inbox\n");
+    }
+
+    #[tokio::test]
+    async fn test_create_inbox_from_template() {
+        let (_dir, mut config) = create_test_vault();
+        config.inbox_file = "Capture/Quick.md".to_string();
+        config.inbox_template = Some("---\ncreated: {{date}}\n---\n# {{title}}".to_string());
+        let vault_path = config.vault_path.clone();
+        let new_task = NewTask {
+            title: "Templated".to_string(),
+            priority: Priority::None,
+            due: None,
+            tags: vec![],
+            backend: BackendSource::Obsidian,
+        };
+
+        let task = ObsidianBackend::new(config).create_task(&new_task).await.unwrap();
+        assert_eq!(task.id, "obsidian:Capture/Quick.md:5");
+        let today = crate::clock::today().format("%Y-%m-%d");
+        let content = fs::read_to_string(vault_path.join("Capture/Quick.md")).unwrap();
+        assert_eq!(
+            content,
+            format!("---\ncreated: {}\n---\n# Quick\n- [ ] Templated\n", today)
+        );
+
+        // A path to a template file uses the file's text
+        let (_dir, mut config) = create_test_vault();
+        let vault_path = config.vault_path.clone();
+        fs::write(vault_path.join("Inbox template.md"), "# {{title}}\n\n").unwrap();
+        config.inbox_file = "Capture/Other.md".to_string();
+        config.inbox_template = Some("Inbox template.md".to_string());
+        let task = ObsidianBackend::new(config).create_task(&new_task).await.unwrap();
+        assert_eq!(task.id, "obsidian:Capture/Other.md:3");
+        let content = fs::read_to_string(vault_path.join("Capture/Other.md")).unwrap();
+        assert_eq!(content, "# Other\n\n- [ ] Templated\n");
+    }
+
+    #[tokio::test]
+    async fn test_read_only_vault_refuses_changes() {
+        let (_dir, mut config) = create_test_vault();
+        config.read_only = true;
+        let note = config.vault_path.join("Daily Notes/2025-02-25.md");
+        let before = fs::read_to_string(&note).unwrap();
+        let backend = ObsidianBackend::new(config);
+
+        let caps = backend.capabilities();
+        assert!(!caps.can_create && !caps.can_update && !caps.can_delete);
+        assert_eq!(backend.fetch_tasks(&TaskFilter::default()).await.unwrap().len(), 5);
+
+        let id = "obsidian:Daily Notes/2025-02-25.md:3".to_string();
+        let err = backend.complete_task(&id, None).await.unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{}", err);
+        assert!(backend.uncomplete_task(&id, None).await.is_err());
+        assert!(backend.delete_task(&id).await.is_err());
+        let update = TaskUpdate {
+            title: Some("Renamed".to_string()),
+            ..Default::default()
+        };
+        assert!(backend.update_task(&id, &update).await.is_err());
+        let new_task = NewTask {
+            title: "Nope".to_string(),
+            priority: Priority::None,
+            due: None,
+            tags: vec![],
+            backend: BackendSource::Obsidian,
+        };
+        assert!(backend.create_task(&new_task).await.is_err());
+
+        assert_eq!(fs::read_to_string(&note).unwrap(), before);
+        assert_eq!(fs::read_to_string(backend.config.vault_path.join("Inbox.md")).unwrap(), "");
+
+        let table: toml::Table = toml::from_str(&format!(
+            "vault_path = {:?}\nread_only = true",
+            backend.config.vault_path.to_string_lossy()
+        ))
+        .unwrap();
+        assert!(ObsidianConfig::from_table(&table).unwrap().read_only);
+    }
+
+    #[test]
+    fn test_global_filter_from_tasks_plugin() {
+        let dir = TempDir::new().unwrap();
+        let plugin = dir.path().join(".obsidian/plugins/obsidian-tasks-plugin");
+        fs::create_dir_all(&plugin).unwrap();
+        let table = |extra: &str| -> toml::Table {
+            toml::from_str(&format!("vault_path = {:?}\n{}", dir.path().to_string_lossy(), extra))
+                .unwrap()
+        };
+
+        let config = ObsidianConfig::from_table(&table("")).unwrap();
+        assert_eq!(config.global_filter, None);
+
+        fs::write(plugin.join("data.json"), r##"{"globalFilter": "#task", "removeGlobalFilter": false}"##)
+            .unwrap();
+        let config = ObsidianConfig::from_table(&table("")).unwrap();
+        assert_eq!(config.global_filter.as_deref(), Some("#task"));
+
+        let config = ObsidianConfig::from_table(&table("global_filter = \"TODO\"")).unwrap();
+        assert_eq!(config.global_filter.as_deref(), Some("TODO"));
+        let config = ObsidianConfig::from_table(&table("global_filter = \"\"")).unwrap();
+        assert_eq!(config.global_filter, None);
+    }
+
+    #[tokio::test]
+    async fn test_global_filter_limits_and_round_trips_tasks() {
+        let (_dir, mut config) = create_test_vault();
+        config.global_filter = Some("#task".to_string());
+        config.add_created_date = Some(false);
+        let path = config.vault_path.join("Groceries.md");
+        fs::write(
+            &path,
+            "- [ ] Milk\n- [ ] Pay rent #task #home 📅 2025-03-01\n- [ ] Tasks for later #tasks\n- [ ] #task\n",
+        )
+        .unwrap();
+        let backend = ObsidianBackend::new(config);
+
+        // Nothing else in the vault carries the filter
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "Pay rent");
+        assert_eq!(tasks[0].tags, vec!["home"]);
+
+        // Edits keep exactly one filter tag, even when setting the tags
+        let update = TaskUpdate {
+            priority: Some(Priority::High),
+            tags: Some(vec!["home".to_string(), "task".to_string()]),
+            ..Default::default()
+        };
+        let updated = backend.update_task(&tasks[0].id, &update).await.unwrap();
+        assert_eq!(updated.tags, vec!["home"]);
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content.lines().nth(1).unwrap(),
+            "- [ ] #task Pay rent ⏫ 📅 2025-03-01 #home"
+        );
+
+        let new_task = NewTask {
+            title: "Buy bread".to_string(),
+            priority: Priority::None,
+            due: None,
+            tags: vec!["task".to_string(), "shop".to_string()],
+            backend: BackendSource::Obsidian,
+        };
+        let created = backend.create_task(&new_task).await.unwrap();
+        assert_eq!(created.tags, vec!["shop"]);
+        let inbox = fs::read_to_string(backend.config.vault_path.join("Inbox.md")).unwrap();
+        assert!(inbox.ends_with("- [ ] #task Buy bread #shop\n"), "{}", inbox);
+    }
+
+    #[tokio::test]
+    async fn test_create_task_adds_created_date() {
+        let today = crate::clock::today();
+        let new_task = NewTask {
+            title: "Dated task".to_string(),
+            priority: Priority::None,
+            due: None,
+            tags: vec![],
+            backend: BackendSource::Obsidian,
+        };
+
+        // No Tasks metadata anywhere in the vault: left off
+        let (_dir, config) = create_test_vault();
+        let vault_path = config.vault_path.clone();
+        let backend = ObsidianBackend::new(config);
+        let task = backend.create_task(&new_task).await.unwrap();
+        assert_eq!(task.created_at, None);
+        // Worked out once, not on every create
+        fs::write(
+            vault_path.join("Bills.md"),
+            "- [ ] Pay rent 📅 2025-03-01\n",
+        )
+        .unwrap();
+        let task = backend.create_task(&new_task).await.unwrap();
+        assert_eq!(task.created_at, None);
+
+        // Detected from a note using Tasks emoji
+        let (_dir, config) = create_test_vault();
+        let vault_path = config.vault_path.clone();
+        fs::write(vault_path.join("Bills.md"), "- [ ] Pay rent 📅 2025-03-01\n").unwrap();
+        let backend = ObsidianBackend::new(config);
+        let task = backend.create_task(&new_task).await.unwrap();
+        assert_eq!(task.created_at, today.and_hms_opt(0, 0, 0));
+
+        let update = TaskUpdate {
+            priority: Some(Priority::High),
+            ..Default::default()
+        };
+        backend.update_task(&task.id, &update).await.unwrap();
+        backend.complete_task(&task.id, None).await.unwrap();
+        let content = fs::read_to_string(vault_path.join("Inbox.md")).unwrap();
+        assert_eq!(
+            content,
+            format!("- [x] Dated task ⏫ ➕ {} ✅ {}\n", today, today)
+        );
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        let fetched = tasks.iter().find(|t| t.title == "Dated task").unwrap();
+        assert_eq!(fetched.created_at, task.created_at);
+
+        // Explicitly off wins over detection
+        let (_dir, mut config) = create_test_vault();
+        fs::write(config.vault_path.join("Bills.md"), "- [ ] Pay rent 📅 2025-03-01\n").unwrap();
+        config.add_created_date = Some(false);
+        let backend = ObsidianBackend::new(config);
+        assert_eq!(backend.create_task(&new_task).await.unwrap().created_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_preserves_callout_prefix() {
+        let (_dir, config) = create_test_vault();
+        let vault_path = config.vault_path.clone();
+        fs::write(
+            vault_path.join("Callouts.md"),
+            "> [!todo] Week\n> - [ ] Quoted task\n    - [ ] Indented task\n",
+        )
+        .unwrap();
+        let backend = ObsidianBackend::new(config);
+
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        for title in ["Quoted task", "Indented task"] {
+            let task = tasks.iter().find(|t| t.title == title).unwrap();
+            let update = TaskUpdate {
+                priority: Some(Priority::High),
+                ..Default::default()
+            };
+            backend.update_task(&task.id, &update).await.unwrap();
+        }
+
+        let task = tasks.iter().find(|t| t.title == "Quoted task").unwrap();
+        backend.complete_task(&task.id, None).await.unwrap();
+
+        let content = fs::read_to_string(vault_path.join("Callouts.md")).unwrap();
+        assert_eq!(
+            content,
+            format!(
+                "> [!todo] Week\n> - [x] Quoted task ⏫ ✅ {}\n    - [ ] Indented task ⏫\n",
+                crate::clock::today()
+            )
+        );
+    }
+
     #[tokio::test]
     async fn test_delete_task() {
         let (_dir, config) = create_test_vault();
@@ -844,6 +2227,73 @@ mod tests {
         assert!(!tasks.iter().any(|t| t.title == "Should not appear"));
     }
 
+    #[test]
+    fn test_normalize_folder() {
+        assert_eq!(normalize_folder("Projects/"), "Projects");
+        assert_eq!(normalize_folder("./Projects"), "Projects");
+        assert_eq!(normalize_folder("Areas\\Work\\"), "Areas/Work");
+        assert_eq!(normalize_folder("/Areas//Work"), "Areas/Work");
+    }
+
+    #[test]
+    fn test_folders_from_table_normalized() {
+        let table: toml::Table = toml::from_str(
+            r#"
+vault_path = "/tmp/vault"
+folders = ["Projects/", "Areas\\Work"]
+"#,
+        )
+        .unwrap();
+        let config = ObsidianConfig::from_table(&table).unwrap();
+        assert_eq!(
+            config.folders,
+            Some(vec!["Projects".to_string(), "Areas/Work".to_string()])
+        );
+        assert!(!config.folders_ignore_case);
+    }
+
+    #[test]
+    fn test_in_allowed_folder_matches_components() {
+        let (_dir, mut config) = create_test_vault();
+        config.folders = Some(vec!["Work".to_string(), "Areas/Home".to_string()]);
+
+        assert!(config.in_allowed_folder(Path::new("Work/plan.md")));
+        assert!(config.in_allowed_folder(Path::new("Areas/Home/list.md")));
+        assert!(!config.in_allowed_folder(Path::new("Workouts/legs.md")));
+        assert!(!config.in_allowed_folder(Path::new("Areas/Homework.md")));
+        assert!(!config.in_allowed_folder(Path::new("work/plan.md")));
+
+        config.folders_ignore_case = true;
+        assert!(config.in_allowed_folder(Path::new("work/plan.md")));
+        assert!(config.in_allowed_folder(Path::new("areas/HOME/list.md")));
+        assert!(!config.in_allowed_folder(Path::new("workouts/legs.md")));
+    }
+
+    #[tokio::test]
+    async fn test_folders_trailing_separator_keeps_tasks() {
+        let (_dir, mut config) = create_test_vault();
+        config.folders = Some(vec![normalize_folder("Daily Notes/")]);
+
+        let backend = ObsidianBackend::new(config);
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        assert!(tasks.iter().any(|t| t.title == "Call dentist"));
+        assert!(tasks.iter().all(|t| t.id.starts_with("obsidian:Daily Notes/")));
+    }
+
+    #[test]
+    fn test_missing_folders() {
+        let (_dir, mut config) = create_test_vault();
+        config.folders = Some(vec![
+            "Daily Notes".to_string(),
+            "daily notes".to_string(),
+            "Gone".to_string(),
+        ]);
+        assert_eq!(config.missing_folders(), vec!["daily notes", "Gone"]);
+
+        config.folders_ignore_case = true;
+        assert_eq!(config.missing_folders(), vec!["Gone"]);
+    }
+
     #[tokio::test]
     async fn test_code_blocks_skipped() {
         let (_dir, config) = create_test_vault();
@@ -862,6 +2312,52 @@ mod tests {
             .any(|t| t.title == "Real task below code block"));
     }
 
+    #[tokio::test]
+    async fn test_project_from_filename() {
+        let (_dir, mut config) = create_test_vault();
+        fs::create_dir_all(config.vault_path.join("Projects")).unwrap();
+        fs::write(
+            config.vault_path.join("Projects/Website.md"),
+            "# Company Site\n\n- [ ] Update footer\n",
+        )
+        .unwrap();
+        config.project_from = ProjectFrom::Filename;
+        let backend = ObsidianBackend::new(config);
+
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        let task = tasks.iter().find(|t| t.title == "Update footer").unwrap();
+        assert_eq!(task.project.as_deref(), Some("Website"));
+    }
+
+    #[tokio::test]
+    async fn test_project_from_h1() {
+        let (_dir, mut config) = create_test_vault();
+        fs::create_dir_all(config.vault_path.join("Projects")).unwrap();
+        fs::write(
+            config.vault_path.join("Projects/Website.md"),
+            "# Company Site\n\n## Backlog\n- [ ] Update footer\n",
+        )
+        .unwrap();
+        config.project_from = ProjectFrom::H1;
+        let vault_path = config.vault_path.clone();
+        let backend = ObsidianBackend::new(config);
+
+        let tasks = backend.fetch_tasks(&TaskFilter::default()).await.unwrap();
+        let task = tasks.iter().find(|t| t.title == "Update footer").unwrap();
+        assert_eq!(task.project.as_deref(), Some("Company Site"));
+        assert_eq!(task.heading_context.as_deref(), Some("Backlog"));
+
+        let other = tasks
+            .iter()
+            .find(|t| t.title == "Real task above code block")
+            .unwrap();
+        assert_eq!(other.project.as_deref(), Some("Code Examples"));
+
+        // Derived projects are never written back to the note
+        let content = fs::read_to_string(vault_path.join("Projects/Website.md")).unwrap();
+        assert_eq!(content, "# Company Site\n\n## Backlog\n- [ ] Update footer\n");
+    }
+
     #[test]
     fn test_parse_task_id() {
         let (path, line) =
@@ -877,11 +2373,22 @@ mod tests {
         let config = ObsidianConfig {
             vault_path: dir.path().to_path_buf(),
             folders: None,
+            folders_ignore_case: false,
             ignore_folders: vec![],
             inbox_file: "Inbox.md".to_string(),
+            inbox_template: None,
             daily_notes_folder: None,
             daily_notes_format: "%Y-%m-%d".to_string(),
             daily_notes_lookback: None,
+            project_from: ProjectFrom::None,
+            keep_tag_only: false,
+            skip_code_blocks: true,
+            add_created_date: None,
+            global_filter: None,
+            read_only: false,
+            on_complete: OnComplete::InPlace,
+            done_heading: "Done".to_string(),
+            archive_file: "Archive.md".to_string(),
         };
         assert!(!config.is_obsidian_vault());
 
@@ -1,6 +1,6 @@
 use chrono::NaiveDate;
 
-use crate::model::{Priority, TaskStatus};
+use crate::model::{dedupe_tags, normalize_tag, BangPriorities, Priority, TaskStatus};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParsedTask {
@@ -12,21 +12,102 @@ pub struct ParsedTask {
     pub created_at: Option<NaiveDate>,
     pub tags: Vec<String>,
     pub heading_context: Option<String>,
+    /// Leading indentation and blockquote markers, kept so write-back
+    /// reproduces them (e.g. `"    "` or `"> > "`)
+    pub prefix: String,
 }
 
-// Parse a checkbox line into a ParsedTask
-pub fn parse_checkbox_line(line: &str) -> Option<ParsedTask> {
-    let trimmed = line.trim_start();
+// Split off indentation and any `>` blockquote/callout markers
+fn split_prefix(line: &str) -> (&str, &str) {
+    let mut end = 0;
+    let bytes = line.as_bytes();
+    while end < bytes.len() {
+        match bytes[end] {
+            b' ' | b'\t' | b'>' => end += 1,
+            _ => break,
+        }
+    }
+    line.split_at(end)
+}
 
-    if !trimmed.starts_with("- [") {
+// Find a `- [?]` checkbox: the byte offset of the status character in
+// `line`, the character, and the text after `]`. Any spaces or tabs may
+// follow the dash (`-  [ ]`, `-\t[ ]`); Obsidian shows those as checkboxes too.
+fn checkbox_at(line: &str) -> Option<(usize, char, &str)> {
+    let (_, trimmed) = split_prefix(line);
+    let after_dash = trimmed.strip_prefix('-')?;
+    let at_box = after_dash.trim_start_matches([' ', '\t']);
+    if at_box.len() == after_dash.len() {
         return None;
     }
 
-    let after_bracket = &trimmed[3..];
-    let status_char = after_bracket.chars().next()?;
-    if !after_bracket.starts_with(']') && after_bracket.chars().nth(1) != Some(']') {
-        return None;
+    let inner = at_box.strip_prefix('[')?;
+    let status_char = inner.chars().next()?;
+    let rest = inner[status_char.len_utf8()..].strip_prefix(']')?;
+    Some((line.len() - inner.len(), status_char, rest))
+}
+
+/// Where a checkbox moved by `on_complete` came from, kept at the end of
+/// its line as an Obsidian comment: `%%tasuki-from: Notes/Work.md:12%%`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    /// Note, relative to the vault
+    pub path: String,
+    /// 1-indexed line the checkbox was on
+    pub line: usize,
+}
+
+const ORIGIN_START: &str = "%%tasuki-from:";
+
+impl Origin {
+    pub fn comment(&self) -> String {
+        format!("{} {}:{}%%", ORIGIN_START, self.path, self.line)
+    }
+
+    /// The origin noted on `line`, and `line` without it. A malformed
+    /// note is left in place.
+    pub fn split(line: &str) -> (String, Option<Origin>) {
+        let parsed = line.find(ORIGIN_START).and_then(|start| {
+            let inner_start = start + ORIGIN_START.len();
+            let len = line[inner_start..].find("%%")?;
+            let inner = line[inner_start..inner_start + len].trim();
+            let (path, num) = inner.rsplit_once(':')?;
+            let origin = Origin {
+                path: path.trim().to_string(),
+                line: num.parse().ok().filter(|n| *n > 0)?,
+            };
+            let rest = format!(
+                "{}{}",
+                line[..start].trim_end(),
+                &line[inner_start + len + 2..]
+            );
+            Some((rest, origin))
+        });
+        match parsed {
+            Some((rest, origin)) => (rest, Some(origin)),
+            None => (line.to_string(), None),
+        }
     }
+}
+
+// Parse a checkbox line into a ParsedTask. A checkbox holding only metadata
+// (`- [ ] #waiting`) is skipped unless `keep_untitled`, which leaves its
+// title empty.
+pub fn parse_checkbox_line(line: &str, keep_untitled: bool) -> Option<ParsedTask> {
+    parse_checkbox_line_with(line, keep_untitled, &BangPriorities::default())
+}
+
+/// `parse_checkbox_line` with `!`, `!!` and `!!!` read through `bangs`.
+/// Those and a bare `p1`-`p3` only count before the title, as in todo.txt;
+/// `(p1)` counts anywhere.
+pub fn parse_checkbox_line_with(
+    line: &str,
+    keep_untitled: bool,
+    bangs: &BangPriorities,
+) -> Option<ParsedTask> {
+    let (line, _) = Origin::split(line);
+    let (prefix, _) = split_prefix(&line);
+    let (_, status_char, rest) = checkbox_at(&line)?;
 
     let status = match status_char {
         ' ' => TaskStatus::Pending,
@@ -34,7 +115,7 @@ pub fn parse_checkbox_line(line: &str) -> Option<ParsedTask> {
         _ => return None,
     };
 
-    let rest = &trimmed[5..].trim_start();
+    let rest = rest.trim_start();
     if rest.is_empty() {
         return None;
     }
@@ -108,27 +189,16 @@ pub fn parse_checkbox_line(line: &str) -> Option<ParsedTask> {
             continue;
         }
 
-        if token == "(p1)" {
-            priority = Priority::High;
-            i += 1;
-            continue;
-        }
-        if token == "(p2)" {
-            priority = Priority::Medium;
-            i += 1;
-            continue;
-        }
-        if token == "(p3)" {
-            priority = Priority::Low;
+        let leading = title_parts.is_empty() || token.starts_with('(');
+        if let Some(p) = Priority::from_token(token, bangs).filter(|_| leading) {
+            priority = p;
             i += 1;
             continue;
         }
 
         // Tags
         if let Some(tag) = token.strip_prefix('#') {
-            if !tag.is_empty() {
-                tags.push(tag.to_string());
-            }
+            tags.extend(normalize_tag(tag, false));
             i += 1;
             continue;
         }
@@ -147,7 +217,7 @@ pub fn parse_checkbox_line(line: &str) -> Option<ParsedTask> {
     }
 
     let title = title_parts.join(" ");
-    if title.is_empty() {
+    if title.is_empty() && !keep_untitled {
         return None;
     }
 
@@ -158,17 +228,49 @@ pub fn parse_checkbox_line(line: &str) -> Option<ParsedTask> {
         due,
         completed_at,
         created_at,
-        tags,
+        tags: dedupe_tags(tags),
         heading_context: None,
+        prefix: prefix.to_string(),
     })
 }
 
-pub fn parse_file(content: &str) -> Vec<(usize, ParsedTask)> {
+/// `line` with its checkbox set to `status`. Only the character between
+/// the brackets changes, so spacing, the title (even one containing
+/// `- [ ]`) and metadata are left alone. `None` if `line` isn't a task.
+pub fn set_checkbox_status(line: &str, status: TaskStatus) -> Option<String> {
+    let current = parse_checkbox_line(line, true)?;
+    if current.status == status {
+        return Some(line.to_string());
+    }
+
+    let (at, status_char, _) = checkbox_at(line)?;
+    let mark = match status {
+        TaskStatus::Pending => ' ',
+        TaskStatus::Done => 'x',
+    };
+    Some(format!(
+        "{}{}{}",
+        &line[..at],
+        mark,
+        &line[at + status_char.len_utf8()..]
+    ))
+}
+
+/// Checkbox tasks in a note, with their 1-indexed line numbers. With
+/// `skip_code_blocks`, checkboxes inside fenced code blocks are ignored;
+/// headings there never count either way.
+pub fn parse_file<S: AsRef<str>>(
+    lines: impl IntoIterator<Item = S>,
+    keep_untitled: bool,
+    skip_code_blocks: bool,
+    bangs: &BangPriorities,
+) -> Vec<(usize, ParsedTask)> {
     let mut results = Vec::new();
     let mut in_code_block = false;
     let mut current_heading: Option<String> = None;
 
-    for (idx, line) in content.lines().enumerate() {
+    for (idx, line) in lines.into_iter().enumerate() {
+        let line = line.as_ref();
         let trimmed = line.trim();
 
         if trimmed.starts_with("```") {
@@ -176,16 +278,16 @@ pub fn parse_file(content: &str) -> Vec<(usize, ParsedTask)> {
             continue;
         }
 
-        if in_code_block {
+        if in_code_block && skip_code_blocks {
             continue;
         }
 
-        if let Some(heading) = parse_heading(trimmed) {
+        if let Some(heading) = parse_heading(trimmed).filter(|_| !in_code_block) {
             current_heading = Some(heading);
             continue;
         }
 
-        if let Some(mut task) = parse_checkbox_line(line) {
+        if let Some(mut task) = parse_checkbox_line_with(line, keep_untitled, bangs) {
             task.heading_context = current_heading.clone();
             results.push((idx + 1, task));
         }
@@ -194,6 +296,92 @@ pub fn parse_file(content: &str) -> Vec<(usize, ParsedTask)> {
     results
 }
 
+// First level-one heading outside code blocks
+pub fn first_h1<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Option<String> {
+    let mut in_code_block = false;
+
+    for line in lines {
+        let trimmed = line.as_ref().trim();
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block || trimmed.starts_with("##") {
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix("# ") {
+            let heading = heading.trim();
+            if !heading.is_empty() {
+                return Some(heading.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+// Lines nested under the task on 1-indexed `line_num` (description, child
+// bullets), dedented. Stops at the first line indented no deeper than the task.
+pub fn child_lines(content: &str, line_num: usize) -> Vec<String> {
+    let indent = |line: &str| {
+        line.chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum::<usize>()
+    };
+
+    let mut lines = content.lines().skip(line_num.saturating_sub(1));
+    let Some(task_line) = lines.next() else {
+        return Vec::new();
+    };
+    let task_indent = indent(task_line);
+
+    let mut children: Vec<&str> = lines
+        .take_while(|line| line.trim().is_empty() || indent(line) > task_indent)
+        .collect();
+    while children.last().is_some_and(|line| line.trim().is_empty()) {
+        children.pop();
+    }
+
+    let min_indent = children
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent(line))
+        .min()
+        .unwrap_or(0);
+
+    children
+        .iter()
+        .map(|line| {
+            let mut width = 0;
+            let start = line
+                .char_indices()
+                .find(|&(_, c)| {
+                    if width >= min_indent || !c.is_whitespace() {
+                        return true;
+                    }
+                    width += if c == '\t' { 4 } else { 1 };
+                    false
+                })
+                .map_or(line.len(), |(i, _)| i);
+            line[start..].trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Open checkboxes among `lines` (as from `child_lines`): a task's
+/// unfinished subtasks.
+pub fn pending_checkboxes<S: AsRef<str>>(lines: &[S]) -> usize {
+    lines
+        .iter()
+        .filter_map(|line| parse_checkbox_line(line.as_ref(), true))
+        .filter(|task| task.status == TaskStatus::Pending)
+        .count()
+}
+
 fn parse_heading(line: &str) -> Option<String> {
     if !line.starts_with('#') {
         return None;
@@ -209,6 +397,12 @@ fn parse_heading(line: &str) -> Option<String> {
     Some(rest.to_string())
 }
 
+/// Whether `line` is a checkbox carrying Obsidian Tasks emoji metadata
+/// (dates, priorities, recurrence).
+pub fn has_tasks_metadata(line: &str) -> bool {
+    checkbox_at(line).is_some_and(|(_, _, rest)| rest.split_whitespace().any(is_tasks_emoji))
+}
+
 fn try_parse_next_date(tokens: &[&str], idx: usize) -> Option<NaiveDate> {
     if idx >= tokens.len() {
         return None;
@@ -217,6 +411,14 @@ fn try_parse_next_date(tokens: &[&str], idx: usize) -> Option<NaiveDate> {
 }
 
 fn is_metadata_token(token: &str) -> bool {
+    is_tasks_emoji(token)
+        || token.starts_with('#')
+        || token.starts_with("due:")
+        || matches!(token, "(p1)" | "(p2)" | "(p3)")
+}
+
+/// The signifiers Obsidian Tasks puts before dates, priorities and the like.
+fn is_tasks_emoji(token: &str) -> bool {
     matches!(
         token,
         "📅" | "🗓️"
@@ -234,9 +436,7 @@ fn is_metadata_token(token: &str) -> bool {
             | "🆔"
             | "⛔"
             | "🏁"
-    ) || token.starts_with('#')
-        || token.starts_with("due:")
-        || matches!(token, "(p1)" | "(p2)" | "(p3)")
+    )
 }
 
 #[cfg(test)]
@@ -246,7 +446,7 @@ mod tests {
 
     #[test]
     fn test_plain_checkbox_pending() {
-        let result = parse_checkbox_line("- [ ] Buy groceries").unwrap();
+        let result = parse_checkbox_line("- [ ] Buy groceries", false).unwrap();
         assert_eq!(result.title, "Buy groceries");
         assert_eq!(result.status, TaskStatus::Pending);
         assert_eq!(result.priority, Priority::None);
@@ -255,27 +455,27 @@ mod tests {
 
     #[test]
     fn test_plain_checkbox_done() {
-        let result = parse_checkbox_line("- [x] Submit report").unwrap();
+        let result = parse_checkbox_line("- [x] Submit report", false).unwrap();
         assert_eq!(result.title, "Submit report");
         assert_eq!(result.status, TaskStatus::Done);
     }
 
     #[test]
     fn test_uppercase_x() {
-        let result = parse_checkbox_line("- [X] Submit report").unwrap();
+        let result = parse_checkbox_line("- [X] Submit report", false).unwrap();
         assert_eq!(result.status, TaskStatus::Done);
     }
 
     #[test]
     fn test_indented_checkbox() {
-        let result = parse_checkbox_line("    - [ ] Nested task").unwrap();
+        let result = parse_checkbox_line("    - [ ] Nested task", false).unwrap();
         assert_eq!(result.title, "Nested task");
         assert_eq!(result.status, TaskStatus::Pending);
     }
 
     #[test]
     fn test_tasks_plugin_due_date() {
-        let result = parse_checkbox_line("- [ ] Fix bug 📅 2025-03-15").unwrap();
+        let result = parse_checkbox_line("- [ ] Fix bug 📅 2025-03-15", false).unwrap();
         assert_eq!(result.title, "Fix bug");
         assert_eq!(
             result.due,
@@ -285,7 +485,8 @@ mod tests {
 
     #[test]
     fn test_tasks_plugin_completion_date() {
-        let result = parse_checkbox_line("- [x] Done thing 📅 2025-01-15 ✅ 2025-01-14").unwrap();
+        let result =
+            parse_checkbox_line("- [x] Done thing 📅 2025-01-15 ✅ 2025-01-14", false).unwrap();
         assert_eq!(result.title, "Done thing");
         assert_eq!(result.status, TaskStatus::Done);
         assert_eq!(
@@ -298,44 +499,106 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_origin_comment_round_trips_and_stays_out_of_title() {
+        let origin = Origin {
+            path: "Projects/Q3: plan.md".to_string(),
+            line: 12,
+        };
+        let line = format!("- [x] Ship it ✅ 2025-03-01 {} #work", origin.comment());
+        let (rest, parsed) = Origin::split(&line);
+        assert_eq!(parsed, Some(origin));
+        assert_eq!(rest, "- [x] Ship it ✅ 2025-03-01 #work");
+
+        let task = parse_checkbox_line(&line, false).unwrap();
+        assert_eq!(task.title, "Ship it");
+        assert_eq!(task.tags, vec!["work".to_string()]);
+
+        // Malformed notes are left alone
+        for line in [
+            "- [ ] a %%tasuki-from: Work.md%%",
+            "- [ ] a %%tasuki-from: Work.md:0%%",
+            "- [ ] a %%tasuki-from: W.md:3",
+        ] {
+            assert_eq!(Origin::split(line), (line.to_string(), None));
+        }
+    }
+
     #[test]
     fn test_tasks_plugin_priority_high() {
-        let result = parse_checkbox_line("- [ ] Important task ⏫").unwrap();
+        let result = parse_checkbox_line("- [ ] Important task ⏫", false).unwrap();
         assert_eq!(result.title, "Important task");
         assert_eq!(result.priority, Priority::High);
     }
 
     #[test]
     fn test_tasks_plugin_priority_medium() {
-        let result = parse_checkbox_line("- [ ] Normal task 🔼").unwrap();
+        let result = parse_checkbox_line("- [ ] Normal task 🔼", false).unwrap();
         assert_eq!(result.title, "Normal task");
         assert_eq!(result.priority, Priority::Medium);
     }
 
     #[test]
     fn test_tasks_plugin_priority_low() {
-        let result = parse_checkbox_line("- [ ] Low task 🔽").unwrap();
+        let result = parse_checkbox_line("- [ ] Low task 🔽", false).unwrap();
         assert_eq!(result.title, "Low task");
         assert_eq!(result.priority, Priority::Low);
     }
 
     #[test]
     fn test_inline_priority() {
-        let result = parse_checkbox_line("- [ ] Fix bug (p1)").unwrap();
+        let result = parse_checkbox_line("- [ ] Fix bug (p1)", false).unwrap();
         assert_eq!(result.title, "Fix bug");
         assert_eq!(result.priority, Priority::High);
     }
 
+    #[test]
+    fn test_bare_and_bang_priority() {
+        let result = parse_checkbox_line("- [ ] p2 Fix bug", false).unwrap();
+        assert_eq!(result.title, "Fix bug");
+        assert_eq!(result.priority, Priority::Medium);
+
+        let result = parse_checkbox_line("- [ ] ! Fix bug", false).unwrap();
+        assert_eq!(result.title, "Fix bug");
+        assert_eq!(result.priority, Priority::High);
+
+        let result = parse_checkbox_line("- [ ] Fix bug (p2)", false).unwrap();
+        assert_eq!(result.title, "Fix bug");
+        assert_eq!(result.priority, Priority::Medium);
+
+        // Only before the title, as in todo.txt
+        let result = parse_checkbox_line("- [ ] Call p1 back about the ! sign", false).unwrap();
+        assert_eq!(result.title, "Call p1 back about the ! sign");
+        assert_eq!(result.priority, Priority::None);
+
+        let result = parse_checkbox_line("- [ ] Help! Fix bug", false).unwrap();
+        assert_eq!(result.title, "Help! Fix bug");
+        assert_eq!(result.priority, Priority::None);
+
+        let reversed =
+            BangPriorities::from_names(&["low".into(), "medium".into(), "high".into()]).unwrap();
+        let result = parse_checkbox_line_with("- [ ] ! Fix bug", false, &reversed).unwrap();
+        assert_eq!(result.priority, Priority::Low);
+    }
+
     #[test]
     fn test_tags() {
-        let result = parse_checkbox_line("- [ ] Review PR #work #urgent").unwrap();
+        let result = parse_checkbox_line("- [ ] Review PR #work #urgent", false).unwrap();
         assert_eq!(result.title, "Review PR");
         assert_eq!(result.tags, vec!["work", "urgent"]);
+
+        let nested = parse_checkbox_line("- [ ] Send invoice #work/clientA", false).unwrap();
+        assert_eq!(nested.title, "Send invoice");
+        assert_eq!(nested.tags, vec!["work/clientA"]);
+
+        let repeated = parse_checkbox_line("- [ ] Ship it #Work. #work #urgent,", false).unwrap();
+        assert_eq!(repeated.title, "Ship it");
+        assert_eq!(repeated.tags, vec!["Work", "urgent"]);
     }
 
     #[test]
     fn test_due_date_todotxt_style() {
-        let result = parse_checkbox_line("- [ ] Call dentist due:2025-03-20").unwrap();
+        let result = parse_checkbox_line("- [ ] Call dentist due:2025-03-20", false).unwrap();
         assert_eq!(result.title, "Call dentist");
         assert_eq!(
             result.due,
@@ -345,8 +608,11 @@ mod tests {
 
     #[test]
     fn test_full_tasks_plugin_line() {
-        let result =
-            parse_checkbox_line("- [ ] Review PR #work ⏫ 📅 2025-03-15 ➕ 2025-03-01").unwrap();
+        let result = parse_checkbox_line(
+            "- [ ] Review PR #work ⏫ 📅 2025-03-15 ➕ 2025-03-01",
+            false,
+        )
+        .unwrap();
         assert_eq!(result.title, "Review PR");
         assert_eq!(result.priority, Priority::High);
         assert_eq!(
@@ -363,7 +629,8 @@ mod tests {
     #[test]
     fn test_recurrence_skipped() {
         let result =
-            parse_checkbox_line("- [ ] Weekly review 🔁 every Monday 📅 2025-03-17").unwrap();
+            parse_checkbox_line("- [ ] Weekly review 🔁 every Monday 📅 2025-03-17", false)
+                .unwrap();
         assert_eq!(result.title, "Weekly review");
         assert_eq!(
             result.due,
@@ -371,18 +638,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_blockquote_task() {
+        let result = parse_checkbox_line("> - [ ] Quoted task #work", false).unwrap();
+        assert_eq!(result.title, "Quoted task");
+        assert_eq!(result.tags, vec!["work"]);
+        assert_eq!(result.prefix, "> ");
+    }
+
+    #[test]
+    fn test_nested_blockquote_task() {
+        let result = parse_checkbox_line("> > - [x] Nested quote", false).unwrap();
+        assert_eq!(result.title, "Nested quote");
+        assert_eq!(result.status, TaskStatus::Done);
+        assert_eq!(result.prefix, "> > ");
+
+        let result = parse_checkbox_line(">>   - [ ] Tight markers", false).unwrap();
+        assert_eq!(result.title, "Tight markers");
+        assert_eq!(result.prefix, ">>   ");
+    }
+
+    #[test]
+    fn test_indented_prefix() {
+        let result = parse_checkbox_line("    - [ ] Nested task", false).unwrap();
+        assert_eq!(result.prefix, "    ");
+    }
+
+    #[test]
+    fn test_parse_file_callout() {
+        let content = "\
+> [!todo] This week
+> - [ ] Inside callout
+> > - [ ] Nested inside
+";
+        let tasks = parse_file(content.lines(), false, true, &BangPriorities::default());
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].0, 2);
+        assert_eq!(tasks[0].1.title, "Inside callout");
+        assert_eq!(tasks[1].1.title, "Nested inside");
+    }
+
     #[test]
     fn test_not_a_checkbox() {
-        assert!(parse_checkbox_line("Just some text").is_none());
-        assert!(parse_checkbox_line("- Regular list item").is_none());
-        assert!(parse_checkbox_line("* [ ] Asterisk checkbox").is_none());
-        assert!(parse_checkbox_line("").is_none());
-        assert!(parse_checkbox_line("# Heading").is_none());
+        assert!(parse_checkbox_line("Just some text", false).is_none());
+        assert!(parse_checkbox_line("- Regular list item", false).is_none());
+        assert!(parse_checkbox_line("* [ ] Asterisk checkbox", false).is_none());
+        assert!(parse_checkbox_line("", false).is_none());
+        assert!(parse_checkbox_line("# Heading", false).is_none());
     }
 
     #[test]
     fn test_empty_checkbox() {
-        assert!(parse_checkbox_line("- [ ] ").is_none());
+        assert!(parse_checkbox_line("- [ ] ", false).is_none());
     }
 
     #[test]
@@ -399,7 +706,7 @@ mod tests {
 Some notes here
 - [ ] Another task #work
 ";
-        let tasks = parse_file(content);
+        let tasks = parse_file(content.lines(), false, true, &BangPriorities::default());
         assert_eq!(tasks.len(), 3);
         assert_eq!(tasks[0].0, 4);
         assert_eq!(tasks[0].1.title, "First task");
@@ -424,22 +731,163 @@ Some notes here
 - [ ] Also not a task
 ```
 ";
-        let tasks = parse_file(content);
+        let tasks = parse_file(content.lines(), false, true, &BangPriorities::default());
         assert_eq!(tasks.len(), 2);
         assert_eq!(tasks[0].1.title, "Real task");
         assert_eq!(tasks[1].1.title, "Another real task");
     }
 
+    #[test]
+    fn test_parse_file_keeps_code_blocks_when_asked() {
+        let content = "\
+## Plan
+- [ ] Real task
+
+```markdown
+# Not a heading
+- [ ] Fenced task #todo
+```
+- [ ] After the fence
+";
+        let tasks = parse_file(content.lines(), false, false, &BangPriorities::default());
+        let found: Vec<(usize, &str)> = tasks.iter().map(|(n, t)| (*n, t.title.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![(2, "Real task"), (6, "Fenced task"), (8, "After the fence")]
+        );
+        assert_eq!(tasks[1].1.tags, vec!["todo"]);
+        // The fence's "# " line isn't a heading
+        assert!(tasks
+            .iter()
+            .all(|(_, t)| t.heading_context.as_deref() == Some("Plan")));
+    }
+
+    #[test]
+    fn test_first_h1() {
+        let content = "\
+---
+tags: project
+---
+```
+# Not a heading
+```
+## Subheading
+# Website Redesign
+# Second H1
+";
+        assert_eq!(
+            first_h1(content.lines()),
+            Some("Website Redesign".to_string())
+        );
+        assert_eq!(first_h1("## Only h2\n- [ ] Task\n".lines()), None);
+    }
+
+    #[test]
+    fn test_child_lines() {
+        let content = "\
+- [ ] Plan trip
+  Book before March.
+  - [ ] Flights
+    - window seat
+
+  - Hotel
+- [ ] Next task
+";
+        assert_eq!(
+            child_lines(content, 1),
+            vec![
+                "Book before March.",
+                "- [ ] Flights",
+                "  - window seat",
+                "",
+                "- Hotel",
+            ]
+        );
+        assert_eq!(child_lines(content, 3), vec!["- window seat"]);
+        assert!(child_lines(content, 7).is_empty());
+        assert!(child_lines(content, 99).is_empty());
+    }
+
+    #[test]
+    fn test_pending_checkboxes() {
+        let children = child_lines(
+            "- [ ] Plan trip\n  - [ ] Flights\n  - [x] Visa\n    - [ ] Photos\n  - Hotel\n  - [-] Car\n",
+            1,
+        );
+        assert_eq!(pending_checkboxes(&children), 2);
+        assert_eq!(pending_checkboxes::<&str>(&[]), 0);
+    }
+
+    #[test]
+    fn test_tag_only_checkbox() {
+        assert!(parse_checkbox_line("- [ ] #waiting", false).is_none());
+
+        let result = parse_checkbox_line("- [ ] #waiting", true).unwrap();
+        assert_eq!(result.title, "");
+        assert_eq!(result.tags, vec!["waiting"]);
+
+        let content = "- [ ] #waiting\n- [ ] Real task\n";
+        assert_eq!(
+            parse_file(content.lines(), false, true, &BangPriorities::default()).len(),
+            1
+        );
+        let kept = parse_file(content.lines(), true, true, &BangPriorities::default());
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].0, 1);
+    }
+
+    #[test]
+    fn test_link_only_checkbox_keeps_link_as_title() {
+        let result = parse_checkbox_line("- [ ] [[Project Plan]] #work", false).unwrap();
+        assert_eq!(result.title, "[[Project Plan]]");
+        assert_eq!(result.tags, vec!["work"]);
+    }
+
+    #[test]
+    fn test_checkbox_spacing_variants() {
+        for line in ["-  [ ] Spaced", "-\t[ ] Spaced", "  - \t [x] Spaced"] {
+            let task = parse_checkbox_line(line, false).unwrap();
+            assert_eq!(task.title, "Spaced", "{:?}", line);
+        }
+        assert!(parse_checkbox_line("-[ ] No space", false).is_none());
+        assert!(parse_checkbox_line("- [?] Unknown", false).is_none());
+    }
+
+    #[test]
+    fn test_set_checkbox_status() {
+        assert_eq!(
+            set_checkbox_status("-\t[ ] Task 📅 2025-03-01", TaskStatus::Done).as_deref(),
+            Some("-\t[x] Task 📅 2025-03-01")
+        );
+        assert_eq!(
+            set_checkbox_status("> - [X] Shout", TaskStatus::Pending).as_deref(),
+            Some("> - [ ] Shout")
+        );
+        // Only the real checkbox changes, not one in the title
+        assert_eq!(
+            set_checkbox_status("- [x] Teach - [x] and - [ ]", TaskStatus::Pending).as_deref(),
+            Some("- [ ] Teach - [x] and - [ ]")
+        );
+        assert_eq!(
+            set_checkbox_status("- [X] Already", TaskStatus::Done).as_deref(),
+            Some("- [X] Already")
+        );
+        assert_eq!(
+            set_checkbox_status("Mentions - [ ] inline", TaskStatus::Done),
+            None
+        );
+    }
+
     #[test]
     fn test_parse_file_empty() {
-        let tasks = parse_file("");
+        let tasks = parse_file("".lines(), false, true, &BangPriorities::default());
         assert!(tasks.is_empty());
     }
 
     #[test]
     fn test_parse_file_no_tasks() {
         let content = "# Just a heading\n\nSome paragraph text.\n";
-        let tasks = parse_file(content);
+        let tasks = parse_file(content.lines(), false, true, &BangPriorities::default());
         assert!(tasks.is_empty());
     }
 }
@@ -0,0 +1,209 @@
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// How file reads and writes retry errors that go away by themselves, such
+/// as a network mount (SSHFS, NFS) returning EIO while it reconnects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Tries in all, counting the first; 1 never retries
+    pub attempts: u32,
+    /// Wait before the first retry, doubled before each one after
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: DEFAULT_RETRY_ATTEMPTS,
+            backoff: Duration::from_millis(DEFAULT_RETRY_BACKOFF_MS),
+        }
+    }
+}
+
+/// Default for `[backends] retry_attempts`.
+pub const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
+
+/// Default for `[backends] retry_backoff_ms`.
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 100;
+
+impl RetryPolicy {
+    /// Fail on the first error.
+    pub const NONE: Self = Self {
+        attempts: 1,
+        backoff: Duration::ZERO,
+    };
+}
+
+/// Whether `e` is worth another try: the errors a network filesystem gives
+/// while its connection drops and comes back.
+pub fn is_transient(e: &io::Error) -> bool {
+    use io::ErrorKind::*;
+
+    // EIO is 5 on every Unix; ESTALE's number varies, so only Linux's
+    #[cfg(unix)]
+    const EIO: i32 = 5;
+    #[cfg(target_os = "linux")]
+    const ESTALE: i32 = 116;
+
+    // Still what it was after retrying
+    if let Some(gave_up) = e.get_ref().and_then(|inner| inner.downcast_ref::<GaveUp>()) {
+        return is_transient(&gave_up.error);
+    }
+    if matches!(
+        e.kind(),
+        Interrupted | WouldBlock | TimedOut | NotConnected | ConnectionReset | ConnectionAborted
+    ) {
+        return true;
+    }
+    #[cfg(unix)]
+    if e.raw_os_error() == Some(EIO) {
+        return true;
+    }
+    #[cfg(target_os = "linux")]
+    if e.raw_os_error() == Some(ESTALE) {
+        return true;
+    }
+    false
+}
+
+/// The last error of an operation that was retried, and how many tries
+/// it got.
+#[derive(Debug)]
+struct GaveUp {
+    error: io::Error,
+    attempts: u32,
+}
+
+impl std::fmt::Display for GaveUp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (gave up after {} attempts)",
+            self.error, self.attempts
+        )
+    }
+}
+
+impl std::error::Error for GaveUp {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Run `op`, trying again after a transient error as `policy` allows. An
+/// error after retrying says how many attempts were made, so a failed write
+/// is clearly a failure rather than one that may have gone through. The
+/// waits between tries yield to the runtime rather than block a thread.
+pub async fn retry<T>(
+    policy: RetryPolicy,
+    what: &str,
+    mut op: impl FnMut() -> io::Result<T>,
+) -> io::Result<T> {
+    let mut wait = policy.backoff;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => {
+                if attempt > 1 {
+                    tracing::info!("{} succeeded on attempt {}", what, attempt);
+                }
+                return Ok(value);
+            }
+            Err(e) if attempt < policy.attempts && is_transient(&e) => {
+                tracing::warn!("{} failed ({}), retrying in {:?}", what, e, wait);
+                tokio::time::sleep(wait).await;
+                wait *= 2;
+                attempt += 1;
+            }
+            Err(e) if attempt > 1 => {
+                return Err(io::Error::new(
+                    e.kind(),
+                    GaveUp {
+                        error: e,
+                        attempts: attempt,
+                    },
+                ));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// `fs::read_to_string`, retried.
+pub async fn read_to_string(policy: RetryPolicy, path: &Path) -> io::Result<String> {
+    let what = format!("Reading {}", path.display());
+    retry(policy, &what, || std::fs::read_to_string(path)).await
+}
+
+/// `fs::write`, retried. Each attempt rewrites the whole file, so a retry
+/// after a partial write leaves nothing behind.
+pub async fn write(policy: RetryPolicy, path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let what = format!("Writing {}", path.display());
+    retry(policy, &what, || std::fs::write(path, contents.as_ref())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_millis(1),
+        }
+    }
+
+    /// An operation that fails with `error` the first `failures` times.
+    fn flaky(failures: u32, error: fn() -> io::Error) -> impl FnMut() -> io::Result<u32> {
+        let mut calls = 0;
+        move || {
+            calls += 1;
+            if calls <= failures {
+                Err(error())
+            } else {
+                Ok(calls)
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let eio = || io::Error::from_raw_os_error(5);
+        let not_connected = || io::Error::from(io::ErrorKind::NotConnected);
+
+        assert_eq!(retry(policy(), "read", flaky(2, eio)).await.unwrap(), 3);
+        assert_eq!(
+            retry(policy(), "read", flaky(1, not_connected))
+                .await
+                .unwrap(),
+            2
+        );
+
+        let err = retry(policy(), "read", flaky(3, eio)).await.unwrap_err();
+        assert!(
+            err.to_string().contains("gave up after 3 attempts"),
+            "{}",
+            err
+        );
+        assert!(is_transient(&err));
+
+        assert!(retry(RetryPolicy::NONE, "read", flaky(1, eio))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_permanent_errors_fail_at_once() {
+        let mut calls = 0;
+        let err = retry(policy(), "read", || {
+            calls += 1;
+            Err::<(), _>(io::Error::from(io::ErrorKind::NotFound))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(calls, 1);
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(!err.to_string().contains("gave up"));
+    }
+}
@@ -14,6 +14,18 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub config: Option<PathBuf>,
 
+    /// Directory for config.toml, themes/ and state (default: ~/.config/tasuki)
+    #[arg(long, global = true)]
+    pub config_dir: Option<PathBuf>,
+
+    /// Use profiles/<name>.toml in the config dir, with its own themes and state
+    #[arg(long, global = true, env = "TASUKI_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Treat this date as today (for demos and tests)
+    #[arg(long, global = true, hide = true, env = "TASUKI_TODAY")]
+    pub today: Option<chrono::NaiveDate>,
+
     /// Increase log verbosity (-v, -vv, -vvv)
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
@@ -22,7 +34,28 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Command {
     /// Output JSON for Waybar custom module
-    Waybar,
+    Waybar {
+        /// Print a plain line like "2! 3 today" instead of JSON
+        #[arg(long)]
+        plain: bool,
+
+        /// Also count tasks done today, like `[waybar] include_done`
+        #[arg(long)]
+        all: bool,
+
+        /// Keep running, printing a line every minute and whenever a task
+        /// file changes (for Waybar's continuous modules)
+        #[arg(long)]
+        watch: bool,
+
+        /// Seconds between lines with `--watch` (implies it; default 60)
+        #[arg(long, value_name = "SECS")]
+        interval: Option<f64>,
+
+        /// Stop after this many lines
+        #[arg(long, value_name = "N")]
+        count: Option<usize>,
+    },
 
     /// Open the interactive TUI (default in terminal)
     Tui,
@@ -30,22 +63,236 @@ pub enum Command {
     /// Quick-add a task from the command line
     Add {
         /// Task text (supports natural language: "Buy milk tomorrow #groceries @obsidian")
+        #[arg(required_unless_present_any = ["stdin", "clipboard"])]
         text: Vec<String>,
+
+        /// Add a task per line read from stdin (`- [ ]` checklists or plain lines)
+        #[arg(long, group = "batch", conflicts_with_all = ["text", "clipboard"])]
+        stdin: bool,
+
+        /// Like --stdin, reading the clipboard (wl-paste, xclip or xsel)
+        #[arg(long, group = "batch", conflicts_with = "text")]
+        clipboard: bool,
+
+        /// With --stdin/--clipboard: show what would be added without adding
+        #[arg(long, requires = "batch")]
+        dry_run: bool,
+
+        /// Backend for tasks without an @backend: local, obsidian
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Print the created task as JSON
+        #[arg(long, conflicts_with_all = ["stdin", "clipboard", "quiet"])]
+        json: bool,
+
+        /// Print only the new task's ID
+        #[arg(short, long, visible_alias = "print-id", conflicts_with_all = ["stdin", "clipboard"])]
+        quiet: bool,
     },
 
     /// List tasks to stdout (for scripting)
     List {
-        /// Filter: today, upcoming, all, notes, done
+        /// Filter: today, overdue, week, upcoming, all, notes, done
         #[arg(default_value = "today")]
         filter: String,
 
+        /// Output format: text, table, json, json-compat (bare array, deprecated)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Comma-separated JSON fields to emit (e.g. id,title,due,tags)
+        #[arg(long, value_delimiter = ',')]
+        fields: Vec<String>,
+
+        /// Print each task with a template, e.g. "{status} {priority} {title} {due}"
+        #[arg(long, conflicts_with = "format")]
+        output_template: Option<String>,
+
+        /// Text output in sections: due, tag, backend, priority
+        #[arg(long)]
+        group: Option<String>,
+
+        /// Only tasks with this tag; `work` also matches `work/clientA`
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Show at most this many tasks
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Skip this many tasks first, to page through with `--limit`
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+
+        /// Print straight to the terminal instead of through `$PAGER`
+        #[arg(long)]
+        no_pager: bool,
+
+        /// Most urgent last: reverse the sort order (and section order
+        /// with --group)
+        #[arg(long)]
+        reverse: bool,
+
+        /// Keep each task on one line in text output instead of wrapping
+        /// long titles to the terminal's width
+        #[arg(long)]
+        no_wrap: bool,
+    },
+
+    /// Show a single task in detail (exits 4 if no task matches)
+    Show {
+        /// Number from the last `list`, exact task ID (as printed by
+        /// `list --format json`) or a title query
+        #[arg(required_unless_present = "id")]
+        query: Vec<String>,
+
+        /// Exact task ID only, no number or title matching (exits 2 if it
+        /// no longer resolves)
+        #[arg(long, conflicts_with = "query")]
+        id: Option<String>,
+
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Mark a task done (exits 4 if no task matches)
+    Done {
+        /// Number from the last `list`, exact task ID or a title query
+        #[arg(required_unless_present = "id")]
+        query: Vec<String>,
+
+        /// Exact task ID only, no number or title matching
+        #[arg(long, conflicts_with = "query")]
+        id: Option<String>,
+    },
+
+    /// Delete a task (asks for confirmation unless --yes)
+    Delete {
+        /// Number from the last `list`, exact task ID or a title query
+        #[arg(required_unless_present = "id")]
+        query: Vec<String>,
+
+        /// Exact task ID only, no number or title matching
+        #[arg(long, conflicts_with = "query")]
+        id: Option<String>,
+
+        /// Skip the confirmation prompt (required when stdin is not a terminal)
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Move a task to another backend (recreated there, then deleted here)
+    Move {
+        /// Number from the last `list`, exact task ID or a title query
+        #[arg(required_unless_present = "id")]
+        query: Vec<String>,
+
+        /// Exact task ID only, no number or title matching
+        #[arg(long, conflicts_with = "query")]
+        id: Option<String>,
+
+        /// Backend to move it to: local, obsidian
+        #[arg(long)]
+        to: String,
+    },
+
+    /// Mirror tasks from one backend into another and sync completion both ways
+    Sync {
+        /// Backend to mirror from: local, obsidian
+        #[arg(long)]
+        from: String,
+
+        /// Backend to mirror into: local, obsidian
+        #[arg(long)]
+        to: String,
+
+        /// Only mirror source tasks with this tag (without the #), or nested under it
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Resolve conflicts (both sides changed) in favour of: from, to
+        #[arg(long)]
+        prefer: Option<String>,
+    },
+
+    /// Show enabled backends with their task counts and fetch times
+    Backends,
+
+    /// Show pending counts, tasks done today, the daily goal streak and
+    /// completion per tag and project
+    Stats {
         /// Output format: text, json
         #[arg(short, long, default_value = "text")]
         format: String,
     },
 
+    /// Print pending tasks under a header per due date
+    Agenda {
+        /// How many days ahead get a header of their own; later tasks are
+        /// listed together
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+
+        /// Day the agenda is for: today, tomorrow or YYYY-MM-DD
+        #[arg(long, default_value = "today")]
+        date: String,
+
+        /// Output format: text, markdown, html
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// List tasks completed over a span of days, and what's still overdue
+    Report {
+        /// First day: today, yesterday, a weekday (the last one) or YYYY-MM-DD
+        #[arg(long, default_value = "monday")]
+        since: String,
+
+        /// Last day, included; same forms as --since
+        #[arg(long, default_value = "today")]
+        until: String,
+
+        /// Only tasks with this tag (without the #), or nested under it
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Split each day by project, or first tag
+        #[arg(long)]
+        by_project: bool,
+
+        /// Output format: text, markdown, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Print time tracked with the TUI's timer, per day and task
+    Timelog {
+        /// Only tasks with this ID, or with this in their title
+        #[arg(long)]
+        task: Option<String>,
+
+        /// First day: today, yesterday, a weekday (the last one) or YYYY-MM-DD
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Answer JSON-RPC 2.0 requests (tasks/list, tasks/create, ...) for editors
+    Serve {
+        /// Read requests from stdin and reply on stdout, one per line
+        #[arg(long)]
+        stdio: bool,
+    },
+
     /// Print the active config (resolved, with defaults)
-    Config,
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+
+    /// Alias for `config edit`
+    EditConfig,
 
     /// Generate shell completions
     Completions {
@@ -53,3 +300,15 @@ pub enum Command {
         shell: Shell,
     },
 }
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Validate a config file without side effects (non-zero exit on errors)
+    Check {
+        /// Config file to check (default: --config or ~/.config/tasuki/config.toml)
+        path: Option<PathBuf>,
+    },
+
+    /// Open the config in your editor, then validate it (non-zero exit on errors)
+    Edit,
+}
@@ -0,0 +1,26 @@
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use std::sync::OnceLock;
+
+// The date tasuki treats as "today", which `--today` / `$TASUKI_TODAY` can
+// pin for demos, screenshots and deterministic tests.
+
+static TODAY_OVERRIDE: OnceLock<NaiveDate> = OnceLock::new();
+
+/// Pin `today()` to `date` for the rest of the process. Only the first call
+/// takes effect.
+pub fn set_today(date: NaiveDate) {
+    let _ = TODAY_OVERRIDE.set(date);
+}
+
+/// Today's date: the override if one was set, else the local date.
+pub fn today() -> NaiveDate {
+    TODAY_OVERRIDE
+        .get()
+        .copied()
+        .unwrap_or_else(|| Local::now().date_naive())
+}
+
+/// The local time of day on `today()`.
+pub fn now() -> NaiveDateTime {
+    today().and_time(Local::now().time())
+}
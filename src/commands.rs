@@ -0,0 +1,1056 @@
+//! The `tasuki` binary: argument handling and the CLI subcommands.
+
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use clap::{CommandFactory, Parser};
+use tracing::info;
+
+use crate::backends::obsidian::ObsidianBackend;
+use crate::backends::BackendManager;
+use crate::cli::{Cli, Command, ConfigAction};
+use crate::config::{self, Config};
+use crate::error::{Result, TasukiError};
+use crate::last_list::{self, LastList, ListedTask};
+use crate::model::{self, BackendSource, Priority, Task, TaskFilter, TaskStatus};
+use crate::nlp::parse_quick_add_with;
+use crate::{
+    agenda, clock, editor, export, hooks, import, output, report, serve, stats, streak, sync,
+    timelog, tui, waybar,
+};
+
+const NO_BACKENDS_MSG: &str = "No backends enabled.\n\nCreate ~/.config/tasuki/config.toml with:\n\n[backends.local]\nenabled = true\n\nTasks are stored in ~/.local/share/tasuki/todo.txt by default.";
+
+fn setup_logging(verbose: u8) {
+    let filter = match verbose {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+}
+
+#[tokio::main]
+pub async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    setup_logging(cli.verbose);
+
+    if let Some(today) = cli.today {
+        clock::set_today(today);
+    }
+
+    info!("Starting tasuki v0.0.1");
+
+    // Must run before Config::load so a broken file can still be diagnosed
+    if let Some(Command::Config {
+        action: Some(ConfigAction::Check { ref path }),
+    }) = cli.command
+    {
+        let path = path.clone().or(cli.config.clone());
+        let result = Config::resolve_path(path, cli.config_dir.as_deref(), cli.profile.as_deref())
+            .and_then(check_config);
+        return match result {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ExitCode::from(e.exit_code())
+            }
+        };
+    }
+
+    if matches!(
+        cli.command,
+        Some(Command::EditConfig)
+            | Some(Command::Config {
+                action: Some(ConfigAction::Edit)
+            })
+    ) {
+        let result = Config::resolve_path(
+            cli.config.clone(),
+            cli.config_dir.as_deref(),
+            cli.profile.as_deref(),
+        )
+        .and_then(edit_config);
+        return match result {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ExitCode::from(e.exit_code())
+            }
+        };
+    }
+
+    // $TASUKI_BACKENDS stands in for the config file unless one is named
+    let backends_spec = std::env::var(crate::config::BACKENDS_ENV)
+        .ok()
+        .filter(|s| !s.trim().is_empty());
+    let loaded = if backends_spec.is_some()
+        && cli.config.is_none()
+        && cli.config_dir.is_none()
+        && cli.profile.is_none()
+    {
+        Ok(Config::default())
+    } else {
+        Config::load(
+            cli.config.clone(),
+            cli.config_dir.clone(),
+            cli.profile.clone(),
+        )
+    }
+    .and_then(|config| config.with_backends_spec(backends_spec));
+    let config = match loaded {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Error loading config: {}", e);
+            return ExitCode::from(e.exit_code());
+        }
+    };
+
+    // TTY = TUI, non-TTY = Waybar
+    let is_tty = atty::is(atty::Stream::Stdout);
+    let command = cli.command.unwrap_or(if is_tty {
+        Command::Tui
+    } else {
+        Command::Waybar {
+            plain: false,
+            all: false,
+            watch: false,
+            interval: None,
+            count: None,
+        }
+    });
+
+    match run(command, config).await {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+/// A task for `list` text output after `prefix` (its number), wrapped to
+/// `width` columns if given.
+fn text_line(task: &model::Task, config: &Config, prefix: &str, width: Option<usize>) -> String {
+    let icon = match task.status {
+        TaskStatus::Pending => "☐",
+        TaskStatus::Done => "✓",
+    };
+    let due_str = task
+        .due
+        .map(|d| format!(" (due {})", output::format_date(d, config, clock::today())))
+        .unwrap_or_default();
+    let priority_str = match task.priority {
+        Priority::High => " [!]",
+        Priority::Medium => "",
+        Priority::Low => "",
+        Priority::None => "",
+    };
+    let prefix = format!("{}{} ", prefix, icon);
+    let suffix = format!("{}{}", due_str, priority_str);
+    match width {
+        Some(width) => output::wrap_line(&prefix, &task.title, &suffix, width),
+        None => format!("{}{}{}", prefix, task.title, suffix),
+    }
+}
+
+/// Remember the tasks `list` numbered, in order, so `done 3` can find them
+/// again. No tasks clears the numbers of an earlier listing.
+fn save_last_list(config: &Config, tasks: Vec<ListedTask>) {
+    let Some(path) = LastList::path(config) else {
+        return;
+    };
+    let saved = if tasks.is_empty() {
+        LastList::clear(&path)
+    } else {
+        LastList::new(tasks).save(&path)
+    };
+    if let Err(e) = saved {
+        tracing::warn!("Could not save list numbers {}: {}", path.display(), e);
+    }
+}
+
+/// The task `--id` or `query` names: a number from the last `list`, an
+/// exact ID or a title query. `--id` skips straight to the exact ID, and
+/// one that no longer resolves is a stale ID (exit 2). Numbers and
+/// `--id` fail the same way if the task there isn't the one last listed.
+async fn find_task(
+    backend_manager: &BackendManager,
+    config: &Config,
+    query: &[String],
+    id: Option<&str>,
+) -> Result<model::Task> {
+    let path = LastList::path(config);
+    let task = if let Some(id) = id {
+        backend_manager.task_by_id(id).await.map_err(|e| match e {
+            TasukiError::NotFound(message) => TasukiError::StaleId(message),
+            e => e,
+        })?
+    } else {
+        let query = query.join(" ");
+        match last_list::resolve(&query, path.as_deref())? {
+            Some(listed) => backend_manager.find_task(&listed.id).await?,
+            None => return backend_manager.find_task(&query).await,
+        }
+    };
+    if let Some(list) = path.as_deref().and_then(LastList::load) {
+        list.check(&task)?;
+    }
+    Ok(task)
+}
+
+fn check_config(path: std::path::PathBuf) -> Result<()> {
+    let diagnostics = Config::check(&path);
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == config::Severity::Error)
+        .count();
+
+    if errors > 0 {
+        return Err(TasukiError::Config(format!(
+            "{} has {} error(s)",
+            path.display(),
+            errors
+        )));
+    }
+
+    println!("✓ {} is valid", path.display());
+    Ok(())
+}
+
+/// Open `path` in the configured editor, then run the same checks as
+/// `config check` so mistakes are reported before the TUI or Waybar trips
+/// over them.
+fn edit_config(path: std::path::PathBuf) -> Result<()> {
+    // A broken config shouldn't stop you from fixing it, so fall back to
+    // the environment when it can't be read
+    let general = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| toml::from_str::<Config>(&content).ok())
+        .map(|c| c.general)
+        .unwrap_or_default();
+    let editor = editor::resolve(&general);
+    let cmd = editor::command(&editor, &[path.to_string_lossy().into_owned()])?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let status = std::process::Command::new(&cmd[0])
+        .args(&cmd[1..])
+        .status()
+        .map_err(|e| TasukiError::Config(format!("Failed to start editor '{}': {}", editor, e)))?;
+    if !status.success() {
+        return Err(TasukiError::Aborted(format!(
+            "{} exited with code {}",
+            editor,
+            status.code().unwrap_or(-1)
+        )));
+    }
+
+    if !path.exists() {
+        return Ok(());
+    }
+    check_config(path)
+}
+
+/// Ask before a destructive CLI operation, mirroring the TUI confirm dialog.
+/// `--yes` skips the prompt; without it a non-interactive stdin is refused.
+fn confirm(prompt: &str, yes: bool) -> Result<()> {
+    use std::io::{BufRead, Write};
+
+    if yes {
+        return Ok(());
+    }
+
+    if !atty::is(atty::Stream::Stdin) {
+        return Err(TasukiError::Aborted(
+            "stdin is not a terminal; pass --yes to confirm".into(),
+        ));
+    }
+
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(TasukiError::Aborted("not confirmed".into())),
+    }
+}
+
+/// Create a task per line of `input` and print how each line went, as a
+/// table. Fails after the table if any task could not be created.
+async fn import_lines(
+    backend_manager: &BackendManager,
+    config: &Config,
+    input: &str,
+    backend: BackendSource,
+    dry_run: bool,
+) -> Result<()> {
+    let mut failed = 0;
+
+    let options = backend_manager.quick_add_options(config);
+    for line in import::plan(input, &options, backend) {
+        let (result, detail) = match line.outcome {
+            import::Outcome::Create(new_task) if dry_run => (
+                "would create",
+                format!("{} in {}", new_task.title, new_task.backend.name()),
+            ),
+            import::Outcome::Create(new_task) => {
+                match backend_manager.create_task(&new_task).await {
+                    Ok(task) => ("created", format!("{} ({})", task.title, task.id)),
+                    Err(e) => {
+                        failed += 1;
+                        ("failed", format!("{}: {}", line.text, e))
+                    }
+                }
+            }
+            import::Outcome::SkippedDone => ("skipped-done", line.text),
+            import::Outcome::SkippedChild(parent) => (
+                "skipped",
+                format!("{} (note under line {})", line.text, parent),
+            ),
+            import::Outcome::SkippedHeading => ("skipped", line.text),
+            import::Outcome::ParseFailed(e) => ("parse-failed", format!("{}: {}", line.text, e)),
+        };
+        println!("{:>4}  {:<13} {}", line.line, result, detail);
+    }
+
+    if failed > 0 {
+        return Err(TasukiError::Backend {
+            backend: backend.name().into(),
+            message: format!("{} task(s) could not be created", failed),
+        });
+    }
+    Ok(())
+}
+
+/// The file `task` was written to: vault-relative for Obsidian, the full
+/// path otherwise.
+fn created_in(task: &Task) -> Option<String> {
+    if task.source == BackendSource::Obsidian {
+        if let Ok((rel_path, _)) = ObsidianBackend::parse_task_id(&task.id) {
+            return Some(rel_path);
+        }
+    }
+    task.source_path.clone()
+}
+
+/// The backend called `name` in an option such as `--backend`.
+fn parse_source(name: &str) -> Result<BackendSource> {
+    BackendSource::from_name(name).ok_or_else(|| {
+        TasukiError::Parse(format!(
+            "Unknown backend '{}' (valid: {})",
+            name,
+            BackendSource::names()
+        ))
+    })
+}
+
+async fn run(command: Command, config: Config) -> Result<()> {
+    match command {
+        Command::Waybar {
+            plain,
+            all,
+            watch,
+            interval,
+            count,
+        } => {
+            let mut config = config;
+            config.waybar.include_done |= all;
+            let backend_manager = BackendManager::from_config(&config)?;
+            if watch || interval.is_some() || count.is_some() {
+                let secs = interval.unwrap_or(60.0);
+                if !(secs.is_finite() && secs > 0.0) {
+                    return Err(TasukiError::Parse(format!(
+                        "--interval must be a positive number of seconds, not {}",
+                        secs
+                    )));
+                }
+                let interval = std::time::Duration::from_secs_f64(secs);
+                waybar::watch(&backend_manager, &config, plain, interval, count).await?;
+            } else {
+                waybar::output(&backend_manager, &config, plain).await?;
+            }
+        }
+        Command::Tui => {
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            tui::run(backend_manager, config).await?;
+        }
+        Command::Add {
+            text,
+            stdin,
+            clipboard,
+            dry_run,
+            backend,
+            json,
+            quiet,
+        } => {
+            let task_text = text.join(" ");
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let default_backend = match backend {
+                Some(name) => parse_source(&name)?,
+                None => backend_manager
+                    .default_source()
+                    .unwrap_or(BackendSource::LocalFile),
+            };
+
+            if stdin || clipboard {
+                let input = if clipboard {
+                    import::read_clipboard()?
+                } else {
+                    std::io::read_to_string(std::io::stdin())?
+                };
+                return import_lines(&backend_manager, &config, &input, default_backend, dry_run)
+                    .await;
+            }
+
+            let new_task =
+                parse_quick_add_with(&task_text, &backend_manager.quick_add_options(&config))?
+                    .into_new_task(default_backend);
+
+            let task = backend_manager.create_task(&new_task).await?;
+            if let Some(caps) = backend_manager.capabilities(task.source) {
+                let dropped = caps.dropped_fields(&new_task);
+                if !dropped.is_empty() {
+                    eprintln!(
+                        "warning: {} does not support {}; it was not saved",
+                        task.source.name(),
+                        dropped.join(", ")
+                    );
+                }
+            }
+            let fallback = (task.source != new_task.backend)
+                .then(|| format!("{} is not enabled", new_task.backend.name()));
+
+            if json {
+                if let Some(ref note) = fallback {
+                    eprintln!("note: created in {}; {}", task.source.name(), note);
+                }
+                let json = serde_json::to_string_pretty(&serde_json::json!({
+                    "version": output::JSON_SCHEMA_VERSION,
+                    "task": output::TaskView::new(&task, output::Field::ALL),
+                }))?;
+                println!("{}", json);
+            } else if quiet {
+                if let Some(ref note) = fallback {
+                    eprintln!("note: created in {}; {}", task.source.name(), note);
+                }
+                println!("{}", task.id);
+            } else {
+                let mut line = format!(
+                    "✓ Created task: {} (ID: {}) in {}",
+                    task.title,
+                    task.id,
+                    task.source.name()
+                );
+                if let Some(file) = created_in(&task) {
+                    line.push_str(&format!(" ({})", file));
+                }
+                if let Some(note) = fallback {
+                    line.push_str(&format!(" — {}", note));
+                }
+                println!("{}", line);
+            }
+        }
+        Command::List {
+            filter,
+            format,
+            fields,
+            output_template,
+            group,
+            tag,
+            limit,
+            offset,
+            no_pager,
+            no_wrap,
+            reverse,
+        } => {
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let mut task_filter = match filter.as_str() {
+                "today" => TaskFilter {
+                    status: Some(TaskStatus::Pending),
+                    due_before: Some(clock::today()),
+                    ..Default::default()
+                },
+                "overdue" => TaskFilter {
+                    status: Some(TaskStatus::Pending),
+                    due_before: Some(clock::today() - chrono::Duration::days(1)),
+                    has_due: Some(true),
+                    ..Default::default()
+                },
+                "week" => TaskFilter {
+                    status: Some(TaskStatus::Pending),
+                    due_before: Some(clock::today() + chrono::Duration::days(7)),
+                    has_due: Some(true),
+                    ..Default::default()
+                },
+                "upcoming" => TaskFilter {
+                    status: Some(TaskStatus::Pending),
+                    due_after: Some(clock::today() + chrono::Duration::days(1)),
+                    ..Default::default()
+                },
+                "notes" => TaskFilter {
+                    status: Some(TaskStatus::Pending),
+                    has_due: Some(false),
+                    ..Default::default()
+                },
+                "all" => TaskFilter::default(),
+                "done" => TaskFilter {
+                    status: Some(TaskStatus::Done),
+                    ..Default::default()
+                },
+                _ => TaskFilter::default(),
+            };
+            task_filter.tag = tag;
+
+            let fields = if fields.is_empty() {
+                output::Field::ALL.to_vec()
+            } else {
+                output::Field::parse_list(&fields)?
+            };
+
+            let group = group
+                .as_deref()
+                .map(str::parse::<output::GroupBy>)
+                .transpose()?;
+
+            let template = output_template
+                .as_deref()
+                .map(str::parse::<output::Template>)
+                .transpose()?;
+
+            // Already sorted, so a page is a slice of the full listing
+            let mut tasks = backend_manager.all_tasks(&task_filter).await?;
+            if reverse {
+                tasks.reverse();
+            }
+            let tasks: Vec<Task> = tasks
+                .into_iter()
+                .skip(offset)
+                .take(limit.unwrap_or(usize::MAX))
+                .collect();
+
+            let mut text = String::new();
+            let mut numbered = Vec::new();
+
+            if let Some(template) = template {
+                for task in &tasks {
+                    writeln!(text, "{}", template.render(task)).ok();
+                }
+                output::page(&text, no_pager);
+                save_last_list(&config, numbered);
+                return Ok(());
+            }
+
+            match format.as_str() {
+                "json" => {
+                    let json =
+                        serde_json::to_string_pretty(&output::TaskList::new(&tasks, &fields))?;
+                    writeln!(text, "{}", json).ok();
+                }
+                "json-compat" => {
+                    let json =
+                        serde_json::to_string_pretty(&output::TaskArray::new(&tasks, &fields))?;
+                    writeln!(text, "{}", json).ok();
+                }
+                "table" if !tasks.is_empty() => {
+                    let table =
+                        output::table(&tasks, &config, clock::today(), output::terminal_width());
+                    writeln!(text, "{}", table).ok();
+                    numbered = tasks.iter().map(ListedTask::from).collect();
+                }
+                _ => {
+                    let wrap = output::wrap_width(no_wrap);
+                    if tasks.is_empty() {
+                        writeln!(text, "No tasks found.").ok();
+                    } else if let Some(group) = group {
+                        let color = atty::is(atty::Stream::Stdout);
+                        let today = clock::today();
+                        let mut sections = output::group_tasks(&tasks, group, &config, today);
+                        // The tasks are reversed already; the sections follow
+                        if reverse {
+                            sections.reverse();
+                        }
+                        for (i, section) in sections.iter().enumerate() {
+                            if i > 0 {
+                                text.push('\n');
+                            }
+                            let header = format!("{} ({})", section.label, section.tasks.len());
+                            match (color, section.date) {
+                                (true, Some(d)) if d < today => {
+                                    writeln!(text, "\x1b[1;31m{}\x1b[0m", header)
+                                }
+                                (true, _) => writeln!(text, "\x1b[1;36m{}\x1b[0m", header),
+                                (false, _) => writeln!(text, "{}", header),
+                            }
+                            .ok();
+                            for task in &section.tasks {
+                                numbered.push(ListedTask::from(task));
+                                let number = format!("  {:>3} ", numbered.len());
+                                writeln!(text, "{}", text_line(task, &config, &number, wrap)).ok();
+                            }
+                        }
+                    } else {
+                        for task in &tasks {
+                            numbered.push(ListedTask::from(task));
+                            let number = format!("{:>3} ", numbered.len());
+                            writeln!(text, "{}", text_line(task, &config, &number, wrap)).ok();
+                        }
+                    }
+                }
+            }
+            output::page(&text, no_pager);
+            save_last_list(&config, numbered);
+        }
+        Command::Show { query, id, format } => {
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let task = find_task(&backend_manager, &config, &query, id.as_deref()).await?;
+
+            match format.as_str() {
+                "json" => {
+                    let json = serde_json::to_string_pretty(&serde_json::json!({
+                        "version": output::JSON_SCHEMA_VERSION,
+                        "task": output::TaskView::new(&task, output::Field::ALL),
+                    }))?;
+                    println!("{}", json);
+                }
+                _ => {
+                    let today = clock::today();
+                    println!("{}", task.title);
+                    for (label, value) in output::detail_rows(&task, &config, today) {
+                        println!("  {:<10} {}", format!("{}:", label), value);
+                    }
+                }
+            }
+        }
+        Command::Done { query, id } => {
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let mut task = find_task(&backend_manager, &config, &query, id.as_deref()).await?;
+            if task.status == TaskStatus::Done {
+                println!("Already done: {} (ID: {})", task.title, task.id);
+                return Ok(());
+            }
+
+            task.id = backend_manager
+                .complete_task(&task.id, Some(&task.title))
+                .await?;
+            task.status = TaskStatus::Done;
+            hooks::on_complete(&config.general, &task);
+            println!("✓ Completed: {} (ID: {})", task.title, task.id);
+        }
+        Command::Delete { query, id, yes } => {
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let task = find_task(&backend_manager, &config, &query, id.as_deref()).await?;
+            confirm(&format!("Delete \"{}\"?", task.title), yes)?;
+
+            backend_manager.delete_task(&task.id).await?;
+            println!("✓ Deleted task: {} (ID: {})", task.title, task.id);
+        }
+        Command::Move { query, id, to } => {
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let to = parse_source(&to)?;
+            let task = find_task(&backend_manager, &config, &query, id.as_deref()).await?;
+            let moved = backend_manager.move_task(&task, to).await?;
+            println!("✓ Moved: {} ({} → {})", moved.title, task.id, moved.id);
+        }
+        Command::Backends => {
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let (_, reports) = backend_manager
+                .all_tasks_timed(&TaskFilter::default())
+                .await;
+            for report in &reports {
+                let elapsed = format!("{}ms", report.elapsed.as_millis());
+                match report.result {
+                    Ok(count) => println!(
+                        "{:<10} {:>5} tasks  {:>7}",
+                        report.source.name(),
+                        count,
+                        elapsed
+                    ),
+                    Err(ref e) => {
+                        println!("{:<10} error: {} ({})", report.source.name(), e, elapsed)
+                    }
+                }
+            }
+            println!("updated {}", chrono::Local::now().format("%H:%M:%S"));
+
+            if reports.iter().any(|r| r.result.is_err()) {
+                return Err(TasukiError::Backend {
+                    backend: "backends".into(),
+                    message: "one or more backends failed".into(),
+                });
+            }
+        }
+        Command::Stats { format } => {
+            if !["text", "json"].contains(&format.as_str()) {
+                return Err(TasukiError::Parse(format!(
+                    "Unknown stats format '{}' (valid: text, json)",
+                    format
+                )));
+            }
+
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let tasks = backend_manager.all_tasks(&TaskFilter::default()).await?;
+            let today = clock::today();
+            let pending: Vec<_> = tasks
+                .iter()
+                .filter(|t| t.status == TaskStatus::Pending)
+                .collect();
+            let overdue = pending
+                .iter()
+                .filter(|t| t.due.is_some_and(|d| d < today))
+                .count();
+            let due_today = pending.iter().filter(|t| t.due == Some(today)).count();
+            let progress = config.general.daily_goal.map(|goal| {
+                let history = streak::History::path(&config);
+                streak::progress(history.as_deref(), &tasks, goal, today)
+            });
+
+            let week = crate::nlp::start_of_week(today, config.general.week_start());
+            let mut tracked = timelog::log_path(&config)
+                .map(|path| timelog::tracked_since(&timelog::read(&path), week))
+                .unwrap_or_else(chrono::Duration::zero);
+            if let Some(timer) =
+                timelog::Timer::path(&config).and_then(|p| timelog::Timer::load(&p))
+            {
+                if timer.start.date() >= week {
+                    tracked += timer.elapsed(chrono::Local::now().naive_local());
+                }
+            }
+
+            let tags = stats::by_tag(&tasks);
+            let projects = stats::by_project(&tasks);
+
+            if format == "json" {
+                let json = serde_json::json!({
+                    "version": output::JSON_SCHEMA_VERSION,
+                    "pending": pending.len(),
+                    "overdue": overdue,
+                    "due_today": due_today,
+                    "done_today": progress.as_ref().map_or(streak::done_on(&tasks, today), |p| p.done_today),
+                    "goal": config.general.daily_goal,
+                    "streak": progress.as_ref().map(|p| p.streak),
+                    "best_streak": progress.as_ref().map(|p| p.best_streak),
+                    "tracked_this_week_secs": tracked.num_seconds(),
+                    "tags": tags,
+                    "projects": projects,
+                });
+                println!("{}", serde_json::to_string_pretty(&json)?);
+                return Ok(());
+            }
+
+            println!(
+                "{:<11} {} ({} overdue, {} due today)",
+                "pending",
+                pending.len(),
+                overdue,
+                due_today
+            );
+            match progress {
+                Some(progress) => {
+                    let mark = if progress.met() { "  ✓" } else { "" };
+                    println!(
+                        "{:<11} {}/{}{}",
+                        "done today", progress.done_today, progress.goal, mark
+                    );
+                    println!(
+                        "{:<11} {} day{} (best {})",
+                        "streak",
+                        progress.streak,
+                        if progress.streak == 1 { "" } else { "s" },
+                        progress.best_streak
+                    );
+                }
+                None => {
+                    println!("{:<11} {}", "done today", streak::done_on(&tasks, today));
+                    println!("{:<11} set general.daily_goal to track one", "streak");
+                }
+            }
+            println!(
+                "{:<11} {} this week",
+                "tracked",
+                timelog::format_duration(tracked)
+            );
+
+            for section in [
+                stats::render("Tags", &tags, "#"),
+                stats::render("Projects", &projects, ""),
+            ] {
+                if !section.is_empty() {
+                    print!("\n{}", section);
+                }
+            }
+        }
+        Command::Timelog { task, since } => {
+            let today = clock::today();
+            let since = since
+                .map(|text| {
+                    crate::nlp::parse_past_day(&text, today).ok_or_else(|| {
+                        TasukiError::Parse(format!(
+                            "Invalid date '{}' (expected today, yesterday, a weekday or YYYY-MM-DD)",
+                            text
+                        ))
+                    })
+                })
+                .transpose()?;
+
+            let mut entries = timelog::log_path(&config)
+                .map(|path| timelog::read(&path))
+                .unwrap_or_default();
+            entries.retain(|e| {
+                since.map_or(true, |day| e.start.date() >= day)
+                    && task.as_deref().map_or(true, |query| e.matches(query))
+            });
+
+            if entries.is_empty() {
+                println!("No time tracked");
+            } else {
+                print!("{}", timelog::render(&entries, &config));
+            }
+            if let Some(timer) =
+                timelog::Timer::path(&config).and_then(|p| timelog::Timer::load(&p))
+            {
+                let elapsed = timer.elapsed(chrono::Local::now().naive_local());
+                println!(
+                    "running {} ({})",
+                    timer.title,
+                    timelog::format_duration(elapsed)
+                );
+            }
+        }
+        Command::Agenda { days, date, format } => {
+            let today = clock::today();
+            let day = agenda::parse_day(&date, today)?;
+            if !["text", "markdown", "md", "html"].contains(&format.as_str()) {
+                return Err(TasukiError::Parse(format!(
+                    "Unknown agenda format '{}' (valid: text, markdown, html)",
+                    format
+                )));
+            }
+
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let filter = TaskFilter {
+                status: Some(TaskStatus::Pending),
+                ..Default::default()
+            };
+            let tasks = backend_manager.all_tasks(&filter).await?;
+            let document = match format.as_str() {
+                "markdown" | "md" => export::markdown::agenda(&tasks, day, days, &config, today),
+                "html" => {
+                    let theme =
+                        tui::theme::Theme::load(&config.general.theme, config.dir().as_deref());
+                    export::html::agenda(&tasks, day, days, &config, today, &theme)
+                }
+                _ => agenda::render(&tasks, day, days, &config, today),
+            };
+            print!("{}", document);
+        }
+        Command::Report {
+            since,
+            until,
+            tag,
+            by_project,
+            format,
+        } => {
+            let today = clock::today();
+            let day = |text: &str| {
+                crate::nlp::parse_past_day(text, today).ok_or_else(|| {
+                    TasukiError::Parse(format!(
+                        "Invalid date '{}' (expected today, yesterday, a weekday or YYYY-MM-DD)",
+                        text
+                    ))
+                })
+            };
+            let (since, until) = (day(&since)?, day(&until)?);
+            if since > until {
+                return Err(TasukiError::Parse(format!(
+                    "--since {} is after --until {}",
+                    since, until
+                )));
+            }
+            if !["text", "markdown", "md", "json"].contains(&format.as_str()) {
+                return Err(TasukiError::Parse(format!(
+                    "Unknown report format '{}' (valid: text, markdown, json)",
+                    format
+                )));
+            }
+
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let filter = TaskFilter {
+                tag,
+                ..Default::default()
+            };
+            let tasks = backend_manager.all_tasks(&filter).await?;
+            let report = report::build(&tasks, since, until, today);
+            match format.as_str() {
+                "markdown" | "md" => {
+                    print!(
+                        "{}",
+                        export::markdown::report(&report, by_project, &config, today)
+                    )
+                }
+                "json" => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report::to_json(&report))?
+                ),
+                _ => print!("{}", report::render(&report, by_project, &config, today)),
+            }
+        }
+        Command::Serve { stdio } => {
+            if !stdio {
+                return Err(TasukiError::Parse(
+                    "serve needs a transport; only --stdio is supported".into(),
+                ));
+            }
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            serve::serve(
+                &backend_manager,
+                &config,
+                std::io::stdin().lock(),
+                std::io::stdout().lock(),
+            )
+            .await?;
+        }
+        Command::Config { .. } | Command::EditConfig => {
+            let config_toml = toml::to_string_pretty(&config)
+                .map_err(|e| TasukiError::Config(format!("Failed to serialize config: {}", e)))?;
+            println!("{}", config_toml);
+        }
+        Command::Sync {
+            from,
+            to,
+            tag,
+            prefer,
+        } => {
+            let backend_manager = BackendManager::from_config(&config)?;
+
+            if backend_manager.is_empty() {
+                return Err(TasukiError::Config(NO_BACKENDS_MSG.into()));
+            }
+
+            let from = parse_source(&from)?;
+            let to = parse_source(&to)?;
+            let prefer = prefer.as_deref().map(str::parse).transpose()?;
+
+            let store_path = config
+                .data_dir()
+                .ok_or_else(|| TasukiError::Config("Could not find a data directory".into()))?
+                .join("sync.json");
+
+            let report = sync::run(
+                &backend_manager,
+                &store_path,
+                from,
+                to,
+                tag.as_deref(),
+                prefer,
+            )
+            .await?;
+
+            println!(
+                "✓ Synced {} → {}: {} created, {} completed, {} reopened, {} conflicts",
+                from.name(),
+                to.name(),
+                report.created,
+                report.completed,
+                report.reopened,
+                report.conflicts.len()
+            );
+            if report.orphaned > 0 {
+                println!(
+                    "  {} paired task(s) no longer exist on one side",
+                    report.orphaned
+                );
+            }
+            for conflict in &report.conflicts {
+                println!(
+                    "  conflict: {} ({:?}) ↔ {} ({:?})",
+                    conflict.from.title,
+                    conflict.from.status,
+                    conflict.to.title,
+                    conflict.to.status
+                );
+            }
+            if !report.conflicts.is_empty() {
+                println!("  Re-run with --prefer from|to to resolve.");
+            }
+        }
+        Command::Completions { shell } => {
+            let mut cmd = Cli::command();
+            let name = cmd.get_name().to_string();
+            clap_complete::generate(shell, &mut cmd, name, &mut std::io::stdout());
+        }
+    }
+
+    Ok(())
+}
@@ -1,43 +1,213 @@
+use chrono::Weekday;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, TasukiError};
+use crate::model::{BackendSource, BangPriorities, SearchMode, Tiebreak};
+use crate::nlp::{QuickAddOptions, TimeWords, WeekdayPolicy};
+use crate::paths;
 
-#[derive(Debug, Deserialize, Serialize)]
+/// The whole config file, `config.toml`.
+#[derive(Debug, Deserialize, Serialize, Default)]
 pub struct Config {
+    /// `[general]`
     #[serde(default)]
     pub general: GeneralConfig,
+    /// `[waybar]`
     #[serde(default)]
     pub waybar: WaybarConfig,
+    /// `[backends]`
     #[serde(default)]
     pub backends: BackendsConfig,
+    /// `[nlp]`
+    #[serde(default)]
+    pub nlp: NlpConfig,
+    /// Per-tag settings, `[tags.<name>]`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, TagConfig>,
+    /// `--config-dir` override; `None` means the XDG config dir
+    #[serde(skip)]
+    pub config_dir: Option<PathBuf>,
+    /// `--profile` name, which keeps themes and state apart from other setups
+    #[serde(skip)]
+    pub profile: Option<String>,
+    /// The file this config was loaded from (or would be, if missing)
+    #[serde(skip)]
+    pub config_path: Option<PathBuf>,
+    /// `$TASUKI_BACKENDS` spec that replaced `[backends.*]`, kept so a
+    /// reload applies it again
+    #[serde(skip)]
+    pub backends_spec: Option<String>,
+}
+
+/// Settings for one tag, `[tags.<name>]`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct TagConfig {
+    /// Hex color for this tag in the TUI, e.g. "#e06c75"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
 }
 
+/// The `[waybar]` table.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct WaybarConfig {
     /// "overdue_today" (default), "all", "today_only"
     #[serde(default = "default_tooltip_scope")]
     pub tooltip_scope: String,
+    /// Module text, e.g. "{count} · {done_today}/{goal}"; `{count}` is the
+    /// usual badge
+    #[serde(default = "default_waybar_format")]
+    pub format: String,
+    /// Module text when nothing dated is pending; "" hides the module
+    /// (with Waybar's `hide-empty-text`)
+    #[serde(default = "default_done_text", alias = "all_done_text")]
+    pub done_text: String,
+    /// Module text when no backend is configured or tasks can't be read
+    #[serde(default = "default_error_text")]
+    pub error_text: String,
+    /// Show `done_text` while undated tasks remain; when false the module
+    /// is hidden until something is due
+    #[serde(default = "default_true")]
+    pub show_zero: bool,
+    /// Count tasks completed today in the JSON (`done_today`) and the
+    /// tooltip summary; the badge still counts only pending tasks
+    #[serde(default)]
+    pub include_done: bool,
+    /// Tags that add a `tag-<tag>-due` class while one of their tasks is
+    /// due today or overdue
+    #[serde(default)]
+    pub alert_tags: Vec<String>,
+    /// "string" (default): `class` is the main class alone, as before;
+    /// "array": every class, the main one first
+    #[serde(default = "default_class_style")]
+    pub class_style: String,
 }
 
 impl Default for WaybarConfig {
     fn default() -> Self {
         Self {
             tooltip_scope: default_tooltip_scope(),
+            format: default_waybar_format(),
+            done_text: default_done_text(),
+            error_text: default_error_text(),
+            show_zero: true,
+            include_done: false,
+            alert_tags: Vec::new(),
+            class_style: default_class_style(),
         }
     }
 }
 
+fn default_class_style() -> String {
+    "string".into()
+}
+
 fn default_tooltip_scope() -> String {
     "overdue_today".into()
 }
 
+fn default_waybar_format() -> String {
+    "{count}".into()
+}
+
+fn default_done_text() -> String {
+    "✓".into()
+}
+
+fn default_error_text() -> String {
+    "!".into()
+}
+
+/// The `[general]` table.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GeneralConfig {
+    /// View the TUI opens in
     #[serde(default = "default_view")]
     pub default_view: String,
+    /// TUI theme name
     #[serde(default = "default_theme")]
     pub theme: String,
+    /// Backend for quick-add without an `@backend`: "local" or "obsidian".
+    /// Unset means the first enabled backend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_backend: Option<String>,
+    /// Priorities for `!`, `!!` and `!!!` in quick-add and in the local and
+    /// Obsidian task files
+    #[serde(default = "default_bang_priorities")]
+    pub bang_priorities: Vec<String>,
+    /// How tasks due the same day are ordered: any of "priority",
+    /// "created", "source", "title", applied in order
+    #[serde(default = "default_tiebreak")]
+    pub tiebreak: Vec<String>,
+    /// Shell command run (detached, output discarded) after a task is completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_complete_command: Option<String>,
+    /// Briefly flash a completed row in the TUI before it's refreshed away
+    #[serde(default = "default_true")]
+    pub flash_on_complete: bool,
+    /// Treat pending tasks overdue by more than this many days as high
+    /// priority (display and sorting only; files are left alone).
+    /// `overdue_escalation_days` is accepted as another name for it.
+    #[serde(
+        default,
+        alias = "overdue_escalation_days",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub escalate_overdue_after_days: Option<u32>,
+    /// Idle TUI status bar, e.g. "{pending} pending · {overdue} overdue".
+    /// Unset shows the keybinding cheat sheet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_bar: Option<String>,
+    /// TUI layout: "single" list, or "split" with a detail pane on wide terminals
+    #[serde(default = "default_layout")]
+    pub layout: String,
+    /// Give tags without a `[tags.<name>]` color a stable color picked from
+    /// the theme by hashing the name
+    #[serde(default)]
+    pub hash_tag_colors: bool,
+    /// Board view columns: "due" (Overdue/Today/Upcoming/No date) or "priority"
+    #[serde(default = "default_board_columns")]
+    pub board_columns: String,
+    /// Show the TUI's "refreshed … ago" in warning colors once the task
+    /// list is older than this many seconds
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+    /// Editor command, e.g. "code --wait"; falls back to `$VISUAL`,
+    /// `$EDITOR`, then `vi`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    /// Tasks to complete each day; enables `{done_today}`, `{goal}` and
+    /// `{streak}` in `waybar.format` and the streak in `tasuki stats`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_goal: Option<u32>,
+    /// strftime format for dates shown in the TUI, `list` and Waybar,
+    /// e.g. "%d.%m.%Y"; unset means ISO (2025-03-01)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    /// Show dates within a week as "yesterday", "in 3 days" and so on
+    #[serde(default)]
+    pub relative_dates: bool,
+    /// First day of the week ("monday", "sunday", ...), which "next week"
+    /// in quick-add resolves to
+    #[serde(default = "default_week_start")]
+    pub week_start: String,
+    /// How searches match titles: "substring", "word" (whole words only)
+    /// or "regex"
+    #[serde(default = "default_search_mode")]
+    pub search_mode: String,
+    /// What a weekday typed on that weekday means in quick-add: "next"
+    /// (a week away) or "today_if_match" (today)
+    #[serde(default = "default_weekday_today_policy")]
+    pub weekday_today_policy: String,
+    /// Lowercase tags typed in quick-add, so `#Work` and `#work` don't
+    /// end up side by side
+    #[serde(default)]
+    pub lowercase_tags: bool,
+    /// How often (ms) the TUI redraws while something is animating, such
+    /// as a completed row flashing; when idle it sleeps until an event
+    #[serde(default = "default_tick_ms")]
+    pub tick_ms: u64,
 }
 
 impl Default for GeneralConfig {
@@ -45,10 +215,231 @@ impl Default for GeneralConfig {
         Self {
             default_view: default_view(),
             theme: default_theme(),
+            default_backend: None,
+            bang_priorities: default_bang_priorities(),
+            tiebreak: default_tiebreak(),
+            on_complete_command: None,
+            flash_on_complete: true,
+            escalate_overdue_after_days: None,
+            status_bar: None,
+            layout: default_layout(),
+            hash_tag_colors: false,
+            board_columns: default_board_columns(),
+            stale_after_secs: default_stale_after_secs(),
+            editor: None,
+            daily_goal: None,
+            date_format: None,
+            relative_dates: false,
+            week_start: default_week_start(),
+            search_mode: default_search_mode(),
+            weekday_today_policy: default_weekday_today_policy(),
+            lowercase_tags: false,
+            tick_ms: default_tick_ms(),
         }
     }
 }
 
+/// The `[nlp]` table: how quick-add reads words like `eod`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NlpConfig {
+    /// `[nlp.times]`
+    #[serde(default)]
+    pub times: NlpTimes,
+    /// A time word whose time has passed today means tomorrow
+    #[serde(default = "default_true")]
+    pub rollover: bool,
+}
+
+impl Default for NlpConfig {
+    fn default() -> Self {
+        Self {
+            times: NlpTimes::default(),
+            rollover: true,
+        }
+    }
+}
+
+/// The `[nlp.times]` table: "HH:MM" for each time word.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct NlpTimes {
+    /// End of day, default 17:00
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eod: Option<String>,
+    /// Default 20:00
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tonight: Option<String>,
+    /// Default 09:00
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub morning: Option<String>,
+    /// Default 12:00
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noon: Option<String>,
+}
+
+impl NlpTimes {
+    /// Each word's name, as configured (if it is), and default time.
+    fn entries(&self) -> [(&'static str, Option<&str>, chrono::NaiveTime); 4] {
+        let defaults = TimeWords::default();
+        [
+            ("eod", self.eod.as_deref(), defaults.eod),
+            ("tonight", self.tonight.as_deref(), defaults.tonight),
+            ("morning", self.morning.as_deref(), defaults.morning),
+            ("noon", self.noon.as_deref(), defaults.noon),
+        ]
+    }
+
+    /// The configured times, each falling back to its default if unset or
+    /// invalid (`config check` reports the problem).
+    pub fn time_words(&self) -> TimeWords {
+        let [eod, tonight, morning, noon] = self.entries().map(|(_, configured, default)| {
+            configured.and_then(parse_time_of_day).unwrap_or(default)
+        });
+        TimeWords {
+            eod,
+            tonight,
+            morning,
+            noon,
+        }
+    }
+}
+
+/// "17:00" or "9:30" as a time of day.
+fn parse_time_of_day(text: &str) -> Option<chrono::NaiveTime> {
+    chrono::NaiveTime::parse_from_str(text.trim(), "%H:%M").ok()
+}
+
+impl Default for BackendsConfig {
+    fn default() -> Self {
+        Self {
+            allow_fallback: false,
+            max_file_size_mb: default_max_file_size_mb(),
+            retry_attempts: default_retry_attempts(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+            dedupe_window_secs: None,
+            obsidian: None,
+            local: None,
+            ical: None,
+        }
+    }
+}
+
+fn default_max_file_size_mb() -> u64 {
+    crate::backends::DEFAULT_MAX_FILE_SIZE_MB
+}
+
+fn default_retry_attempts() -> u32 {
+    crate::backends::retry::DEFAULT_RETRY_ATTEMPTS
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    crate::backends::retry::DEFAULT_RETRY_BACKOFF_MS
+}
+
+fn default_stale_after_secs() -> u64 {
+    300
+}
+
+fn default_tick_ms() -> u64 {
+    250
+}
+
+/// The shortest `general.tick_ms` honoured
+const MIN_TICK_MS: u64 = 10;
+
+fn default_bang_priorities() -> Vec<String> {
+    vec!["high".into(), "medium".into(), "low".into()]
+}
+
+fn default_tiebreak() -> Vec<String> {
+    vec!["priority".into(), "title".into()]
+}
+
+impl GeneralConfig {
+    /// `bang_priorities`, falling back to the default mapping if invalid
+    /// (`config check` reports the problem).
+    pub fn bangs(&self) -> BangPriorities {
+        BangPriorities::from_names(&self.bang_priorities).unwrap_or_default()
+    }
+
+    /// `tiebreak`, falling back to the default order if invalid
+    /// (`config check` reports the problem).
+    pub fn tiebreak(&self) -> Tiebreak {
+        Tiebreak::from_names(&self.tiebreak).unwrap_or_default()
+    }
+
+    /// `date_format`, falling back to ISO if unset or invalid
+    /// (`config check` reports the problem).
+    pub fn date_format(&self) -> &str {
+        self.date_format
+            .as_deref()
+            .filter(|f| check_date_format(f).is_ok())
+            .unwrap_or("%Y-%m-%d")
+    }
+
+    /// `week_start`, falling back to Monday if invalid
+    /// (`config check` reports the problem).
+    pub fn week_start(&self) -> Weekday {
+        self.week_start.parse().unwrap_or(Weekday::Mon)
+    }
+
+    /// `search_mode`, falling back to substring matching if invalid
+    /// (`config check` reports the problem).
+    pub fn search_mode(&self) -> SearchMode {
+        SearchMode::from_name(&self.search_mode).unwrap_or_default()
+    }
+
+    /// `weekday_today_policy`, falling back to "next" if invalid
+    /// (`config check` reports the problem).
+    pub fn weekday_policy(&self) -> WeekdayPolicy {
+        WeekdayPolicy::from_name(&self.weekday_today_policy).unwrap_or_default()
+    }
+
+    /// `tick_ms` as a duration, no shorter than 10 ms.
+    pub fn tick_rate(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.tick_ms.max(MIN_TICK_MS))
+    }
+}
+
+/// Whether `format` is a strftime format chrono can render a date with;
+/// an invalid one would panic when displayed.
+pub fn check_date_format(format: &str) -> std::result::Result<(), String> {
+    use chrono::format::{Item, StrftimeItems};
+    use std::fmt::Write;
+
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("\"{}\" is not a valid strftime format", format));
+    }
+    // Time fields have nothing to show for a date
+    let probe = chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    let mut out = String::new();
+    write!(out, "{}", probe.format(format))
+        .map_err(|_| format!("\"{}\" has fields a date can't fill (e.g. %H)", format))
+}
+
+fn default_week_start() -> String {
+    "monday".into()
+}
+
+fn default_search_mode() -> String {
+    "substring".into()
+}
+
+fn default_weekday_today_policy() -> String {
+    "next".into()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_board_columns() -> String {
+    "due".into()
+}
+
+fn default_layout() -> String {
+    "single".into()
+}
+
 fn default_view() -> String {
     "today".into()
 }
@@ -57,45 +448,743 @@ fn default_theme() -> String {
     "omarchy".into()
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+/// The `[backends]` table.
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BackendsConfig {
+    /// Create tasks in the first enabled backend when the requested one
+    /// (e.g. `@obsidian`) isn't enabled, instead of failing
+    #[serde(default)]
+    pub allow_fallback: bool,
+    /// Files larger than this are skipped (with a warning) when reading tasks
+    #[serde(default = "default_max_file_size_mb")]
+    pub max_file_size_mb: u64,
+    /// Tries for a file read or write that fails with a transient error
+    /// (e.g. EIO from a network mount), counting the first; 1 never retries
+    #[serde(default = "default_retry_attempts")]
+    pub retry_attempts: u32,
+    /// Wait before the first retry, doubled before each one after
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Seconds during which creating the same task (title, priority, due
+    /// date, tags and backend) again returns the one just created instead;
+    /// unset never dedupes
+    #[serde(default)]
+    pub dedupe_window_secs: Option<u64>,
+    /// `[backends.obsidian]`, read by `ObsidianConfig::from_table`
     #[serde(default)]
     pub obsidian: Option<toml::Table>,
+    /// `[backends.local]`, read by `LocalFileConfig::from_table`
     #[serde(default)]
     pub local: Option<toml::Table>,
+    /// `[backends.ical]`, read by `IcalConfig::from_table`
+    #[serde(default)]
+    pub ical: Option<toml::Table>,
+}
+
+/// Environment variable the CLI reads into `Config::with_backends_spec`,
+/// e.g. `TASUKI_BACKENDS=local=/tmp/todo.txt`.
+pub const BACKENDS_ENV: &str = "TASUKI_BACKENDS";
+
+impl BackendsConfig {
+    /// These settings with the backends from a `$TASUKI_BACKENDS` spec such
+    /// as `local=/tmp/todo.txt,obsidian=/tmp/vault` instead of the ones in
+    /// `[backends.*]`. Lets CI and demos point tasuki at fixtures without a
+    /// config file.
+    pub fn with_spec(&self, spec: &str) -> Result<Self> {
+        let mut backends = BackendsConfig {
+            allow_fallback: self.allow_fallback,
+            max_file_size_mb: self.max_file_size_mb,
+            retry_attempts: self.retry_attempts,
+            retry_backoff_ms: self.retry_backoff_ms,
+            dedupe_window_secs: self.dedupe_window_secs,
+            obsidian: None,
+            local: None,
+            ical: None,
+        };
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let invalid = || {
+                TasukiError::Config(format!(
+                    "{}: expected <backend>=<path>, got \"{}\"",
+                    BACKENDS_ENV, entry
+                ))
+            };
+            let (name, path) = entry.split_once('=').ok_or_else(invalid)?;
+            let (slot, key) = match BackendSource::from_name(name.trim()).ok_or_else(invalid)? {
+                BackendSource::LocalFile => (&mut backends.local, "path"),
+                BackendSource::Obsidian => (&mut backends.obsidian, "vault_path"),
+                BackendSource::ICal => (&mut backends.ical, "path"),
+            };
+
+            let mut table = toml::Table::new();
+            table.insert("enabled".into(), true.into());
+            table.insert(key.into(), path.trim().into());
+            *slot = Some(table);
+        }
+
+        Ok(backends)
+    }
+
+    /// `retry_attempts` and `retry_backoff_ms`, with at least one attempt.
+    pub fn retry_policy(&self) -> crate::backends::retry::RetryPolicy {
+        crate::backends::retry::RetryPolicy {
+            attempts: self.retry_attempts.max(1),
+            backoff: std::time::Duration::from_millis(self.retry_backoff_ms),
+        }
+    }
 }
 
 impl Config {
-    pub fn load(path: Option<PathBuf>) -> Result<Self> {
-        let config_path = match path {
-            Some(p) => p,
-            None => Self::default_config_path()?,
+    /// How quick-add should read `!` tokens, "next week" and `eod`.
+    pub fn quick_add_options(&self) -> QuickAddOptions {
+        QuickAddOptions {
+            bangs: self.general.bangs(),
+            week_start: self.general.week_start(),
+            lowercase_tags: self.general.lowercase_tags,
+            weekday_policy: self.general.weekday_policy(),
+            time_words: self.nlp.times.time_words(),
+            rollover: self.nlp.rollover,
+            // The enabled backends, filled in by
+            // `BackendManager::quick_add_options`
+            targets: Vec::new(),
+        }
+    }
+
+    /// Load `path`, or `config.toml` (or `profile`'s file) in `config_dir` or
+    /// the XDG config dir. A missing file gives the defaults, except for a
+    /// profile, where it's most likely a typo.
+    pub fn load(
+        path: Option<PathBuf>,
+        config_dir: Option<PathBuf>,
+        profile: Option<String>,
+    ) -> Result<Self> {
+        let explicit = path.is_some();
+        let config_path = Self::resolve_path(path, config_dir.as_deref(), profile.as_deref())?;
+        if let Some(ref name) = profile {
+            if !explicit && !config_path.exists() {
+                return Err(TasukiError::Config(format!(
+                    "Profile '{}' not found (expected {})",
+                    name,
+                    config_path.display()
+                )));
+            }
+        }
+
+        let mut config = if config_path.exists() {
+            let content = std::fs::read_to_string(&config_path)?;
+            toml::from_str(&content)
+                .map_err(|e| TasukiError::Config(format!("Failed to parse config: {}", e)))?
+        } else {
+            Config::default()
         };
 
-        if !config_path.exists() {
-            return Ok(Config::default());
+        config.config_dir = config_dir;
+        config.config_path = Some(config_path);
+        config.profile = profile;
+        Ok(config)
+    }
+
+    /// Replace `[backends.*]` with the backends in `spec` (see
+    /// `BackendsConfig::with_spec`); `None` keeps them.
+    pub fn with_backends_spec(mut self, spec: Option<String>) -> Result<Self> {
+        if let Some(ref spec) = spec {
+            self.backends = self.backends.with_spec(spec)?;
         }
+        self.backends_spec = spec;
+        Ok(self)
+    }
+
+    /// Re-read the same file this config came from.
+    pub fn reload(&self) -> Result<Self> {
+        Self::load(
+            self.config_path.clone(),
+            self.config_dir.clone(),
+            self.profile.clone(),
+        )
+        .and_then(|config| config.with_backends_spec(self.backends_spec.clone()))
+    }
 
-        let content = std::fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&content)
+    /// Directory holding `themes/`: the one holding config.toml, or its
+    /// `profiles/<name>` with `--profile`.
+    pub fn dir(&self) -> Option<PathBuf> {
+        self.config_dir
+            .clone()
+            .or_else(paths::config_dir)
+            .map(|dir| self.in_profile(dir))
+    }
+
+    /// Where state files live: `<config-dir>/state` when `--config-dir` is
+    /// given, so portable installs stay self-contained, otherwise XDG state.
+    pub fn state_dir(&self) -> Option<PathBuf> {
+        match self.config_dir {
+            Some(ref dir) => Some(dir.join("state")),
+            None => paths::state_dir(),
+        }
+        .map(|dir| self.in_profile(dir))
+    }
+
+    /// Where data files live: `<config-dir>/data` with `--config-dir`,
+    /// otherwise the XDG data dir.
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        match self.config_dir {
+            Some(ref dir) => Some(dir.join("data")),
+            None => paths::data_dir(),
+        }
+        .map(|dir| self.in_profile(dir))
+    }
+
+    /// `dir/profiles/<name>` with `--profile`, else `dir`.
+    fn in_profile(&self, dir: PathBuf) -> PathBuf {
+        match self.profile {
+            Some(ref name) => dir.join("profiles").join(name),
+            None => dir,
+        }
+    }
+
+    /// `--config` if given, else `config.toml` (or `profiles/<profile>.toml`)
+    /// in `--config-dir` or the XDG config dir.
+    pub fn resolve_path(
+        path: Option<PathBuf>,
+        config_dir: Option<&Path>,
+        profile: Option<&str>,
+    ) -> Result<PathBuf> {
+        if let Some(name) = profile {
+            if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+                return Err(TasukiError::Config(format!(
+                    "Invalid profile name '{}' (use a plain name like \"work\")",
+                    name
+                )));
+            }
+        }
+        let file = |dir: &Path| match profile {
+            Some(name) => dir.join("profiles").join(format!("{}.toml", name)),
+            None => dir.join("config.toml"),
+        };
+        match (path, config_dir) {
+            (Some(p), _) => Ok(p),
+            (None, Some(dir)) => Ok(file(dir)),
+            (None, None) => paths::config_dir()
+                .map(|dir| file(&dir))
+                .ok_or_else(|| TasukiError::Config("Could not find config directory".into())),
+        }
+    }
+
+    /// Validate the config file at `path` without creating or writing anything.
+    pub fn check(path: &Path) -> Vec<Diagnostic> {
+        if !path.exists() {
+            return vec![Diagnostic::error(format!(
+                "{} does not exist",
+                path.display()
+            ))];
+        }
+
+        let content = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => return vec![Diagnostic::error(format!("Failed to read: {}", e))],
+        };
+
+        match toml::from_str::<Config>(&content) {
+            Ok(config) => config.validate(),
+            Err(e) => vec![Diagnostic::error(format!("Failed to parse config: {}", e))],
+        }
+    }
+
+    /// Semantic checks on an already-parsed config.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        use crate::backends::{
+            ical::IcalConfig, localfile::LocalFileConfig, obsidian::ObsidianConfig,
+        };
+
+        let mut diagnostics = Vec::new();
+
+        const VIEWS: &[&str] = &["all", "today", "week", "upcoming", "notes", "done"];
+        if !VIEWS.contains(&self.general.default_view.as_str()) {
+            diagnostics.push(Diagnostic::warning(format!(
+                "general.default_view \"{}\" is not one of {}; falling back to \"all\"",
+                self.general.default_view,
+                VIEWS.join(", ")
+            )));
+        }
+
+        const LAYOUTS: &[&str] = &["single", "split"];
+        if !LAYOUTS.contains(&self.general.layout.as_str()) {
+            diagnostics.push(Diagnostic::warning(format!(
+                "general.layout \"{}\" is not one of {}; falling back to \"single\"",
+                self.general.layout,
+                LAYOUTS.join(", ")
+            )));
+        }
+
+        const BOARD_COLUMNS: &[&str] = &["due", "priority"];
+        if !BOARD_COLUMNS.contains(&self.general.board_columns.as_str()) {
+            diagnostics.push(Diagnostic::warning(format!(
+                "general.board_columns \"{}\" is not one of {}; falling back to \"due\"",
+                self.general.board_columns,
+                BOARD_COLUMNS.join(", ")
+            )));
+        }
+
+        // Tag colors and the status bar template are only used by the TUI
+        #[cfg(feature = "tui")]
+        {
+            let mut tags: Vec<_> = self.tags.iter().collect();
+            tags.sort_by_key(|(name, _)| name.as_str());
+            for (name, tag) in tags {
+                if let Some(ref color) = tag.color {
+                    if crate::tui::theme::hex_to_color(color).is_none() {
+                        diagnostics.push(Diagnostic::warning(format!(
+                            "tags.{}.color \"{}\" is not a #rrggbb color; using the theme color",
+                            name, color
+                        )));
+                    }
+                }
+            }
+        }
+
+        const SCOPES: &[&str] = &["overdue_today", "all", "today_only"];
+        if !SCOPES.contains(&self.waybar.tooltip_scope.as_str()) {
+            diagnostics.push(Diagnostic::warning(format!(
+                "waybar.tooltip_scope \"{}\" is not one of {}",
+                self.waybar.tooltip_scope,
+                SCOPES.join(", ")
+            )));
+        }
+
+        if !["string", "array"].contains(&self.waybar.class_style.as_str()) {
+            diagnostics.push(Diagnostic::warning(format!(
+                "waybar.class_style \"{}\" is not string or array; using string",
+                self.waybar.class_style
+            )));
+        }
+
+        if let Err(e) = BangPriorities::from_names(&self.general.bang_priorities) {
+            diagnostics.push(Diagnostic::error(e));
+        }
+
+        if let Err(e) = Tiebreak::from_names(&self.general.tiebreak) {
+            diagnostics.push(Diagnostic::error(e));
+        }
+
+        if let Some(ref format) = self.general.date_format {
+            if let Err(e) = check_date_format(format) {
+                diagnostics.push(Diagnostic::error(format!("general.date_format: {}", e)));
+            }
+        }
+
+        if self.general.week_start.parse::<Weekday>().is_err() {
+            diagnostics.push(Diagnostic::warning(format!(
+                "general.week_start \"{}\" is not a day of the week; using monday",
+                self.general.week_start
+            )));
+        }
+
+        if SearchMode::from_name(&self.general.search_mode).is_none() {
+            diagnostics.push(Diagnostic::warning(format!(
+                "general.search_mode \"{}\" is not substring, word or regex; using substring",
+                self.general.search_mode
+            )));
+        }
+
+        if WeekdayPolicy::from_name(&self.general.weekday_today_policy).is_none() {
+            diagnostics.push(Diagnostic::warning(format!(
+                "general.weekday_today_policy \"{}\" is not next or today_if_match; using next",
+                self.general.weekday_today_policy
+            )));
+        }
+
+        for (word, configured, default) in self.nlp.times.entries() {
+            if let Some(text) = configured.filter(|t| parse_time_of_day(t).is_none()) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "nlp.times.{} \"{}\" is not HH:MM; using {}",
+                    word,
+                    text,
+                    default.format("%H:%M")
+                )));
+            }
+        }
+
+        if self.backends.retry_attempts == 0 {
+            diagnostics.push(Diagnostic::warning(
+                "backends.retry_attempts 0 would never read a file; using 1".to_string(),
+            ));
+        }
+
+        if self.general.tick_ms < MIN_TICK_MS {
+            diagnostics.push(Diagnostic::warning(format!(
+                "general.tick_ms {} is below {}; using {}",
+                self.general.tick_ms, MIN_TICK_MS, MIN_TICK_MS
+            )));
+        }
+
+        #[cfg(feature = "tui")]
+        if let Err(e) = crate::waybar::check_format(&self.waybar.format) {
+            diagnostics.push(Diagnostic::error(format!("waybar.format: {}", e)));
+        }
+
+        if self.general.daily_goal.is_none()
+            && ["{done_today}", "{goal}", "{streak}"]
+                .iter()
+                .any(|p| self.waybar.format.contains(p))
+        {
+            diagnostics.push(Diagnostic::warning(
+                "waybar.format uses the daily goal, but general.daily_goal is not set".to_string(),
+            ));
+        }
+
+        if self.general.daily_goal == Some(0) {
+            diagnostics.push(Diagnostic::warning(
+                "general.daily_goal = 0 is met every day".to_string(),
+            ));
+        }
+
+        #[cfg(feature = "tui")]
+        if let Some(ref template) = self.general.status_bar {
+            if let Err(e) = template.parse::<crate::tui::status_bar::StatusBarTemplate>() {
+                diagnostics.push(Diagnostic::error(format!("general.status_bar: {}", e)));
+            }
+        }
+
+        if let Some(ref editor) = self.general.editor {
+            if let Err(e) = crate::editor::command(editor, &[]) {
+                diagnostics.push(Diagnostic::error(format!("general.editor: {}", e)));
+            }
+        }
+
+        let mut enabled = 0;
+
+        if let Some(ref table) = self.backends.local {
+            if is_enabled(table) {
+                enabled += 1;
+                match LocalFileConfig::resolve_path(table) {
+                    Ok(path) => {
+                        if path.is_dir() {
+                            diagnostics.push(Diagnostic::error(format!(
+                                "backends.local.path {} is a directory",
+                                path.display()
+                            )));
+                        }
+                    }
+                    Err(e) => diagnostics.push(Diagnostic::error(format!("backends.local: {}", e))),
+                }
+            }
+        }
+
+        if let Some(ref table) = self.backends.obsidian {
+            if is_enabled(table) {
+                enabled += 1;
+                match ObsidianConfig::from_table(table) {
+                    Ok(obs) => {
+                        if !obs.vault_path.is_dir() {
+                            diagnostics.push(Diagnostic::error(format!(
+                                "backends.obsidian.vault_path {} does not exist",
+                                obs.vault_path.display()
+                            )));
+                        } else {
+                            for folder in obs.missing_folders() {
+                                diagnostics.push(Diagnostic::warning(format!(
+                                    "backends.obsidian.folders: \"{}\" not found in the vault",
+                                    folder
+                                )));
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        diagnostics.push(Diagnostic::error(format!("backends.obsidian: {}", e)))
+                    }
+                }
+            }
+        }
+
+        if let Some(ref table) = self.backends.ical {
+            if is_enabled(table) {
+                enabled += 1;
+                match IcalConfig::from_table(table) {
+                    Ok(ical) => {
+                        if ical.url.is_none() && !ical.path.is_file() {
+                            diagnostics.push(Diagnostic::error(format!(
+                                "backends.ical.path {} does not exist",
+                                ical.path.display()
+                            )));
+                        }
+                    }
+                    Err(e) => diagnostics.push(Diagnostic::error(format!("backends.ical: {}", e))),
+                }
+            }
+        }
+
+        if enabled == 0 {
+            diagnostics.push(Diagnostic::error("No backends enabled".to_string()));
+        }
+
+        if let Some(ref name) = self.general.default_backend {
+            let table = match BackendSource::from_name(name) {
+                Some(BackendSource::LocalFile) => self.backends.local.as_ref(),
+                Some(BackendSource::Obsidian) => self.backends.obsidian.as_ref(),
+                Some(BackendSource::ICal) => self.backends.ical.as_ref(),
+                None => {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "general.default_backend \"{}\" is not one of {}",
+                        name,
+                        BackendSource::names()
+                    )));
+                    return diagnostics;
+                }
+            };
+            if !table.is_some_and(is_enabled) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "general.default_backend \"{}\" is not enabled; using the first enabled backend",
+                    name
+                )));
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Set (or with `None`, remove) the dotted option `key`, e.g.
+    /// `general.theme`, in the file at `path`. Comments, ordering and keys
+    /// tasuki doesn't know about are left untouched.
+    pub fn set_option(path: &Path, key: &str, value: Option<toml_edit::Value>) -> Result<()> {
+        let content = if path.exists() {
+            std::fs::read_to_string(path)?
+        } else {
+            String::new()
+        };
+        let mut doc: toml_edit::DocumentMut = content
+            .parse()
             .map_err(|e| TasukiError::Config(format!("Failed to parse config: {}", e)))?;
 
-        Ok(config)
+        let (section, name) = key
+            .split_once('.')
+            .ok_or_else(|| TasukiError::Config(format!("Not a config option: {}", key)))?;
+
+        match value {
+            Some(mut value) => {
+                if !doc.contains_table(section) {
+                    doc.insert(section, toml_edit::table());
+                }
+                let table = doc[section]
+                    .as_table_mut()
+                    .ok_or_else(|| TasukiError::Config(format!("[{}] is not a table", section)))?;
+                match table.get_mut(name).and_then(|item| item.as_value_mut()) {
+                    Some(existing) => {
+                        // Keep an inline comment after the old value
+                        *value.decor_mut() = existing.decor().clone();
+                        *existing = value;
+                    }
+                    None => {
+                        table.insert(name, toml_edit::Item::Value(value));
+                    }
+                }
+            }
+            None => {
+                if let Some(table) = doc.get_mut(section).and_then(|t| t.as_table_mut()) {
+                    table.remove(name);
+                }
+            }
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, doc.to_string())?;
+        Ok(())
     }
 
+    /// `config.toml` in the XDG config dir.
     pub fn default_config_path() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
+        let config_dir = paths::config_dir()
             .ok_or_else(|| TasukiError::Config("Could not find config directory".into()))?;
-        Ok(config_dir.join("tasuki").join("config.toml"))
+        Ok(config_dir.join("config.toml"))
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
+fn is_enabled(table: &toml::Table) -> bool {
+    table
+        .get("enabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// How serious a `Diagnostic` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Worth fixing, but the config still loads
+    Warning,
+    /// The config can't be used as is
+    Error,
+}
+
+/// One problem found by the config check.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// How serious it is
+    pub severity: Severity,
+    /// What is wrong, naming the offending key
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
         Self {
-            general: GeneralConfig::default(),
-            waybar: WaybarConfig::default(),
-            backends: BackendsConfig::default(),
+            severity: Severity::Warning,
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}", label, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENTED: &str = r#"# My tasuki setup
+# (hand-written, please keep the comments)
+
+[general]
+theme = "dark"   # matches the terminal
+# default_view = "all"
+
+# Options from a newer tasuki
+[experimental]
+sparkles = true   # don't touch
+nested = { a = 1, b = [2, 3] }
+
+[backends.local]
+enabled = true
+path = "~/todo.txt"  # synced folder
+"#;
+
+    #[test]
+    fn test_profile_paths() {
+        let dir = Path::new("/cfg");
+        assert_eq!(
+            Config::resolve_path(None, Some(dir), Some("work")).unwrap(),
+            Path::new("/cfg/profiles/work.toml")
+        );
+        assert_eq!(
+            Config::resolve_path(Some("/x.toml".into()), Some(dir), Some("work")).unwrap(),
+            Path::new("/x.toml")
+        );
+        assert!(Config::resolve_path(None, Some(dir), Some("../work")).is_err());
+
+        let config = Config {
+            config_dir: Some(dir.to_path_buf()),
+            profile: Some("work".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.dir().unwrap(), Path::new("/cfg/profiles/work"));
+        assert_eq!(
+            config.state_dir().unwrap(),
+            Path::new("/cfg/state/profiles/work")
+        );
+    }
+
+    #[test]
+    fn test_set_option_preserves_comments_and_unknown_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, COMMENTED).unwrap();
+
+        Config::set_option(&path, "general.theme", Some("light".into())).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            written,
+            COMMENTED.replace(r#"theme = "dark""#, r#"theme = "light""#)
+        );
+
+        Config::set_option(&path, "waybar.tooltip_scope", Some("all".into())).unwrap();
+        Config::set_option(&path, "general.flash_on_complete", Some(false.into())).unwrap();
+        Config::set_option(&path, "general.theme", None).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# My tasuki setup\n# (hand-written"));
+        assert!(written.contains("# default_view = \"all\""));
+        assert!(written.contains("sparkles = true   # don't touch"));
+        assert!(written.contains("nested = { a = 1, b = [2, 3] }"));
+        assert!(written.contains("path = \"~/todo.txt\"  # synced folder"));
+        assert!(!written.contains("theme ="));
+
+        let config: Config = toml::from_str(&written).unwrap();
+        assert_eq!(config.waybar.tooltip_scope, "all");
+        assert!(!config.general.flash_on_complete);
+        assert_eq!(config.general.theme, "omarchy");
+    }
+
+    #[test]
+    fn test_date_settings() {
+        let config: Config =
+            toml::from_str("[general]\ndate_format = \"%d.%m.%Y\"\nweek_start = \"sunday\"\n")
+                .unwrap();
+        assert_eq!(config.general.date_format(), "%d.%m.%Y");
+        assert_eq!(config.general.week_start(), Weekday::Sun);
+
+        assert!(check_date_format("%A, %e %B %Y").is_ok());
+        assert!(check_date_format("%Q").is_err());
+        assert!(check_date_format("%Y %H:%M").is_err());
+
+        let config: Config =
+            toml::from_str("[general]\ndate_format = \"%Q\"\nweek_start = \"caturday\"\n").unwrap();
+        assert_eq!(config.general.date_format(), "%Y-%m-%d");
+        assert_eq!(config.general.week_start(), Weekday::Mon);
+        let severities: Vec<Severity> = config
+            .validate()
+            .into_iter()
+            .filter(|d| d.message.contains("date_format") || d.message.contains("week_start"))
+            .map(|d| d.severity)
+            .collect();
+        assert_eq!(severities, vec![Severity::Error, Severity::Warning]);
+    }
+
+    #[test]
+    fn test_escalation_setting_names() {
+        for key in ["escalate_overdue_after_days", "overdue_escalation_days"] {
+            let config: Config = toml::from_str(&format!("[general]\n{} = 3\n", key)).unwrap();
+            assert_eq!(config.general.escalate_overdue_after_days, Some(3));
         }
     }
+
+    #[test]
+    fn test_nlp_times() {
+        let config: Config = toml::from_str(
+            "[nlp]\nrollover = false\n[nlp.times]\neod = \"18:30\"\ntonight = \"late\"\n",
+        )
+        .unwrap();
+        let options = config.quick_add_options();
+        assert!(!options.rollover);
+        assert_eq!(
+            options.time_words.eod,
+            chrono::NaiveTime::from_hms_opt(18, 30, 0).unwrap()
+        );
+        // Unset and invalid fall back to the defaults
+        assert_eq!(options.time_words.tonight, TimeWords::default().tonight);
+        assert_eq!(options.time_words.noon, TimeWords::default().noon);
+        let warnings: Vec<String> = config
+            .validate()
+            .into_iter()
+            .filter(|d| d.message.starts_with("nlp."))
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            warnings,
+            vec!["nlp.times.tonight \"late\" is not HH:MM; using 20:00"]
+        );
+
+        assert!(Config::default().quick_add_options().rollover);
+    }
 }
@@ -0,0 +1,65 @@
+use crate::config::GeneralConfig;
+use crate::error::{Result, TasukiError};
+
+/// The editor string in effect: `general.editor`, then `$VISUAL`, then
+/// `$EDITOR`, then `vi`. Empty or blank values are skipped.
+pub fn resolve(config: &GeneralConfig) -> String {
+    resolve_from(
+        config.editor.as_deref(),
+        std::env::var("VISUAL").ok().as_deref(),
+        std::env::var("EDITOR").ok().as_deref(),
+    )
+}
+
+fn resolve_from(configured: Option<&str>, visual: Option<&str>, editor: Option<&str>) -> String {
+    [configured, visual, editor]
+        .into_iter()
+        .flatten()
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or("vi")
+        .to_string()
+}
+
+/// Split an editor string like `code --wait` into program and arguments,
+/// honoring shell quoting, then append `args`.
+pub fn command(editor: &str, args: &[String]) -> Result<Vec<String>> {
+    let mut words = shell_words::split(editor)
+        .map_err(|e| TasukiError::Config(format!("Invalid editor '{}': {}", editor, e)))?;
+    if words.is_empty() {
+        return Err(TasukiError::Config("Editor command is empty".into()));
+    }
+    words.extend(args.iter().cloned());
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_chain_skips_empty_values() {
+        assert_eq!(resolve_from(Some("hx"), Some("code"), Some("nano")), "hx");
+        assert_eq!(
+            resolve_from(None, Some("code --wait"), Some("nano")),
+            "code --wait"
+        );
+        assert_eq!(resolve_from(Some(""), Some("  "), Some("nano")), "nano");
+        assert_eq!(resolve_from(None, Some(""), Some("")), "vi");
+        assert_eq!(resolve_from(None, None, None), "vi");
+    }
+
+    #[test]
+    fn test_command_keeps_editor_arguments() {
+        let args = vec!["+3".to_string(), "/tmp/tasks.md".to_string()];
+        assert_eq!(
+            command("code --wait", &args).unwrap(),
+            vec!["code", "--wait", "+3", "/tmp/tasks.md"]
+        );
+        assert_eq!(
+            command("'/opt/My Editor/bin/edit' -n", &[]).unwrap(),
+            vec!["/opt/My Editor/bin/edit", "-n"]
+        );
+        assert!(command("vim \"unterminated", &[]).is_err());
+        assert!(command("   ", &[]).is_err());
+    }
+}
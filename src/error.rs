@@ -1,26 +1,95 @@
 use thiserror::Error;
 
+/// Everything that can go wrong in tasuki.
 #[derive(Debug, Error)]
 pub enum TasukiError {
+    /// The config file is missing, unreadable or invalid
     #[error("Config error: {0}")]
     Config(String),
 
+    /// A backend failed to read or write tasks
     #[error("Backend '{backend}' error: {message}")]
-    Backend { backend: String, message: String },
+    Backend {
+        /// Name of the backend
+        backend: String,
+        /// What went wrong
+        message: String,
+    },
 
+    /// A backend's files can't be reached for now, e.g. a network mount
+    /// reconnecting; unlike `Backend`, trying again later may work
+    #[error("Backend '{backend}' unavailable: {source}")]
+    Unavailable {
+        /// Name of the backend
+        backend: String,
+        /// What the filesystem said
+        source: std::io::Error,
+    },
+
+    /// A filesystem error outside any one backend
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Input that couldn't be understood, e.g. a bad date or task ID
     #[error("Parse error: {0}")]
     Parse(String),
 
+    /// JSON could not be read or written
     #[error("JSON error: {0}")]
     Json(String),
 
+    /// Watching files for changes failed
     #[error("Watch error: {0}")]
     Watch(String),
+
+    /// No task (or other item) matches what was asked for
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// A task ID, or a number from the last `list`, that no longer names
+    /// the task it did, e.g. its line was deleted or now holds another
+    #[error("Stale task ID: {0}")]
+    StaleId(String),
+
+    /// The user declined or cancelled the operation
+    #[error("Aborted: {0}")]
+    Aborted(String),
+}
+
+impl TasukiError {
+    /// Process exit code for this error, so scripts can tell failures apart:
+    ///
+    /// | Code | Meaning                      |
+    /// |------|------------------------------|
+    /// | 1    | other / IO                   |
+    /// | 2    | config error or stale ID     |
+    /// | 3    | backend error or unavailable |
+    /// | 4    | not found                    |
+    /// | 5    | parse error                  |
+    /// | 6    | aborted                      |
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            TasukiError::Config(_) | TasukiError::StaleId(_) => 2,
+            TasukiError::Backend { .. } | TasukiError::Unavailable { .. } => 3,
+            TasukiError::NotFound(_) => 4,
+            TasukiError::Parse(_) => 5,
+            TasukiError::Aborted(_) => 6,
+            TasukiError::Io(_) | TasukiError::Json(_) | TasukiError::Watch(_) => 1,
+        }
+    }
+
+    /// Whether this may clear up by itself (see `retry::is_transient`), so
+    /// results from a little earlier beat showing the error.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            TasukiError::Unavailable { .. } => true,
+            TasukiError::Io(e) => crate::backends::retry::is_transient(e),
+            _ => false,
+        }
+    }
 }
 
+#[cfg(feature = "tui")]
 impl From<notify::Error> for TasukiError {
     fn from(e: notify::Error) -> Self {
         TasukiError::Watch(e.to_string())
@@ -33,4 +102,5 @@ impl From<serde_json::Error> for TasukiError {
     }
 }
 
+/// `Result` with `TasukiError`.
 pub type Result<T> = std::result::Result<T, TasukiError>;
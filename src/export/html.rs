@@ -0,0 +1,189 @@
+//! The agenda as a single HTML page with its CSS inline, colored like the
+//! TUI theme.
+
+use chrono::NaiveDate;
+use ratatui::style::Color;
+
+use crate::agenda;
+use crate::config::Config;
+use crate::model::Task;
+use crate::output;
+use crate::tui::theme::Theme;
+
+/// `tasuki agenda --format html`: the same layout as the Markdown agenda,
+/// with checkboxes that can be ticked on screen or on paper.
+pub fn agenda(
+    tasks: &[Task],
+    day: NaiveDate,
+    days: u32,
+    config: &Config,
+    today: NaiveDate,
+    theme: &Theme,
+) -> String {
+    let title = escape(&super::title(day, config));
+    let mut doc = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title,
+        style(theme),
+        title
+    );
+
+    let sections = agenda::sections(tasks, day, days, config, today);
+    if sections.is_empty() {
+        doc.push_str("<p>Nothing to do.</p>\n");
+    }
+    for section in &sections {
+        let class = if section.overdue {
+            " class=\"overdue\""
+        } else {
+            ""
+        };
+        doc.push_str(&format!(
+            "<section>\n<h2{}>{} ({})</h2>\n",
+            class,
+            escape(&section.title),
+            section.tasks.len()
+        ));
+        for (group, group_tasks) in super::by_group(&section.tasks) {
+            if let Some(group) = group {
+                doc.push_str(&format!("<h3>{}</h3>\n", escape(&group)));
+            }
+            doc.push_str("<ul>\n");
+            for task in group_tasks {
+                doc.push_str(&format!(
+                    "<li><label><input type=\"checkbox\"> {}",
+                    escape(&task.title)
+                ));
+                if let Some(due) = task.due.filter(|_| section.dated) {
+                    doc.push_str(&format!(
+                        " <span class=\"due\">{}</span>",
+                        escape(&output::format_date(due, config, today))
+                    ));
+                }
+                doc.push_str("</label></li>\n");
+            }
+            doc.push_str("</ul>\n");
+        }
+        doc.push_str("</section>\n");
+    }
+
+    doc.push_str("</body>\n</html>\n");
+    doc
+}
+
+/// The page's CSS: theme colors on screen, black on white when printed
+/// with only the headers keeping theirs.
+fn style(theme: &Theme) -> String {
+    format!(
+        "body {{ font-family: sans-serif; max-width: 40em; margin: 2em auto; padding: 0 1em; color: {fg}; background: {bg}; }}
+h1, h2 {{ color: {accent}; }}
+h2 {{ border-bottom: 1px solid {muted}; }}
+h2.overdue {{ color: {error}; }}
+h3 {{ color: {highlight}; margin-bottom: 0.2em; }}
+ul {{ list-style: none; padding-left: 0; }}
+.due {{ color: {muted}; }}
+@media print {{ body {{ color: black; background: white; }} }}
+",
+        fg = css_color(theme.foreground),
+        bg = css_color(theme.background),
+        accent = css_color(theme.accent),
+        muted = css_color(theme.muted),
+        error = css_color(theme.error),
+        highlight = css_color(theme.highlight),
+    )
+}
+
+/// `color` as CSS; terminal colors without an RGB value get the nearest
+/// CSS name, and the terminal default gets `inherit`.
+fn css_color(color: Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::Black => "black".into(),
+        Color::Red | Color::LightRed => "red".into(),
+        Color::Green | Color::LightGreen => "green".into(),
+        Color::Yellow | Color::LightYellow => "goldenrod".into(),
+        Color::Blue | Color::LightBlue => "blue".into(),
+        Color::Magenta | Color::LightMagenta => "magenta".into(),
+        Color::Cyan | Color::LightCyan => "darkcyan".into(),
+        Color::Gray | Color::DarkGray => "gray".into(),
+        Color::White => "white".into(),
+        _ => "inherit".into(),
+    }
+}
+
+/// `text` safe to place in HTML text or an attribute.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::fixture;
+
+    #[test]
+    fn test_agenda_html() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let html = agenda(
+            &fixture(),
+            today,
+            7,
+            &Config::default(),
+            today,
+            &Theme::dark(),
+        );
+        let body = &html[html.find("<body>").unwrap()..];
+        assert_eq!(
+            body,
+            "\
+<body>
+<h1>Agenda for Saturday 2025-03-01</h1>
+<section>
+<h2 class=\"overdue\">Overdue (1)</h2>
+<h3>#home</h3>
+<ul>
+<li><label><input type=\"checkbox\"> Pay rent <span class=\"due\">2025-02-27</span></label></li>
+</ul>
+</section>
+<section>
+<h2>Today (3)</h2>
+<ul>
+<li><label><input type=\"checkbox\"> Call &lt;Sam&gt; &amp; Jo</label></li>
+</ul>
+<h3>#work</h3>
+<ul>
+<li><label><input type=\"checkbox\"> Standup</label></li>
+</ul>
+<h3>Q1 Review</h3>
+<ul>
+<li><label><input type=\"checkbox\"> Draft report</label></li>
+</ul>
+</section>
+<section>
+<h2>Tomorrow (1)</h2>
+<ul>
+<li><label><input type=\"checkbox\"> Dentist</label></li>
+</ul>
+</section>
+<section>
+<h2>No due date (1)</h2>
+<ul>
+<li><label><input type=\"checkbox\"> Read book</label></li>
+</ul>
+</section>
+</body>
+</html>
+"
+        );
+
+        // Self-contained, in the theme's colors
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<title>Agenda for Saturday 2025-03-01</title>"));
+        assert!(html.contains("color: #dcdcdc; background: #1e1e1e;"));
+        assert!(html.contains("h2.overdue { color: #cd5c5c; }"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+    }
+}
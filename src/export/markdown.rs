@@ -0,0 +1,175 @@
+//! The agenda and the completed-work report as Markdown, to print or paste
+//! into a daily note or chat.
+
+use chrono::NaiveDate;
+
+use crate::agenda;
+use crate::config::Config;
+use crate::model::Task;
+use crate::output;
+use crate::report::{self, Report};
+
+/// `tasuki agenda --format markdown`: a `#` title, a `##` header per
+/// section and `###` per project or tag, tasks as `- [ ]` lines.
+pub fn agenda(
+    tasks: &[Task],
+    day: NaiveDate,
+    days: u32,
+    config: &Config,
+    today: NaiveDate,
+) -> String {
+    let mut doc = format!("# {}\n", super::title(day, config));
+    let sections = agenda::sections(tasks, day, days, config, today);
+    if sections.is_empty() {
+        doc.push_str("\nNothing to do.\n");
+        return doc;
+    }
+
+    for section in &sections {
+        doc.push_str(&format!(
+            "\n## {} ({})\n",
+            section.title,
+            section.tasks.len()
+        ));
+        for (group, group_tasks) in super::by_group(&section.tasks) {
+            if let Some(group) = group {
+                doc.push_str(&format!("\n### {}\n", group));
+            }
+            doc.push('\n');
+            for task in group_tasks {
+                doc.push_str(&format!("- [ ] {}", task.title));
+                if let Some(due) = task.due.filter(|_| section.dated) {
+                    doc.push_str(&format!(" ({})", output::format_date(due, config, today)));
+                }
+                doc.push('\n');
+            }
+        }
+    }
+    doc
+}
+
+/// `tasuki report --format markdown`: a `#` title with the counts, a `##`
+/// header per day (and `###` per project or tag with `by_group`), done
+/// tasks as `- [x]` lines, then what's still overdue.
+pub fn report(report: &Report, by_group: bool, config: &Config, today: NaiveDate) -> String {
+    let mut doc = format!(
+        "# {}\n\n{} completed, {} overdue.\n",
+        report::title(report, config),
+        report.completed(),
+        report.overdue.len()
+    );
+
+    for (day, tasks) in &report.days {
+        doc.push_str(&format!("\n## {}\n", report::day_label(*day, config)));
+        let groups = if by_group {
+            super::by_group(tasks)
+        } else {
+            vec![(None, tasks.clone())]
+        };
+        for (group, group_tasks) in groups {
+            if let Some(group) = group {
+                doc.push_str(&format!("\n### {}\n", group));
+            }
+            doc.push('\n');
+            for task in group_tasks {
+                doc.push_str(&format!("- [x] {}\n", task.title));
+            }
+        }
+    }
+
+    if !report.overdue.is_empty() {
+        doc.push_str("\n## Still overdue\n\n");
+        for task in &report.overdue {
+            let due = task
+                .due
+                .map(|d| output::format_date(d, config, today))
+                .unwrap_or_default();
+            doc.push_str(&format!("- [ ] {} ({})\n", task.title, due));
+        }
+    }
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::fixture;
+
+    #[test]
+    fn test_agenda_markdown() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert_eq!(
+            agenda(&fixture(), today, 7, &Config::default(), today),
+            "\
+# Agenda for Saturday 2025-03-01
+
+## Overdue (1)
+
+### #home
+
+- [ ] Pay rent (2025-02-27)
+
+## Today (3)
+
+- [ ] Call <Sam> & Jo
+
+### #work
+
+- [ ] Standup
+
+### Q1 Review
+
+- [ ] Draft report
+
+## Tomorrow (1)
+
+- [ ] Dentist
+
+## No due date (1)
+
+- [ ] Read book
+"
+        );
+
+        let empty = agenda(&[], today, 7, &Config::default(), today);
+        assert_eq!(
+            empty,
+            "# Agenda for Saturday 2025-03-01\n\nNothing to do.\n"
+        );
+    }
+
+    #[test]
+    fn test_report_markdown() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let mut tasks = fixture();
+        for (task, day) in tasks.iter_mut().zip([27, 28, 28]) {
+            task.status = crate::model::TaskStatus::Done;
+            task.completed_at = NaiveDate::from_ymd_opt(2025, 2, day)
+                .unwrap()
+                .and_hms_opt(9, 0, 0);
+        }
+        let done = report::build(&tasks[..3], today - chrono::Duration::days(2), today, today);
+        assert_eq!(
+            report(&done, true, &Config::default(), today),
+            "\
+# Done Thursday 2025-02-27 – Saturday 2025-03-01
+
+3 completed, 0 overdue.
+
+## Thursday 2025-02-27
+
+### #home
+
+- [x] Pay rent
+
+## Friday 2025-02-28
+
+- [x] Call <Sam> & Jo
+
+### #work
+
+- [x] Standup
+"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Printable documents built from tasks: `tasuki agenda --format
+//! markdown|html` and `tasuki report --format markdown`.
+//!
+//! Both agenda formats lay out `agenda::sections`, splitting each section
+//! by project (or first tag) with `by_group`.
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::model::Task;
+
+pub mod html;
+pub mod markdown;
+
+/// `tasks` split by project, or first tag for tasks without one: tasks with
+/// neither first (under no heading), then the groups by name, each keeping
+/// the order given.
+pub(crate) fn by_group<'a>(tasks: &[&'a Task]) -> Vec<(Option<String>, Vec<&'a Task>)> {
+    let mut groups: Vec<(Option<String>, Vec<&'a Task>)> = Vec::new();
+    for task in tasks {
+        let name = task
+            .project
+            .clone()
+            .or_else(|| task.tags.first().map(|tag| format!("#{}", tag)));
+        match groups.iter_mut().find(|(group, _)| *group == name) {
+            Some((_, group_tasks)) => group_tasks.push(task),
+            None => groups.push((name, vec![task])),
+        }
+    }
+    // `None` sorts first
+    groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+    groups
+}
+
+/// "Agenda for Saturday 2025-03-01", always with an absolute date since a
+/// printout outlives "today".
+fn title(day: NaiveDate, config: &Config) -> String {
+    format!(
+        "Agenda for {} {}",
+        day.format("%A"),
+        day.format(config.general.date_format())
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// The fixed task set the format tests render, for Saturday 2025-03-01.
+    pub(crate) fn fixture() -> Vec<Task> {
+        let due = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        vec![
+            Task::build("Pay rent")
+                .with_due(due("2025-02-27"))
+                .with_tags(&["home"]),
+            Task::build("Standup")
+                .with_due(due("2025-03-01"))
+                .with_tags(&["work"]),
+            Task::build("Call <Sam> & Jo").with_due(due("2025-03-01")),
+            Task::build("Draft report")
+                .with_due(due("2025-03-01"))
+                .with_project("Q1 Review"),
+            Task::build("Dentist").with_due(due("2025-03-02")),
+            Task::build("Read book"),
+        ]
+    }
+
+    #[test]
+    fn test_by_group() {
+        let tasks = fixture();
+        let refs: Vec<&Task> = tasks.iter().collect();
+        let groups: Vec<(Option<String>, usize)> = by_group(&refs)
+            .into_iter()
+            .map(|(name, tasks)| (name, tasks.len()))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                (None, 3),
+                (Some("#home".to_string()), 1),
+                (Some("#work".to_string()), 1),
+                (Some("Q1 Review".to_string()), 1),
+            ]
+        );
+    }
+}
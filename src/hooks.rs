@@ -0,0 +1,63 @@
+use std::process::{Command, Stdio};
+
+use crate::config::GeneralConfig;
+use crate::model::Task;
+
+/// Run `general.on_complete_command` for a just-completed task. The command
+/// goes through `sh -c` with `TASUKI_TASK_ID` and `TASUKI_TASK_TITLE` set;
+/// it is detached and its output discarded, so it never delays the caller.
+pub fn on_complete(config: &GeneralConfig, task: &Task) {
+    let Some(ref command) = config.on_complete_command else {
+        return;
+    };
+
+    let spawned = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("TASUKI_TASK_ID", &task.id)
+        .env("TASUKI_TASK_TITLE", &task.title)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    match spawned {
+        // Reap in the background so the child doesn't linger as a zombie
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => tracing::warn!("on_complete_command failed to start: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TaskStatus;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_on_complete_runs_detached_with_task_env() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("out.txt");
+        let config = GeneralConfig {
+            on_complete_command: Some(format!(
+                "echo \"$TASUKI_TASK_ID $TASUKI_TASK_TITLE\" > '{}'",
+                out.display()
+            )),
+            ..Default::default()
+        };
+        let task = Task::build("Buy milk")
+            .with_id("local:1")
+            .with_status(TaskStatus::Done);
+
+        on_complete(&config, &task);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !out.exists() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "local:1 Buy milk\n");
+    }
+}
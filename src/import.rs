@@ -0,0 +1,212 @@
+//! `tasuki add --stdin` / `--clipboard`: turn a pasted checklist into tasks.
+//!
+//! `- [ ]` and `* [ ]` lines go through the Obsidian checkbox parser, other
+//! lines through quick-add. `plan` only decides what each line becomes;
+//! the caller creates the tasks (or not, for `--dry-run`).
+
+use std::process::Command;
+
+use crate::backends::obsidian::parser::parse_checkbox_line_with;
+use crate::error::{Result, TasukiError};
+use crate::model::{BackendSource, NewTask, TaskStatus};
+use crate::nlp::{parse_quick_add_with, QuickAddOptions};
+
+/// What one input line turns into.
+#[derive(Debug)]
+pub enum Outcome {
+    Create(NewTask),
+    /// An already checked `- [x]` item
+    SkippedDone,
+    /// A plain line indented under the task on this (1-based) line; it would
+    /// be that task's description, which no backend stores yet
+    SkippedChild(usize),
+    /// A heading such as `## Action items`
+    SkippedHeading,
+    ParseFailed(String),
+}
+
+#[derive(Debug)]
+pub struct ImportLine {
+    /// 1-based line number in the input
+    pub line: usize,
+    pub text: String,
+    pub outcome: Outcome,
+}
+
+fn indent_of(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// `* [ ]` and `+ [ ]` as the `- [ ]` the checkbox parser knows.
+fn as_checkbox(trimmed: &str) -> Option<String> {
+    let rest = trimmed
+        .strip_prefix("- [")
+        .or_else(|| trimmed.strip_prefix("* ["))
+        .or_else(|| trimmed.strip_prefix("+ ["))?;
+    Some(format!("- [{}", rest))
+}
+
+/// Decide what every non-blank line of `input` becomes.
+pub fn plan(input: &str, options: &QuickAddOptions, backend: BackendSource) -> Vec<ImportLine> {
+    let mut lines = Vec::new();
+    // Indentation and line number of the last task created from a line
+    let mut parent: Option<(usize, usize)> = None;
+
+    for (idx, raw) in input.lines().enumerate() {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let line = idx + 1;
+        let indent = indent_of(raw);
+
+        let outcome = if let Some(checkbox) = as_checkbox(trimmed) {
+            match parse_checkbox_line_with(&checkbox, false, &options.bangs) {
+                Some(task) if task.status == TaskStatus::Done => Outcome::SkippedDone,
+                Some(task) => Outcome::Create(NewTask {
+                    title: task.title,
+                    priority: task.priority,
+                    due: task.due,
+                    tags: task.tags,
+                    backend,
+                }),
+                None => Outcome::ParseFailed("not a task checkbox".into()),
+            }
+        } else if let Some((parent_line, _)) = parent.filter(|&(_, i)| indent > i) {
+            Outcome::SkippedChild(parent_line)
+        } else if trimmed.starts_with('#') && trimmed.trim_start_matches('#').starts_with(' ') {
+            Outcome::SkippedHeading
+        } else {
+            let text = trimmed
+                .strip_prefix("- ")
+                .or_else(|| trimmed.strip_prefix("* "))
+                .unwrap_or(trimmed);
+            // Quick-add never fails, but can leave nothing to call the task
+            let parsed = parse_quick_add_with(text, options).map(|p| p.into_new_task(backend));
+            match parsed {
+                Ok(task) if task.title.is_empty() => Outcome::ParseFailed("no title".into()),
+                Ok(task) => Outcome::Create(task),
+                Err(e) => Outcome::ParseFailed(e.to_string()),
+            }
+        };
+
+        if matches!(outcome, Outcome::Create(_) | Outcome::SkippedDone) {
+            parent = Some((line, indent));
+        }
+        lines.push(ImportLine {
+            line,
+            text: trimmed.to_string(),
+            outcome,
+        });
+    }
+
+    lines
+}
+
+/// Text from the system clipboard, trying Wayland then X11 tools.
+pub fn read_clipboard() -> Result<String> {
+    const TOOLS: &[&[&str]] = &[
+        &["wl-paste", "--no-newline"],
+        &["xclip", "-selection", "clipboard", "-o"],
+        &["xsel", "--clipboard", "--output"],
+    ];
+
+    for argv in TOOLS {
+        match Command::new(argv[0]).args(&argv[1..]).output() {
+            Ok(output) if output.status.success() => {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+            Ok(_) | Err(_) => continue,
+        }
+    }
+
+    Err(TasukiError::Config(
+        "Could not read the clipboard; install wl-clipboard, xclip or xsel".into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Priority;
+
+    fn outcomes(input: &str) -> Vec<(usize, String)> {
+        plan(input, &QuickAddOptions::default(), BackendSource::LocalFile)
+            .into_iter()
+            .map(|l| {
+                let what = match l.outcome {
+                    Outcome::Create(task) => format!("create {}", task.title),
+                    Outcome::SkippedDone => "done".to_string(),
+                    Outcome::SkippedChild(parent) => format!("child of {}", parent),
+                    Outcome::SkippedHeading => "heading".to_string(),
+                    Outcome::ParseFailed(e) => format!("failed: {}", e),
+                };
+                (l.line, what)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_checklist() {
+        let input = "\
+## Action items
+
+- [ ] Send notes #team
+* [x] Book room
+- [ ] Draft plan
+    context from the meeting
+    - [ ] Ask Sam
+- [?] maybe
+Call vendor tomorrow
+- #waiting
+";
+        assert_eq!(
+            outcomes(input),
+            vec![
+                (1, "heading".to_string()),
+                (3, "create Send notes".to_string()),
+                (4, "done".to_string()),
+                (5, "create Draft plan".to_string()),
+                (6, "child of 5".to_string()),
+                (7, "create Ask Sam".to_string()),
+                (8, "failed: not a task checkbox".to_string()),
+                (9, "create Call vendor".to_string()),
+                (10, "failed: no title".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_keeps_time_words_in_titles() {
+        assert_eq!(
+            outcomes("- [ ] Morning pages\n- [ ] Noon walk with Sam\n"),
+            vec![
+                (1, "create Morning pages".to_string()),
+                (2, "create Noon walk with Sam".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_keeps_metadata() {
+        let lines = plan(
+            "- [ ] Pay rent 📅 2025-03-01 ⏫ #home",
+            &QuickAddOptions::default(),
+            BackendSource::Obsidian,
+        );
+        let Outcome::Create(ref task) = lines[0].outcome else {
+            panic!("expected a task");
+        };
+        assert_eq!(task.title, "Pay rent");
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(
+            task.due.map(|d| d.to_string()).as_deref(),
+            Some("2025-03-01")
+        );
+        assert_eq!(task.tags, vec!["home"]);
+        assert_eq!(task.backend, BackendSource::Obsidian);
+    }
+}
@@ -0,0 +1,179 @@
+//! Numbers printed by `tasuki list`, so `tasuki done 3` can name the third
+//! task listed.
+//!
+//! Each `list` replaces `<state dir>/last-list.json`: text output saves the
+//! IDs and titles in the order they were numbered, other formats clear it
+//! so numbers from an older listing can't complete the wrong task. The
+//! titles catch an ID that now names another task, e.g. an Obsidian line
+//! that moved.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::{Result, TasukiError};
+use crate::model::Task;
+
+/// One task as the last `list` showed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListedTask {
+    pub id: String,
+    pub title: String,
+}
+
+impl From<&Task> for ListedTask {
+    fn from(task: &Task) -> Self {
+        Self {
+            id: task.id.clone(),
+            title: task.title.clone(),
+        }
+    }
+}
+
+/// Tasks in the order the last `list` numbered them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastList {
+    tasks: Vec<ListedTask>,
+}
+
+impl LastList {
+    pub fn new(tasks: Vec<ListedTask>) -> Self {
+        Self { tasks }
+    }
+
+    pub fn path(config: &Config) -> Option<PathBuf> {
+        config.state_dir().map(|dir| dir.join("last-list.json"))
+    }
+
+    /// The saved list, or `None` if there is none (or it can't be read).
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content)
+            .map_err(|e| tracing::warn!("Ignoring corrupt list numbers {}: {}", path.display(), e))
+            .ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Forget the saved list, if any.
+    pub fn clear(path: &Path) -> Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// The task listed as `number` (counting from 1).
+    pub fn get(&self, number: usize) -> Option<&ListedTask> {
+        number.checked_sub(1).and_then(|i| self.tasks.get(i))
+    }
+
+    /// Fail if `task`'s ID was listed under another title: whatever the
+    /// ID named then has moved, and `task` is not what the user saw.
+    pub fn check(&self, task: &Task) -> Result<()> {
+        match self.tasks.iter().find(|t| t.id == task.id) {
+            Some(listed) if listed.title != task.title => Err(TasukiError::StaleId(format!(
+                "Task '{}' is now \"{}\", not \"{}\" as last listed; run `tasuki list` again",
+                task.id, task.title, listed.title
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The task a bare number like `3` stands for, given the list saved at
+/// `path`. Other queries, and numbers when nothing has been listed, are
+/// left to title matching (`Ok(None)`).
+pub fn resolve(query: &str, path: Option<&Path>) -> Result<Option<ListedTask>> {
+    let Ok(number) = query.trim().parse::<usize>() else {
+        return Ok(None);
+    };
+    let Some(list) = path.and_then(LastList::load) else {
+        return Ok(None);
+    };
+
+    match list.get(number) {
+        Some(listed) => Ok(Some(listed.clone())),
+        None => Err(TasukiError::NotFound(format!(
+            "No task #{} in the last `tasuki list` ({} listed)",
+            number,
+            list.tasks.len()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(id: &str, title: &str) -> ListedTask {
+        ListedTask {
+            id: id.into(),
+            title: title.into(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_and_resolve() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("state").join("last-list.json");
+
+        // Nothing listed yet: numbers are just titles
+        assert_eq!(resolve("2", Some(&path)).unwrap(), None);
+
+        let list = LastList::new(vec![
+            listed("local:1", "Pay rent"),
+            listed("obsidian:Daily Notes/2025-02-25.md:3", "Call the bank"),
+        ]);
+        list.save(&path).unwrap();
+        assert_eq!(LastList::load(&path), Some(list));
+
+        assert_eq!(
+            resolve("2", Some(&path)).unwrap(),
+            Some(listed(
+                "obsidian:Daily Notes/2025-02-25.md:3",
+                "Call the bank"
+            ))
+        );
+        assert_eq!(
+            resolve(" 1 ", Some(&path)).unwrap(),
+            Some(listed("local:1", "Pay rent"))
+        );
+        assert!(matches!(
+            resolve("3", Some(&path)),
+            Err(TasukiError::NotFound(_))
+        ));
+        assert!(matches!(
+            resolve("0", Some(&path)),
+            Err(TasukiError::NotFound(_))
+        ));
+        assert_eq!(resolve("buy milk", Some(&path)).unwrap(), None);
+        assert_eq!(resolve("2", None).unwrap(), None);
+
+        LastList::clear(&path).unwrap();
+        LastList::clear(&path).unwrap();
+        assert_eq!(resolve("2", Some(&path)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_check_catches_moved_task() {
+        let list = LastList::new(vec![listed("local:1", "Pay rent")]);
+        assert!(list
+            .check(&Task::build("Pay rent").with_id("local:1"))
+            .is_ok());
+        assert!(list.check(&Task::build("Other").with_id("local:2")).is_ok());
+
+        let err = list
+            .check(&Task::build("Call the bank").with_id("local:1"))
+            .unwrap_err();
+        assert!(matches!(err, TasukiError::StaleId(_)));
+        assert_eq!(err.exit_code(), 2);
+    }
+}
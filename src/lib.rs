@@ -0,0 +1,74 @@
+//! Task aggregation across plain-text backends.
+//!
+//! This is the library behind the `tasuki` binary. Embedders get the task
+//! [`model`], the [`backends`] (todo.txt-style local file and Obsidian
+//! vault) behind the [`backends::TaskBackend`] trait, the quick-add parser
+//! in [`nlp`] and the [`config`] file format.
+//!
+//! The terminal UI, Waybar output and command line live behind the default
+//! `tui` feature. Build with `default-features = false` to leave out
+//! ratatui, crossterm and the rest of the binary's dependencies.
+
+#![warn(missing_docs)]
+
+/// Task storage: the `TaskBackend` trait, its implementations and
+/// `BackendManager`.
+pub mod backends;
+/// The current date, which can be pinned for tests and demos.
+pub mod clock;
+/// The config file format, loading and checking.
+pub mod config;
+/// Which editor to run and how to split its command line.
+pub mod editor;
+/// The error type shared by the whole crate.
+pub mod error;
+/// Tasks and the types used to create, change and filter them.
+pub mod model;
+/// The quick-add syntax: `Buy milk tomorrow #errands !!`.
+pub mod nlp;
+/// Config, data and state directories.
+pub mod paths;
+
+#[cfg(feature = "tui")]
+mod agenda;
+#[cfg(feature = "tui")]
+mod cli;
+#[cfg(feature = "tui")]
+mod commands;
+#[cfg(feature = "tui")]
+mod export;
+#[cfg(feature = "tui")]
+mod hooks;
+#[cfg(feature = "tui")]
+mod import;
+#[cfg(feature = "tui")]
+mod last_list;
+#[cfg(feature = "tui")]
+mod output;
+#[cfg(feature = "tui")]
+mod report;
+#[cfg(feature = "tui")]
+mod serve;
+#[cfg(feature = "tui")]
+mod stats;
+#[cfg(feature = "tui")]
+mod streak;
+#[cfg(feature = "tui")]
+mod sync;
+#[cfg(feature = "tui")]
+mod timelog;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "tui")]
+mod watch;
+#[cfg(feature = "tui")]
+mod waybar;
+
+pub use error::{Result, TasukiError};
+
+/// Entry point of the `tasuki` binary.
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub fn run() -> std::process::ExitCode {
+    commands::main()
+}
@@ -1,82 +1,694 @@
 use chrono::{NaiveDate, NaiveDateTime};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+/// Backend-specific task ID, e.g. `local:3`.
 pub type TaskId = String;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/// Whether a task is still to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskStatus {
+    /// Still to do
     Pending,
+    /// Completed
     Done,
 }
 
+/// Task priority, ordered from `None` up to `High`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum Priority {
+    /// No priority set
     None = 0,
+    /// `p3` / `!!!` by default
     Low = 1,
+    /// `p2` / `!!` by default
     Medium = 2,
+    /// `p1` / `!` by default
     High = 3,
 }
 
+impl Priority {
+    /// Read a standalone priority token: `(p1)`/`p1` style, or `!`, `!!`,
+    /// `!!!` mapped through `bangs`. Words that merely end in `!` don't count.
+    pub fn from_token(token: &str, bangs: &BangPriorities) -> Option<Priority> {
+        match token {
+            "(p1)" | "p1" => Some(Priority::High),
+            "(p2)" | "p2" => Some(Priority::Medium),
+            "(p3)" | "p3" => Some(Priority::Low),
+            "!" => Some(bangs.0[0]),
+            "!!" => Some(bangs.0[1]),
+            "!!!" => Some(bangs.0[2]),
+            _ => None,
+        }
+    }
+
+    /// Parse a priority name: `high`, `medium`, `low` or `none`.
+    pub fn from_name(name: &str) -> Option<Priority> {
+        match name {
+            "high" => Some(Priority::High),
+            "medium" => Some(Priority::Medium),
+            "low" => Some(Priority::Low),
+            "none" => Some(Priority::None),
+            _ => None,
+        }
+    }
+}
+
+/// Priority meant by `!`, `!!` and `!!!`, in that order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BangPriorities(pub [Priority; 3]);
+
+impl Default for BangPriorities {
+    fn default() -> Self {
+        Self([Priority::High, Priority::Medium, Priority::Low])
+    }
+}
+
+impl BangPriorities {
+    /// From `general.bang_priorities`, e.g. `["low", "medium", "high"]`.
+    pub fn from_names(names: &[String]) -> Result<Self, String> {
+        let parsed: Vec<Priority> = names
+            .iter()
+            .map(|n| {
+                Priority::from_name(n).ok_or_else(|| {
+                    format!(
+                        "general.bang_priorities: unknown priority '{}' (valid: high, medium, low, none)",
+                        n
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let bangs: [Priority; 3] = parsed.try_into().map_err(|_| {
+            "general.bang_priorities needs exactly 3 entries (for !, !! and !!!)".to_string()
+        })?;
+        Ok(Self(bangs))
+    }
+}
+
+/// A way to order tasks that are due the same day; see `Tiebreak`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Higher (effective) priority first
+    Priority,
+    /// Older first; tasks without a creation date last
+    Created,
+    /// Grouped by backend name
+    Source,
+    /// Alphabetical by title, ignoring case
+    Title,
+}
+
+impl SortKey {
+    /// Parse a key name as used in `general.tiebreak`.
+    pub fn from_name(name: &str) -> Option<SortKey> {
+        match name {
+            "priority" => Some(SortKey::Priority),
+            "created" => Some(SortKey::Created),
+            "source" => Some(SortKey::Source),
+            "title" => Some(SortKey::Title),
+            _ => None,
+        }
+    }
+}
+
+/// Keys applied in order after the due-date sort, from `general.tiebreak`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tiebreak(pub Vec<SortKey>);
+
+impl Default for Tiebreak {
+    fn default() -> Self {
+        Self(vec![SortKey::Priority, SortKey::Title])
+    }
+}
+
+impl Tiebreak {
+    /// From `general.tiebreak`, e.g. `["created", "title"]`.
+    pub fn from_names(names: &[String]) -> Result<Self, String> {
+        names
+            .iter()
+            .map(|n| {
+                SortKey::from_name(n).ok_or_else(|| {
+                    format!(
+                        "general.tiebreak: unknown key '{}' (valid: priority, created, source, title)",
+                        n
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// A task as read from a backend.
 #[derive(Debug, Clone, Serialize)]
 pub struct Task {
+    /// Identifies the task within its backend
     pub id: TaskId,
+    /// Task text, without priority, tags or dates
     pub title: String,
+    /// Pending or done
     pub status: TaskStatus,
+    /// Stored priority; see `effective_priority` for sorting
     pub priority: Priority,
+    /// Due date, if any
     pub due: Option<NaiveDate>,
+    /// Tags without the leading `#`
     pub tags: Vec<String>,
+    /// Backend the task came from
     pub source: BackendSource,
+    /// Line of the task in its file
     pub source_line: Option<usize>,
+    /// File the task came from
     pub source_path: Option<String>,
+    /// When the task was created, if the backend records it
     pub created_at: Option<NaiveDateTime>,
+    /// When the task was completed, if the backend records it
     pub completed_at: Option<NaiveDateTime>,
+    /// Nearest heading above the task in its note
     pub heading_context: Option<String>,
+    /// Project the task belongs to, e.g. its note's name
+    pub project: Option<String>,
+}
+
+impl Task {
+    /// Priority used for sorting and display: a pending task overdue by more
+    /// than `escalate_after` days counts as High. The stored priority is
+    /// never changed.
+    pub fn effective_priority(&self, today: NaiveDate, escalate_after: Option<u32>) -> Priority {
+        if self.is_escalated(today, escalate_after) {
+            Priority::High
+        } else {
+            self.priority
+        }
+    }
+
+    /// Whether `effective_priority` bumps this task above its stored priority.
+    pub fn is_escalated(&self, today: NaiveDate, escalate_after: Option<u32>) -> bool {
+        let (Some(days), Some(due)) = (escalate_after, self.due) else {
+            return false;
+        };
+        self.status == TaskStatus::Pending
+            && self.priority != Priority::High
+            && (today - due).num_days() > i64::from(days)
+    }
+
+    /// Whether the task has `wanted` or a tag nested under it; see `tag_matches`.
+    pub fn has_tag(&self, wanted: &str) -> bool {
+        self.tags.iter().any(|tag| tag_matches(tag, wanted))
+    }
+
+    /// Whether a done task was completed on a day after its due date.
+    /// Needs both `due` and `completed_at`; finishing on the due day is on time.
+    pub fn was_completed_late(&self) -> bool {
+        let (Some(due), Some(completed)) = (self.due, self.completed_at) else {
+            return false;
+        };
+        self.status == TaskStatus::Done && completed.date() > due
+    }
+}
+
+/// Tasks for tests: `Task::build("Buy milk")` is a pending local task with
+/// nothing else set, and the `with_*` methods fill in the rest.
+#[cfg(test)]
+impl Task {
+    pub(crate) fn build(title: &str) -> Self {
+        Self {
+            id: format!("local:{}", title),
+            title: title.to_string(),
+            status: TaskStatus::Pending,
+            priority: Priority::None,
+            due: None,
+            tags: Vec::new(),
+            source: BackendSource::LocalFile,
+            source_line: None,
+            source_path: None,
+            created_at: None,
+            completed_at: None,
+            heading_context: None,
+            project: None,
+        }
+    }
+
+    /// Also sets `source` when `id` starts with a backend's name.
+    pub(crate) fn with_id(mut self, id: &str) -> Self {
+        if let Some(source) = id.split(':').next().and_then(BackendSource::from_name) {
+            self.source = source;
+        }
+        self.id = id.to_string();
+        self
+    }
+
+    pub(crate) fn with_status(mut self, status: TaskStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub(crate) fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub(crate) fn with_due(mut self, due: impl Into<Option<NaiveDate>>) -> Self {
+        self.due = due.into();
+        self
+    }
+
+    pub(crate) fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    pub(crate) fn with_source(mut self, source: BackendSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    #[cfg(feature = "tui")]
+    pub(crate) fn with_project(mut self, project: &str) -> Self {
+        self.project = Some(project.to_string());
+        self
+    }
+
+    pub(crate) fn with_created(mut self, at: impl Into<Option<NaiveDateTime>>) -> Self {
+        self.created_at = at.into();
+        self
+    }
+
+    pub(crate) fn with_completed(mut self, at: impl Into<Option<NaiveDateTime>>) -> Self {
+        self.completed_at = at.into();
+        self
+    }
+}
+
+/// `raw` (the text after `#`) as a tag: sentence punctuation typed after it
+/// (`#work.`) and stray `#`s are dropped, and with `lowercase` it's
+/// lowercased. `None` when no tag is left.
+pub fn normalize_tag(raw: &str, lowercase: bool) -> Option<String> {
+    let tag = raw
+        .trim_start_matches('#')
+        .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '"', '\'', '/']);
+    if tag.is_empty() {
+        return None;
+    }
+    Some(if lowercase {
+        tag.to_lowercase()
+    } else {
+        tag.to_string()
+    })
+}
+
+/// `tags` without repeats, keeping the first spelling of each. Case is
+/// ignored, as in `tag_matches`, so `#Work #work` is one tag.
+pub fn dedupe_tags(tags: Vec<String>) -> Vec<String> {
+    let mut kept: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        if !kept.iter().any(|k| k.eq_ignore_ascii_case(&tag)) {
+            kept.push(tag);
+        }
+    }
+    kept
+}
+
+/// Whether `tag` is `wanted` or nested under it, as Obsidian treats nested
+/// tags: `work` matches `work/clientA` but not `workshop`. Case is ignored
+/// and a leading `#` on `wanted` is allowed.
+pub fn tag_matches(tag: &str, wanted: &str) -> bool {
+    let wanted = wanted
+        .strip_prefix('#')
+        .unwrap_or(wanted)
+        .trim_end_matches('/');
+    match tag.get(..wanted.len()) {
+        Some(head) if head.eq_ignore_ascii_case(wanted) => {
+            tag.len() == wanted.len() || tag[wanted.len()..].starts_with('/')
+        }
+        _ => false,
+    }
 }
 
+/// Which backend a task lives in.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum BackendSource {
+    /// An Obsidian vault
     Obsidian,
+    /// The local todo.txt file
     LocalFile,
+    /// A read-only iCalendar file or subscription
+    ICal,
 }
 
 impl BackendSource {
-    pub fn name(&self) -> &str {
+    /// Every source, in the order backends are set up.
+    pub const ALL: &'static [BackendSource] = &[
+        BackendSource::LocalFile,
+        BackendSource::Obsidian,
+        BackendSource::ICal,
+    ];
+
+    /// Look up a source by its config name (`local`, `obsidian`, `ical`).
+    pub fn from_name(name: &str) -> Option<BackendSource> {
+        Self::ALL.iter().copied().find(|s| s.name() == name)
+    }
+
+    /// Every source's name, for messages: `local, obsidian, ical`.
+    pub fn names() -> String {
+        let names: Vec<&str> = Self::ALL.iter().map(|s| s.name()).collect();
+        names.join(", ")
+    }
+
+    /// Config name of this source, as in `[backends.<name>]` and `@name`.
+    pub fn name(&self) -> &'static str {
         match self {
             Self::Obsidian => "obsidian",
             Self::LocalFile => "local",
+            Self::ICal => "ical",
         }
     }
 
-    pub fn icon(&self) -> &str {
+    /// Glyph marking tasks from this source in lists.
+    pub fn icon(&self) -> &'static str {
         match self {
             Self::Obsidian => "◆",
             Self::LocalFile => "■",
+            Self::ICal => "▲",
         }
     }
 }
 
+/// A task to be created.
+#[derive(Debug, Clone, PartialEq)]
 pub struct NewTask {
+    /// Task text
     pub title: String,
+    /// Priority to store, if the backend supports it
     pub priority: Priority,
+    /// Due date, if any
     pub due: Option<NaiveDate>,
+    /// Tags without the leading `#`
     pub tags: Vec<String>,
+    /// Backend to create the task in
     pub backend: BackendSource,
 }
 
+/// Changes to a task; `None` fields are left alone.
 #[derive(Debug, Clone, Default)]
 pub struct TaskUpdate {
+    /// New title
     pub title: Option<String>,
+    /// New status
     pub status: Option<TaskStatus>,
+    /// New priority
     pub priority: Option<Priority>,
+    /// New due date; `Some(None)` removes it
     pub due: Option<Option<NaiveDate>>,
+    /// New tags, replacing the old ones
     pub tags: Option<Vec<String>>,
 }
 
-#[derive(Default)]
+/// How a search matches task titles, from `general.search_mode`. Case is
+/// ignored in every mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchMode {
+    /// The text anywhere in the title
+    #[default]
+    Substring,
+    /// The text as whole words: `cat` matches "Feed the cat" but not
+    /// "category"
+    Word,
+    /// A regular expression
+    Regex,
+}
+
+impl SearchMode {
+    /// Parse a mode name as used in `general.search_mode`.
+    pub fn from_name(name: &str) -> Option<SearchMode> {
+        match name {
+            "substring" => Some(SearchMode::Substring),
+            "word" => Some(SearchMode::Word),
+            "regex" => Some(SearchMode::Regex),
+            _ => None,
+        }
+    }
+}
+
+/// A search query ready to test titles against. Build it once per fetch;
+/// compiling is where an invalid regex is reported.
+#[derive(Debug, Clone)]
+pub struct SearchMatcher(Matcher);
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    /// Lowercased query
+    Substring(String),
+    /// Lowercased, trimmed query
+    Word(String),
+    Regex(regex::Regex),
+}
+
+impl SearchMatcher {
+    /// Compile `query` for `mode`; fails with a parse error on an invalid
+    /// regex.
+    pub fn new(query: &str, mode: SearchMode) -> crate::error::Result<Self> {
+        Ok(SearchMatcher(match mode {
+            SearchMode::Substring => Matcher::Substring(query.to_lowercase()),
+            SearchMode::Word => Matcher::Word(query.trim().to_lowercase()),
+            SearchMode::Regex => Matcher::Regex(
+                regex::RegexBuilder::new(query)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| {
+                        // The full message draws a caret under the pattern;
+                        // its last line says what's wrong
+                        let e = e.to_string();
+                        let reason = e.lines().last().unwrap_or_default();
+                        crate::error::TasukiError::Parse(format!(
+                            "Invalid search regex: {}",
+                            reason.trim_start_matches("error: ")
+                        ))
+                    })?,
+            ),
+        }))
+    }
+
+    /// Whether `title` matches.
+    pub fn matches(&self, title: &str) -> bool {
+        match &self.0 {
+            Matcher::Substring(query) => title.to_lowercase().contains(query.as_str()),
+            Matcher::Word(query) => contains_word(&title.to_lowercase(), query),
+            Matcher::Regex(re) => re.is_match(title),
+        }
+    }
+}
+
+/// Whether `task`'s title matches `search`.
+pub fn task_matches_search(task: &Task, search: &SearchMatcher) -> bool {
+    search.matches(&task.title)
+}
+
+/// Whether `needle` occurs in `haystack` with no letter, digit or `_`
+/// directly on either side.
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// Which tasks to fetch; `None` fields don't filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct TaskFilter {
+    /// Only tasks with this status
     pub status: Option<TaskStatus>,
+    /// Only tasks due on or before this date
     pub due_before: Option<NaiveDate>,
+    /// Only tasks due on or after this date
     pub due_after: Option<NaiveDate>,
+    /// Only tasks whose title matches this text, as `search_mode` says
     pub search: Option<String>,
+    /// How `search` is matched
+    pub search_mode: SearchMode,
+    /// Only tasks with (`true`) or without (`false`) a due date
     pub has_due: Option<bool>,
+    /// Only tasks with this tag or one nested under it
+    pub tag: Option<String>,
+}
+
+impl TaskFilter {
+    /// `search` compiled for `search_mode`, if set. Fails on an invalid
+    /// regex.
+    pub fn search_matcher(&self) -> crate::error::Result<Option<SearchMatcher>> {
+        self.search
+            .as_deref()
+            .map(|query| SearchMatcher::new(query, self.search_mode))
+            .transpose()
+    }
+
+    /// Keep only the `tasks` this filter lets through; the backends that
+    /// can't filter as they read call this on what they read. Fails on an
+    /// invalid search regex.
+    pub fn retain(&self, tasks: &mut Vec<Task>) -> crate::error::Result<()> {
+        let search = self.search_matcher()?;
+        tasks.retain(|t| {
+            self.status.as_ref().map_or(true, |s| &t.status == s)
+                && self
+                    .due_before
+                    .map_or(true, |d| t.due.is_some_and(|due| due <= d))
+                && self
+                    .due_after
+                    .map_or(true, |d| t.due.is_some_and(|due| due >= d))
+                && search.as_ref().map_or(true, |s| task_matches_search(t, s))
+                && self.has_due.map_or(true, |has| t.due.is_some() == has)
+                && self.tag.as_ref().map_or(true, |tag| t.has_tag(tag))
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn done_task(due: Option<NaiveDate>, completed: Option<NaiveDate>) -> Task {
+        Task::build("Report")
+            .with_status(TaskStatus::Done)
+            .with_due(due)
+            .with_completed(completed.map(|d| d.and_hms_opt(18, 0, 0).unwrap()))
+    }
+
+    #[test]
+    fn test_search_modes() {
+        let titles = [
+            "Feed the cat",
+            "Update category list",
+            "cat_food order",
+            "Call Cat's vet (urgent)",
+            "Invoice 2025-03",
+        ];
+        let found = |query: &str, mode| {
+            let search = SearchMatcher::new(query, mode).unwrap();
+            titles
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| search.matches(t))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(found("cat", SearchMode::Substring), vec![0, 1, 2, 3]);
+        assert_eq!(found("CAT", SearchMode::Word), vec![0, 3]);
+        assert_eq!(found("the cat", SearchMode::Word), vec![0]);
+        assert_eq!(found("(urgent)", SearchMode::Word), vec![3]);
+        assert_eq!(found(r"^cat\b", SearchMode::Regex), Vec::<usize>::new());
+        assert_eq!(found(r"\bcat", SearchMode::Regex), vec![0, 1, 2, 3]);
+        assert_eq!(found(r"^(feed|call)", SearchMode::Regex), vec![0, 3]);
+        assert_eq!(found(r"\d{4}-\d{2}$", SearchMode::Regex), vec![4]);
+
+        let err = SearchMatcher::new("(unclosed", SearchMode::Regex).unwrap_err();
+        assert!(matches!(err, crate::error::TasukiError::Parse(_)));
+        assert!(!err.to_string().contains('\n'));
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        assert_eq!(normalize_tag("Work.", false).as_deref(), Some("Work"));
+        assert_eq!(normalize_tag("Work.", true).as_deref(), Some("work"));
+        assert_eq!(
+            normalize_tag("work/clientA", false).as_deref(),
+            Some("work/clientA")
+        );
+        assert_eq!(normalize_tag("done?!", false).as_deref(), Some("done"));
+        assert_eq!(normalize_tag("#home", false).as_deref(), Some("home"));
+        assert_eq!(normalize_tag("", false), None);
+        assert_eq!(normalize_tag("#", false), None);
+        assert_eq!(normalize_tag("...", false), None);
+
+        let tags = ["Work", "home", "work", "Home", "work/clientA"];
+        assert_eq!(
+            dedupe_tags(tags.iter().map(|t| t.to_string()).collect()),
+            vec!["Work", "home", "work/clientA"]
+        );
+    }
+
+    #[test]
+    fn test_tag_matches_nested() {
+        assert!(tag_matches("work", "work"));
+        assert!(tag_matches("work/clientA", "work"));
+        assert!(tag_matches("work/clientA/billing", "#work/clientA"));
+        assert!(tag_matches("Work/ClientA", "work/"));
+        assert!(!tag_matches("workshop", "work"));
+        assert!(!tag_matches("work", "work/clientA"));
+        assert!(!tag_matches("homework", "work"));
+        assert!(!tag_matches("wörk", "w"));
+
+        let task = done_task(None, None).with_tags(&["home", "work/clientA"]);
+        assert!(task.has_tag("work"));
+        assert!(!task.has_tag("clientA"));
+    }
+
+    #[test]
+    fn test_was_completed_late() {
+        let due = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let day_after = NaiveDate::from_ymd_opt(2025, 3, 11).unwrap();
+
+        assert!(done_task(Some(due), Some(day_after)).was_completed_late());
+        assert!(!done_task(Some(due), Some(due)).was_completed_late());
+        assert!(!done_task(Some(day_after), Some(due)).was_completed_late());
+        assert!(!done_task(None, Some(day_after)).was_completed_late());
+        assert!(!done_task(Some(due), None).was_completed_late());
+
+        let mut pending = done_task(Some(due), Some(day_after));
+        pending.status = TaskStatus::Pending;
+        assert!(!pending.was_completed_late());
+    }
+
+    #[test]
+    fn test_filter_retain() {
+        let march = |day| NaiveDate::from_ymd_opt(2025, 3, day);
+        let mut tasks = vec![
+            done_task(march(5), None),
+            done_task(march(20), None),
+            done_task(None, None),
+        ];
+        tasks[1].status = TaskStatus::Pending;
+        tasks[2].tags = vec!["work".to_string()];
+        let kept = |filter: TaskFilter| {
+            let mut kept = tasks.clone();
+            filter.retain(&mut kept).unwrap();
+            kept.iter().map(|t| t.due).collect::<Vec<_>>()
+        };
+
+        // Undated tasks aren't due before or after anything
+        let before = TaskFilter {
+            due_before: march(10),
+            ..Default::default()
+        };
+        assert_eq!(kept(before), vec![march(5)]);
+        let after = TaskFilter {
+            due_after: march(10),
+            ..Default::default()
+        };
+        assert_eq!(kept(after), vec![march(20)]);
+        let pending = TaskFilter {
+            status: Some(TaskStatus::Pending),
+            ..Default::default()
+        };
+        assert_eq!(kept(pending), vec![march(20)]);
+        let undated_work = TaskFilter {
+            has_due: Some(false),
+            tag: Some("work".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(kept(undated_work), vec![None]);
+
+        let bad_regex = TaskFilter {
+            search: Some("(".to_string()),
+            search_mode: SearchMode::Regex,
+            ..Default::default()
+        };
+        assert!(bad_regex.retain(&mut tasks.clone()).is_err());
+    }
 }
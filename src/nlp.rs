@@ -1,86 +1,348 @@
-use chrono::{Datelike, Local, NaiveDate, Weekday};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 
-use crate::backends::BackendManager;
 use crate::error::Result;
-use crate::model::{BackendSource, Priority};
+use crate::model::{dedupe_tags, normalize_tag, BackendSource, BangPriorities, NewTask, Priority};
+
+/// What a quick-add word was interpreted as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Part of the title
+    Title,
+    /// `#tag`
+    Tag,
+    /// `!`, `p1` and the like
+    Priority,
+    /// A due date such as `tomorrow` or `@2025-03-01`
+    Due,
+    /// `@local` or another backend in `QuickAddOptions::targets`
+    Backend,
+    /// `@word` that is neither a date nor a backend; kept in the title
+    Context,
+}
+
+/// One word of quick-add input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The word as typed
+    pub text: String,
+    /// What it was read as
+    pub kind: TokenKind,
+}
+
+/// Quick-add input split into task fields.
+#[derive(Debug, Clone)]
+pub struct ParsedQuickAdd {
+    /// Words left after taking out tags, priority, date and backend
+    pub title: String,
+    /// Priority from `!` or `p1`-style tokens
+    pub priority: Priority,
+    /// Due date, if one was given
+    pub due: Option<NaiveDate>,
+    /// Time of day from a word like `eod`. Tasks only store a date, so
+    /// this just picks the day and is shown in the TUI's preview
+    pub due_time: Option<NaiveTime>,
+    /// Tags without the leading `#`
+    pub tags: Vec<String>,
+    /// `None` when no `@backend` was given
+    pub backend: Option<BackendSource>,
+    /// Every input word in order, with how it was interpreted
+    pub tokens: Vec<Token>,
+}
+
+impl ParsedQuickAdd {
+    /// A `NewTask` going to `backend`, or `default_backend` if none was given.
+    pub fn into_new_task(self, default_backend: BackendSource) -> NewTask {
+        NewTask {
+            title: self.title,
+            priority: self.priority,
+            due: self.due,
+            tags: self.tags,
+            backend: self.backend.unwrap_or(default_backend),
+        }
+    }
+}
+
+/// Which day a weekday typed on that same weekday means, from
+/// `general.weekday_today_policy`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WeekdayPolicy {
+    /// A week from today: "monday" typed on a Monday is next Monday
+    #[default]
+    Next,
+    /// Today itself
+    TodayIfMatch,
+}
+
+impl WeekdayPolicy {
+    /// Parse a policy name as used in `general.weekday_today_policy`.
+    pub fn from_name(name: &str) -> Option<WeekdayPolicy> {
+        match name {
+            "next" => Some(WeekdayPolicy::Next),
+            "today_if_match" => Some(WeekdayPolicy::TodayIfMatch),
+            _ => None,
+        }
+    }
+}
+
+/// The times of day that `eod`, `tonight`, `morning` and `noon` stand
+/// for, from `[nlp.times]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWords {
+    /// `eod`, the end of the working day
+    pub eod: NaiveTime,
+    /// `tonight`
+    pub tonight: NaiveTime,
+    /// `morning`
+    pub morning: NaiveTime,
+    /// `noon`
+    pub noon: NaiveTime,
+}
+
+impl Default for TimeWords {
+    fn default() -> Self {
+        let at = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        Self {
+            eod: at(17),
+            tonight: at(20),
+            morning: at(9),
+            noon: at(12),
+        }
+    }
+}
+
+impl TimeWords {
+    /// The time `word` (lowercase) stands for, if it is one of them.
+    pub fn get(&self, word: &str) -> Option<NaiveTime> {
+        match word {
+            "eod" => Some(self.eod),
+            "tonight" => Some(self.tonight),
+            "morning" => Some(self.morning),
+            "noon" => Some(self.noon),
+            _ => None,
+        }
+    }
+}
+
+/// Settings from `[general]` and `[nlp]` that change how quick-add reads
+/// its input.
+#[derive(Debug, Clone)]
+pub struct QuickAddOptions {
+    /// What `!`, `!!` and `!!!` mean
+    pub bangs: BangPriorities,
+    /// First day of the week; "next week" is the next one of these
+    pub week_start: Weekday,
+    /// Lowercase `#tags`
+    pub lowercase_tags: bool,
+    /// What a weekday means on that weekday
+    pub weekday_policy: WeekdayPolicy,
+    /// What `eod` and the like mean
+    pub time_words: TimeWords,
+    /// A time word whose time has passed means tomorrow rather than today
+    pub rollover: bool,
+    /// Backends `@name` can send a task to, by `TaskBackend::name`, with
+    /// the source their tasks report; any other `@name` is a context. Empty
+    /// by default: `BackendManager::quick_add_options` fills it in
+    pub targets: Vec<(String, BackendSource)>,
+}
+
+impl Default for QuickAddOptions {
+    fn default() -> Self {
+        Self {
+            bangs: BangPriorities::default(),
+            week_start: Weekday::Mon,
+            lowercase_tags: false,
+            weekday_policy: WeekdayPolicy::Next,
+            time_words: TimeWords::default(),
+            rollover: true,
+            targets: Vec::new(),
+        }
+    }
+}
+
+/// Parse quick-add text with the default options.
+pub fn parse_quick_add(text: &str) -> Result<ParsedQuickAdd> {
+    parse_quick_add_with(text, &QuickAddOptions::default())
+}
+
+/// Like `parse_quick_add`, with `options` from the config.
+pub fn parse_quick_add_with(text: &str, options: &QuickAddOptions) -> Result<ParsedQuickAdd> {
+    parse_quick_add_at(text, options, crate::clock::now())
+}
 
-pub fn parse_quick_add(
+/// Like `parse_quick_add_with`, as if it were `now`.
+pub fn parse_quick_add_at(
     text: &str,
-    _backend_manager: &BackendManager,
-) -> Result<(
-    String,
-    Priority,
-    Option<NaiveDate>,
-    Vec<String>,
-    BackendSource,
-)> {
+    options: &QuickAddOptions,
+    now: NaiveDateTime,
+) -> Result<ParsedQuickAdd> {
     let words: Vec<&str> = text.split_whitespace().collect();
     let mut tags = Vec::new();
     let mut priority = Priority::None;
     let mut due: Option<NaiveDate> = None;
+    let mut due_time: Option<NaiveTime> = None;
     let mut backend: Option<BackendSource> = None;
     let mut title_words = Vec::new();
+    let mut tokens: Vec<Token> = Vec::new();
 
-    let today = Local::now().date_naive();
+    let today = now.date();
+    let policy = options.weekday_policy;
 
     let mut i = 0;
     while i < words.len() {
         let word = words[i];
+        let token = |kind| Token {
+            text: word.to_string(),
+            kind,
+        };
+
+        // `@eod`, `@tonight` and the like; without the `@` see below
+        let time = word
+            .strip_prefix('@')
+            .and_then(|rest| options.time_words.get(&rest.to_lowercase()));
+        if let Some(time) = time {
+            due_time = Some(time);
+            tokens.push(token(TokenKind::Due));
+            i += 1;
+            continue;
+        }
 
-        if word.starts_with('@') && backend.is_none() {
-            let backend_name = &word[1..];
-            backend = match backend_name {
-                "obsidian" => Some(BackendSource::Obsidian),
-                "local" => Some(BackendSource::LocalFile),
-                _ => None,
-            };
-            if backend.is_some() {
+        // `@` is a date if it parses as one, else a backend, else a context
+        if let Some(rest) = word.strip_prefix('@').filter(|rest| !rest.is_empty()) {
+            if let Some(date) = parse_date_word(&rest.to_lowercase(), rest, today, policy) {
+                due = Some(date);
+                tokens.push(token(TokenKind::Due));
                 i += 1;
                 continue;
             }
-        }
 
-        if word.starts_with('#') {
-            tags.push(word[1..].to_string());
-            i += 1;
-            continue;
+            let named = options
+                .targets
+                .iter()
+                .find(|(name, _)| name == rest)
+                .map(|&(_, source)| source);
+            if named.is_some() && backend.is_none() {
+                backend = named;
+                tokens.push(token(TokenKind::Backend));
+                i += 1;
+                continue;
+            }
+
+            if named.is_none() {
+                tokens.push(token(TokenKind::Context));
+                title_words.push(word);
+                i += 1;
+                continue;
+            }
         }
 
-        if word == "(p1)" {
-            priority = Priority::High;
+        // A bare `#` isn't a tag and stays in the title
+        let tag = word
+            .strip_prefix('#')
+            .and_then(|tag| normalize_tag(tag, options.lowercase_tags));
+        if let Some(tag) = tag {
+            tags.push(tag);
+            tokens.push(token(TokenKind::Tag));
             i += 1;
             continue;
         }
-        if word == "(p2)" {
-            priority = Priority::Medium;
+
+        if let Some(p) = Priority::from_token(word, &options.bangs) {
+            priority = p;
+            tokens.push(token(TokenKind::Priority));
             i += 1;
             continue;
         }
-        if word == "(p3)" {
-            priority = Priority::Low;
-            i += 1;
+
+        let lower = word.to_lowercase();
+
+        if lower == "next"
+            && words
+                .get(i + 1)
+                .is_some_and(|w| w.eq_ignore_ascii_case("week"))
+        {
+            due = Some(start_of_next_week(today, options.week_start));
+            tokens.push(token(TokenKind::Due));
+            tokens.push(Token {
+                text: words[i + 1].to_string(),
+                kind: TokenKind::Due,
+            });
+            i += 2;
             continue;
         }
 
-        let lower = word.to_lowercase();
-        if let Some(date) = try_parse_date(&lower, word, &words, i, today, &mut title_words) {
+        let title_len = title_words.len();
+        if let Some(date) = try_parse_date(&lower, word, &words, i, today, policy, &mut title_words)
+        {
+            // A preceding "on"/"by" was pulled out of the title as part of the date
+            if title_words.len() < title_len {
+                if let Some(prev) = tokens.iter_mut().rev().find(|t| t.kind == TokenKind::Title) {
+                    prev.kind = TokenKind::Due;
+                }
+            }
             due = Some(date);
+            tokens.push(token(TokenKind::Due));
             i += 1;
             continue;
         }
 
+        tokens.push(token(TokenKind::Title));
         title_words.push(word);
         i += 1;
     }
 
+    // A bare time word is only a deadline as the title's last word, so
+    // "Morning pages" keeps its words while "Call mom tonight" doesn't
+    if due_time.is_none() && title_words.len() > 1 {
+        let time = title_words
+            .last()
+            .and_then(|word| options.time_words.get(&word.to_lowercase()));
+        if let Some(time) = time {
+            due_time = Some(time);
+            title_words.pop();
+            if let Some(last) = tokens.iter_mut().rev().find(|t| t.kind == TokenKind::Title) {
+                last.kind = TokenKind::Due;
+            }
+        }
+    }
+
+    // A time without a date is today's, or tomorrow's once it's gone by
+    if let (None, Some(time)) = (due, due_time) {
+        due = Some(if options.rollover && now.time() >= time {
+            today + chrono::Duration::days(1)
+        } else {
+            today
+        });
+    }
+
+    let tags = dedupe_tags(tags);
     let title = title_words.join(" ");
 
-    let backend = backend.unwrap_or(BackendSource::LocalFile);
+    Ok(ParsedQuickAdd {
+        title,
+        priority,
+        due,
+        due_time,
+        tags,
+        backend,
+        tokens,
+    })
+}
+
+/// The first day of the week `today` is in, weeks starting on `week_start`.
+pub fn start_of_week(today: NaiveDate, week_start: Weekday) -> NaiveDate {
+    let into_week =
+        (today.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7;
+    today - chrono::Duration::days(i64::from(into_week))
+}
 
-    Ok((title, priority, due, tags, backend))
+/// The first day of the week after the one `today` is in.
+fn start_of_next_week(today: NaiveDate, week_start: Weekday) -> NaiveDate {
+    start_of_week(today, week_start) + chrono::Duration::days(7)
 }
 
-fn parse_weekday(day: &str, today: NaiveDate) -> Option<NaiveDate> {
+/// The next `day` (a weekday name) after `today`, or today itself when it
+/// is that day and `policy` says so.
+fn parse_weekday(day: &str, today: NaiveDate, policy: WeekdayPolicy) -> Option<NaiveDate> {
     let target_weekday = match day {
         "monday" | "mon" => Weekday::Mon,
         "tuesday" | "tue" | "tues" => Weekday::Tue,
@@ -97,7 +359,10 @@ fn parse_weekday(day: &str, today: NaiveDate) -> Option<NaiveDate> {
         - today_weekday.num_days_from_monday() as i64
         + 7)
         % 7;
-    let days_until = if days_until == 0 { 7 } else { days_until };
+    let days_until = match (days_until, policy) {
+        (0, WeekdayPolicy::Next) => 7,
+        (days, _) => days,
+    };
 
     Some(today + chrono::Duration::days(days_until))
 }
@@ -108,25 +373,61 @@ fn try_parse_date(
     words: &[&str],
     idx: usize,
     today: NaiveDate,
+    policy: WeekdayPolicy,
     title_words: &mut Vec<&str>,
 ) -> Option<NaiveDate> {
-    match lower {
-        "today" => return Some(today),
-        "tomorrow" | "tmr" => return Some(today + chrono::Duration::days(1)),
-        _ => {}
-    }
-
     if idx > 0 {
         let prev = words[idx - 1].to_lowercase();
         if (prev == "on" || prev == "by") && !title_words.is_empty() {
-            if let Some(date) = parse_weekday(lower, today) {
+            if let Some(date) = parse_weekday(lower, today, policy) {
                 title_words.pop(); // Remove "on" or "by"
                 return Some(date);
             }
         }
     }
 
-    if let Some(date) = parse_weekday(lower, today) {
+    parse_date_word(lower, word, today, policy)
+}
+
+/// A due date typed on its own, as quick-add reads one: today, tomorrow, a
+/// weekday or YYYY-MM-DD, with or without a leading `@`.
+pub fn parse_due(text: &str, today: NaiveDate, policy: WeekdayPolicy) -> Option<NaiveDate> {
+    let word = text.trim();
+    let word = word.strip_prefix('@').unwrap_or(word);
+    parse_date_word(&word.to_lowercase(), word, today, policy)
+}
+
+/// Like `parse_due`, but for a day that has come already, as in `tasuki
+/// report --since monday`: a weekday is the latest one up to and including
+/// `today`, and `yesterday` works too.
+pub fn parse_past_day(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let word = text.trim();
+    let lower = word.strip_prefix('@').unwrap_or(word).to_lowercase();
+    if lower == "yesterday" {
+        return Some(today - chrono::Duration::days(1));
+    }
+    // `parse_weekday` looks ahead, one to seven days
+    if let Some(next) = parse_weekday(&lower, today, WeekdayPolicy::Next) {
+        return Some(next - chrono::Duration::days(7));
+    }
+    parse_due(text, today, WeekdayPolicy::Next)
+}
+
+/// A single word that is a date on its own: today, tomorrow, a weekday or
+/// YYYY-MM-DD.
+fn parse_date_word(
+    lower: &str,
+    word: &str,
+    today: NaiveDate,
+    policy: WeekdayPolicy,
+) -> Option<NaiveDate> {
+    match lower {
+        "today" => return Some(today),
+        "tomorrow" | "tmr" => return Some(today + chrono::Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some(date) = parse_weekday(lower, today, policy) {
         return Some(date);
     }
 
@@ -136,15 +437,17 @@ fn try_parse_date(
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn create_empty_backend_manager() -> BackendManager {
-        BackendManager::new(vec![])
-    }
+    use chrono::Local;
 
     #[test]
     fn test_parse_simple_task() {
-        let bm = create_empty_backend_manager();
-        let (title, priority, due, tags, _) = parse_quick_add("Buy milk", &bm).unwrap();
+        let ParsedQuickAdd {
+            title,
+            priority,
+            due,
+            tags,
+            ..
+        } = parse_quick_add("Buy milk").unwrap();
         assert_eq!(title, "Buy milk");
         assert_eq!(priority, Priority::None);
         assert!(due.is_none());
@@ -153,91 +456,136 @@ mod tests {
 
     #[test]
     fn test_parse_with_tags() {
-        let bm = create_empty_backend_manager();
-        let (title, _, _, tags, _) = parse_quick_add("Buy milk #groceries #shopping", &bm).unwrap();
+        let ParsedQuickAdd { title, tags, .. } =
+            parse_quick_add("Buy milk #groceries #shopping").unwrap();
         assert_eq!(title, "Buy milk");
         assert_eq!(tags, vec!["groceries", "shopping"]);
     }
 
+    #[test]
+    fn test_parse_nested_tags() {
+        let ParsedQuickAdd { title, tags, .. } =
+            parse_quick_add("Send invoice #work/clientA/billing #home").unwrap();
+        assert_eq!(title, "Send invoice");
+        assert_eq!(tags, vec!["work/clientA/billing", "home"]);
+    }
+
+    #[test]
+    fn test_parse_normalizes_tags() {
+        let ParsedQuickAdd { title, tags, .. } =
+            parse_quick_add("Email Sam about # budget #work #Work.").unwrap();
+        assert_eq!(title, "Email Sam about # budget");
+        assert_eq!(tags, vec!["work"]);
+
+        let ParsedQuickAdd { tags, .. } = parse_quick_add("Plan #Work. #work").unwrap();
+        assert_eq!(tags, vec!["Work"]);
+
+        let options = QuickAddOptions {
+            lowercase_tags: true,
+            ..Default::default()
+        };
+        let ParsedQuickAdd { tags, .. } =
+            parse_quick_add_with("Plan #Work. #Home/Garden", &options).unwrap();
+        assert_eq!(tags, vec!["work", "home/garden"]);
+    }
+
     #[test]
     fn test_parse_with_priority_p1() {
-        let bm = create_empty_backend_manager();
-        let (_, priority, _, _, _) = parse_quick_add("Call dentist (p1)", &bm).unwrap();
+        let ParsedQuickAdd { priority, .. } = parse_quick_add("Call dentist (p1)").unwrap();
         assert_eq!(priority, Priority::High);
     }
 
     #[test]
     fn test_parse_with_priority_p2() {
-        let bm = create_empty_backend_manager();
-        let (_, priority, _, _, _) = parse_quick_add("Submit report (p2)", &bm).unwrap();
+        let ParsedQuickAdd { priority, .. } = parse_quick_add("Submit report (p2)").unwrap();
         assert_eq!(priority, Priority::Medium);
     }
 
     #[test]
     fn test_parse_with_priority_p3() {
-        let bm = create_empty_backend_manager();
-        let (_, priority, _, _, _) = parse_quick_add("Buy groceries (p3)", &bm).unwrap();
+        let ParsedQuickAdd { priority, .. } = parse_quick_add("Buy groceries (p3)").unwrap();
         assert_eq!(priority, Priority::Low);
     }
 
     #[test]
     fn test_parse_with_priority_p123() {
-        let bm = create_empty_backend_manager();
-        let (_, priority_p1, _, _, _) = parse_quick_add("Important task (p1)", &bm).unwrap();
+        let ParsedQuickAdd {
+            priority: priority_p1,
+            ..
+        } = parse_quick_add("Important task (p1)").unwrap();
         assert_eq!(priority_p1, Priority::High);
 
-        let (_, priority_p2, _, _, _) = parse_quick_add("Medium task (p2)", &bm).unwrap();
+        let ParsedQuickAdd {
+            priority: priority_p2,
+            ..
+        } = parse_quick_add("Medium task (p2)").unwrap();
         assert_eq!(priority_p2, Priority::Medium);
 
-        let (_, priority_p3, _, _, _) = parse_quick_add("Low task (p3)", &bm).unwrap();
+        let ParsedQuickAdd {
+            priority: priority_p3,
+            ..
+        } = parse_quick_add("Low task (p3)").unwrap();
         assert_eq!(priority_p3, Priority::Low);
     }
 
     #[test]
     fn test_parse_due_today() {
-        let bm = create_empty_backend_manager();
-        let (_, _, due, _, _) = parse_quick_add("Call mom today", &bm).unwrap();
+        let ParsedQuickAdd { due, .. } = parse_quick_add("Call mom today").unwrap();
         let today = Local::now().date_naive();
         assert_eq!(due, Some(today));
     }
 
     #[test]
     fn test_parse_due_tomorrow() {
-        let bm = create_empty_backend_manager();
-        let (_, _, due, _, _) = parse_quick_add("Submit report tomorrow", &bm).unwrap();
+        let ParsedQuickAdd { due, .. } = parse_quick_add("Submit report tomorrow").unwrap();
         let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
         assert_eq!(due, Some(tomorrow));
     }
 
     #[test]
     fn test_parse_due_tmr() {
-        let bm = create_empty_backend_manager();
-        let (_, _, due, _, _) = parse_quick_add("Buy milk tmr", &bm).unwrap();
+        let ParsedQuickAdd { due, .. } = parse_quick_add("Buy milk tmr").unwrap();
         let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
         assert_eq!(due, Some(tomorrow));
     }
 
     #[test]
     fn test_parse_due_specific_date() {
-        let bm = create_empty_backend_manager();
-        let (_, _, due, _, _) = parse_quick_add("Meeting 2025-03-15", &bm).unwrap();
+        let ParsedQuickAdd { due, .. } = parse_quick_add("Meeting 2025-03-15").unwrap();
         assert_eq!(
             due,
             Some(chrono::NaiveDate::from_ymd_opt(2025, 3, 15).unwrap())
         );
     }
 
+    /// Options with `@local` and `@obsidian` as targets, as a manager
+    /// with both enabled gives.
+    fn routed() -> QuickAddOptions {
+        QuickAddOptions {
+            targets: vec![
+                ("local".to_string(), BackendSource::LocalFile),
+                ("obsidian".to_string(), BackendSource::Obsidian),
+            ],
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_parse_combined() {
-        let bm = create_empty_backend_manager();
-        let (title, priority, due, tags, backend) =
-            parse_quick_add("Review PR #work (p1) tomorrow @obsidian", &bm).unwrap();
+        let ParsedQuickAdd {
+            title,
+            priority,
+            due,
+            tags,
+            backend,
+            ..
+        } = parse_quick_add_with("Review PR #work (p1) tomorrow @obsidian", &routed()).unwrap();
 
         assert_eq!(title, "Review PR");
         assert_eq!(priority, Priority::High);
         assert!(due.is_some());
         assert_eq!(tags, vec!["work"]);
-        assert_eq!(backend, BackendSource::Obsidian);
+        assert_eq!(backend, Some(BackendSource::Obsidian));
     }
 
     #[test]
@@ -247,21 +595,45 @@ mod tests {
         let words: Vec<&str> = vec![];
         let mut title_words: Vec<&str> = vec![];
         assert_eq!(
-            try_parse_date("today", "today", &words, 0, today, &mut title_words),
+            try_parse_date(
+                "today",
+                "today",
+                &words,
+                0,
+                today,
+                WeekdayPolicy::Next,
+                &mut title_words
+            ),
             Some(today)
         );
 
         let words: Vec<&str> = vec![];
         let mut title_words: Vec<&str> = vec![];
         assert_eq!(
-            try_parse_date("tomorrow", "tomorrow", &words, 0, today, &mut title_words),
+            try_parse_date(
+                "tomorrow",
+                "tomorrow",
+                &words,
+                0,
+                today,
+                WeekdayPolicy::Next,
+                &mut title_words
+            ),
             Some(today + chrono::Duration::days(1))
         );
 
         let words: Vec<&str> = vec![];
         let mut title_words: Vec<&str> = vec![];
         assert_eq!(
-            try_parse_date("tmr", "tmr", &words, 0, today, &mut title_words),
+            try_parse_date(
+                "tmr",
+                "tmr",
+                &words,
+                0,
+                today,
+                WeekdayPolicy::Next,
+                &mut title_words
+            ),
             Some(today + chrono::Duration::days(1))
         );
     }
@@ -279,6 +651,7 @@ mod tests {
                 &words,
                 0,
                 today,
+                WeekdayPolicy::Next,
                 &mut title_words
             ),
             Some(chrono::NaiveDate::from_ymd_opt(2025, 3, 15).unwrap())
@@ -287,34 +660,337 @@ mod tests {
         let words: Vec<&str> = vec![];
         let mut title_words: Vec<&str> = vec![];
         assert_eq!(
-            try_parse_date("invalid", "invalid", &words, 0, today, &mut title_words),
+            try_parse_date(
+                "invalid",
+                "invalid",
+                &words,
+                0,
+                today,
+                WeekdayPolicy::Next,
+                &mut title_words
+            ),
             None
         );
     }
 
+    #[test]
+    fn test_parse_past_day() {
+        // Saturday
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2025, 2, d).unwrap();
+
+        assert_eq!(parse_past_day("monday", today), Some(day(24)));
+        assert_eq!(parse_past_day("Fri", today), Some(day(28)));
+        assert_eq!(parse_past_day("saturday", today), Some(today));
+        assert_eq!(parse_past_day("yesterday", today), Some(day(28)));
+        assert_eq!(parse_past_day("today", today), Some(today));
+        assert_eq!(parse_past_day("@2025-02-10", today), Some(day(10)));
+        assert_eq!(parse_past_day("last week", today), None);
+    }
+
     #[test]
     fn test_try_parse_date_with_preposition() {
         let today = Local::now().date_naive();
         let mut title_words: Vec<&str> = vec!["Meeting"];
         let words: Vec<&str> = vec!["Meeting", "on", "monday"];
 
-        let result = try_parse_date("monday", "monday", &words, 2, today, &mut title_words);
+        let result = try_parse_date(
+            "monday",
+            "monday",
+            &words,
+            2,
+            today,
+            WeekdayPolicy::Next,
+            &mut title_words,
+        );
         assert!(result.is_some());
         assert!(title_words.is_empty());
     }
 
     #[test]
     fn test_parse_backend_routing() {
-        let bm = create_empty_backend_manager();
-
-        let (_, _, _, _, backend) = parse_quick_add("Task @obsidian", &bm).unwrap();
-        assert_eq!(backend, BackendSource::Obsidian);
+        let ParsedQuickAdd { backend, .. } =
+            parse_quick_add_with("Task @obsidian", &routed()).unwrap();
+        assert_eq!(backend, Some(BackendSource::Obsidian));
+
+        // Only backends that can take the task
+        let parsed = parse_quick_add_with("Standup @ical", &routed()).unwrap();
+        assert_eq!(parsed.backend, None);
+        assert_eq!(parsed.title, "Standup @ical");
+
+        // Named as registered, whatever the source
+        let local_only = QuickAddOptions {
+            targets: vec![("inbox".to_string(), BackendSource::LocalFile)],
+            ..Default::default()
+        };
+        let parsed = parse_quick_add_with("Task @inbox", &local_only).unwrap();
+        assert_eq!(parsed.backend, Some(BackendSource::LocalFile));
+        assert_eq!(parsed.title, "Task");
+        let parsed = parse_quick_add_with("Task @obsidian", &local_only).unwrap();
+        assert_eq!(parsed.backend, None);
+        assert_eq!(parsed.title, "Task @obsidian");
     }
 
     #[test]
     fn test_parse_default_backend() {
-        let bm = create_empty_backend_manager();
-        let (_, _, _, _, backend) = parse_quick_add("Simple task", &bm).unwrap();
-        assert_eq!(backend, BackendSource::LocalFile);
+        let parsed = parse_quick_add("Simple task").unwrap();
+        assert_eq!(parsed.backend, None);
+        let new_task = parsed.into_new_task(BackendSource::LocalFile);
+        assert_eq!(new_task.backend, BackendSource::LocalFile);
+    }
+
+    #[test]
+    fn test_parse_tokens() {
+        let parsed =
+            parse_quick_add_with("Call mom on monday #family (p2) @local", &routed()).unwrap();
+        let kinds: Vec<(&str, TokenKind)> = parsed
+            .tokens
+            .iter()
+            .map(|t| (t.text.as_str(), t.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Call", TokenKind::Title),
+                ("mom", TokenKind::Title),
+                ("on", TokenKind::Due),
+                ("monday", TokenKind::Due),
+                ("#family", TokenKind::Tag),
+                ("(p2)", TokenKind::Priority),
+                ("@local", TokenKind::Backend),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bare_priorities() {
+        let ParsedQuickAdd {
+            title, priority, ..
+        } = parse_quick_add("Call dentist p1").unwrap();
+        assert_eq!(title, "Call dentist");
+        assert_eq!(priority, Priority::High);
+
+        let ParsedQuickAdd { priority, .. } = parse_quick_add("p2 Submit report").unwrap();
+        assert_eq!(priority, Priority::Medium);
+
+        let ParsedQuickAdd {
+            title, priority, ..
+        } = parse_quick_add("Fix p3-bug").unwrap();
+        assert_eq!(title, "Fix p3-bug");
+        assert_eq!(priority, Priority::None);
+    }
+
+    #[test]
+    fn test_parse_bang_priorities() {
+        let ParsedQuickAdd {
+            title, priority, ..
+        } = parse_quick_add("Call dentist !").unwrap();
+        assert_eq!(title, "Call dentist");
+        assert_eq!(priority, Priority::High);
+
+        let ParsedQuickAdd { priority, .. } = parse_quick_add("Submit report !!").unwrap();
+        assert_eq!(priority, Priority::Medium);
+
+        let ParsedQuickAdd { priority, .. } = parse_quick_add("!!! Buy groceries").unwrap();
+        assert_eq!(priority, Priority::Low);
+    }
+
+    #[test]
+    fn test_parse_bang_in_title_is_not_priority() {
+        let ParsedQuickAdd {
+            title, priority, ..
+        } = parse_quick_add("Help! I need somebody").unwrap();
+        assert_eq!(title, "Help! I need somebody");
+        assert_eq!(priority, Priority::None);
+
+        let ParsedQuickAdd {
+            title, priority, ..
+        } = parse_quick_add("Ship it!!! tomorrow").unwrap();
+        assert_eq!(title, "Ship it!!!");
+        assert_eq!(priority, Priority::None);
+    }
+
+    #[test]
+    fn test_parse_configured_bangs() {
+        let bangs =
+            BangPriorities::from_names(&["low".into(), "medium".into(), "high".into()]).unwrap();
+        let options = QuickAddOptions {
+            bangs,
+            ..Default::default()
+        };
+        let ParsedQuickAdd { priority, .. } = parse_quick_add_with("Deploy !!!", &options).unwrap();
+        assert_eq!(priority, Priority::High);
+        let ParsedQuickAdd { priority, .. } = parse_quick_add_with("Deploy !", &options).unwrap();
+        assert_eq!(priority, Priority::Low);
+
+        assert!(BangPriorities::from_names(&["high".into()]).is_err());
+        assert!(
+            BangPriorities::from_names(&["urgent".into(), "medium".into(), "low".into()]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_weekday_on_that_weekday_follows_policy() {
+        // Monday 2025-03-03
+        let monday = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let next_monday = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+
+        for (policy, expected) in [
+            (WeekdayPolicy::Next, next_monday),
+            (WeekdayPolicy::TodayIfMatch, monday),
+        ] {
+            assert_eq!(parse_due("monday", monday, policy), Some(expected));
+            assert_eq!(parse_due("mon", monday, policy), Some(expected));
+            // Other days don't depend on it
+            assert_eq!(parse_due("tuesday", monday, policy), Some(tuesday));
+
+            // "on monday" reads the weekday the same way
+            let words = vec!["Meeting", "on", "monday"];
+            let mut title_words = vec!["Meeting", "on"];
+            assert_eq!(
+                try_parse_date(
+                    "monday",
+                    "monday",
+                    &words,
+                    2,
+                    monday,
+                    policy,
+                    &mut title_words
+                ),
+                Some(expected)
+            );
+            assert_eq!(title_words, vec!["Meeting"]);
+        }
+
+        assert_eq!(
+            WeekdayPolicy::from_name("today_if_match"),
+            Some(WeekdayPolicy::TodayIfMatch)
+        );
+        assert_eq!(WeekdayPolicy::from_name("soonest"), None);
+    }
+
+    #[test]
+    fn test_next_week_follows_week_start() {
+        // Wednesday 2025-03-05
+        let wed = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert_eq!(start_of_next_week(wed, Weekday::Mon), date(10));
+        assert_eq!(start_of_next_week(wed, Weekday::Sun), date(9));
+        assert_eq!(start_of_next_week(wed, Weekday::Sat), date(8));
+        // On the first day of a week, "next week" is a full week away
+        assert_eq!(start_of_next_week(date(9), Weekday::Sun), date(16));
+        assert_eq!(start_of_next_week(date(9), Weekday::Mon), date(10));
+        assert_eq!(start_of_week(wed, Weekday::Mon), date(3));
+        assert_eq!(start_of_week(wed, Weekday::Sun), date(2));
+        assert_eq!(start_of_week(wed, Weekday::Wed), wed);
+
+        let options = QuickAddOptions {
+            week_start: Weekday::Sun,
+            ..Default::default()
+        };
+        let parsed = parse_quick_add_with("Plan trip next week", &options).unwrap();
+        assert_eq!(parsed.title, "Plan trip");
+        let due = parsed.due.unwrap();
+        assert_eq!(due.weekday(), Weekday::Sun);
+        assert!(due > crate::clock::today());
+    }
+
+    #[test]
+    fn test_parse_at_date_backend_and_context() {
+        let ParsedQuickAdd {
+            title,
+            due,
+            backend,
+            ..
+        } = parse_quick_add("Meeting @2025-06-01").unwrap();
+        assert_eq!(title, "Meeting");
+        assert_eq!(due, Some(NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()));
+        assert_eq!(backend, None);
+
+        let ParsedQuickAdd {
+            title,
+            due,
+            backend,
+            ..
+        } = parse_quick_add_with("Meeting @obsidian", &routed()).unwrap();
+        assert_eq!(title, "Meeting");
+        assert_eq!(due, None);
+        assert_eq!(backend, Some(BackendSource::Obsidian));
+
+        let parsed = parse_quick_add("Water plants @home @tomorrow").unwrap();
+        assert_eq!(parsed.title, "Water plants @home");
+        assert_eq!(parsed.backend, None);
+        assert_eq!(
+            parsed.due,
+            Some(Local::now().date_naive() + chrono::Duration::days(1))
+        );
+        assert_eq!(parsed.tokens[2].kind, TokenKind::Context);
+        assert_eq!(parsed.tokens[3].kind, TokenKind::Due);
+    }
+
+    #[test]
+    fn test_time_words_pick_today_or_tomorrow() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let at = |h, m| day.and_hms_opt(h, m, 0).unwrap();
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let options = QuickAddOptions::default();
+
+        let parsed = parse_quick_add_at("Send invoice eod #work", &options, at(10, 0)).unwrap();
+        assert_eq!(parsed.title, "Send invoice");
+        assert_eq!(parsed.due, Some(day));
+        assert_eq!(parsed.due_time, Some(time(17, 0)));
+        assert_eq!(parsed.tags, vec!["work".to_string()]);
+        assert_eq!(parsed.tokens[2].kind, TokenKind::Due);
+
+        // Once the time has passed it's tomorrow's, unless rollover is off
+        let parsed = parse_quick_add_at("Call mom tonight", &options, at(20, 0)).unwrap();
+        assert_eq!(parsed.title, "Call mom");
+        assert_eq!(parsed.due, Some(day + chrono::Duration::days(1)));
+        assert_eq!(parsed.due_time, Some(time(20, 0)));
+        let no_rollover = QuickAddOptions {
+            rollover: false,
+            ..Default::default()
+        };
+        let parsed = parse_quick_add_at("Call mom tonight", &no_rollover, at(21, 30)).unwrap();
+        assert_eq!(parsed.due, Some(day));
+
+        // Bare, only as the title's last word
+        let parsed = parse_quick_add_at("Morning pages", &options, at(8, 0)).unwrap();
+        assert_eq!(parsed.title, "Morning pages");
+        assert_eq!(parsed.due_time, None);
+        assert_eq!(parsed.due, None);
+        let parsed = parse_quick_add_at("Morning pages @tonight", &options, at(8, 0)).unwrap();
+        assert_eq!(parsed.title, "Morning pages");
+        assert_eq!(parsed.due_time, Some(time(20, 0)));
+        let parsed = parse_quick_add_at("Noon", &options, at(8, 0)).unwrap();
+        assert_eq!(parsed.title, "Noon");
+
+        // A date given as well wins; case and `@` don't matter
+        let parsed = parse_quick_add_at("Standup friday @Morning", &options, at(12, 0)).unwrap();
+        assert_eq!(parsed.title, "Standup");
+        assert_eq!(
+            parsed.due,
+            Some(NaiveDate::from_ymd_opt(2026, 10, 16).unwrap())
+        );
+        assert_eq!(parsed.due_time, Some(time(9, 0)));
+
+        // Configured times
+        let options = QuickAddOptions {
+            time_words: TimeWords {
+                noon: time(12, 30),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let parsed = parse_quick_add_at("Lunch noon", &options, at(12, 15)).unwrap();
+        assert_eq!(parsed.due, Some(day));
+        assert_eq!(parsed.due_time, Some(time(12, 30)));
+        let parsed = parse_quick_add_at("Lunch noon", &options, at(12, 30)).unwrap();
+        assert_eq!(parsed.due, Some(day + chrono::Duration::days(1)));
+
+        let parsed = parse_quick_add_at("Buy milk", &options, at(9, 0)).unwrap();
+        assert_eq!(parsed.due_time, None);
+        assert_eq!(parsed.due, None);
     }
 }
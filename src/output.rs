@@ -0,0 +1,1252 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::config::Config;
+use crate::error::TasukiError;
+use crate::model::{BackendSource, Priority, Task, TaskStatus};
+
+/// Bumped whenever a field is renamed, removed, or changes type. Adding a new
+/// field does not bump the version.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// A task field that can be emitted in JSON output.
+///
+/// The comment on each variant is the JSON type scripts can rely on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// string — `"local:<line>"` or `"obsidian:<path>:<line>"`
+    Id,
+    /// string
+    Title,
+    /// string — `"Pending"` or `"Done"`
+    Status,
+    /// string — `"None"`, `"Low"`, `"Medium"` or `"High"`
+    Priority,
+    /// string (`YYYY-MM-DD`) or null
+    Due,
+    /// array of strings, without the leading `#`
+    Tags,
+    /// string — `"Obsidian"` or `"LocalFile"`
+    Source,
+    /// integer (1-based) or null
+    SourceLine,
+    /// string (absolute path) or null
+    SourcePath,
+    /// string (`YYYY-MM-DDTHH:MM:SS`) or null
+    CreatedAt,
+    /// string (`YYYY-MM-DDTHH:MM:SS`) or null
+    CompletedAt,
+    /// string or null — nearest markdown heading above the task
+    HeadingContext,
+    /// string or null — project derived from the note (Obsidian `project_from`)
+    Project,
+}
+
+impl Field {
+    pub const ALL: &'static [Field] = &[
+        Field::Id,
+        Field::Title,
+        Field::Status,
+        Field::Priority,
+        Field::Due,
+        Field::Tags,
+        Field::Source,
+        Field::SourceLine,
+        Field::SourcePath,
+        Field::CreatedAt,
+        Field::CompletedAt,
+        Field::HeadingContext,
+        Field::Project,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Field::Id => "id",
+            Field::Title => "title",
+            Field::Status => "status",
+            Field::Priority => "priority",
+            Field::Due => "due",
+            Field::Tags => "tags",
+            Field::Source => "source",
+            Field::SourceLine => "source_line",
+            Field::SourcePath => "source_path",
+            Field::CreatedAt => "created_at",
+            Field::CompletedAt => "completed_at",
+            Field::HeadingContext => "heading_context",
+            Field::Project => "project",
+        }
+    }
+
+    pub fn parse_list(fields: &[String]) -> Result<Vec<Field>, TasukiError> {
+        fields.iter().map(|f| f.parse()).collect()
+    }
+
+    /// Plain-text value for templates; empty when the field is unset.
+    pub fn text(&self, task: &Task) -> String {
+        fn opt<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+        }
+
+        match self {
+            Field::Id => task.id.clone(),
+            Field::Title => task.title.clone(),
+            Field::Status => match task.status {
+                TaskStatus::Pending => "pending",
+                TaskStatus::Done => "done",
+            }
+            .to_string(),
+            Field::Priority => match task.priority {
+                Priority::High => "p1",
+                Priority::Medium => "p2",
+                Priority::Low => "p3",
+                Priority::None => "",
+            }
+            .to_string(),
+            Field::Due => opt(&task.due),
+            Field::Tags => task.tags.join(","),
+            Field::Source => task.source.name().to_string(),
+            Field::SourceLine => opt(&task.source_line),
+            Field::SourcePath => opt(&task.source_path),
+            Field::CreatedAt => opt(&task.created_at),
+            Field::CompletedAt => opt(&task.completed_at),
+            Field::HeadingContext => opt(&task.heading_context),
+            Field::Project => opt(&task.project),
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = TasukiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Field::ALL
+            .iter()
+            .copied()
+            .find(|f| f.key() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Field::ALL.iter().map(|f| f.key()).collect();
+                TasukiError::Parse(format!(
+                    "Unknown field '{}' (valid: {})",
+                    s,
+                    valid.join(", ")
+                ))
+            })
+    }
+}
+
+/// Serializes a task with only the selected fields, in the order given.
+pub struct TaskView<'a> {
+    task: &'a Task,
+    fields: &'a [Field],
+}
+
+impl<'a> TaskView<'a> {
+    pub fn new(task: &'a Task, fields: &'a [Field]) -> Self {
+        Self { task, fields }
+    }
+}
+
+impl Serialize for TaskView<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let task = self.task;
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in self.fields {
+            let key = field.key();
+            match field {
+                Field::Id => map.serialize_entry(key, &task.id)?,
+                Field::Title => map.serialize_entry(key, &task.title)?,
+                Field::Status => map.serialize_entry(key, &task.status)?,
+                Field::Priority => map.serialize_entry(key, &task.priority)?,
+                Field::Due => map.serialize_entry(key, &task.due)?,
+                Field::Tags => map.serialize_entry(key, &task.tags)?,
+                Field::Source => map.serialize_entry(key, &task.source)?,
+                Field::SourceLine => map.serialize_entry(key, &task.source_line)?,
+                Field::SourcePath => map.serialize_entry(key, &task.source_path)?,
+                Field::CreatedAt => map.serialize_entry(key, &task.created_at)?,
+                Field::CompletedAt => map.serialize_entry(key, &task.completed_at)?,
+                Field::HeadingContext => map.serialize_entry(key, &task.heading_context)?,
+                Field::Project => map.serialize_entry(key, &task.project)?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// The bare task array, used by `--format json-compat`.
+pub struct TaskArray<'a> {
+    tasks: &'a [Task],
+    fields: &'a [Field],
+}
+
+impl<'a> TaskArray<'a> {
+    pub fn new(tasks: &'a [Task], fields: &'a [Field]) -> Self {
+        Self { tasks, fields }
+    }
+}
+
+impl Serialize for TaskArray<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            self.tasks
+                .iter()
+                .map(|task| TaskView::new(task, self.fields)),
+        )
+    }
+}
+
+/// The versioned `--format json` envelope: `{"version":1,"tasks":[...]}`.
+pub struct TaskList<'a>(TaskArray<'a>);
+
+impl<'a> TaskList<'a> {
+    pub fn new(tasks: &'a [Task], fields: &'a [Field]) -> Self {
+        Self(TaskArray::new(tasks, fields))
+    }
+}
+
+impl Serialize for TaskList<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("version", &JSON_SCHEMA_VERSION)?;
+        map.serialize_entry("tasks", &self.0)?;
+        map.end()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment<F> {
+    Literal(String),
+    Field(F),
+}
+
+/// A `list --output-template` such as `"{status} {priority} {title} {due}"`.
+/// Placeholders are field keys; unset values render as empty strings. Other
+/// templates, like the TUI status bar's, parse their own placeholders as `F`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template<F = Field> {
+    segments: Vec<Segment<F>>,
+}
+
+impl<F> Template<F> {
+    pub fn segments(&self) -> &[Segment<F>] {
+        &self.segments
+    }
+}
+
+impl Template {
+    pub fn render(&self, task: &Task) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.clone(),
+                Segment::Field(field) => field.text(task),
+            })
+            .collect()
+    }
+}
+
+impl<F: FromStr<Err = TasukiError>> FromStr for Template<F> {
+    type Err = TasukiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut rest = s;
+
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                return Err(TasukiError::Parse(format!(
+                    "Unclosed '{{' in template: {}",
+                    s
+                )));
+            };
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            segments.push(Segment::Field(rest[start + 1..start + len].parse()?));
+            rest = &rest[start + len + 1..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        Ok(Template { segments })
+    }
+}
+
+/// How `list --group` and the TUI split tasks into sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Due,
+    Tag,
+    Backend,
+    Priority,
+}
+
+impl GroupBy {
+    /// The name `--group` takes for this grouping.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GroupBy::Due => "due",
+            GroupBy::Tag => "tag",
+            GroupBy::Backend => "backend",
+            GroupBy::Priority => "priority",
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = TasukiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "due" => Ok(GroupBy::Due),
+            "tag" => Ok(GroupBy::Tag),
+            "backend" => Ok(GroupBy::Backend),
+            "priority" => Ok(GroupBy::Priority),
+            _ => Err(TasukiError::Parse(format!(
+                "Unknown group '{}' (valid: due, tag, backend, priority)",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub label: String,
+    /// The due date this group collects, for `GroupBy::Due`
+    pub date: Option<NaiveDate>,
+    pub tasks: Vec<Task>,
+}
+
+/// How dates read in the TUI, `list` text output and the Waybar tooltip:
+/// in `general.date_format` (ISO by default), or with `general.relative_dates`
+/// as "yesterday", "in 3 days" and so on when within a week of `today`.
+pub fn format_date(date: NaiveDate, config: &Config, today: NaiveDate) -> String {
+    if is_relative(date, config, today) {
+        return relative_date(date, today);
+    }
+    date.format(config.general.date_format()).to_string()
+}
+
+fn is_relative(date: NaiveDate, config: &Config, today: NaiveDate) -> bool {
+    config.general.relative_dates && (date - today).num_days().abs() < 7
+}
+
+/// Section header for tasks due on `date`: "Overdue - 2025-03-01", "Today",
+/// "Tomorrow", "Friday 2025-03-07" (or "Friday (in 3 days)"), or "Notes"
+/// for undated tasks. Dates go through `format_date`.
+pub fn due_label(date: Option<NaiveDate>, config: &Config, today: NaiveDate) -> String {
+    match date {
+        Some(d) if d < today => format!("Overdue - {}", format_date(d, config, today)),
+        Some(d) if d == today => "Today".to_string(),
+        Some(d) if d == today + chrono::Duration::days(1) => "Tomorrow".to_string(),
+        Some(d) if is_relative(d, config, today) => {
+            format!("{} ({})", d.format("%A"), relative_date(d, today))
+        }
+        Some(d) => format!("{} {}", d.format("%A"), format_date(d, config, today)),
+        None => "Notes".to_string(),
+    }
+}
+
+/// Split tasks into labelled sections, keeping each section in the order the
+/// tasks were given. Due groups run oldest first with undated tasks last; tag
+/// groups are alphabetical with untagged tasks last (a task with several tags
+/// appears under each); backend groups follow `BackendSource::ALL`; priority
+/// groups run from High down to None by effective priority (see
+/// `Task::effective_priority`). Empty groups are left out.
+pub fn group_tasks(tasks: &[Task], by: GroupBy, config: &Config, today: NaiveDate) -> Vec<Group> {
+    match by {
+        GroupBy::Due => {
+            let mut dates: Vec<Option<NaiveDate>> = tasks.iter().map(|t| t.due).collect();
+            dates.sort_by(|a, b| match (a, b) {
+                (Some(da), Some(db)) => da.cmp(db),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            });
+            dates.dedup();
+
+            dates
+                .into_iter()
+                .map(|date| Group {
+                    label: due_label(date, config, today),
+                    date,
+                    tasks: tasks.iter().filter(|t| t.due == date).cloned().collect(),
+                })
+                .collect()
+        }
+        GroupBy::Tag => {
+            let mut tags: Vec<&str> = tasks
+                .iter()
+                .flat_map(|t| t.tags.iter().map(String::as_str))
+                .collect();
+            tags.sort_unstable();
+            tags.dedup();
+
+            let mut groups: Vec<Group> = tags
+                .into_iter()
+                .map(|tag| Group {
+                    label: format!("#{}", tag),
+                    date: None,
+                    tasks: tasks
+                        .iter()
+                        .filter(|t| t.tags.iter().any(|x| x == tag))
+                        .cloned()
+                        .collect(),
+                })
+                .collect();
+
+            let untagged: Vec<Task> = tasks
+                .iter()
+                .filter(|t| t.tags.is_empty())
+                .cloned()
+                .collect();
+            if !untagged.is_empty() {
+                groups.push(Group {
+                    label: "Untagged".to_string(),
+                    date: None,
+                    tasks: untagged,
+                });
+            }
+            groups
+        }
+        GroupBy::Backend => BackendSource::ALL
+            .iter()
+            .map(|source| Group {
+                label: source.name().to_string(),
+                date: None,
+                tasks: tasks
+                    .iter()
+                    .filter(|t| t.source == *source)
+                    .cloned()
+                    .collect(),
+            })
+            .filter(|g| !g.tasks.is_empty())
+            .collect(),
+        GroupBy::Priority => {
+            let escalate_after = config.general.escalate_overdue_after_days;
+            let priority = |t: &Task| t.effective_priority(today, escalate_after);
+            // By `Priority`'s order, not the labels': High sorts before None
+            let mut priorities: Vec<Priority> = tasks.iter().map(priority).collect();
+            priorities.sort_unstable_by(|a, b| b.cmp(a));
+            priorities.dedup();
+
+            priorities
+                .into_iter()
+                .map(|p| Group {
+                    label: priority_label(p).to_string(),
+                    date: None,
+                    tasks: tasks.iter().filter(|t| priority(t) == p).cloned().collect(),
+                })
+                .collect()
+        }
+    }
+}
+
+/// Section header for `GroupBy::Priority`.
+fn priority_label(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => "High",
+        Priority::Medium => "Medium",
+        Priority::Low => "Low",
+        Priority::None => "None",
+    }
+}
+
+/// Describe `date` relative to `today`: "today", "tomorrow", "in 3 days",
+/// "2 days ago".
+pub fn relative_date(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        -1 => "yesterday".to_string(),
+        n if n > 0 => format!("in {} days", n),
+        n => format!("{} days ago", -n),
+    }
+}
+
+/// Labelled rows describing a task, shared by `tasuki show` and the TUI so the
+/// two never drift. Empty optional fields are omitted.
+pub fn detail_rows(task: &Task, config: &Config, today: NaiveDate) -> Vec<(&'static str, String)> {
+    let mut rows = vec![("ID", task.id.clone())];
+
+    let status = match task.status {
+        TaskStatus::Pending => "Pending",
+        TaskStatus::Done => "Done",
+    };
+    rows.push(("Status", status.to_string()));
+
+    let priority = match task.priority {
+        Priority::High => "High (p1)",
+        Priority::Medium => "Medium (p2)",
+        Priority::Low => "Low (p3)",
+        Priority::None => "None",
+    };
+    rows.push(("Priority", priority.to_string()));
+
+    if let Some(due) = task.due {
+        let date = format_date(due, config, today);
+        if is_relative(due, config, today) {
+            rows.push(("Due", date));
+        } else {
+            rows.push(("Due", format!("{} ({})", date, relative_date(due, today))));
+        }
+    }
+
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|t| format!("#{}", t)).collect();
+        rows.push(("Tags", tags.join(" ")));
+    }
+
+    if let Some(ref project) = task.project {
+        rows.push(("Project", project.clone()));
+    }
+
+    if let Some(ref heading) = task.heading_context {
+        rows.push(("Heading", heading.clone()));
+    }
+
+    rows.push(("Backend", task.source.name().to_string()));
+
+    if let Some(ref path) = task.source_path {
+        let location = match task.source_line {
+            Some(line) => format!("{}:{}", path, line),
+            None => path.clone(),
+        };
+        rows.push(("Source", location));
+    }
+
+    if let Some(created) = task.created_at {
+        rows.push(("Created", format_date(created.date(), config, today)));
+    }
+
+    if let Some(completed) = task.completed_at {
+        rows.push(("Completed", format_date(completed.date(), config, today)));
+    }
+
+    rows
+}
+
+/// Terminal columns `s` takes up; CJK and most emoji take two.
+pub fn display_width(s: &str) -> usize {
+    s.width()
+}
+
+/// `s` cut to at most `max` columns, ending in "…" if anything was cut.
+pub fn truncate_to_width(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    if max > 0 {
+        out.push('…');
+    }
+    out
+}
+
+/// `s` as one clean line for a list row or tooltip: each run of whitespace
+/// (newlines and tabs included) becomes a single space, and control and
+/// zero-width characters are dropped.
+pub fn single_line(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut space = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            space = true;
+        } else if !c.is_control() && !matches!(c, '\u{200B}' | '\u{2060}' | '\u{FEFF}') {
+            if space && !out.is_empty() {
+                out.push(' ');
+            }
+            space = false;
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `s` padded with spaces to `width` columns.
+fn pad_to_width(s: &str, width: usize) -> String {
+    format!("{}{}", s, " ".repeat(width.saturating_sub(s.width())))
+}
+
+/// `list --format table`: numbered, aligned columns sized to the data. When
+/// `max_width` (the terminal's) is too narrow, titles and then tags are cut
+/// with "…" so rows don't wrap.
+pub fn table(
+    tasks: &[Task],
+    config: &Config,
+    today: NaiveDate,
+    max_width: Option<usize>,
+) -> String {
+    const HEADERS: [&str; 7] = ["#", "STATUS", "PRI", "DUE", "TITLE", "TAGS", "SOURCE"];
+    const TITLE: usize = 4;
+    const TAGS: usize = 5;
+    const GAP: usize = 2;
+
+    let rows: Vec<[String; 7]> = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let tags: Vec<String> = task.tags.iter().map(|t| format!("#{}", t)).collect();
+            [
+                (i + 1).to_string(),
+                match task.status {
+                    TaskStatus::Pending => "pending".to_string(),
+                    TaskStatus::Done => "done".to_string(),
+                },
+                match task.priority {
+                    Priority::None => String::new(),
+                    p => format!("{:?}", p).to_lowercase(),
+                },
+                task.due
+                    .map(|d| format_date(d, config, today))
+                    .unwrap_or_default(),
+                task.title.clone(),
+                tags.join(" "),
+                task.source.name().to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = HEADERS.map(display_width);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(display_width(cell));
+        }
+    }
+
+    if let Some(max_width) = max_width {
+        let total = |widths: &[usize; 7]| widths.iter().sum::<usize>() + GAP * (widths.len() - 1);
+        for (column, min) in [(TITLE, 10), (TAGS, HEADERS[TAGS].len())] {
+            let over = total(&widths).saturating_sub(max_width);
+            widths[column] -= over.min(widths[column].saturating_sub(min));
+        }
+    }
+
+    let render = |cells: [&str; 7]| {
+        let last = cells.len() - 1;
+        let line: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let cell = truncate_to_width(cell, widths[i]);
+                if i == last {
+                    cell
+                } else {
+                    pad_to_width(&cell, widths[i])
+                }
+            })
+            .collect();
+        line.join(&" ".repeat(GAP)).trim_end().to_string()
+    };
+
+    let mut lines = vec![render(HEADERS)];
+    for row in &rows {
+        lines.push(render(std::array::from_fn(|i| row[i].as_str())));
+    }
+    lines.join("\n")
+}
+
+/// Columns to fit output into: the terminal's, or `$COLUMNS` when piped.
+pub fn terminal_width() -> Option<usize> {
+    if atty::is(atty::Stream::Stdout) {
+        if let Ok((cols, _)) = crossterm::terminal::size() {
+            return Some(cols as usize);
+        }
+    }
+    std::env::var("COLUMNS").ok()?.parse().ok()
+}
+
+/// Columns to wrap `list` text output at: `terminal_width`, or `None`
+/// (one line per task) with `no_wrap` or when neither is known.
+pub fn wrap_width(no_wrap: bool) -> Option<usize> {
+    if no_wrap {
+        return None;
+    }
+    terminal_width()
+}
+
+/// `prefix` then `title`, wrapped at word boundaries to `width` columns.
+/// Later lines are indented to line up under the title. `suffix` (the due
+/// date and priority) stays on the same line as the title's last word. A
+/// word wider than a whole line is split wherever it has to be.
+pub fn wrap_line(prefix: &str, title: &str, suffix: &str, width: usize) -> String {
+    let indent = prefix.width();
+    let room = width.saturating_sub(indent).max(1);
+
+    let mut words: Vec<String> = title.split_whitespace().map(str::to_string).collect();
+    match words.last_mut() {
+        Some(last) => last.push_str(suffix),
+        None => words.push(suffix.trim_start().to_string()),
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for (i, word) in words.iter().enumerate() {
+        let is_last = i + 1 == words.len();
+        let needed = if line.is_empty() {
+            word.width()
+        } else {
+            line.width() + 1 + word.width()
+        };
+        if needed <= room {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        // The last word carries the suffix; better to run over than to
+        // break it off
+        if word.width() <= room || (is_last && word.width().saturating_sub(suffix.width()) <= room)
+        {
+            line.push_str(word);
+            continue;
+        }
+        for c in word.chars() {
+            if line.width() + c.width().unwrap_or(0) > room {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    lines.push(line);
+
+    let mut text = String::from(prefix);
+    text.push_str(&lines.join(&format!("\n{}", " ".repeat(indent))));
+    text
+}
+
+/// The pager to show `lines` lines of output through, or `None` to print
+/// them directly. Only output taller than the terminal (`tty_height`, `None`
+/// when stdout isn't one) is paged, with `$PAGER` (`less -R`, which keeps
+/// colors, when unset; set but empty turns paging off).
+pub fn pager_command(
+    lines: usize,
+    tty_height: Option<usize>,
+    pager: Option<&str>,
+) -> Option<Vec<String>> {
+    let height = tty_height?;
+    if lines < height {
+        return None;
+    }
+    let words = shell_words::split(pager.unwrap_or("less -R")).ok()?;
+    (!words.is_empty()).then_some(words)
+}
+
+/// Print `text`, through `$PAGER` when stdout is a terminal it doesn't fit
+/// on, unless `no_pager`.
+pub fn page(text: &str, no_pager: bool) {
+    let tty_height = if no_pager || !atty::is(atty::Stream::Stdout) {
+        None
+    } else {
+        crossterm::terminal::size()
+            .ok()
+            .map(|(_, rows)| rows as usize)
+    };
+    let pager = std::env::var("PAGER").ok();
+    // stdout closed early (e.g. piped into `head`) is nothing to report
+    let _ = page_to(text, tty_height, pager.as_deref(), &mut std::io::stdout());
+}
+
+/// `page` with the terminal height and `$PAGER` given, writing to `out`
+/// when there's no pager or it can't be started.
+fn page_to(
+    text: &str,
+    tty_height: Option<usize>,
+    pager: Option<&str>,
+    out: &mut impl std::io::Write,
+) -> std::io::Result<()> {
+    if let Some(command) = pager_command(text.lines().count(), tty_height, pager) {
+        if run_pager(&command, text).is_ok() {
+            return Ok(());
+        }
+    }
+    out.write_all(text.as_bytes())
+}
+
+fn run_pager(command: &[String], text: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(&command[0])
+        .args(&command[1..])
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager before the end closes the pipe
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{BackendSource, Priority, TaskStatus};
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    fn full_task() -> Task {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        Task {
+            source_line: Some(4),
+            source_path: Some("/vault/Projects/Website.md".to_string()),
+            heading_context: Some("Launch".to_string()),
+            ..Task::build("Ship landing page")
+                .with_id("obsidian:Projects/Website.md:4")
+                .with_status(TaskStatus::Done)
+                .with_priority(Priority::High)
+                .with_due(date)
+                .with_tags(&["work", "web"])
+                .with_project("Website")
+                .with_created(date.and_hms_opt(0, 0, 0))
+                .with_completed(date.and_hms_opt(0, 0, 0))
+        }
+    }
+
+    fn empty_task() -> Task {
+        Task::build("Buy milk").with_id("local:1")
+    }
+
+    #[test]
+    fn test_schema_full_task() {
+        let tasks = vec![full_task()];
+        let value = serde_json::to_value(TaskList::new(&tasks, Field::ALL)).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "version": 1,
+                "tasks": [{
+                    "id": "obsidian:Projects/Website.md:4",
+                    "title": "Ship landing page",
+                    "status": "Done",
+                    "priority": "High",
+                    "due": "2025-03-15",
+                    "tags": ["work", "web"],
+                    "source": "Obsidian",
+                    "source_line": 4,
+                    "source_path": "/vault/Projects/Website.md",
+                    "created_at": "2025-03-15T00:00:00",
+                    "completed_at": "2025-03-15T00:00:00",
+                    "heading_context": "Launch",
+                    "project": "Website"
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_schema_empty_optionals() {
+        let tasks = vec![empty_task()];
+        let value = serde_json::to_value(TaskList::new(&tasks, Field::ALL)).unwrap();
+        assert_eq!(
+            value["tasks"][0],
+            json!({
+                "id": "local:1",
+                "title": "Buy milk",
+                "status": "Pending",
+                "priority": "None",
+                "due": null,
+                "tags": [],
+                "source": "LocalFile",
+                "source_line": null,
+                "source_path": null,
+                "created_at": null,
+                "completed_at": null,
+                "heading_context": null,
+                "project": null
+            })
+        );
+    }
+
+    #[test]
+    fn test_full_view_matches_task_serialization() {
+        let task = full_task();
+        let view = serde_json::to_value(TaskView::new(&task, Field::ALL)).unwrap();
+        assert_eq!(view, serde_json::to_value(&task).unwrap());
+    }
+
+    #[test]
+    fn test_fields_selector() {
+        let tasks = vec![full_task()];
+        let fields = Field::parse_list(&["id".into(), "title".into(), "due".into()]).unwrap();
+        let value = serde_json::to_value(TaskArray::new(&tasks, &fields)).unwrap();
+        assert_eq!(
+            value,
+            json!([{
+                "id": "obsidian:Projects/Website.md:4",
+                "title": "Ship landing page",
+                "due": "2025-03-15"
+            }])
+        );
+    }
+
+    #[test]
+    fn test_relative_date() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert_eq!(relative_date(day(15), today), "today");
+        assert_eq!(relative_date(day(16), today), "tomorrow");
+        assert_eq!(relative_date(day(14), today), "yesterday");
+        assert_eq!(relative_date(day(20), today), "in 5 days");
+        assert_eq!(relative_date(day(10), today), "5 days ago");
+    }
+
+    #[test]
+    fn test_detail_rows() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 13).unwrap();
+        let rows = detail_rows(&full_task(), &Config::default(), today);
+        let get = |label| {
+            rows.iter()
+                .find(|(l, _)| *l == label)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("Due"), Some("2025-03-15 (in 2 days)"));
+        assert_eq!(get("Tags"), Some("#work #web"));
+        assert_eq!(get("Source"), Some("/vault/Projects/Website.md:4"));
+
+        let rows = detail_rows(&empty_task(), &Config::default(), today);
+        assert!(!rows.iter().any(|(l, _)| *l == "Due" || *l == "Source"));
+
+        let mut relative = Config::default();
+        relative.general.relative_dates = true;
+        let rows = detail_rows(&full_task(), &relative, today);
+        assert!(rows.contains(&("Due", "in 2 days".to_string())));
+    }
+
+    #[test]
+    fn test_template_full_task() {
+        let template: Template = "{status} {priority} {title} {due} [{tags}] {source} {id}"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            template.render(&full_task()),
+            "done p1 Ship landing page 2025-03-15 [work,web] obsidian obsidian:Projects/Website.md:4"
+        );
+    }
+
+    #[test]
+    fn test_template_minimal_task() {
+        let template: Template = "{status} {priority} {title} {due}".parse().unwrap();
+        assert_eq!(template.render(&empty_task()), "pending  Buy milk ");
+
+        let template: Template = "- {title}|{tags}|".parse().unwrap();
+        assert_eq!(template.render(&empty_task()), "- Buy milk||");
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert!("{title".parse::<Template>().is_err());
+        assert!("{nope}".parse::<Template>().is_err());
+    }
+
+    fn dated(title: &str, due: Option<NaiveDate>, tags: &[&str], source: BackendSource) -> Task {
+        Task::build(title)
+            .with_due(due)
+            .with_tags(tags)
+            .with_source(source)
+    }
+
+    #[test]
+    fn test_group_by_due() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let day = |d| Some(NaiveDate::from_ymd_opt(2025, 3, d).unwrap());
+        let tasks = vec![
+            dated("undated", None, &[], BackendSource::LocalFile),
+            dated("friday", day(7), &[], BackendSource::LocalFile),
+            dated("today", day(5), &[], BackendSource::LocalFile),
+            dated("overdue", day(3), &[], BackendSource::LocalFile),
+            dated("today too", day(5), &[], BackendSource::LocalFile),
+            dated("tomorrow", day(6), &[], BackendSource::LocalFile),
+        ];
+
+        let groups = group_tasks(&tasks, GroupBy::Due, &Config::default(), today);
+        let summary: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|g| (g.label.as_str(), g.tasks.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Overdue - 2025-03-03", 1),
+                ("Today", 2),
+                ("Tomorrow", 1),
+                ("Friday 2025-03-07", 1),
+                ("Notes", 1),
+            ]
+        );
+        assert_eq!(groups[1].tasks[1].title, "today too");
+
+        let mut config = Config::default();
+        config.general.date_format = Some("%d.%m.%Y".to_string());
+        let labels: Vec<String> = group_tasks(&tasks, GroupBy::Due, &config, today)
+            .into_iter()
+            .map(|g| g.label)
+            .collect();
+        assert_eq!(labels[0], "Overdue - 03.03.2025");
+        assert_eq!(labels[3], "Friday 07.03.2025");
+
+        config.general.relative_dates = true;
+        let labels: Vec<String> = group_tasks(&tasks, GroupBy::Due, &config, today)
+            .into_iter()
+            .map(|g| g.label)
+            .collect();
+        assert_eq!(labels[0], "Overdue - 2 days ago");
+        assert_eq!(labels[3], "Friday (in 2 days)");
+    }
+
+    #[test]
+    fn test_table_aligns_and_truncates() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let long = Task::build("A very long task title that will not fit in a narrow terminal")
+            .with_tags(&["work"])
+            .with_priority(Priority::High)
+            .with_due(NaiveDate::from_ymd_opt(2025, 3, 7));
+        let wide = Task::build("日本語のタスク");
+
+        let table = table(&[long, wide], &Config::default(), today, Some(60));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[0],
+            "#  STATUS   PRI   DUE         TITLE            TAGS   SOURCE"
+        );
+        assert!(lines[1].starts_with("1  pending  high  2025-03-07  A very long ta…  #work  local"));
+        assert!(lines.iter().all(|l| display_width(l) <= 60));
+        // Wide characters count double, so the columns still line up
+        assert_eq!(
+            lines[2],
+            "2  pending                    日本語のタスク          local"
+        );
+
+        let untruncated = super::table(&[full_task()], &Config::default(), today, None);
+        assert!(untruncated.contains(&full_task().title));
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("hello", 5), "hello");
+        assert_eq!(truncate_to_width("hello world", 6), "hello…");
+        assert_eq!(truncate_to_width("日本語", 4), "日…");
+        assert_eq!(display_width("日本語"), 6);
+    }
+
+    #[test]
+    fn test_wrap_line_at_40_and_100_columns() {
+        let prefix = "  1 ☐ ";
+        let title = "Review the 東京オフィス 移転計画 notes before the quarterly planning meeting";
+        let suffix = " (due 2025-03-07) [!]";
+
+        let wrapped = wrap_line(prefix, title, suffix, 40);
+        assert_eq!(
+            wrapped,
+            "  1 ☐ Review the 東京オフィス 移転計画\n\
+             \x20     notes before the quarterly\n\
+             \x20     planning\n\
+             \x20     meeting (due 2025-03-07) [!]"
+        );
+        // Double-width characters count twice
+        assert!(wrapped.lines().all(|l| l.width() <= 40));
+
+        // The due date doesn't go to a line of its own, even if that
+        // runs over
+        assert_eq!(
+            wrap_line(prefix, "Call the bank", suffix, 24),
+            "  1 ☐ Call the\n      bank (due 2025-03-07) [!]"
+        );
+
+        // 103 columns in all, so only the last word moves down
+        assert_eq!(
+            wrap_line(prefix, title, suffix, 100),
+            "  1 ☐ Review the 東京オフィス 移転計画 notes before the quarterly planning\n\
+             \x20     meeting (due 2025-03-07) [!]"
+        );
+        assert_eq!(
+            wrap_line(prefix, title, "", 100),
+            format!("{}{}", prefix, title)
+        );
+
+        // A title of one unbroken CJK run is split by width
+        let wrapped = wrap_line(prefix, &"漢".repeat(20), "", 40);
+        assert_eq!(
+            wrapped,
+            format!("{}{}\n      {}", prefix, "漢".repeat(17), "漢".repeat(3))
+        );
+        assert_eq!(
+            wrap_line(prefix, "", " (due today)", 40),
+            "  1 ☐ (due today)"
+        );
+    }
+
+    #[test]
+    fn test_pager_only_for_tall_output_on_a_terminal() {
+        // Not a terminal: never paged
+        assert_eq!(pager_command(500, None, Some("less")), None);
+        assert_eq!(pager_command(10, Some(40), None), None);
+        assert_eq!(
+            pager_command(40, Some(40), None),
+            Some(vec!["less".to_string(), "-R".to_string()])
+        );
+        assert_eq!(
+            pager_command(50, Some(40), Some("more -d")),
+            Some(vec!["more".to_string(), "-d".to_string()])
+        );
+        assert_eq!(pager_command(50, Some(40), Some("")), None);
+        assert_eq!(
+            pager_command(50, Some(40), Some("less \"unterminated")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_page_falls_back_to_printing() {
+        let text = "one\ntwo\nthree\n";
+        let mut out = Vec::new();
+        page_to(text, None, Some("less -R"), &mut out).unwrap();
+        assert_eq!(out, text.as_bytes());
+
+        let mut out = Vec::new();
+        page_to(text, Some(2), Some("/nonexistent/tasuki-pager"), &mut out).unwrap();
+        assert_eq!(out, text.as_bytes());
+    }
+
+    #[test]
+    fn test_single_line() {
+        assert_eq!(single_line("Call\n\tSam \r\n back "), "Call Sam back");
+        assert_eq!(single_line("zero\u{200B}width\u{7}"), "zerowidth");
+        assert_eq!(single_line("👨\u{200D}👩 ok"), "👨\u{200D}👩 ok");
+        assert_eq!(single_line(" \n "), "");
+    }
+
+    #[test]
+    fn test_format_date() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        let mut config = Config::default();
+
+        assert_eq!(format_date(day(7), &config, today), "2025-03-07");
+
+        config.general.date_format = Some("%d.%m.%Y".to_string());
+        assert_eq!(format_date(day(7), &config, today), "07.03.2025");
+
+        config.general.relative_dates = true;
+        assert_eq!(format_date(day(4), &config, today), "yesterday");
+        assert_eq!(format_date(day(5), &config, today), "today");
+        assert_eq!(format_date(day(11), &config, today), "in 6 days");
+        // A week or more away is shown as a date
+        assert_eq!(format_date(day(12), &config, today), "12.03.2025");
+        assert_eq!(
+            format_date(
+                NaiveDate::from_ymd_opt(2025, 2, 26).unwrap(),
+                &config,
+                today
+            ),
+            "26.02.2025"
+        );
+
+        // An invalid format falls back to ISO instead of panicking
+        config.general.relative_dates = false;
+        config.general.date_format = Some("%Q".to_string());
+        assert_eq!(format_date(day(7), &config, today), "2025-03-07");
+        config.general.date_format = Some("%H:%M".to_string());
+        assert_eq!(format_date(day(7), &config, today), "2025-03-07");
+    }
+
+    #[test]
+    fn test_group_by_tag_and_backend() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let tasks = vec![
+            dated("a", None, &["work", "email"], BackendSource::Obsidian),
+            dated("b", None, &["work"], BackendSource::LocalFile),
+            dated("c", None, &[], BackendSource::LocalFile),
+        ];
+
+        let groups = group_tasks(&tasks, GroupBy::Tag, &Config::default(), today);
+        let summary: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|g| (g.label.as_str(), g.tasks.len()))
+            .collect();
+        assert_eq!(summary, vec![("#email", 1), ("#work", 2), ("Untagged", 1)]);
+
+        let groups = group_tasks(&tasks, GroupBy::Backend, &Config::default(), today);
+        let summary: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|g| (g.label.as_str(), g.tasks.len()))
+            .collect();
+        assert_eq!(summary, vec![("local", 2), ("obsidian", 1)]);
+    }
+
+    #[test]
+    fn test_group_by_priority_runs_high_to_none() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let task = |title: &str, priority, due: Option<NaiveDate>| {
+            Task::build(title).with_priority(priority).with_due(due)
+        };
+        let long_overdue = NaiveDate::from_ymd_opt(2025, 2, 20);
+        let tasks = vec![
+            task("low", Priority::Low, None),
+            task("none", Priority::None, None),
+            task("high", Priority::High, None),
+            task("late low", Priority::Low, long_overdue),
+            task("another low", Priority::Low, None),
+        ];
+
+        let summary = |config: &Config| -> Vec<(String, Vec<String>)> {
+            group_tasks(&tasks, GroupBy::Priority, config, today)
+                .into_iter()
+                .map(|g| (g.label, g.tasks.into_iter().map(|t| t.title).collect()))
+                .collect()
+        };
+        let names = |titles: &[&str]| titles.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        // No medium tasks, so no Medium group
+        assert_eq!(
+            summary(&Config::default()),
+            vec![
+                ("High".to_string(), names(&["high"])),
+                (
+                    "Low".to_string(),
+                    names(&["low", "late low", "another low"])
+                ),
+                ("None".to_string(), names(&["none"])),
+            ]
+        );
+
+        // Escalation moves the long-overdue task up to High
+        let mut config = Config::default();
+        config.general.escalate_overdue_after_days = Some(7);
+        assert_eq!(
+            summary(&config),
+            vec![
+                ("High".to_string(), names(&["high", "late low"])),
+                ("Low".to_string(), names(&["low", "another low"])),
+                ("None".to_string(), names(&["none"])),
+            ]
+        );
+        assert_eq!("priority".parse::<GroupBy>().unwrap(), GroupBy::Priority);
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        assert!("nope".parse::<Field>().is_err());
+        assert_eq!(" tags".parse::<Field>().unwrap(), Field::Tags);
+    }
+}
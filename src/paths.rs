@@ -0,0 +1,109 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use crate::error::{Result, TasukiError};
+
+// All of tasuki's file locations, following the XDG base directory spec.
+
+/// The user's home directory, taken from `$HOME`.
+///
+/// Returns `None` when `$HOME` is unset or empty so callers can report a clear
+/// error instead of treating `~` as a literal directory name.
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Expand a leading `~` in a configured path, failing if there is no home
+/// directory to expand it against.
+pub fn expand_path(raw: &str) -> Result<PathBuf> {
+    if raw.starts_with('~') && home_dir().is_none() {
+        return Err(TasukiError::Config(format!(
+            "Cannot expand '{}': no home directory found ($HOME is unset)",
+            raw
+        )));
+    }
+    let expanded = shellexpand::tilde_with_context(raw, || {
+        home_dir().map(|h| h.to_string_lossy().into_owned())
+    });
+    Ok(PathBuf::from(expanded.into_owned()))
+}
+
+/// `$XDG_CONFIG_HOME/tasuki`, falling back to `~/.config/tasuki`.
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir(std::env::var_os("XDG_CONFIG_HOME"), home_dir(), ".config")
+}
+
+/// `$XDG_DATA_HOME/tasuki`, falling back to `~/.local/share/tasuki`.
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir(
+        std::env::var_os("XDG_DATA_HOME"),
+        home_dir(),
+        ".local/share",
+    )
+}
+
+/// `$XDG_CACHE_HOME/tasuki`, falling back to `~/.cache/tasuki`.
+pub fn cache_dir() -> Option<PathBuf> {
+    xdg_dir(std::env::var_os("XDG_CACHE_HOME"), home_dir(), ".cache")
+}
+
+/// `$XDG_STATE_HOME/tasuki`, falling back to `~/.local/state/tasuki`.
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir(
+        std::env::var_os("XDG_STATE_HOME"),
+        home_dir(),
+        ".local/state",
+    )
+}
+
+/// `~/.tasuki`, where todo.txt lived before tasuki followed XDG.
+pub fn legacy_dir() -> Option<PathBuf> {
+    home_dir().map(|h| h.join(".tasuki"))
+}
+
+// The spec says relative values must be ignored, same as unset ones.
+fn xdg_dir(var: Option<OsString>, home: Option<PathBuf>, fallback: &str) -> Option<PathBuf> {
+    let base = var
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| home.map(|h| h.join(fallback)))?;
+    Some(base.join("tasuki"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xdg_var_wins() {
+        let dir = xdg_dir(
+            Some(OsString::from("/tmp/xdg-data")),
+            Some(PathBuf::from("/home/me")),
+            ".local/share",
+        );
+        assert_eq!(dir, Some(PathBuf::from("/tmp/xdg-data/tasuki")));
+    }
+
+    #[test]
+    fn test_xdg_falls_back_to_home() {
+        let dir = xdg_dir(None, Some(PathBuf::from("/home/me")), ".local/state");
+        assert_eq!(dir, Some(PathBuf::from("/home/me/.local/state/tasuki")));
+    }
+
+    #[test]
+    fn test_xdg_relative_var_ignored() {
+        let dir = xdg_dir(
+            Some(OsString::from("relative/path")),
+            Some(PathBuf::from("/home/me")),
+            ".cache",
+        );
+        assert_eq!(dir, Some(PathBuf::from("/home/me/.cache/tasuki")));
+    }
+
+    #[test]
+    fn test_xdg_nothing_resolvable() {
+        assert_eq!(xdg_dir(None, None, ".config"), None);
+    }
+}
@@ -0,0 +1,256 @@
+//! `tasuki report`: tasks completed over a span of days, for standups and
+//! weekly reviews, with the ones still overdue.
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::model::{Task, TaskStatus};
+use crate::{export, output, streak};
+
+/// Completed work from `since` to `until`, both included.
+#[derive(Debug)]
+pub struct Report<'a> {
+    pub since: NaiveDate,
+    pub until: NaiveDate,
+    /// Tasks completed on each day of the span, oldest first, each day's in
+    /// the order they were done; days without any are left out
+    pub days: Vec<(NaiveDate, Vec<&'a Task>)>,
+    /// Pending tasks due before today
+    pub overdue: Vec<&'a Task>,
+}
+
+impl Report<'_> {
+    /// Tasks completed across the span.
+    pub fn completed(&self) -> usize {
+        self.days.iter().map(|(_, tasks)| tasks.len()).sum()
+    }
+}
+
+/// The report over `tasks` for `since` to `until`.
+pub fn build(tasks: &[Task], since: NaiveDate, until: NaiveDate, today: NaiveDate) -> Report<'_> {
+    let mut done: Vec<&Task> = streak::completed_between(tasks, since, until).collect();
+    done.sort_by_key(|t| t.completed_at);
+
+    let mut days: Vec<(NaiveDate, Vec<&Task>)> = Vec::new();
+    for task in done {
+        let Some(day) = task.completed_at.map(|at| at.date()) else {
+            continue;
+        };
+        match days.last_mut() {
+            Some((last, day_tasks)) if *last == day => day_tasks.push(task),
+            _ => days.push((day, vec![task])),
+        }
+    }
+
+    let overdue = tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::Pending && t.due.is_some_and(|d| d < today))
+        .collect();
+
+    Report {
+        since,
+        until,
+        days,
+        overdue,
+    }
+}
+
+/// "Done Monday 2025-02-24 – Saturday 2025-03-01", or "Done Saturday
+/// 2025-03-01" for a single day.
+pub fn title(report: &Report, config: &Config) -> String {
+    if report.since == report.until {
+        format!("Done {}", day_label(report.since, config))
+    } else {
+        format!(
+            "Done {} – {}",
+            day_label(report.since, config),
+            day_label(report.until, config)
+        )
+    }
+}
+
+/// "Monday 2025-02-24", always absolute since a report is read later.
+pub fn day_label(day: NaiveDate, config: &Config) -> String {
+    format!(
+        "{} {}",
+        day.format("%A"),
+        day.format(config.general.date_format())
+    )
+}
+
+/// `tasuki report`: a header per day, tasks under it (split by project or
+/// first tag with `by_group`), then what's still overdue.
+pub fn render(report: &Report, by_group: bool, config: &Config, today: NaiveDate) -> String {
+    let mut text = format!(
+        "{}: {} completed, {} overdue\n",
+        title(report, config),
+        report.completed(),
+        report.overdue.len()
+    );
+
+    for (day, tasks) in &report.days {
+        text.push_str(&format!(
+            "\n{} ({})\n",
+            day_label(*day, config),
+            tasks.len()
+        ));
+        if !by_group {
+            for task in tasks {
+                text.push_str(&format!("  ✓ {}\n", task.title));
+            }
+            continue;
+        }
+        for (group, group_tasks) in export::by_group(tasks) {
+            let indent = match group {
+                Some(group) => {
+                    text.push_str(&format!("  {}\n", group));
+                    "    "
+                }
+                None => "  ",
+            };
+            for task in group_tasks {
+                text.push_str(&format!("{}✓ {}\n", indent, task.title));
+            }
+        }
+    }
+
+    if !report.overdue.is_empty() {
+        text.push_str(&format!("\nStill overdue ({})\n", report.overdue.len()));
+        for task in &report.overdue {
+            let due = task
+                .due
+                .map(|d| output::format_date(d, config, today))
+                .unwrap_or_default();
+            text.push_str(&format!("  ☐ {} ({})\n", task.title, due));
+        }
+    }
+    text
+}
+
+/// `tasuki report --format json`.
+pub fn to_json(report: &Report) -> serde_json::Value {
+    fn view<'a>(task: &&'a Task) -> output::TaskView<'a> {
+        output::TaskView::new(task, output::Field::ALL)
+    }
+    serde_json::json!({
+        "version": output::JSON_SCHEMA_VERSION,
+        "since": report.since.to_string(),
+        "until": report.until.to_string(),
+        "counts": {
+            "completed": report.completed(),
+            "overdue": report.overdue.len(),
+        },
+        "days": report.days.iter().map(|(day, tasks)| serde_json::json!({
+            "date": day.to_string(),
+            "tasks": tasks.iter().map(view).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+        "overdue": report.overdue.iter().map(view).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn done(title: &str, on: &str) -> Task {
+        Task::build(title)
+            .with_status(TaskStatus::Done)
+            .with_completed(date(on).and_hms_opt(12, 0, 0))
+    }
+
+    #[test]
+    fn test_window_includes_both_ends() {
+        let late_night = done("Late night", "2025-03-01")
+            .with_completed(date("2025-03-01").and_hms_opt(23, 59, 59));
+        let undated = Task::build("Undated").with_status(TaskStatus::Done);
+        let tasks = vec![
+            done("Before", "2025-02-23"),
+            done("First day", "2025-02-24"),
+            late_night,
+            done("Middle", "2025-02-26"),
+            done("After", "2025-03-02"),
+            undated,
+            Task::build("Pending").with_due(date("2025-02-25")),
+        ];
+
+        let report = build(
+            &tasks,
+            date("2025-02-24"),
+            date("2025-03-01"),
+            date("2025-03-01"),
+        );
+        let days: Vec<(String, Vec<&str>)> = report
+            .days
+            .iter()
+            .map(|(day, tasks)| {
+                (
+                    day.to_string(),
+                    tasks.iter().map(|t| t.title.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            days,
+            vec![
+                ("2025-02-24".to_string(), vec!["First day"]),
+                ("2025-02-26".to_string(), vec!["Middle"]),
+                ("2025-03-01".to_string(), vec!["Late night"]),
+            ]
+        );
+        assert_eq!(report.completed(), 3);
+        assert_eq!(report.overdue.len(), 1);
+
+        // A one-day window is just that day
+        let report = build(
+            &tasks,
+            date("2025-02-24"),
+            date("2025-02-24"),
+            date("2025-03-01"),
+        );
+        assert_eq!(report.completed(), 1);
+    }
+
+    #[test]
+    fn test_render_report() {
+        let tasks = vec![
+            done("Review PR", "2025-02-24").with_tags(&["work"]),
+            done("Water plants", "2025-02-24"),
+            done("Ship release", "2025-02-26").with_project("Q1 Review"),
+            Task::build("Pay rent")
+                .with_due(date("2025-02-27"))
+                .with_tags(&["home"]),
+        ];
+        let config = Config::default();
+        let today = date("2025-03-01");
+        let report = build(&tasks, date("2025-02-24"), today, today);
+
+        assert_eq!(
+            render(&report, false, &config, today),
+            "\
+Done Monday 2025-02-24 – Saturday 2025-03-01: 3 completed, 1 overdue
+
+Monday 2025-02-24 (2)
+  ✓ Review PR
+  ✓ Water plants
+
+Wednesday 2025-02-26 (1)
+  ✓ Ship release
+
+Still overdue (1)
+  ☐ Pay rent (2025-02-27)
+"
+        );
+        assert!(render(&report, true, &config, today)
+            .contains("Monday 2025-02-24 (2)\n  ✓ Water plants\n  #work\n    ✓ Review PR\n"));
+
+        let json = to_json(&report);
+        assert_eq!(json["counts"]["completed"], 3);
+        assert_eq!(json["days"][1]["date"], "2025-02-26");
+        assert_eq!(json["days"][1]["tasks"][0]["title"], "Ship release");
+        assert_eq!(json["overdue"][0]["title"], "Pay rent");
+    }
+}
@@ -0,0 +1,455 @@
+//! `tasuki serve --stdio`: a JSON-RPC 2.0 server for editors and assistants.
+//!
+//! One request (or batch) per line on stdin, one response per line on
+//! stdout. Requests run one at a time against a single `BackendManager`, so
+//! writes never interleave. Tasks are serialized as in `list --format json`.
+//!
+//! | Method           | Params                                              |
+//! |------------------|-----------------------------------------------------|
+//! | `tasks/list`     | `status`, `due_before`, `due_after`, `search`, `has_due`, `tag` |
+//! | `tasks/create`   | `text` (quick-add), or `title`, `priority`, `due`, `tags`, `backend` |
+//! | `tasks/complete` | `id`                                                |
+//! | `tasks/update`   | `id`, `title`, `status`, `priority`, `due` (`null` clears), `tags` |
+//! | `tasks/delete`   | `id`                                                |
+//!
+//! Tasuki errors come back with code `-32000 - exit code` (so not found is
+//! `-32004`) and `data.kind` naming the error.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+use crate::backends::BackendManager;
+use crate::config::Config;
+use crate::error::{Result, TasukiError};
+use crate::model::{BackendSource, NewTask, Priority, Task, TaskFilter, TaskStatus, TaskUpdate};
+use crate::nlp::parse_quick_add_with;
+use crate::output::{Field, TaskList, TaskView, JSON_SCHEMA_VERSION};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// A failed call: a JSON-RPC error object.
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        let mut error = json!({ "code": self.code, "message": self.message });
+        if let Some(ref data) = self.data {
+            error["data"] = data.clone();
+        }
+        error
+    }
+}
+
+impl From<TasukiError> for RpcError {
+    fn from(e: TasukiError) -> Self {
+        let kind = match e {
+            TasukiError::Config(_) => "config",
+            TasukiError::Backend { .. } => "backend",
+            TasukiError::Unavailable { .. } => "unavailable",
+            TasukiError::Io(_) => "io",
+            TasukiError::Parse(_) => "parse",
+            TasukiError::Json(_) => "json",
+            TasukiError::Watch(_) => "watch",
+            TasukiError::NotFound(_) => "not_found",
+            TasukiError::StaleId(_) => "stale_id",
+            TasukiError::Aborted(_) => "aborted",
+        };
+        Self {
+            code: -32000 - i64::from(e.exit_code()),
+            message: e.to_string(),
+            data: Some(json!({ "kind": kind, "exit_code": e.exit_code() })),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ListParams {
+    status: Option<String>,
+    due_before: Option<NaiveDate>,
+    due_after: Option<NaiveDate>,
+    search: Option<String>,
+    has_due: Option<bool>,
+    tag: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CreateParams {
+    text: Option<String>,
+    title: Option<String>,
+    priority: Option<String>,
+    due: Option<NaiveDate>,
+    tags: Vec<String>,
+    backend: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UpdateParams {
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    priority: Option<String>,
+    /// Absent leaves the date alone; `null` clears it
+    #[serde(default, deserialize_with = "present")]
+    due: Option<Option<NaiveDate>>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IdParams {
+    id: String,
+}
+
+/// Wraps any value that is present, including `null`, in `Some`.
+fn present<'de, D: Deserializer<'de>>(
+    d: D,
+) -> std::result::Result<Option<Option<NaiveDate>>, D::Error> {
+    Option::<NaiveDate>::deserialize(d).map(Some)
+}
+
+fn params<T: for<'de> Deserialize<'de> + Default>(
+    params: Value,
+) -> std::result::Result<T, RpcError> {
+    if params.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn required_params<T: for<'de> Deserialize<'de>>(
+    params: Value,
+) -> std::result::Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn status(name: &str) -> std::result::Result<TaskStatus, RpcError> {
+    match name {
+        "pending" => Ok(TaskStatus::Pending),
+        "done" => Ok(TaskStatus::Done),
+        _ => Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("status '{}' is not one of pending, done", name),
+        )),
+    }
+}
+
+fn priority(name: &str) -> std::result::Result<Priority, RpcError> {
+    Priority::from_name(name).ok_or_else(|| {
+        RpcError::new(
+            INVALID_PARAMS,
+            format!("priority '{}' is not one of high, medium, low, none", name),
+        )
+    })
+}
+
+fn task_result(task: &Task) -> Value {
+    json!({
+        "version": JSON_SCHEMA_VERSION,
+        "task": TaskView::new(task, Field::ALL),
+    })
+}
+
+async fn call(
+    manager: &BackendManager,
+    config: &Config,
+    method: &str,
+    raw: Value,
+) -> std::result::Result<Value, RpcError> {
+    match method {
+        "tasks/list" => {
+            let p: ListParams = params(raw)?;
+            let filter = TaskFilter {
+                status: p.status.as_deref().map(status).transpose()?,
+                due_before: p.due_before,
+                due_after: p.due_after,
+                search: p.search,
+                search_mode: config.general.search_mode(),
+                has_due: p.has_due,
+                tag: p.tag,
+            };
+            let tasks = manager.all_tasks(&filter).await?;
+            Ok(serde_json::to_value(TaskList::new(&tasks, Field::ALL))
+                .map_err(TasukiError::from)?)
+        }
+        "tasks/create" => {
+            let p: CreateParams = params(raw)?;
+            let default_backend = manager.default_source().unwrap_or(BackendSource::LocalFile);
+            let new_task = match (p.text, p.title) {
+                (Some(text), None) => {
+                    parse_quick_add_with(&text, &manager.quick_add_options(config))?
+                        .into_new_task(default_backend)
+                }
+                (None, Some(title)) => NewTask {
+                    title,
+                    priority: p
+                        .priority
+                        .as_deref()
+                        .map(priority)
+                        .transpose()?
+                        .unwrap_or(Priority::None),
+                    due: p.due,
+                    tags: p.tags,
+                    backend: match p.backend {
+                        Some(name) => BackendSource::from_name(&name).ok_or_else(|| {
+                            RpcError::new(
+                                INVALID_PARAMS,
+                                format!(
+                                    "backend '{}' is not one of {}",
+                                    name,
+                                    BackendSource::names()
+                                ),
+                            )
+                        })?,
+                        None => default_backend,
+                    },
+                },
+                _ => {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        "give either 'text' (quick-add) or 'title'",
+                    ))
+                }
+            };
+            let task = manager.create_task(&new_task).await?;
+            Ok(task_result(&task))
+        }
+        "tasks/complete" => {
+            let p: IdParams = required_params(raw)?;
+            let mut task = manager.task_by_id(&p.id).await?;
+            task.id = manager.complete_task(&p.id, Some(&task.title)).await?;
+            task.status = TaskStatus::Done;
+            Ok(task_result(&task))
+        }
+        "tasks/update" => {
+            let p: UpdateParams = required_params(raw)?;
+            let update = TaskUpdate {
+                title: p.title,
+                status: p.status.as_deref().map(status).transpose()?,
+                priority: p.priority.as_deref().map(priority).transpose()?,
+                due: p.due,
+                tags: p.tags,
+            };
+            let task = manager.update_task(&p.id, &update).await?;
+            Ok(task_result(&task))
+        }
+        "tasks/delete" => {
+            let p: IdParams = required_params(raw)?;
+            let task = manager.task_by_id(&p.id).await?;
+            manager.delete_task(&p.id).await?;
+            Ok(task_result(&task))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method '{}'", method),
+        )),
+    }
+}
+
+/// Handle one request object; `None` for notifications, which get no reply.
+async fn handle(manager: &BackendManager, config: &Config, request: Value) -> Option<Value> {
+    let id = request.get("id").cloned();
+    let reply = |outcome: std::result::Result<Value, RpcError>| {
+        let id = id.clone().unwrap_or(Value::Null);
+        Some(match outcome {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => json!({ "jsonrpc": "2.0", "id": id, "error": e.to_value() }),
+        })
+    };
+
+    let method = match request.get("method").and_then(Value::as_str) {
+        Some(method) if request.get("jsonrpc") == Some(&json!("2.0")) => method,
+        _ => {
+            return reply(Err(RpcError::new(
+                INVALID_REQUEST,
+                "Not a JSON-RPC 2.0 request",
+            )))
+        }
+    };
+    let raw = request.get("params").cloned().unwrap_or(Value::Null);
+    let outcome = call(manager, config, method, raw).await;
+
+    id.as_ref()?;
+    reply(outcome)
+}
+
+/// The reply to a line that isn't JSON (or isn't text at all).
+fn parse_error(e: impl std::fmt::Display) -> Value {
+    let error = RpcError::new(PARSE_ERROR, format!("Parse error: {}", e));
+    json!({ "jsonrpc": "2.0", "id": null, "error": error.to_value() })
+}
+
+/// Answer one line of input: a request, a batch, or garbage.
+async fn handle_line(manager: &BackendManager, config: &Config, line: &str) -> Option<Value> {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => return Some(parse_error(e)),
+    };
+
+    match value {
+        Value::Array(batch) if batch.is_empty() => {
+            let error = RpcError::new(INVALID_REQUEST, "Empty batch");
+            Some(json!({ "jsonrpc": "2.0", "id": null, "error": error.to_value() }))
+        }
+        Value::Array(batch) => {
+            let mut replies = Vec::new();
+            for request in batch {
+                replies.extend(handle(manager, config, request).await);
+            }
+            (!replies.is_empty()).then_some(Value::Array(replies))
+        }
+        request => handle(manager, config, request).await,
+    }
+}
+
+/// Serve requests from `input` until it ends, writing replies to `output`.
+/// A line that isn't UTF-8 gets a parse error like any other garbage.
+pub async fn serve<R: BufRead, W: Write>(
+    manager: &BackendManager,
+    config: &Config,
+    input: R,
+    mut output: W,
+) -> Result<()> {
+    for line in input.split(b'\n') {
+        let line = line?;
+        let reply = match std::str::from_utf8(&line) {
+            Ok(line) if line.trim().is_empty() => continue,
+            Ok(line) => handle_line(manager, config, line).await,
+            Err(e) => Some(parse_error(e)),
+        };
+        if let Some(reply) = reply {
+            writeln!(output, "{}", reply)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+    use tempfile::TempDir;
+
+    fn manager(dir: &TempDir, contents: &str) -> BackendManager {
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, contents).unwrap();
+        BackendManager::new(vec![Box::new(LocalFileBackend::new(LocalFileConfig {
+            path,
+        }))])
+    }
+
+    async fn run(manager: &BackendManager, input: impl AsRef<[u8]>) -> Vec<Value> {
+        let mut output = Vec::new();
+        serve(manager, &Config::default(), input.as_ref(), &mut output)
+            .await
+            .unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_create_complete_delete() {
+        let dir = TempDir::new().unwrap();
+        let manager = manager(&dir, "Water plants\n");
+        let replies = run(
+            &manager,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tasks/list","params":{"status":"pending"}}
+{"jsonrpc":"2.0","id":2,"method":"tasks/create","params":{"text":"Call mom #family"}}
+{"jsonrpc":"2.0","id":3,"method":"tasks/complete","params":{"id":"local:1"}}
+{"jsonrpc":"2.0","id":4,"method":"tasks/update","params":{"id":"local:2","priority":"high","due":"2025-03-01"}}
+{"jsonrpc":"2.0","id":5,"method":"tasks/delete","params":{"id":"local:1"}}
+"#,
+        )
+        .await;
+
+        assert_eq!(replies.len(), 5);
+        assert_eq!(replies[0]["id"], 1);
+        assert_eq!(replies[0]["result"]["tasks"][0]["title"], "Water plants");
+        assert_eq!(replies[1]["result"]["task"]["title"], "Call mom");
+        assert_eq!(replies[1]["result"]["task"]["tags"], json!(["family"]));
+        assert_eq!(replies[2]["result"]["task"]["status"], "Done");
+        assert_eq!(replies[2]["result"]["task"]["title"], "Water plants");
+        assert_eq!(replies[3]["result"]["task"]["priority"], "High");
+        assert_eq!(replies[3]["result"]["task"]["due"], "2025-03-01");
+        assert_eq!(replies[4]["result"]["task"]["title"], "Water plants");
+
+        let content = std::fs::read_to_string(dir.path().join("todo.txt")).unwrap();
+        assert!(!content.contains("Water plants"));
+    }
+
+    #[tokio::test]
+    async fn test_protocol_errors_keep_serving() {
+        let dir = TempDir::new().unwrap();
+        let manager = manager(&dir, "Water plants\n");
+        let mut input = br#"{"jsonrpc":"2.0","id":1,"method":
+{"jsonrpc":"2.0","id":2,"method":"tasks/explode"}
+{"id":3,"method":"tasks/list"}
+{"jsonrpc":"2.0","id":4,"method":"tasks/complete","params":{"id":"local:99"}}
+{"jsonrpc":"2.0","id":5,"method":"tasks/update","params":{"title":"No ID"}}
+{"jsonrpc":"2.0","method":"tasks/complete","params":{"id":"local:1"}}
+[{"jsonrpc":"2.0","id":6,"method":"tasks/list"},{"jsonrpc":"2.0","id":7,"method":"tasks/list","params":{"status":"late"}}]
+"#
+        .to_vec();
+        input.extend_from_slice(b"\xff\xfe\n");
+        input.extend_from_slice(br#"{"jsonrpc":"2.0","id":8,"method":"tasks/list"}"#);
+        input.extend_from_slice(
+            br#"
+{"jsonrpc":"2.0","id":9,"method":"tasks/delete","params":{"id":"nope:1"}}"#,
+        );
+        let replies = run(&manager, input).await;
+
+        assert_eq!(replies.len(), 9);
+        assert_eq!(replies[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(replies[0]["id"], Value::Null);
+        assert_eq!(replies[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(replies[2]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(replies[2]["id"], 3);
+        assert_eq!(replies[3]["error"]["code"], -32004);
+        assert_eq!(replies[3]["error"]["data"]["kind"], "not_found");
+        assert_eq!(replies[4]["error"]["code"], INVALID_PARAMS);
+
+        // The notification had no reply but still completed the task
+        let batch = replies[5].as_array().unwrap();
+        assert_eq!(batch[0]["result"]["tasks"][0]["status"], "Done");
+        assert_eq!(batch[1]["error"]["code"], INVALID_PARAMS);
+
+        // Bytes that aren't UTF-8 are one bad line, not the end of input
+        assert_eq!(replies[6]["error"]["code"], PARSE_ERROR);
+        assert_eq!(replies[7]["id"], 8);
+
+        // IDs no enabled backend owns fail as they do on the command line
+        assert_eq!(replies[8]["error"]["code"], -32002);
+        assert!(replies[8]["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("enabled: local"));
+    }
+}
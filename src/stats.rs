@@ -0,0 +1,122 @@
+//! Completion ratios per tag and per project for `tasuki stats`.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::model::{Task, TaskStatus};
+
+/// Done tasks out of all tasks in a group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Completion {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Completion {
+    fn count(&mut self, task: &Task) {
+        self.total += 1;
+        if task.status == TaskStatus::Done {
+            self.done += 1;
+        }
+    }
+}
+
+/// Completion per tag. A task with several tags counts toward each of them.
+pub fn by_tag(tasks: &[Task]) -> BTreeMap<String, Completion> {
+    let mut tags: BTreeMap<String, Completion> = BTreeMap::new();
+    for task in tasks {
+        let mut seen: Vec<&str> = Vec::new();
+        for tag in &task.tags {
+            if !seen.contains(&tag.as_str()) {
+                seen.push(tag);
+                tags.entry(tag.clone()).or_default().count(task);
+            }
+        }
+    }
+    tags
+}
+
+/// Completion per project; tasks without one are left out.
+pub fn by_project(tasks: &[Task]) -> BTreeMap<String, Completion> {
+    let mut projects: BTreeMap<String, Completion> = BTreeMap::new();
+    for task in tasks {
+        if let Some(ref project) = task.project {
+            projects.entry(project.clone()).or_default().count(task);
+        }
+    }
+    projects
+}
+
+/// A heading, then "  #work  12/20 done" per group with the names lined
+/// up. Nothing if there are no groups.
+pub fn render(heading: &str, groups: &BTreeMap<String, Completion>, prefix: &str) -> String {
+    if groups.is_empty() {
+        return String::new();
+    }
+    let width = groups
+        .keys()
+        .map(|name| crate::output::display_width(name) + prefix.len())
+        .max()
+        .unwrap_or(0);
+    let mut text = format!("{}\n", heading);
+    for (name, completion) in groups {
+        let label = format!("{}{}", prefix, name);
+        let pad = width - crate::output::display_width(&label);
+        text.push_str(&format!(
+            "  {}{}  {}/{} done\n",
+            label,
+            " ".repeat(pad),
+            completion.done,
+            completion.total
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn done(task: Task) -> Task {
+        task.with_status(TaskStatus::Done)
+    }
+
+    /// A pending task in `project` ("" for none) with `tags`.
+    fn tagged(title: &str, project: &str, tags: &[&str]) -> Task {
+        let task = Task::build(title).with_tags(tags);
+        if project.is_empty() {
+            task
+        } else {
+            task.with_project(project)
+        }
+    }
+
+    #[test]
+    fn test_completion_by_tag_and_project() {
+        let tasks = vec![
+            done(tagged("Report", "Q3", &["work", "urgent"])),
+            tagged("Slides", "Q3", &["work"]),
+            done(tagged("Invoice", "", &["work", "admin", "work"])),
+            tagged("Groceries", "House", &["home"]),
+            tagged("Read", "", &[]),
+        ];
+
+        let tags = by_tag(&tasks);
+        assert_eq!(tags["work"], Completion { done: 2, total: 3 });
+        assert_eq!(tags["urgent"], Completion { done: 1, total: 1 });
+        assert_eq!(tags["admin"], Completion { done: 1, total: 1 });
+        assert_eq!(tags["home"], Completion { done: 0, total: 1 });
+        assert_eq!(tags.len(), 4);
+
+        let projects = by_project(&tasks);
+        assert_eq!(projects["Q3"], Completion { done: 1, total: 2 });
+        assert_eq!(projects["House"], Completion { done: 0, total: 1 });
+        assert_eq!(projects.len(), 2);
+
+        assert_eq!(
+            render("Tags", &tags, "#"),
+            "Tags\n  #admin   1/1 done\n  #home    0/1 done\n  #urgent  1/1 done\n  #work    2/3 done\n"
+        );
+        assert_eq!(render("Projects", &BTreeMap::new(), ""), "");
+    }
+}
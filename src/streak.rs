@@ -0,0 +1,244 @@
+//! `general.daily_goal`: tasks completed per day and the streak of days
+//! meeting the goal.
+//!
+//! Counts come from `completed_at` dates. They're kept in
+//! `<state dir>/streak.json`, so a day still counts after its completed
+//! tasks are deleted or archived, or when tasuki didn't run that day.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::model::{Task, TaskStatus};
+
+/// Tasks completed on each day seen so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct History {
+    days: BTreeMap<NaiveDate, usize>,
+}
+
+/// Done tasks in `tasks` completed on `day`.
+pub fn done_on(tasks: &[Task], day: NaiveDate) -> usize {
+    completed_between(tasks, day, day).count()
+}
+
+/// Done tasks in `tasks` completed from `since` to `until`, both included.
+pub fn completed_between(
+    tasks: &[Task],
+    since: NaiveDate,
+    until: NaiveDate,
+) -> impl Iterator<Item = &Task> {
+    tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::Done)
+        .filter(move |t| {
+            t.completed_at
+                .is_some_and(|at| (since..=until).contains(&at.date()))
+        })
+}
+
+impl History {
+    pub fn path(config: &Config) -> Option<PathBuf> {
+        config.state_dir().map(|dir| dir.join("streak.json"))
+    }
+
+    /// Load the history, starting fresh if the file is missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(_) => return Self::default(),
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring corrupt streak history {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Take in the completion dates of `tasks`. A day's count only goes
+    /// up, so tasks that have since gone away still count. Returns whether
+    /// anything changed.
+    pub fn record(&mut self, tasks: &[Task]) -> bool {
+        let mut seen: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for task in tasks.iter().filter(|t| t.status == TaskStatus::Done) {
+            if let Some(at) = task.completed_at {
+                *seen.entry(at.date()).or_default() += 1;
+            }
+        }
+
+        let mut changed = false;
+        for (day, count) in seen {
+            let stored = self.days.entry(day).or_default();
+            if count > *stored {
+                *stored = count;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Tasks completed on `day`.
+    pub fn done_on(&self, day: NaiveDate) -> usize {
+        self.days.get(&day).copied().unwrap_or(0)
+    }
+
+    /// Consecutive days meeting `goal` up to `today`. Today only breaks the
+    /// streak once it's over, so until the goal is met the streak runs to
+    /// yesterday.
+    pub fn streak(&self, goal: u32, today: NaiveDate) -> u32 {
+        let met = |day: NaiveDate| self.done_on(day) >= goal as usize;
+        let mut day = if met(today) {
+            today
+        } else {
+            today.pred_opt().unwrap_or(today)
+        };
+        let mut streak = 0;
+        while met(day) {
+            streak += 1;
+            match day.pred_opt() {
+                Some(prev) => day = prev,
+                None => break,
+            }
+        }
+        streak
+    }
+
+    /// The longest run of consecutive days meeting `goal`.
+    pub fn best_streak(&self, goal: u32) -> u32 {
+        let mut best = 0;
+        let mut run = 0;
+        let mut last: Option<NaiveDate> = None;
+        for (&day, &count) in &self.days {
+            if count < goal as usize {
+                run = 0;
+                last = None;
+                continue;
+            }
+            run = match last {
+                Some(prev) if prev.succ_opt() == Some(day) => run + 1,
+                _ => 1,
+            };
+            last = Some(day);
+            best = best.max(run);
+        }
+        best
+    }
+}
+
+/// Today's count and streak for `general.daily_goal`, after folding
+/// `tasks` into the saved history.
+pub struct Progress {
+    pub done_today: usize,
+    pub goal: u32,
+    pub streak: u32,
+    pub best_streak: u32,
+}
+
+impl Progress {
+    pub fn met(&self) -> bool {
+        self.done_today >= self.goal as usize
+    }
+}
+
+/// Record `tasks` in the history at `path` (saving it if anything changed)
+/// and work out progress towards `goal`. A history that can't be saved
+/// only costs the days it would have kept.
+pub fn progress(path: Option<&Path>, tasks: &[Task], goal: u32, today: NaiveDate) -> Progress {
+    let mut history = path.map(History::load).unwrap_or_default();
+    if history.record(tasks) {
+        if let Some(path) = path {
+            if let Err(e) = history.save(path) {
+                tracing::warn!("Could not save streak history {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    Progress {
+        done_today: history.done_on(today).max(done_on(tasks, today)),
+        goal,
+        streak: history.streak(goal, today),
+        best_streak: history.best_streak(goal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    /// `counts[i]` tasks completed on March `first + i`.
+    fn completed(first: u32, counts: &[usize]) -> Vec<Task> {
+        let mut tasks = Vec::new();
+        for (i, &count) in counts.iter().enumerate() {
+            for n in 0..count {
+                let title = format!("task {} {}", i, n);
+                tasks.push(
+                    Task::build(&title)
+                        .with_status(TaskStatus::Done)
+                        .with_completed(day(first + i as u32).and_hms_opt(12, 0, 0)),
+                );
+            }
+        }
+        tasks
+    }
+
+    #[test]
+    fn test_streak_over_history() {
+        let mut history = History::default();
+        // March 1..=8: 3 3 1 3 4 3 0 2
+        assert!(history.record(&completed(1, &[3, 3, 1, 3, 4, 3, 0, 2])));
+
+        // Today (8th) isn't met yet and the 7th broke the run
+        assert_eq!(history.streak(3, day(8)), 0);
+        // From the 6th, the run goes back to the 4th
+        assert_eq!(history.streak(3, day(6)), 3);
+        // The 7th isn't over yet when it's today, so the run still counts
+        assert_eq!(history.streak(3, day(7)), 3);
+        assert_eq!(history.best_streak(3), 3);
+        assert_eq!(history.streak(1, day(6)), 6);
+        assert_eq!(history.best_streak(1), 6);
+    }
+
+    #[test]
+    fn test_record_keeps_the_highest_count() {
+        let mut history = History::default();
+        history.record(&completed(1, &[3, 2]));
+        // Two of the 1st's tasks were archived since
+        assert!(!history.record(&completed(1, &[1, 2])));
+        assert_eq!(history.done_on(day(1)), 3);
+        assert!(history.record(&completed(2, &[4])));
+        assert_eq!(history.done_on(day(2)), 4);
+    }
+
+    #[test]
+    fn test_progress_persists_history() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("state").join("streak.json");
+
+        let first = progress(Some(&path), &completed(1, &[2, 2]), 2, day(2));
+        assert!(first.met());
+        assert_eq!(first.streak, 2);
+
+        // Those tasks are gone, but the days they were done still count
+        let later = progress(Some(&path), &completed(3, &[2]), 2, day(3));
+        assert_eq!(later.done_today, 2);
+        assert_eq!(later.streak, 3);
+        assert_eq!(History::load(&path).done_on(day(1)), 2);
+    }
+}
@@ -0,0 +1,769 @@
+//! Mirror tasks from one backend into another and keep their completion
+//! status in step.
+//!
+//! Pairings live in a JSON store. Each pair remembers both task IDs plus the
+//! titles and status as of the last sync, so edits on either side can be
+//! detected, and tasks can be found again by title if their line-based IDs
+//! shift. The reconciliation itself (`plan`) is pure; `run` fetches tasks,
+//! applies the plan through the `BackendManager` and saves the store.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::backends::BackendManager;
+use crate::error::{Result, TasukiError};
+use crate::model::{BackendSource, NewTask, Task, TaskFilter, TaskStatus};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pair {
+    pub from_id: String,
+    pub to_id: String,
+    pub from_title: String,
+    pub to_title: String,
+    /// Status both sides had after the last sync
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PairStore {
+    #[serde(default)]
+    pub pairs: Vec<Pair>,
+}
+
+impl PairStore {
+    /// Load the store; a missing file is an empty store.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Pairs mirroring `from` into `to`.
+    fn between<'a>(
+        &'a self,
+        manager: &'a BackendManager,
+        from: BackendSource,
+        to: BackendSource,
+    ) -> impl Iterator<Item = &'a Pair> {
+        self.pairs
+            .iter()
+            .filter(move |p| p.is_between(manager, from, to))
+    }
+}
+
+impl Pair {
+    /// Whether this mirrors a task in `from` into `to`, going by which of
+    /// `manager`'s backends own the two IDs.
+    fn is_between(&self, manager: &BackendManager, from: BackendSource, to: BackendSource) -> bool {
+        manager.source_of(&self.from_id) == Some(from) && manager.source_of(&self.to_id) == Some(to)
+    }
+}
+
+/// Which side wins when both changed since the last sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefer {
+    From,
+    To,
+}
+
+impl std::str::FromStr for Prefer {
+    type Err = TasukiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "from" => Ok(Prefer::From),
+            "to" => Ok(Prefer::To),
+            _ => Err(TasukiError::Parse(format!(
+                "Unknown --prefer '{}' (valid: from, to)",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    From,
+    To,
+}
+
+/// A status change to apply to one side of a pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusChange {
+    pub side: Side,
+    pub id: String,
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub from: Task,
+    pub to: Task,
+}
+
+#[derive(Debug, Default)]
+pub struct Plan {
+    /// Source tasks with no counterpart yet
+    pub create: Vec<Task>,
+    pub changes: Vec<StatusChange>,
+    pub conflicts: Vec<Conflict>,
+    /// Pairs where one side no longer exists. Pairs whose source is gone are
+    /// dropped; pairs whose counterpart was deleted are kept, so deleting a
+    /// mirrored task doesn't bring it back on the next sync.
+    pub orphaned: Vec<Pair>,
+    /// Surviving pairs with refreshed IDs, titles and status
+    pub pairs: Vec<Pair>,
+}
+
+/// Find a paired task again: by ID if the title still matches, otherwise by
+/// a unique exact title (line-based IDs shift when files are edited).
+fn locate<'a>(tasks: &'a [Task], id: &str, title: &str) -> Option<&'a Task> {
+    if let Some(task) = tasks.iter().find(|t| t.id == id && t.title == title) {
+        return Some(task);
+    }
+    let mut by_title = tasks.iter().filter(|t| t.title == title);
+    match (by_title.next(), by_title.next()) {
+        (Some(task), None) => Some(task),
+        _ => tasks.iter().find(|t| t.id == id),
+    }
+}
+
+/// Work out what to do for existing pairs and which source tasks to mirror.
+/// `from_tasks` and `to_tasks` are every task in each backend; `tag` limits
+/// which unpaired source tasks get a counterpart.
+pub fn plan(
+    pairs: &[Pair],
+    from_tasks: &[Task],
+    to_tasks: &[Task],
+    tag: Option<&str>,
+    prefer: Option<Prefer>,
+) -> Plan {
+    let mut plan = Plan::default();
+    let mut paired_from: Vec<&str> = Vec::new();
+
+    for pair in pairs {
+        let Some(from) = locate(from_tasks, &pair.from_id, &pair.from_title) else {
+            plan.orphaned.push(pair.clone());
+            continue;
+        };
+        paired_from.push(&from.id);
+
+        // The counterpart was deleted: keep the pair so it isn't recreated
+        let Some(to) = locate(to_tasks, &pair.to_id, &pair.to_title) else {
+            plan.orphaned.push(pair.clone());
+            plan.pairs.push(Pair {
+                from_id: from.id.clone(),
+                from_title: from.title.clone(),
+                ..pair.clone()
+            });
+            continue;
+        };
+
+        let mut updated = Pair {
+            from_id: from.id.clone(),
+            to_id: to.id.clone(),
+            from_title: from.title.clone(),
+            to_title: to.title.clone(),
+            status: from.status,
+        };
+
+        if from.status != to.status {
+            let from_changed = from.status != pair.status || from.title != pair.from_title;
+            let to_changed = to.status != pair.status || to.title != pair.to_title;
+
+            let winner = match (from_changed, to_changed, prefer) {
+                (true, false, _) => Some(Side::From),
+                (false, true, _) => Some(Side::To),
+                (_, _, Some(Prefer::From)) => Some(Side::From),
+                (_, _, Some(Prefer::To)) => Some(Side::To),
+                _ => None,
+            };
+
+            match winner {
+                Some(Side::From) => plan.changes.push(StatusChange {
+                    side: Side::To,
+                    id: to.id.clone(),
+                    status: from.status,
+                }),
+                Some(Side::To) => {
+                    updated.status = to.status;
+                    plan.changes.push(StatusChange {
+                        side: Side::From,
+                        id: from.id.clone(),
+                        status: to.status,
+                    });
+                }
+                None => {
+                    plan.conflicts.push(Conflict {
+                        from: from.clone(),
+                        to: to.clone(),
+                    });
+                    // Leave the old snapshot so the conflict is reported again
+                    updated = pair.clone();
+                }
+            }
+        }
+
+        plan.pairs.push(updated);
+    }
+
+    plan.create = from_tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::Pending)
+        .filter(|t| tag.map_or(true, |tag| t.has_tag(tag)))
+        .filter(|t| !paired_from.contains(&t.id.as_str()))
+        .cloned()
+        .collect();
+
+    plan
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub created: usize,
+    pub completed: usize,
+    pub reopened: usize,
+    pub orphaned: usize,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Sync `from` into `to`, reading and updating the pair store at `store_path`.
+pub async fn run(
+    manager: &BackendManager,
+    store_path: &Path,
+    from: BackendSource,
+    to: BackendSource,
+    tag: Option<&str>,
+    prefer: Option<Prefer>,
+) -> Result<Report> {
+    if from == to {
+        return Err(TasukiError::Parse(
+            "--from and --to must be different backends".to_string(),
+        ));
+    }
+    for source in [from, to] {
+        if manager.capabilities(source).is_none() {
+            return Err(TasukiError::Backend {
+                backend: source.name().to_string(),
+                message: "not enabled".to_string(),
+            });
+        }
+    }
+
+    let mut store = PairStore::load(store_path)?;
+    let all = manager.all_tasks(&TaskFilter::default()).await?;
+    let from_tasks: Vec<Task> = all.iter().filter(|t| t.source == from).cloned().collect();
+    let to_tasks: Vec<Task> = all.iter().filter(|t| t.source == to).cloned().collect();
+
+    let existing: Vec<Pair> = store.between(manager, from, to).cloned().collect();
+    let plan = plan(&existing, &from_tasks, &to_tasks, tag, prefer);
+
+    let mut report = Report {
+        orphaned: plan.orphaned.len(),
+        conflicts: plan.conflicts,
+        ..Default::default()
+    };
+
+    let mut pairs = plan.pairs;
+    let applied = apply(
+        manager,
+        &all,
+        &plan.changes,
+        &plan.create,
+        to,
+        &mut pairs,
+        &mut report,
+    )
+    .await;
+
+    // Saved even when a step failed, so the tasks mirrored before it are
+    // still paired and the next run doesn't create them again
+    store.pairs.retain(|p| !p.is_between(manager, from, to));
+    store.pairs.extend(pairs);
+    store.save(store_path)?;
+
+    applied.map(|()| report)
+}
+
+/// Apply status `changes` and mirror the `create` tasks into `to`, keeping
+/// `pairs` and `report` up to date with each step that succeeds.
+async fn apply(
+    manager: &BackendManager,
+    all: &[Task],
+    changes: &[StatusChange],
+    create: &[Task],
+    to: BackendSource,
+    pairs: &mut Vec<Pair>,
+    report: &mut Report,
+) -> Result<()> {
+    // Completing can move a task (Obsidian's `on_complete`) and shift the
+    // lines of others in its note, so after a move IDs are looked up again
+    let mut moved = false;
+    for change in changes {
+        let task = all.iter().find(|t| t.id == change.id);
+        let mut id = change.id.clone();
+        if moved {
+            if let Some(task) = task {
+                id = manager.relocate(task).await?.id;
+            }
+        }
+        let title = task.map(|t| t.title.as_str());
+        let new_id = match change.status {
+            TaskStatus::Done => {
+                let new_id = manager.complete_task(&id, title).await?;
+                report.completed += 1;
+                new_id
+            }
+            TaskStatus::Pending => {
+                let new_id = manager.uncomplete_task(&id, title).await?;
+                report.reopened += 1;
+                new_id
+            }
+        };
+        if new_id != change.id {
+            moved = true;
+            for pair in pairs.iter_mut() {
+                match change.side {
+                    Side::From if pair.from_id == change.id => pair.from_id = new_id.clone(),
+                    Side::To if pair.to_id == change.id => pair.to_id = new_id.clone(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    for task in create {
+        let created = manager
+            .create_task(&NewTask {
+                title: task.title.clone(),
+                priority: task.priority,
+                due: task.due,
+                tags: task.tags.clone(),
+                backend: to,
+            })
+            .await?;
+        pairs.push(Pair {
+            from_id: task.id.clone(),
+            to_id: created.id,
+            from_title: task.title.clone(),
+            to_title: created.title,
+            status: TaskStatus::Pending,
+        });
+        report.created += 1;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn task(id: &str, title: &str, status: TaskStatus, tags: &[&str]) -> Task {
+        Task::build(title)
+            .with_id(id)
+            .with_status(status)
+            .with_tags(tags)
+    }
+
+    fn pair(from_id: &str, to_id: &str, title: &str, status: TaskStatus) -> Pair {
+        Pair {
+            from_id: from_id.to_string(),
+            to_id: to_id.to_string(),
+            from_title: title.to_string(),
+            to_title: title.to_string(),
+            status,
+        }
+    }
+
+    use TaskStatus::{Done, Pending};
+
+    #[test]
+    fn test_store_round_trip_and_missing_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("data").join("sync.json");
+        assert_eq!(PairStore::load(&path).unwrap(), PairStore::default());
+
+        let store = PairStore {
+            pairs: vec![pair("obsidian:a.md:1", "local:1", "Call mom", Pending)],
+        };
+        store.save(&path).unwrap();
+        assert_eq!(PairStore::load(&path).unwrap(), store);
+    }
+
+    #[test]
+    fn test_store_corrupt_file_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("sync.json");
+        fs::write(&path, "not json").unwrap();
+        assert!(PairStore::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_store_filters_by_direction() {
+        let dir = TempDir::new().unwrap();
+        let manager = vault_and_local(dir.path(), &dir.path().join("todo.txt"), 0, "");
+        let store = PairStore {
+            pairs: vec![
+                pair("obsidian:a.md:1", "local:1", "A", Pending),
+                pair("local:2", "obsidian:b.md:1", "B", Pending),
+            ],
+        };
+        let forward: Vec<_> = store
+            .between(&manager, BackendSource::Obsidian, BackendSource::LocalFile)
+            .collect();
+        assert_eq!(forward.len(), 1);
+        assert_eq!(forward[0].from_title, "A");
+    }
+
+    #[test]
+    fn test_creates_only_pending_tagged_unpaired_tasks() {
+        let from = vec![
+            task("obsidian:a.md:1", "Tagged", Pending, &["phone"]),
+            task("obsidian:a.md:2", "Untagged", Pending, &[]),
+            task("obsidian:a.md:3", "Tagged done", Done, &["phone"]),
+            task("obsidian:a.md:4", "Already paired", Pending, &["phone"]),
+        ];
+        let to = vec![task("local:1", "Already paired", Pending, &["phone"])];
+        let pairs = vec![pair(
+            "obsidian:a.md:4",
+            "local:1",
+            "Already paired",
+            Pending,
+        )];
+
+        let plan = plan(&pairs, &from, &to, Some("phone"), None);
+        let titles: Vec<&str> = plan.create.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Tagged"]);
+        assert!(plan.changes.is_empty());
+        assert_eq!(plan.pairs.len(), 1);
+
+        let plan = super::plan(&pairs, &from, &to, None, None);
+        assert_eq!(plan.create.len(), 2);
+    }
+
+    #[test]
+    fn test_completion_propagates_both_ways() {
+        let pairs = vec![
+            pair("obsidian:a.md:1", "local:1", "Done in vault", Pending),
+            pair("obsidian:a.md:2", "local:2", "Done on phone", Pending),
+            pair("obsidian:a.md:3", "local:3", "Reopened on phone", Done),
+        ];
+        let from = vec![
+            task("obsidian:a.md:1", "Done in vault", Done, &[]),
+            task("obsidian:a.md:2", "Done on phone", Pending, &[]),
+            task("obsidian:a.md:3", "Reopened on phone", Done, &[]),
+        ];
+        let to = vec![
+            task("local:1", "Done in vault", Pending, &[]),
+            task("local:2", "Done on phone", Done, &[]),
+            task("local:3", "Reopened on phone", Pending, &[]),
+        ];
+
+        let plan = plan(&pairs, &from, &to, None, None);
+        assert_eq!(
+            plan.changes,
+            vec![
+                StatusChange {
+                    side: Side::To,
+                    id: "local:1".into(),
+                    status: Done
+                },
+                StatusChange {
+                    side: Side::From,
+                    id: "obsidian:a.md:2".into(),
+                    status: Done
+                },
+                StatusChange {
+                    side: Side::From,
+                    id: "obsidian:a.md:3".into(),
+                    status: Pending
+                },
+            ]
+        );
+        assert!(plan.conflicts.is_empty());
+        let statuses: Vec<TaskStatus> = plan.pairs.iter().map(|p| p.status).collect();
+        assert_eq!(statuses, vec![Done, Done, Pending]);
+    }
+
+    #[test]
+    fn test_both_sides_edited_is_a_conflict() {
+        let pairs = vec![pair("obsidian:a.md:1", "local:1", "Call mom", Pending)];
+        let from = vec![task("obsidian:a.md:1", "Call mom", Done, &[])];
+        let to = vec![task("local:1", "Call mom tonight", Pending, &[])];
+
+        let plan = super::plan(&pairs, &from, &to, None, None);
+        assert!(plan.changes.is_empty());
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].to.title, "Call mom tonight");
+        // Snapshot untouched so the next run reports it again
+        assert_eq!(plan.pairs, pairs);
+    }
+
+    #[test]
+    fn test_prefer_resolves_conflicts() {
+        let pairs = vec![pair("obsidian:a.md:1", "local:1", "Call mom", Pending)];
+        let from = vec![task("obsidian:a.md:1", "Call mom", Done, &[])];
+        let to = vec![task("local:1", "Call mom tonight", Pending, &[])];
+
+        let plan = super::plan(&pairs, &from, &to, None, Some(Prefer::From));
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(
+            plan.changes,
+            vec![StatusChange {
+                side: Side::To,
+                id: "local:1".into(),
+                status: Done
+            }]
+        );
+        assert_eq!(plan.pairs[0].to_title, "Call mom tonight");
+
+        let plan = super::plan(&pairs, &from, &to, None, Some(Prefer::To));
+        assert_eq!(
+            plan.changes,
+            vec![StatusChange {
+                side: Side::From,
+                id: "obsidian:a.md:1".into(),
+                status: Pending
+            }]
+        );
+        assert_eq!(plan.pairs[0].status, Pending);
+    }
+
+    #[test]
+    fn test_both_sides_agree_is_not_a_conflict() {
+        let pairs = vec![pair("obsidian:a.md:1", "local:1", "Call mom", Pending)];
+        let from = vec![task("obsidian:a.md:1", "Call mom", Done, &[])];
+        let to = vec![task("local:1", "Call mom", Done, &[])];
+
+        let plan = super::plan(&pairs, &from, &to, None, None);
+        assert!(plan.changes.is_empty());
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.pairs[0].status, Done);
+    }
+
+    #[test]
+    fn test_relocates_shifted_ids_by_title() {
+        let pairs = vec![pair("obsidian:a.md:1", "local:1", "Call mom", Pending)];
+        let from = vec![
+            task("obsidian:a.md:1", "New first line", Pending, &[]),
+            task("obsidian:a.md:2", "Call mom", Done, &[]),
+        ];
+        let to = vec![task("local:1", "Call mom", Pending, &[])];
+
+        let plan = super::plan(&pairs, &from, &to, None, None);
+        assert_eq!(plan.pairs[0].from_id, "obsidian:a.md:2");
+        assert_eq!(
+            plan.changes,
+            vec![StatusChange {
+                side: Side::To,
+                id: "local:1".into(),
+                status: Done
+            }]
+        );
+        let created: Vec<&str> = plan.create.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(created, vec!["New first line"]);
+    }
+
+    #[test]
+    fn test_missing_side_is_orphaned() {
+        let pairs = vec![
+            pair("obsidian:a.md:1", "local:1", "Deleted in vault", Pending),
+            pair("obsidian:a.md:2", "local:2", "Deleted on phone", Pending),
+        ];
+        let from = vec![task("obsidian:a.md:2", "Deleted on phone", Pending, &[])];
+        let to = vec![task("local:1", "Deleted in vault", Pending, &[])];
+
+        let plan = super::plan(&pairs, &from, &to, None, None);
+        assert_eq!(plan.orphaned.len(), 2);
+        // Only the pair whose source still exists is kept, and it isn't
+        // mirrored again
+        assert_eq!(plan.pairs.len(), 1);
+        assert_eq!(plan.pairs[0].from_title, "Deleted on phone");
+        assert!(plan.create.is_empty());
+    }
+
+    /// A local backend whose creates fail once `limit` tasks were added.
+    struct LimitedCreates {
+        inner: crate::backends::localfile::LocalFileBackend,
+        limit: usize,
+        created: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::backends::TaskBackend for LimitedCreates {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn source(&self) -> BackendSource {
+            self.inner.source()
+        }
+
+        fn capabilities(&self) -> crate::backends::BackendCapabilities {
+            self.inner.capabilities()
+        }
+
+        async fn fetch_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
+            self.inner.fetch_tasks(filter).await
+        }
+
+        async fn create_task(&self, task: &NewTask) -> Result<Task> {
+            use std::sync::atomic::Ordering;
+            if self.created.fetch_add(1, Ordering::SeqCst) >= self.limit {
+                return Err(TasukiError::Backend {
+                    backend: "local".to_string(),
+                    message: "disk full".to_string(),
+                });
+            }
+            self.inner.create_task(task).await
+        }
+
+        async fn update_task(
+            &self,
+            id: &crate::model::TaskId,
+            update: &crate::model::TaskUpdate,
+        ) -> Result<Task> {
+            self.inner.update_task(id, update).await
+        }
+
+        async fn complete_task(
+            &self,
+            id: &crate::model::TaskId,
+            title: Option<&str>,
+        ) -> Result<crate::model::TaskId> {
+            self.inner.complete_task(id, title).await
+        }
+
+        async fn uncomplete_task(
+            &self,
+            id: &crate::model::TaskId,
+            title: Option<&str>,
+        ) -> Result<crate::model::TaskId> {
+            self.inner.uncomplete_task(id, title).await
+        }
+
+        async fn delete_task(&self, id: &crate::model::TaskId) -> Result<()> {
+            self.inner.delete_task(id).await
+        }
+    }
+
+    /// A manager over an Obsidian vault at `vault` and a local todo.txt at
+    /// `todo` that takes at most `limit` new tasks.
+    /// `options` are extra `[obsidian]` settings, as TOML.
+    fn vault_and_local(vault: &Path, todo: &Path, limit: usize, options: &str) -> BackendManager {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+        use crate::backends::obsidian::{ObsidianBackend, ObsidianConfig};
+
+        let table: toml::Table = format!("vault_path = {:?}\n{}", vault.to_string_lossy(), options)
+            .parse()
+            .unwrap();
+        let obsidian = ObsidianBackend::new(ObsidianConfig::from_table(&table).unwrap());
+        let local = LimitedCreates {
+            inner: LocalFileBackend::new(LocalFileConfig {
+                path: todo.to_path_buf(),
+            }),
+            limit,
+            created: Default::default(),
+        };
+        BackendManager::new(vec![Box::new(obsidian), Box::new(local)])
+    }
+
+    #[tokio::test]
+    async fn test_failed_create_keeps_pairs_made_before_it() {
+        let dir = TempDir::new().unwrap();
+        let vault = dir.path().join("vault");
+        fs::create_dir(&vault).unwrap();
+        fs::write(vault.join("Inbox.md"), "- [ ] First\n- [ ] Second\n").unwrap();
+        let todo = dir.path().join("todo.txt");
+        let store_path = dir.path().join("sync.json");
+        let (from, to) = (BackendSource::Obsidian, BackendSource::LocalFile);
+
+        let manager = vault_and_local(&vault, &todo, 1, "");
+        let err = run(&manager, &store_path, from, to, None, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("disk full"));
+        let store = PairStore::load(&store_path).unwrap();
+        assert_eq!(store.pairs.len(), 1);
+        assert_eq!(store.pairs[0].from_title, "First");
+
+        let manager = vault_and_local(&vault, &todo, usize::MAX, "");
+        let report = run(&manager, &store_path, from, to, None, None)
+            .await
+            .unwrap();
+        assert_eq!(report.created, 1);
+        let content = fs::read_to_string(&todo).unwrap();
+        assert_eq!(content.lines().count(), 2, "{}", content);
+        assert_eq!(content.matches("First").count(), 1);
+        assert_eq!(PairStore::load(&store_path).unwrap().pairs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_completions_follow_tasks_moved_by_earlier_ones() {
+        let dir = TempDir::new().unwrap();
+        let vault = dir.path().join("vault");
+        fs::create_dir(&vault).unwrap();
+        let note = vault.join("Inbox.md");
+        fs::write(&note, "- [ ] First\n- [ ] Second\n- [ ] Third\n").unwrap();
+        let todo = dir.path().join("todo.txt");
+        let store_path = dir.path().join("sync.json");
+        let (from, to) = (BackendSource::Obsidian, BackendSource::LocalFile);
+        let manager = vault_and_local(
+            &vault,
+            &todo,
+            usize::MAX,
+            "on_complete = \"move_to_done_section\"",
+        );
+
+        run(&manager, &store_path, from, to, None, None)
+            .await
+            .unwrap();
+        // Each completion moves a task to the end, shifting the rest up
+        for id in ["local:1", "local:3"] {
+            manager.complete_task(&id.to_string(), None).await.unwrap();
+        }
+        let report = run(&manager, &store_path, from, to, None, None)
+            .await
+            .unwrap();
+        assert_eq!(report.completed, 2);
+
+        let content = fs::read_to_string(&note).unwrap();
+        assert!(content.starts_with("- [ ] Second\n"), "{}", content);
+        assert!(content.contains("- [x] First"), "{}", content);
+        assert!(content.contains("- [x] Third"), "{}", content);
+
+        // The pairs point at the moved lines: nothing left to do
+        let report = run(&manager, &store_path, from, to, None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            (report.completed, report.reopened, report.created),
+            (0, 0, 0)
+        );
+        let tasks = manager.all_tasks(&TaskFilter::default()).await.unwrap();
+        for pair in PairStore::load(&store_path).unwrap().pairs {
+            let task = tasks.iter().find(|t| t.id == pair.from_id).unwrap();
+            assert_eq!(task.title, pair.from_title);
+        }
+    }
+
+    #[test]
+    fn test_prefer_parse() {
+        assert_eq!("from".parse::<Prefer>().unwrap(), Prefer::From);
+        assert_eq!("to".parse::<Prefer>().unwrap(), Prefer::To);
+        assert!("both".parse::<Prefer>().is_err());
+    }
+}
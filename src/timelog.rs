@@ -0,0 +1,294 @@
+//! Time spent on tasks: the TUI's start/stop timer and `tasuki timelog`.
+//!
+//! The running timer lives in `<state dir>/timer.json`, so it keeps running
+//! across TUI restarts. Stopping it appends one line to
+//! `<data dir>/timelog.jsonl`. Time counts toward the day it started.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::model::Task;
+
+/// The one timer that may be running.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timer {
+    pub task_id: String,
+    /// The task's title when the timer started, for when the task is gone
+    pub title: String,
+    pub start: NaiveDateTime,
+}
+
+/// A stopped timer, as logged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub task_id: String,
+    pub title: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl Timer {
+    pub fn new(task: &Task, start: NaiveDateTime) -> Self {
+        Self {
+            task_id: task.id.clone(),
+            title: task.title.clone(),
+            start,
+        }
+    }
+
+    pub fn path(config: &Config) -> Option<PathBuf> {
+        config.state_dir().map(|dir| dir.join("timer.json"))
+    }
+
+    /// The running timer, or `None` if there is none (or it can't be read).
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content)
+            .map_err(|e| tracing::warn!("Ignoring corrupt timer {}: {}", path.display(), e))
+            .ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Forget the running timer, if any.
+    pub fn clear(path: &Path) -> Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn elapsed(&self, now: NaiveDateTime) -> Duration {
+        (now - self.start).max(Duration::zero())
+    }
+
+    pub fn stop(self, end: NaiveDateTime) -> Entry {
+        Entry {
+            task_id: self.task_id,
+            title: self.title,
+            start: self.start,
+            end: end.max(self.start),
+        }
+    }
+}
+
+impl Entry {
+    pub fn duration(&self) -> Duration {
+        (self.end - self.start).max(Duration::zero())
+    }
+
+    /// Whether `query` is this entry's task ID, or part of its title.
+    pub fn matches(&self, query: &str) -> bool {
+        self.task_id == query || self.title.to_lowercase().contains(&query.to_lowercase())
+    }
+}
+
+/// Start timing `task`, or stop if it's the task being timed. A timer on
+/// another task is stopped first. Returns the stopped timer's entry, if
+/// any, and the timer now running.
+pub fn toggle(
+    running: Option<Timer>,
+    task: &Task,
+    now: NaiveDateTime,
+) -> (Option<Entry>, Option<Timer>) {
+    match running {
+        Some(timer) if timer.task_id == task.id => (Some(timer.stop(now)), None),
+        Some(timer) => (Some(timer.stop(now)), Some(Timer::new(task, now))),
+        None => (None, Some(Timer::new(task, now))),
+    }
+}
+
+pub fn log_path(config: &Config) -> Option<PathBuf> {
+    config.data_dir().map(|dir| dir.join("timelog.jsonl"))
+}
+
+/// Add `entry` to the end of the log at `path`.
+pub fn append(path: &Path, entry: &Entry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Every entry in the log at `path`, oldest first. Lines that don't parse
+/// are skipped.
+pub fn read(path: &Path) -> Vec<Entry> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| tracing::warn!("Skipping {}:{}: {}", path.display(), i + 1, e))
+                .ok()
+        })
+        .collect()
+}
+
+/// Time in `entries` started on or after `since`.
+pub fn tracked_since(entries: &[Entry], since: NaiveDate) -> Duration {
+    entries
+        .iter()
+        .filter(|e| e.start.date() >= since)
+        .map(Entry::duration)
+        .fold(Duration::zero(), |sum, d| sum + d)
+}
+
+/// Time per task title on each day, in order of day and then first seen.
+pub fn by_day(entries: &[Entry]) -> BTreeMap<NaiveDate, Vec<(String, Duration)>> {
+    let mut days: BTreeMap<NaiveDate, Vec<(String, Duration)>> = BTreeMap::new();
+    for entry in entries {
+        let tasks = days.entry(entry.start.date()).or_default();
+        match tasks.iter_mut().find(|(title, _)| *title == entry.title) {
+            Some((_, total)) => *total += entry.duration(),
+            None => tasks.push((entry.title.clone(), entry.duration())),
+        }
+    }
+    days
+}
+
+/// "1h 05m", "25m"; under a minute is "0m".
+pub fn format_duration(d: Duration) -> String {
+    let minutes = d.num_minutes().max(0);
+    match minutes {
+        m if m < 60 => format!("{}m", m),
+        m => format!("{}h {:02}m", m / 60, m % 60),
+    }
+}
+
+/// `tasuki timelog`: a header per day, the time per task under it, then
+/// the total.
+pub fn render(entries: &[Entry], config: &Config) -> String {
+    let mut text = String::new();
+    for (day, tasks) in by_day(entries) {
+        text.push_str(&format!("{}\n", crate::report::day_label(day, config)));
+        for (title, total) in tasks {
+            text.push_str(&format!("  {:>7}  {}\n", format_duration(total), title));
+        }
+        text.push('\n');
+    }
+    let total = entries
+        .iter()
+        .map(Entry::duration)
+        .fold(Duration::zero(), |sum, d| sum + d);
+    text.push_str(&format!("total {}\n", format_duration(total)));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn task(id: &str, title: &str) -> Task {
+        Task::build(title).with_id(id)
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_toggle_keeps_one_timer() {
+        let report = task("local:1", "Write report");
+        let call = task("local:2", "Call Bob");
+
+        let (stopped, timer) = toggle(None, &report, at("2026-10-12 09:00"));
+        assert!(stopped.is_none());
+        assert_eq!(timer.as_ref().unwrap().task_id, "local:1");
+
+        // Starting another task stops the first
+        let (stopped, timer) = toggle(timer, &call, at("2026-10-12 09:25"));
+        let stopped = stopped.unwrap();
+        assert_eq!(stopped.task_id, "local:1");
+        assert_eq!(stopped.duration(), Duration::minutes(25));
+        assert_eq!(timer.as_ref().unwrap().task_id, "local:2");
+
+        // And the same task again stops it
+        let (stopped, timer) = toggle(timer, &call, at("2026-10-12 10:30"));
+        assert_eq!(stopped.unwrap().duration(), Duration::minutes(65));
+        assert!(timer.is_none());
+    }
+
+    #[test]
+    fn test_timer_and_log_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let timer_path = dir.path().join("state").join("timer.json");
+        let log = dir.path().join("data").join("timelog.jsonl");
+
+        let timer = Timer::new(&task("local:1", "Write report"), at("2026-10-12 09:00"));
+        timer.save(&timer_path).unwrap();
+        assert_eq!(Timer::load(&timer_path), Some(timer.clone()));
+        Timer::clear(&timer_path).unwrap();
+        assert_eq!(Timer::load(&timer_path), None);
+        Timer::clear(&timer_path).unwrap();
+
+        let first = timer.stop(at("2026-10-12 09:30"));
+        append(&log, &first).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&log)
+            .unwrap()
+            .write_all(b"{ not json\n")
+            .unwrap();
+        let second = Timer::new(&task("local:1", "Write report"), at("2026-10-13 14:00"))
+            .stop(at("2026-10-13 15:15"));
+        append(&log, &second).unwrap();
+
+        assert_eq!(read(&log), vec![first, second]);
+        assert!(read(&dir.path().join("missing.jsonl")).is_empty());
+    }
+
+    #[test]
+    fn test_totals_per_day() {
+        let entry = |title: &str, start: &str, end: &str| Entry {
+            task_id: String::new(),
+            title: title.to_string(),
+            start: at(start),
+            end: at(end),
+        };
+        let entries = vec![
+            entry("Write report", "2026-10-12 09:00", "2026-10-12 09:30"),
+            entry("Call Bob", "2026-10-12 10:00", "2026-10-12 10:10"),
+            entry("Write report", "2026-10-12 11:00", "2026-10-12 11:35"),
+            entry("Write report", "2026-10-13 23:30", "2026-10-14 00:45"),
+        ];
+
+        let days = by_day(&entries);
+        assert_eq!(
+            days[&NaiveDate::from_ymd_opt(2026, 10, 12).unwrap()],
+            vec![
+                ("Write report".to_string(), Duration::minutes(65)),
+                ("Call Bob".to_string(), Duration::minutes(10)),
+            ]
+        );
+        // Counted on the day it started
+        assert_eq!(days.len(), 2);
+
+        let since = NaiveDate::from_ymd_opt(2026, 10, 13).unwrap();
+        assert_eq!(tracked_since(&entries, since), Duration::minutes(75));
+        assert_eq!(format_duration(Duration::minutes(150)), "2h 30m");
+        assert_eq!(format_duration(Duration::seconds(59)), "0m");
+
+        let text = render(&entries, &Config::default());
+        assert!(text.contains("   1h 05m  Write report\n"));
+        assert!(text.ends_with("total 2h 30m\n"));
+    }
+}
@@ -1,15 +1,33 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use ratatui::widgets::ListState;
 
-use crate::backends::BackendManager;
+use crate::backends::{BackendManager, Created, FetchReport, SortOrder};
 use crate::config::Config;
-use crate::model::{Task, TaskFilter, TaskStatus};
+use crate::model::{BackendSource, Priority, Task, TaskFilter, TaskStatus};
+use crate::output::GroupBy;
+use crate::timelog::{self, Timer};
+use crate::tui::settings::{SettingKind, SETTINGS};
+use crate::tui::state::SessionState;
+use crate::tui::status_bar::StatusBarTemplate;
+
+/// How long after the last quick action the reconciling refresh runs.
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How long a just-completed row flashes in the success color.
+const FLASH_DURATION: Duration = Duration::from_millis(300);
+
+/// How long after submitting input an Enter is ignored, so a doubled one
+/// doesn't toggle the task that's selected once the input closes.
+const SUBMIT_GRACE: Duration = Duration::from_millis(300);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum View {
     All,
     Today,
+    /// Overdue and due within a week, like `tasuki list week`
+    Week,
     Upcoming,
     Notes,
     Done,
@@ -20,6 +38,7 @@ impl View {
         match self {
             View::All => "All",
             View::Today => "Today",
+            View::Week => "Week",
             View::Upcoming => "Upcoming",
             View::Notes => "Notes",
             View::Done => "Done",
@@ -29,7 +48,8 @@ impl View {
     pub fn next(&self) -> View {
         match self {
             View::All => View::Today,
-            View::Today => View::Upcoming,
+            View::Today => View::Week,
+            View::Week => View::Upcoming,
             View::Upcoming => View::Notes,
             View::Notes => View::Done,
             View::Done => View::All,
@@ -40,14 +60,15 @@ impl View {
         match self {
             View::All => View::Done,
             View::Today => View::All,
-            View::Upcoming => View::Today,
+            View::Week => View::Today,
+            View::Upcoming => View::Week,
             View::Notes => View::Upcoming,
             View::Done => View::Notes,
         }
     }
 
-    pub fn to_filter(&self) -> TaskFilter {
-        let today = chrono::Local::now().date_naive();
+    pub fn to_filter(self) -> TaskFilter {
+        let today = crate::clock::today();
         match self {
             View::All => TaskFilter {
                 status: Some(TaskStatus::Pending),
@@ -58,6 +79,12 @@ impl View {
                 due_before: Some(today),
                 ..Default::default()
             },
+            View::Week => TaskFilter {
+                status: Some(TaskStatus::Pending),
+                due_before: Some(today + chrono::Duration::days(7)),
+                has_due: Some(true),
+                ..Default::default()
+            },
             View::Upcoming => TaskFilter {
                 status: Some(TaskStatus::Pending),
                 has_due: Some(true),
@@ -75,9 +102,22 @@ impl View {
         }
     }
 
+    /// Name used in config and the session state file.
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            View::All => "all",
+            View::Today => "today",
+            View::Week => "week",
+            View::Upcoming => "upcoming",
+            View::Notes => "notes",
+            View::Done => "done",
+        }
+    }
+
     pub fn from_config(s: &str) -> View {
         match s {
             "today" => View::Today,
+            "week" => View::Week,
             "upcoming" => View::Upcoming,
             "all" => View::All,
             "notes" => View::Notes,
@@ -93,6 +133,8 @@ pub enum AppMode {
     Input,
     Help,
     Confirm,
+    Alert,
+    Settings,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -100,11 +142,14 @@ pub enum InputMode {
     QuickAdd,
     Search,
     EditTask(String), // Stores the task ID being edited
+    Reschedule(String), // Stores the ID of the task getting a due date
+    EditSetting(String), // Stores the dotted config key
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ConfirmAction {
     DeleteTask(String),
+    CompleteTask(String),
 }
 
 #[derive(Debug, Clone)]
@@ -115,6 +160,40 @@ pub struct TaskGroup {
     pub collapsed: bool,
 }
 
+impl TaskGroup {
+    /// Stable key for remembering collapsed state; labels like "Today" shift.
+    pub fn key(&self) -> String {
+        match self.date {
+            Some(d) => d.to_string(),
+            None => "notes".to_string(),
+        }
+    }
+}
+
+/// What the board view's columns mean (`general.board_columns`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardLayout {
+    /// Overdue, Today, Upcoming, No date
+    Due,
+    /// High, Medium, Low (and none)
+    Priority,
+}
+
+impl BoardLayout {
+    pub fn from_config(name: &str) -> Self {
+        match name {
+            "priority" => BoardLayout::Priority,
+            _ => BoardLayout::Due,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BoardColumn {
+    pub label: &'static str,
+    pub tasks: Vec<Task>,
+}
+
 pub struct App {
     pub mode: AppMode,
     pub current_view: View,
@@ -133,6 +212,50 @@ pub struct App {
     pub should_quit: bool,
     pub pending_confirm: Option<ConfirmAction>,
     pub confirm_message: String,
+    /// Message shown in the `Alert` overlay until any key dismisses it
+    pub alert_message: String,
+    /// Collapsed state restored from the last session, by `TaskGroup::key`
+    pub saved_collapsed: HashMap<String, bool>,
+    /// When a coalesced refresh should run; see `schedule_refresh`
+    pub refresh_at: Option<Instant>,
+    /// Tasks completed moments ago, flashed until the instant stored here
+    pub recently_completed: HashMap<String, Instant>,
+    /// Focus mode: show only the most urgent task
+    pub focus: bool,
+    /// How many tasks "next" has skipped past in focus mode
+    pub focus_skip: usize,
+    /// When `refresh_tasks` last succeeded
+    pub last_refresh: Option<chrono::DateTime<chrono::Local>>,
+    /// Per-backend timing and errors from the last refresh attempt
+    pub fetch_reports: Vec<FetchReport>,
+    /// Tasks across all backends ignoring the view and search, counted
+    /// when the last refresh came back empty
+    pub unfiltered_count: Option<usize>,
+    /// Two-pane layout with a detail pane for the selected task
+    pub split: bool,
+    /// Detail lines for the selected task, by task ID; see `update_details`
+    pub details: Option<(String, Vec<String>)>,
+    /// Board view: pending tasks in columns by due date or priority
+    pub board: bool,
+    /// `tasks` split into the board's columns while it's open, rebuilt
+    /// with `task_groups`
+    pub board_columns: Vec<BoardColumn>,
+    /// Column with keyboard focus in the board view
+    pub board_column: usize,
+    /// Selection and scroll offset per board column (at most four)
+    pub board_states: [ListState; 4],
+    /// Highlighted row in the settings popup, indexing `settings::SETTINGS`
+    pub settings_selected: usize,
+    /// The task being timed, as saved in `Timer::path`
+    pub timer: Option<Timer>,
+    /// `general.status_bar`, parsed when the config is loaded
+    pub status_bar: Option<StatusBarTemplate>,
+    /// How the task list is split into sections
+    pub group_by: GroupBy,
+    /// When input was last submitted; see `just_submitted`
+    pub submitted_at: Option<Instant>,
+    /// List groups, and tasks within them, in the opposite order
+    pub reverse: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -150,10 +273,21 @@ pub enum VisibleItem {
     None,
 }
 
+/// Something to reselect once the list is rebuilt, by identity rather than
+/// position so a task vanishing doesn't throw the cursor into another group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SelectionAnchor {
+    Task(String),
+    /// A group header, by `TaskGroup::key`
+    Group(String),
+}
+
 impl App {
     pub fn new(backend_manager: BackendManager, config: Config) -> Self {
         let current_view = View::from_config(&config.general.default_view);
+        let split = config.general.layout == "split";
         let task_filter = current_view.to_filter();
+        let status_bar = StatusBarTemplate::from_config(&config);
         Self {
             mode: AppMode::Normal,
             current_view,
@@ -172,60 +306,178 @@ impl App {
             should_quit: false,
             pending_confirm: None,
             confirm_message: String::new(),
+            alert_message: String::new(),
+            saved_collapsed: HashMap::new(),
+            refresh_at: None,
+            recently_completed: HashMap::new(),
+            focus: false,
+            focus_skip: 0,
+            last_refresh: None,
+            fetch_reports: Vec::new(),
+            unfiltered_count: None,
+            split,
+            details: None,
+            board: false,
+            board_columns: Vec::new(),
+            board_column: 0,
+            board_states: Default::default(),
+            settings_selected: 0,
+            timer: None,
+            status_bar,
+            group_by: GroupBy::Due,
+            submitted_at: None,
+            reverse: false,
         }
     }
 
-    pub fn group_tasks(&mut self) {
-        use chrono::Local;
-
-        let today = Local::now().date_naive();
-        let mut groups: Vec<TaskGroup> = Vec::new();
-        let mut group_map: HashMap<Option<chrono::NaiveDate>, Vec<Task>> = HashMap::new();
-
-        for task in &self.tasks {
-            group_map.entry(task.due).or_default().push(task.clone());
+    /// Apply a saved session. Call before the first `refresh_tasks`.
+    pub fn restore_session(&mut self, state: SessionState) {
+        if let Some(ref view) = state.view {
+            self.current_view = View::from_config(view);
+            self.task_filter = self.current_view.to_filter();
         }
+        self.saved_collapsed = state.collapsed;
+    }
 
-        let mut dates: Vec<_> = group_map.keys().copied().collect();
-        dates.sort_by(|a, b| match (a, b) {
-            (Some(da), Some(db)) => da.cmp(db),
-            (Some(_), None) => std::cmp::Ordering::Less,
-            (None, Some(_)) => std::cmp::Ordering::Greater,
-            (None, None) => std::cmp::Ordering::Equal,
-        });
+    pub fn session_state(&self) -> SessionState {
+        let mut collapsed = self.saved_collapsed.clone();
+        for group in &self.task_groups {
+            collapsed.insert(group.key(), group.collapsed);
+        }
+        collapsed.retain(|_, c| *c);
 
-        for date in dates {
-            let tasks = group_map.remove(&date).unwrap();
-            let label = match date {
-                Some(d) if d < today => format!("Overdue - {}", d),
-                Some(d) if d == today => "Today".to_string(),
-                Some(d) if d == today + chrono::Duration::days(1) => "Tomorrow".to_string(),
-                Some(d) => format!("{}", d.format("%A %Y-%m-%d")),
-                None => "Notes".to_string(),
-            };
+        SessionState {
+            view: Some(self.current_view.config_name().to_string()),
+            collapsed,
+            selected_task: self.get_selected_visible_task().map(|t| t.id),
+        }
+    }
 
-            let collapsed = self
-                .task_groups
-                .iter()
-                .find(|g| g.date == date)
-                .map(|g| g.collapsed)
-                .unwrap_or(false);
+    /// Move the selection to the task with this ID if it's visible.
+    pub fn select_task_id(&mut self, id: &str) {
+        if let Some(index) = self.find_anchor(&SelectionAnchor::Task(id.to_string())) {
+            self.selected_task = index;
+            self.list_state.select(Some(index));
+            self.update_selected_group();
+        }
+    }
 
-            groups.push(TaskGroup {
-                label,
-                date,
-                tasks,
-                collapsed,
-            });
+    pub fn group_tasks(&mut self) {
+        let today = crate::clock::today();
+        let mut groups =
+            crate::output::group_tasks(&self.tasks, self.group_by, &self.config, today);
+        if self.reverse {
+            groups.reverse();
+            for group in &mut groups {
+                group.tasks.reverse();
+            }
         }
+        let groups = groups
+            .into_iter()
+            .map(|group| {
+                let key = group.date.map_or_else(|| "notes".to_string(), |d| d.to_string());
+                let collapsed = self
+                    .task_groups
+                    .iter()
+                    .find(|g| g.date == group.date)
+                    .map(|g| g.collapsed)
+                    .or_else(|| self.saved_collapsed.get(&key).copied())
+                    .unwrap_or(false);
+
+                TaskGroup {
+                    label: group.label,
+                    date: group.date,
+                    tasks: group.tasks,
+                    collapsed,
+                }
+            })
+            .collect();
 
         self.task_groups = groups;
+        self.board_columns = if self.board {
+            self.build_board_columns()
+        } else {
+            Vec::new()
+        };
 
         if !self.task_groups.is_empty() && self.selected_group >= self.task_groups.len() {
             self.selected_group = self.task_groups.len() - 1;
         }
     }
 
+    /// Flip the order of the list, keeping the selected task selected.
+    pub fn toggle_reverse(&mut self) {
+        self.reverse = !self.reverse;
+        let tasks = std::mem::take(&mut self.tasks);
+        self.set_tasks(tasks);
+        let message = if self.reverse { "Sort reversed" } else { "Sort order restored" };
+        self.set_status(message, StatusLevel::Info);
+    }
+
+    /// Replace the task list, keeping the selection on the same task. If
+    /// that task is gone (completed under a pending-only view, say), the
+    /// next task in its group is selected, then the previous one, then the
+    /// group header, then the header of the group that followed it.
+    pub fn set_tasks(&mut self, tasks: Vec<Task>) {
+        let anchors = self.selection_anchors();
+        self.tasks = tasks;
+        self.group_tasks();
+
+        for anchor in &anchors {
+            if let Some(index) = self.find_anchor(anchor) {
+                self.selected_task = index;
+                self.list_state.select(Some(index));
+                self.update_selected_group();
+                return;
+            }
+        }
+
+        let visible = self.visible_count();
+        if self.selected_task >= visible && visible > 0 {
+            self.selected_task = visible - 1;
+            self.list_state.select(Some(self.selected_task));
+        }
+        self.update_selected_group();
+    }
+
+    /// Candidates for `set_tasks` to reselect, most preferred first.
+    fn selection_anchors(&self) -> Vec<SelectionAnchor> {
+        let mut anchors = Vec::new();
+        let group_idx = match self.get_visible_item(self.selected_task) {
+            VisibleItem::Task(group_idx, task) => {
+                let group = &self.task_groups[group_idx];
+                if let Some(pos) = group.tasks.iter().position(|t| t.id == task.id) {
+                    let next = group.tasks.iter().skip(pos + 1);
+                    let previous = group.tasks[..pos].iter().rev();
+                    anchors.push(SelectionAnchor::Task(task.id));
+                    anchors.extend(next.chain(previous).map(|t| SelectionAnchor::Task(t.id.clone())));
+                }
+                group_idx
+            }
+            VisibleItem::Group(group_idx) => group_idx,
+            VisibleItem::None => return anchors,
+        };
+        anchors.extend(
+            self.task_groups[group_idx..]
+                .iter()
+                .map(|g| SelectionAnchor::Group(g.key())),
+        );
+        anchors
+    }
+
+    fn find_anchor(&self, anchor: &SelectionAnchor) -> Option<usize> {
+        match anchor {
+            SelectionAnchor::Task(id) => (0..self.visible_count()).find(|&i| {
+                matches!(self.get_visible_item(i), VisibleItem::Task(_, ref t) if t.id == *id)
+            }),
+            SelectionAnchor::Group(key) => self
+                .task_groups
+                .iter()
+                .position(|g| g.key() == *key)
+                .map(|idx| self.find_group_start(idx)),
+        }
+    }
+
     pub fn visible_count(&self) -> usize {
         let mut count = self.task_groups.len();
         for group in &self.task_groups {
@@ -272,6 +524,9 @@ impl App {
     }
 
     pub fn get_selected_visible_task(&self) -> Option<Task> {
+        if self.board {
+            return self.board_selected_task();
+        }
         match self.get_visible_item(self.selected_task) {
             VisibleItem::Task(_, task) => Some(task),
             _ => None,
@@ -347,21 +602,40 @@ impl App {
         self.apply_view_filter().await;
     }
 
+    /// Switch to `view`, keeping any search.
+    pub async fn show_view(&mut self, view: View) {
+        self.current_view = view;
+        self.apply_view_filter().await;
+    }
+
+    /// Back to all pending tasks, without a search or tag filter.
+    pub async fn reset_view(&mut self) {
+        self.task_filter.search = None;
+        self.task_filter.tag = None;
+        self.show_view(View::All).await;
+    }
+
     async fn apply_view_filter(&mut self) {
         let search = self.task_filter.search.take();
+        let tag = self.task_filter.tag.take();
         self.task_filter = self.current_view.to_filter();
         self.task_filter.search = search;
+        self.task_filter.tag = tag;
+        self.refresh_tasks().await;
+        // A new view starts at the top rather than near the old selection
         self.selected_task = 0;
         self.selected_group = 0;
         self.list_state = ListState::default().with_selected(Some(0));
-        self.refresh_tasks().await;
     }
 
     pub async fn toggle_pin(&mut self) {
         if let Some(task) = self.get_selected_visible_task() {
+            if !self.check_updatable(&task) {
+                return;
+            }
             let mut new_tags = task.tags.clone();
-            if new_tags.iter().any(|t| t == "pin") {
-                new_tags.retain(|t| t != "pin");
+            if new_tags.iter().any(|t| t.eq_ignore_ascii_case("pin")) {
+                new_tags.retain(|t| !t.eq_ignore_ascii_case("pin"));
             } else {
                 new_tags.push("pin".to_string());
             }
@@ -373,7 +647,7 @@ impl App {
 
             match self.backend_manager.update_task(&task.id, &update).await {
                 Ok(t) => {
-                    let pinned = t.tags.iter().any(|tag| tag == "pin");
+                    let pinned = t.tags.iter().any(|tag| tag.eq_ignore_ascii_case("pin"));
                     let msg = if pinned { "Pinned" } else { "Unpinned" };
                     self.set_status(format!("{}: {}", msg, t.title), StatusLevel::Success);
                 }
@@ -389,36 +663,158 @@ impl App {
         self.status_message = Some((message.into(), level));
     }
 
-    pub async fn reload_config(&mut self) {
-        match Config::load(None) {
-            Ok(new_config) => {
-                match crate::backends::BackendManager::from_config(&new_config) {
-                    Ok(new_manager) => {
-                        self.config = new_config;
-                        self.backend_manager = new_manager;
-                        self.refresh_tasks().await;
-                        self.set_status("Config reloaded", StatusLevel::Success);
-                    }
-                    Err(e) => {
-                        self.set_status(format!("Backend error: {}", e), StatusLevel::Error);
-                    }
+    /// Swap in the config from disk. On any failure, including a config with
+    /// no enabled backends, the previous config and backends stay active.
+    pub fn open_settings(&mut self) {
+        self.settings_selected = 0;
+        self.mode = AppMode::Settings;
+    }
+
+    pub fn close_settings(&mut self) {
+        self.mode = AppMode::Normal;
+    }
+
+    pub fn settings_move(&mut self, delta: isize) {
+        self.settings_selected = self
+            .settings_selected
+            .saturating_add_signed(delta)
+            .min(SETTINGS.len() - 1);
+    }
+
+    /// Enter on a setting: cycle choices and toggles right away, or open the
+    /// input bar for text values.
+    pub async fn activate_setting(&mut self) {
+        let setting = SETTINGS[self.settings_selected];
+        match setting.kind {
+            SettingKind::Text => {
+                self.input_buffer = setting.current(&self.config).unwrap_or_default();
+                self.cursor_position = self.input_buffer.len();
+                self.input_mode = Some(InputMode::EditSetting(setting.key.to_string()));
+                self.mode = AppMode::Input;
+            }
+            SettingKind::Choice(_) | SettingKind::Toggle => {
+                let value = setting.next_value(&self.config);
+                self.write_setting(setting.key, value).await;
+            }
+        }
+    }
+
+    /// Save one option to config.toml and apply it through `reload_config`,
+    /// which rejects (and reports) a value that doesn't validate.
+    async fn write_setting(&mut self, key: &str, value: Option<toml_edit::Value>) {
+        let Some(path) = self.config.config_path.clone() else {
+            self.set_status("No config file to write to", StatusLevel::Error);
+            return;
+        };
+
+        let previous = std::fs::read_to_string(&path).ok();
+        if let Err(e) = Config::set_option(&path, key, value) {
+            self.show_alert(format!("Failed to save {}: {}", key, e));
+            return;
+        }
+
+        self.reload_config().await;
+        if self.mode == AppMode::Alert {
+            // Don't leave a file behind that the next start would choke on
+            match previous {
+                Some(content) => {
+                    let _ = std::fs::write(&path, content);
+                }
+                None => {
+                    let _ = std::fs::remove_file(&path);
                 }
             }
+        } else {
+            self.set_status(format!("Saved {}", key), StatusLevel::Success);
+        }
+    }
+
+    /// Show `message` in a modal that stays up until a key is pressed.
+    pub fn show_alert(&mut self, message: impl Into<String>) {
+        self.alert_message = message.into();
+        self.mode = AppMode::Alert;
+    }
+
+    pub fn dismiss_alert(&mut self) {
+        self.alert_message.clear();
+        self.mode = AppMode::Normal;
+    }
+
+    pub async fn reload_config(&mut self) {
+        // An empty file is a save caught half-way, not a config to load
+        if self.config.config_path.as_deref().is_some_and(crate::watch::is_mid_save) {
+            self.set_status("Config file is empty (keeping previous config)", StatusLevel::Warning);
+            return;
+        }
+        let new_config = match self.config.reload() {
+            Ok(c) => c,
+            Err(e) => {
+                self.show_alert(format!("{}\n\nKeeping the previous config.", e));
+                return;
+            }
+        };
+
+        let errors: Vec<String> = new_config
+            .validate()
+            .into_iter()
+            .filter(|d| d.severity == crate::config::Severity::Error)
+            .map(|d| d.to_string())
+            .collect();
+        if !errors.is_empty() {
+            self.show_alert(format!(
+                "{}\n\nKeeping the previous config.",
+                errors.join("\n")
+            ));
+            return;
+        }
+
+        match crate::backends::BackendManager::from_config(&new_config) {
+            Ok(new_manager) if new_manager.is_empty() => {
+                self.set_status(
+                    "No backends enabled in new config (keeping previous config)",
+                    StatusLevel::Warning,
+                );
+            }
+            Ok(new_manager) => {
+                self.status_bar = StatusBarTemplate::from_config(&new_config);
+                self.config = new_config;
+                self.backend_manager = new_manager;
+                self.refresh_tasks().await;
+                self.set_status("Config reloaded", StatusLevel::Success);
+            }
             Err(e) => {
-                self.set_status(format!("Config error: {}", e), StatusLevel::Error);
+                self.show_alert(format!(
+                    "Backend error: {}\n\nKeeping the previous config.",
+                    e
+                ));
             }
         }
     }
 
     pub async fn refresh_tasks(&mut self) {
-        match self.backend_manager.all_tasks(&self.task_filter).await {
+        self.refresh_at = None;
+        self.task_filter.search_mode = self.config.general.search_mode();
+        let (result, reports) = self.backend_manager.all_tasks_timed(&self.task_filter).await;
+        self.fetch_reports = reports;
+        let stale = self.fetch_reports.iter().any(|r| r.stale);
+        match result {
             Ok(tasks) => {
-                self.tasks = tasks;
-                self.group_tasks();
-                let visible = self.visible_count();
-                if self.selected_task >= visible && visible > 0 {
-                    self.selected_task = visible - 1;
+                // Stale tasks keep the age of the fetch they came from
+                if !stale {
+                    self.last_refresh = Some(chrono::Local::now());
                 }
+                self.details = None;
+                // Only worth a second fetch when the empty state needs it
+                self.unfiltered_count = if tasks.is_empty() {
+                    self.backend_manager
+                        .all_tasks(&TaskFilter::default())
+                        .await
+                        .ok()
+                        .map(|all| all.len())
+                } else {
+                    None
+                };
+                self.set_tasks(tasks);
             }
             Err(e) => {
                 self.set_status(format!("Error loading tasks: {}", e), StatusLevel::Error);
@@ -426,36 +822,540 @@ impl App {
         }
     }
 
+    /// A title search or `#tag` filter is narrowing the list.
+    pub fn has_search(&self) -> bool {
+        self.task_filter.search.is_some() || self.task_filter.tag.is_some()
+    }
+
+    /// Drop the search and tag filter. Returns whether there was one.
+    pub async fn clear_search(&mut self) -> bool {
+        if !self.has_search() {
+            return false;
+        }
+        self.task_filter.search = None;
+        self.task_filter.tag = None;
+        self.refresh_tasks().await;
+        true
+    }
+
+    /// What the task list says when nothing is shown: a failed fetch, a
+    /// search or view that matches nothing, or no tasks at all.
+    pub fn empty_message(&self) -> String {
+        let failed = self
+            .fetch_reports
+            .iter()
+            .find_map(|r| r.result.as_ref().err().map(|e| (r.source, e)));
+        if let Some((source, error)) = failed {
+            return format!(
+                "Backend error ({}): {}\nPress 'r' to retry.",
+                source.name(),
+                error
+            );
+        }
+
+        let view = self.current_view.label();
+        if let Some(ref search) = self.task_filter.search {
+            return format!(
+                "No tasks in {} match \"{}\".\nPress Esc to clear the search, or / to change it.",
+                view, search
+            );
+        }
+        if let Some(ref tag) = self.task_filter.tag {
+            return format!(
+                "No tasks in {} tagged #{}.\nPress Esc to clear the filter, or / to change it.",
+                view, tag
+            );
+        }
+
+        match self.unfiltered_count {
+            Some(0) => "No tasks found. Press 'a' to add a task.".to_string(),
+            Some(n) => format!(
+                "Nothing in {} ({} {} in other views).\nPress 'v' for the next view.",
+                view,
+                n,
+                if n == 1 { "task" } else { "tasks" }
+            ),
+            None => "No tasks found.".to_string(),
+        }
+    }
+
+    /// Re-read tasks after `edited`'s file was changed outside tasuki, e.g.
+    /// checked off in `$EDITOR`. Lines added or removed above it shift its
+    /// line-based ID, so it's found again by title near its old line and
+    /// kept selected.
+    pub async fn refresh_after_edit(&mut self, edited: Option<Task>) {
+        self.refresh_tasks().await;
+        let Some(task) = edited else {
+            return;
+        };
+        match self.backend_manager.relocate(&task).await {
+            Ok(current) => self.select_task_id(&current.id),
+            Err(e) => tracing::debug!("Lost track of {} after editing: {}", task.id, e),
+        }
+    }
+
+    pub fn toggle_split(&mut self) {
+        self.split = !self.split;
+    }
+
+    /// Load the detail pane for the selected task if the selection moved.
+    /// Only reads from the backend while the split layout is on.
+    pub fn update_details(&mut self) {
+        if !self.split {
+            return;
+        }
+        let Some(task) = self.get_selected_visible_task() else {
+            self.details = None;
+            return;
+        };
+        if self.details.as_ref().is_some_and(|(id, _)| *id == task.id) {
+            return;
+        }
+
+        let lines = self.backend_manager.task_details(&task).unwrap_or_else(|e| {
+            tracing::warn!("Failed to read details for {}: {}", task.id, e);
+            Vec::new()
+        });
+        self.details = Some((task.id, lines));
+    }
+
+    /// Enter or leave the board view. The board shows every pending task,
+    /// so the view filter is swapped out while it's open.
+    pub async fn toggle_board(&mut self) {
+        self.board = !self.board;
+        let search = self.task_filter.search.take();
+        let tag = self.task_filter.tag.take();
+        self.task_filter = if self.board {
+            TaskFilter {
+                status: Some(TaskStatus::Pending),
+                ..Default::default()
+            }
+        } else {
+            self.current_view.to_filter()
+        };
+        self.task_filter.search = search;
+        self.task_filter.tag = tag;
+        self.board_column = 0;
+        self.board_states = Default::default();
+        self.refresh_tasks().await;
+    }
+
+    /// Pending tasks split into the board's columns, in urgency order.
+    /// Just-completed tasks stay put while they flash.
+    fn build_board_columns(&self) -> Vec<BoardColumn> {
+        let today = crate::clock::today();
+        let layout = BoardLayout::from_config(&self.config.general.board_columns);
+        let column = |label: &'static str, belongs: &dyn Fn(&Task) -> bool| BoardColumn {
+            label,
+            tasks: self
+                .tasks
+                .iter()
+                .filter(|t| t.status == TaskStatus::Pending || self.is_flashing(&t.id))
+                .filter(|t| belongs(t))
+                .cloned()
+                .collect(),
+        };
+
+        match layout {
+            BoardLayout::Due => vec![
+                column("Overdue", &|t| t.due.is_some_and(|d| d < today)),
+                column("Today", &|t| t.due == Some(today)),
+                column("Upcoming", &|t| t.due.is_some_and(|d| d > today)),
+                column("No date", &|t| t.due.is_none()),
+            ],
+            BoardLayout::Priority => vec![
+                column("High", &|t| t.priority == Priority::High),
+                column("Medium", &|t| t.priority == Priority::Medium),
+                column("Low", &|t| t.priority <= Priority::Low),
+            ],
+        }
+    }
+
+    pub fn board_selected_task(&self) -> Option<Task> {
+        let tasks = &self.board_columns.get(self.board_column)?.tasks;
+        let index = self.board_states[self.board_column].selected().unwrap_or(0);
+        tasks.get(index.min(tasks.len().saturating_sub(1))).cloned()
+    }
+
+    pub fn board_focus_column(&mut self, delta: isize) {
+        let last = self.board_columns.len().saturating_sub(1);
+        self.board_column = self.board_column.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn board_move_selection(&mut self, delta: isize) {
+        let Some(column) = self.board_columns.get(self.board_column) else {
+            return;
+        };
+        let len = column.tasks.len();
+        let state = &mut self.board_states[self.board_column];
+        if len == 0 {
+            state.select(None);
+            return;
+        }
+        let current = state.selected().unwrap_or(0).min(len - 1);
+        state.select(Some(current.saturating_add_signed(delta).min(len - 1)));
+    }
+
+    /// Move the selected task into the neighbouring column, changing its due
+    /// date (Today: today, Upcoming: tomorrow, No date: none) or priority
+    /// to match.
+    pub async fn move_board_task(&mut self, delta: isize) {
+        let Some(task) = self.board_selected_task() else {
+            return;
+        };
+        if !self.check_updatable(&task) {
+            return;
+        }
+        let target = self.board_column.saturating_add_signed(delta);
+        if target == self.board_column || target >= self.board_columns.len() {
+            return;
+        }
+
+        let today = crate::clock::today();
+        let layout = BoardLayout::from_config(&self.config.general.board_columns);
+        let update = match (layout, target) {
+            (BoardLayout::Due, 0) => {
+                self.set_status("Can't move a task into Overdue", StatusLevel::Warning);
+                return;
+            }
+            (BoardLayout::Due, 1) => crate::model::TaskUpdate {
+                due: Some(Some(today)),
+                ..Default::default()
+            },
+            (BoardLayout::Due, 2) => crate::model::TaskUpdate {
+                due: Some(Some(today + chrono::Duration::days(1))),
+                ..Default::default()
+            },
+            (BoardLayout::Due, _) => crate::model::TaskUpdate {
+                due: Some(None),
+                ..Default::default()
+            },
+            (BoardLayout::Priority, _) => crate::model::TaskUpdate {
+                priority: Some([Priority::High, Priority::Medium, Priority::Low][target]),
+                ..Default::default()
+            },
+        };
+
+        match self.backend_manager.update_task(&task.id, &update).await {
+            Ok(t) => {
+                let label = self.board_columns[target].label;
+                self.set_status(format!("Moved to {}: {}", label, t.title), StatusLevel::Success);
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to move task: {}", e), StatusLevel::Error);
+            }
+        }
+        self.refresh_tasks().await;
+
+        self.board_column = target;
+        let index = self.board_columns[target]
+            .tasks
+            .iter()
+            .position(|t| t.id == task.id);
+        self.board_states[target].select(index.or(Some(0)));
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focus = !self.focus;
+        self.focus_skip = 0;
+    }
+
+    /// The task shown in focus mode: the most urgent pending task, or a later
+    /// one after skipping with "next". Wraps around at the end.
+    pub fn focus_task(&self) -> Option<Task> {
+        let order = SortOrder::from_config(&self.config.general);
+        if self.focus_skip == 0 {
+            return crate::backends::next_task(&self.tasks, &order).cloned();
+        }
+
+        let mut queue: Vec<Task> = self
+            .tasks
+            .iter()
+            .filter(|t| t.status == TaskStatus::Pending)
+            .cloned()
+            .collect();
+        if queue.is_empty() {
+            return None;
+        }
+        crate::backends::sort_by_urgency(&mut queue, &order);
+        let index = self.focus_skip % queue.len();
+        Some(queue.swap_remove(index))
+    }
+
+    pub fn focus_next(&mut self) {
+        self.focus_skip += 1;
+    }
+
+    pub async fn complete_focus_task(&mut self) {
+        let Some(task) = self.focus_task() else {
+            return;
+        };
+        if !self.check_updatable(&task) {
+            return;
+        }
+        match self
+            .backend_manager
+            .complete_task(&task.id, Some(&task.title))
+            .await
+        {
+            Ok(_) => {
+                crate::hooks::on_complete(&self.config.general, &task);
+                self.set_status(format!("Completed: {}", task.title), StatusLevel::Success);
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to complete task: {}", e), StatusLevel::Error);
+            }
+        }
+        self.refresh_tasks().await;
+    }
+
+    /// Push the focus task's due date to tomorrow.
+    pub async fn snooze_focus_task(&mut self) {
+        let Some(task) = self.focus_task() else {
+            return;
+        };
+        if !self.check_updatable(&task) {
+            return;
+        }
+        let tomorrow = crate::clock::today() + chrono::Duration::days(1);
+        let update = crate::model::TaskUpdate {
+            due: Some(Some(tomorrow)),
+            ..Default::default()
+        };
+        match self.backend_manager.update_task(&task.id, &update).await {
+            Ok(t) => {
+                self.set_status(format!("Snoozed until tomorrow: {}", t.title), StatusLevel::Success);
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to snooze task: {}", e), StatusLevel::Error);
+            }
+        }
+        self.refresh_tasks().await;
+    }
+
+    /// Clear the selected task's due date, moving it to the undated group.
+    pub async fn defer_selected_task(&mut self) {
+        let Some(task) = self.get_selected_visible_task() else {
+            return;
+        };
+        if !self.check_updatable(&task) {
+            return;
+        }
+        self.set_due(&task.id, None).await;
+    }
+
+    /// Start or stop the timer on the selected task. Starting one while
+    /// another task is timed stops that one first.
+    pub fn toggle_timer(&mut self) {
+        let Some(task) = self.get_selected_visible_task() else {
+            return;
+        };
+        let now = chrono::Local::now().naive_local();
+        let (stopped, timer) = timelog::toggle(self.timer.clone(), &task, now);
+
+        let saved = match (&stopped, timelog::log_path(&self.config)) {
+            (Some(entry), Some(path)) => timelog::append(&path, entry),
+            _ => Ok(()),
+        }
+        .and_then(|_| match (Timer::path(&self.config), &timer) {
+            (Some(path), Some(timer)) => timer.save(&path),
+            (Some(path), None) => Timer::clear(&path),
+            (None, _) => Ok(()),
+        });
+        if let Err(e) = saved {
+            self.set_status(format!("Failed to save timer: {}", e), StatusLevel::Error);
+            return;
+        }
+
+        let stopped = stopped.map(|entry| {
+            format!("Stopped {} after {}", entry.title, timelog::format_duration(entry.duration()))
+        });
+        let message = match (stopped, &timer) {
+            (Some(stopped), Some(timer)) => format!("{} · timing {}", stopped, timer.title),
+            (Some(stopped), None) => stopped,
+            (None, Some(timer)) => format!("Timing {}", timer.title),
+            (None, None) => String::new(),
+        };
+        self.timer = timer;
+        self.set_status(message, StatusLevel::Success);
+    }
+
+    /// Prompt for a new due date for the selected task.
+    pub fn start_reschedule(&mut self) {
+        let Some(task) = self.get_selected_visible_task() else {
+            return;
+        };
+        if !self.check_updatable(&task) {
+            return;
+        }
+        self.mode = AppMode::Input;
+        self.input_mode = Some(InputMode::Reschedule(task.id.clone()));
+        self.input_buffer = task.due.map(|d| d.to_string()).unwrap_or_default();
+        self.cursor_position = self.input_buffer.len();
+    }
+
+    /// Change a task's due date and keep it selected in its new group.
+    async fn set_due(&mut self, id: &crate::model::TaskId, due: Option<chrono::NaiveDate>) {
+        let update = crate::model::TaskUpdate {
+            due: Some(due),
+            ..Default::default()
+        };
+        match self.backend_manager.update_task(id, &update).await {
+            Ok(t) => {
+                let msg = match due {
+                    Some(date) => format!("Due {}: {}", date, t.title),
+                    None => format!("Deferred: {}", t.title),
+                };
+                self.set_status(msg, StatusLevel::Success);
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to reschedule task: {}", e), StatusLevel::Error);
+            }
+        }
+        self.refresh_tasks().await;
+        self.select_task_id(id);
+    }
+
+    /// Flip the selected task's status. Completing a task with open
+    /// subtasks asks first.
     pub async fn toggle_selected_task(&mut self) {
         if let Some(task) = self.get_selected_visible_task() {
-            let task_id = task.id.clone();
-            match task.status {
-                TaskStatus::Pending => {
-                    if let Err(e) = self.backend_manager.complete_task(&task_id).await {
-                        self.set_status(format!("Failed to complete task: {}", e), StatusLevel::Error);
-                    } else {
-                        self.set_status("Task completed", StatusLevel::Success);
-                    }
+            if !self.check_updatable(&task) {
+                return;
+            }
+            if task.status == TaskStatus::Pending {
+                // Not knowing is no reason to get in the way
+                let open = self.backend_manager.pending_subtasks(&task).unwrap_or(0);
+                if open > 0 {
+                    self.confirm_message = format!(
+                        "{} subtask{} incomplete — complete anyway?",
+                        open,
+                        if open == 1 { "" } else { "s" }
+                    );
+                    self.pending_confirm = Some(ConfirmAction::CompleteTask(task.id.clone()));
+                    self.mode = AppMode::Confirm;
+                    return;
                 }
-                TaskStatus::Done => {
-                    if let Err(e) = self.backend_manager.uncomplete_task(&task_id).await {
-                        self.set_status(format!("Failed to uncomplete task: {}", e), StatusLevel::Error);
-                    } else {
-                        self.set_status("Task marked as pending", StatusLevel::Success);
+            }
+            self.flip_status(task).await;
+        }
+    }
+
+    /// Complete or reopen `task`. The in-memory task changes right away; the
+    /// re-fetch that reconciles it is coalesced across rapid toggles.
+    async fn flip_status(&mut self, task: Task) {
+        let task_id = task.id.clone();
+        let (new_status, result) = match task.status {
+            TaskStatus::Pending => (
+                TaskStatus::Done,
+                self.backend_manager
+                    .complete_task(&task_id, Some(&task.title))
+                    .await,
+            ),
+            TaskStatus::Done => (
+                TaskStatus::Pending,
+                self.backend_manager
+                    .uncomplete_task(&task_id, Some(&task.title))
+                    .await,
+            ),
+        };
+
+        match result {
+            Ok(new_id) => {
+                let moved = new_id != task_id;
+                self.set_local_status(&task_id, &new_id, new_status);
+                if new_status == TaskStatus::Done {
+                    crate::hooks::on_complete(&self.config.general, &task);
+                    if self.config.general.flash_on_complete && !moved {
+                        self.recently_completed
+                            .insert(new_id, Instant::now() + FLASH_DURATION);
                     }
                 }
+                let msg = match new_status {
+                    TaskStatus::Done => "Task completed",
+                    TaskStatus::Pending => "Task marked as pending",
+                };
+                self.set_status(msg, StatusLevel::Success);
+                // Moving it shifted the lines, and so the IDs, of the
+                // tasks below it: the next toggle needs fresh ones
+                if moved {
+                    self.refresh_tasks().await;
+                } else {
+                    self.schedule_refresh();
+                }
             }
+            Err(e) => {
+                let verb = match new_status {
+                    TaskStatus::Done => "complete",
+                    TaskStatus::Pending => "uncomplete",
+                };
+                self.set_status(
+                    format!("Failed to {} task: {}", verb, e),
+                    StatusLevel::Error,
+                );
+                self.refresh_tasks().await;
+            }
+        }
+    }
+
+    /// Set `status` on the in-memory task with `id`, now known by `new_id`
+    /// if completing it moved it.
+    fn set_local_status(&mut self, id: &str, new_id: &str, status: TaskStatus) {
+        let tasks = self
+            .tasks
+            .iter_mut()
+            .chain(self.task_groups.iter_mut().flat_map(|g| g.tasks.iter_mut()))
+            .chain(
+                self.board_columns
+                    .iter_mut()
+                    .flat_map(|c| c.tasks.iter_mut()),
+            );
+        for task in tasks.filter(|t| t.id == id) {
+            task.status = status;
+            task.id = new_id.to_string();
+        }
+    }
+
+    /// Ask for a re-fetch shortly; repeated calls push it back so a burst of
+    /// actions costs one refresh.
+    pub fn schedule_refresh(&mut self) {
+        self.refresh_at = Some(Instant::now() + REFRESH_DEBOUNCE);
+    }
+
+    /// Runs the coalesced refresh once it's due, but not while a completed
+    /// row is still flashing, so the flash is seen before the row goes away.
+    pub async fn refresh_if_due(&mut self) {
+        let now = Instant::now();
+        self.recently_completed.retain(|_, until| *until > now);
+        if self.recently_completed.is_empty() && self.refresh_at.is_some_and(|at| now >= at) {
             self.refresh_tasks().await;
         }
     }
 
+    pub fn is_flashing(&self, task_id: &str) -> bool {
+        self.recently_completed.contains_key(task_id)
+    }
+
+    /// Whether the task with this ID is the one being timed.
+    pub fn is_timing(&self, task_id: &str) -> bool {
+        self.timer.as_ref().is_some_and(|t| t.task_id == task_id)
+    }
+
         pub fn edit_selected_task(&mut self) {
         use crate::model::Priority;
         
         if let Some(task) = self.get_selected_visible_task() {
+            if !self.check_updatable(&task) {
+                return;
+            }
             let mut parts = vec![task.title.clone()];
-            
+            let supports_priority = self
+                .backend_manager
+                .capabilities(task.source)
+                .map_or(true, |c| c.supports_priority);
+
             match task.priority {
+                _ if !supports_priority => {}
                 Priority::High => parts.push("(p1)".to_string()),
                 Priority::Medium => parts.push("(p2)".to_string()),
                 Priority::Low => parts.push("(p3)".to_string()),
@@ -494,32 +1394,85 @@ impl App {
     }
 
     pub fn cancel_input(&mut self) {
-        self.mode = AppMode::Normal;
+        self.mode = if matches!(self.input_mode, Some(InputMode::EditSetting(_))) {
+            AppMode::Settings
+        } else {
+            AppMode::Normal
+        };
         self.input_mode = None;
         self.input_buffer.clear();
         self.cursor_position = 0;
     }
 
+    /// Whether input was submitted moments ago, so an Enter now is most
+    /// likely a repeat of the one that submitted it.
+    pub fn just_submitted(&self) -> bool {
+        self.submitted_at
+            .is_some_and(|at| at.elapsed() < SUBMIT_GRACE)
+    }
+
     pub async fn submit_input(&mut self) {
+        self.submitted_at = Some(Instant::now());
+        if let Some(InputMode::EditSetting(key)) = self.input_mode.clone() {
+            self.input_mode = None;
+            let value = self.input_buffer.trim().to_string();
+            self.input_buffer.clear();
+            self.cursor_position = 0;
+            self.mode = AppMode::Settings;
+            self.write_setting(&key, (!value.is_empty()).then(|| value.into()))
+                .await;
+            return;
+        }
+
         if let Some(ref input_mode) = self.input_mode {
             match input_mode {
                 InputMode::QuickAdd => {
                     if !self.input_buffer.is_empty() {
-                        use crate::nlp::parse_quick_add;
-                        use crate::model::NewTask;
-                        
-                        match parse_quick_add(&self.input_buffer, &self.backend_manager) {
-                            Ok((title, priority, due, tags, backend)) => {
-                                let new_task = NewTask {
-                                    title,
-                                    priority,
-                                    due,
-                                    tags,
-                                    backend,
-                                };
-                                
-                                match self.backend_manager.create_task(&new_task).await {
-                                    Ok(task) => {
+                        use crate::nlp::parse_quick_add_with;
+
+                        match parse_quick_add_with(
+                            &self.input_buffer,
+                            &self.backend_manager.quick_add_options(&self.config),
+                        ) {
+                            Ok(parsed) => {
+                                let default_backend = self
+                                    .backend_manager
+                                    .default_source()
+                                    .unwrap_or(BackendSource::LocalFile);
+                                let new_task = parsed.into_new_task(default_backend);
+
+                                let created = self
+                                    .backend_manager
+                                    .create_task_or_existing(&new_task)
+                                    .await;
+                                match created {
+                                    Ok(Created::Existing(task)) => {
+                                        self.set_status(format!("Already added: {}", task.title), StatusLevel::Info);
+                                    }
+                                    Ok(Created::New(task)) if !self.dropped_fields(&task, &new_task).is_empty() => {
+                                        let dropped = self.dropped_fields(&task, &new_task);
+                                        self.set_status(
+                                            format!(
+                                                "Created in {} without {} (not supported): {}",
+                                                task.source.name(),
+                                                dropped.join(", "),
+                                                task.title
+                                            ),
+                                            StatusLevel::Warning,
+                                        );
+                                    }
+                                    Ok(Created::New(task)) if task.source != new_task.backend => {
+                                        self.set_status(
+                                            format!(
+                                                "Created in {} ({} not enabled): {}",
+                                                task.source.name(),
+                                                new_task.backend.name(),
+                                                task.title
+                                            ),
+                                            StatusLevel::Warning,
+                                        );
+                                    }
+                                    Ok(Created::New(task)) => {
                                         self.set_status(format!("Created: {}", task.title), StatusLevel::Success);
                                     }
                                     Err(e) => {
@@ -535,30 +1488,63 @@ impl App {
                     }
                 }
                 InputMode::Search => {
-                    self.task_filter.search = if self.input_buffer.is_empty() {
-                        None
-                    } else {
-                        Some(self.input_buffer.clone())
+                    // A lone `#tag` filters by tag (and nested tags) instead of title
+                    let query = self.input_buffer.trim();
+                    let (search, tag) = match query.strip_prefix('#') {
+                        Some(tag) if !tag.is_empty() && !tag.contains(char::is_whitespace) => {
+                            (None, Some(tag.to_string()))
+                        }
+                        _ if query.is_empty() => (None, None),
+                        _ => (Some(self.input_buffer.clone()), None),
                     };
+                    // Stay in the prompt so a bad regex can be fixed
+                    if let Some(ref query) = search {
+                        let mode = self.config.general.search_mode();
+                        if let Err(e) = crate::model::SearchMatcher::new(query, mode) {
+                            self.set_status(e.to_string(), StatusLevel::Error);
+                            return;
+                        }
+                    }
+                    self.task_filter.search = search;
+                    self.task_filter.tag = tag;
                     self.refresh_tasks().await;
                 }
                 InputMode::EditTask(task_id) => {
                     let task_id = task_id.clone();
                     if !self.input_buffer.is_empty() {
-                        use crate::nlp::parse_quick_add;
                         use crate::model::TaskUpdate;
-                        
-                        match parse_quick_add(&self.input_buffer, &self.backend_manager) {
-                            Ok((title, priority, due, tags, _)) => {
+                        use crate::nlp::parse_quick_add_with;
+
+                        match parse_quick_add_with(
+                            &self.input_buffer,
+                            &self.backend_manager.quick_add_options(&self.config),
+                        ) {
+                            Ok(parsed) => {
+                                let supports_priority = self
+                                    .backend_manager
+                                    .capabilities_for_id(&task_id)
+                                    .map_or(true, |c| c.supports_priority);
+                                let priority_ignored =
+                                    !supports_priority && parsed.priority != crate::model::Priority::None;
                                 let update = TaskUpdate {
-                                    title: Some(title),
+                                    title: Some(parsed.title),
                                     status: None,
-                                    priority: Some(priority),
-                                    due: Some(due),
-                                    tags: Some(tags),
+                                    priority: supports_priority.then_some(parsed.priority),
+                                    due: Some(parsed.due),
+                                    tags: Some(parsed.tags),
                                 };
                                 
                                 match self.backend_manager.update_task(&task_id, &update).await {
+                                    Ok(task) if priority_ignored => {
+                                        self.set_status(
+                                            format!(
+                                                "Updated: {} ({} does not support priorities)",
+                                                task.title,
+                                                task.source.name()
+                                            ),
+                                            StatusLevel::Warning,
+                                        );
+                                    }
                                     Ok(task) => {
                                         self.set_status(format!("Updated: {}", task.title), StatusLevel::Success);
                                     }
@@ -574,6 +1560,21 @@ impl App {
                         self.refresh_tasks().await;
                     }
                 }
+                InputMode::Reschedule(task_id) => {
+                    let task_id = task_id.clone();
+                    let text = self.input_buffer.trim();
+                    let due = match crate::nlp::parse_due(text, crate::clock::today(), self.config.general.weekday_policy()) {
+                        _ if text.is_empty() => None,
+                        Some(date) => Some(date),
+                        // Stay in the prompt so the date can be fixed
+                        None => {
+                            self.set_status(format!("Not a date: {}", text), StatusLevel::Error);
+                            return;
+                        }
+                    };
+                    self.set_due(&task_id, due).await;
+                }
+                InputMode::EditSetting(_) => {} // Taken above
             }
         }
         self.mode = AppMode::Normal;
@@ -582,6 +1583,30 @@ impl App {
         self.cursor_position = 0;
     }
 
+    /// Whether `task`'s backend lets it be changed; warns in the status bar
+    /// when it doesn't.
+    fn check_updatable(&mut self, task: &Task) -> bool {
+        let can_update = self
+            .backend_manager
+            .capabilities(task.source)
+            .map_or(true, |c| c.can_update);
+        if !can_update {
+            self.set_status(
+                format!("{} is read-only", task.source.name()),
+                StatusLevel::Warning,
+            );
+        }
+        can_update
+    }
+
+    /// Fields of `new_task` the backend that stored `task` couldn't keep.
+    fn dropped_fields(&self, task: &Task, new_task: &crate::model::NewTask) -> Vec<&'static str> {
+        self.backend_manager
+            .capabilities(task.source)
+            .map(|c| c.dropped_fields(new_task))
+            .unwrap_or_default()
+    }
+
     pub fn toggle_help(&mut self) {
         if self.mode == AppMode::Help {
             self.mode = AppMode::Normal;
@@ -592,6 +1617,18 @@ impl App {
 
     pub fn start_delete_confirmation(&mut self) {
         if let Some(task) = self.get_selected_visible_task() {
+            let can_delete = self
+                .backend_manager
+                .capabilities(task.source)
+                .map_or(true, |c| c.can_delete);
+            if !can_delete {
+                self.set_status(
+                    format!("{} does not support deleting tasks", task.source.name()),
+                    StatusLevel::Warning,
+                );
+                return;
+            }
+
             let title = if task.title.len() > 40 {
                 format!("{}...", &task.title[..37])
             } else {
@@ -614,6 +1651,12 @@ impl App {
                     }
                     self.refresh_tasks().await;
                 }
+                ConfirmAction::CompleteTask(task_id) => {
+                    let task = self.tasks.iter().find(|t| t.id == task_id).cloned();
+                    if let Some(task) = task.filter(|t| t.status == TaskStatus::Pending) {
+                        self.flip_status(task).await;
+                    }
+                }
             }
         }
         self.confirm_message.clear();
@@ -626,3 +1669,680 @@ impl App {
         self.mode = AppMode::Normal;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Priority;
+    use chrono::{Duration, Local};
+
+    fn task(id: &str, due_in: Option<i64>, priority: Priority) -> Task {
+        let today = Local::now().date_naive();
+        Task::build(id)
+            .with_id(id)
+            .with_priority(priority)
+            .with_due(due_in.map(|d| today + Duration::days(d)))
+    }
+
+    #[test]
+    fn test_focus_task_matches_next_task() {
+        let mut app = App::new(BackendManager::new(vec![]), Config::default());
+        let mut done = task("done-overdue", Some(-3), Priority::High);
+        done.status = TaskStatus::Done;
+        app.tasks = vec![
+            task("later", Some(4), Priority::High),
+            task("undated", None, Priority::High),
+            done,
+            task("today-low", Some(0), Priority::Low),
+            task("today-high", Some(0), Priority::High),
+        ];
+
+        let expected = crate::backends::next_task(&app.tasks, &SortOrder::default()).unwrap().id.clone();
+        assert_eq!(expected, "today-high");
+        assert_eq!(app.focus_task().unwrap().id, expected);
+
+        app.focus_next();
+        assert_eq!(app.focus_task().unwrap().id, "today-low");
+        app.focus_skip = 4;
+        assert_eq!(app.focus_task().unwrap().id, expected);
+    }
+
+    /// Today: a, b, c; Tomorrow: t; Notes: n1, n2
+    fn grouped_app() -> App {
+        let mut app = App::new(BackendManager::new(vec![]), Config::default());
+        app.set_tasks(grouped_tasks(&[]));
+        app
+    }
+
+    fn grouped_tasks(without: &[&str]) -> Vec<Task> {
+        [
+            task("a", Some(0), Priority::High),
+            task("b", Some(0), Priority::Medium),
+            task("c", Some(0), Priority::Low),
+            task("t", Some(1), Priority::None),
+            task("n1", None, Priority::High),
+            task("n2", None, Priority::Low),
+        ]
+        .into_iter()
+        .filter(|t| !without.contains(&t.id.as_str()))
+        .collect()
+    }
+
+    fn selected(app: &App) -> String {
+        match app.get_visible_item(app.selected_task) {
+            VisibleItem::Task(_, task) => task.id,
+            VisibleItem::Group(idx) => format!("[{}]", app.task_groups[idx].label),
+            VisibleItem::None => "none".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reverse_flips_groups_and_keeps_selection() {
+        let mut app = grouped_app();
+        app.select_task_id("b");
+        app.toggle_reverse();
+
+        let order: Vec<(&str, Vec<&str>)> = app
+            .task_groups
+            .iter()
+            .map(|g| (g.label.as_str(), g.tasks.iter().map(|t| t.id.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("Notes", vec!["n2", "n1"]),
+                ("Tomorrow", vec!["t"]),
+                ("Today", vec!["c", "b", "a"]),
+            ]
+        );
+        assert_eq!(selected(&app), "b");
+
+        app.toggle_reverse();
+        assert_eq!(app.task_groups[0].label, "Today");
+        assert_eq!(selected(&app), "b");
+    }
+
+    #[test]
+    fn test_selection_survives_task_leaving_group() {
+        // Middle of a group: the next task
+        let mut app = grouped_app();
+        app.select_task_id("b");
+        app.set_tasks(grouped_tasks(&["b"]));
+        assert_eq!(selected(&app), "c");
+
+        // End of a group: the previous task, not the next group
+        let mut app = grouped_app();
+        app.select_task_id("c");
+        app.set_tasks(grouped_tasks(&["c"]));
+        assert_eq!(selected(&app), "b");
+        assert_eq!(app.selected_group, 0);
+
+        // The task is still there, though it moved
+        let mut app = grouped_app();
+        app.select_task_id("t");
+        app.set_tasks(grouped_tasks(&["a"]));
+        assert_eq!(selected(&app), "t");
+    }
+
+    #[test]
+    fn test_selection_when_group_disappears() {
+        // Single-task group: the header of the group after it
+        let mut app = grouped_app();
+        app.select_task_id("t");
+        app.set_tasks(grouped_tasks(&["t"]));
+        assert_eq!(selected(&app), "[Notes]");
+        assert_eq!(app.selected_group, 1);
+
+        // A selected header stays selected while its tasks change
+        let mut app = grouped_app();
+        app.task_groups[2].collapsed = true;
+        app.selected_task = app.find_group_start(2);
+        app.set_tasks(grouped_tasks(&["n2"]));
+        assert_eq!(selected(&app), "[Notes]");
+
+        // End of the list: the last task left
+        let mut app = grouped_app();
+        app.select_task_id("n2");
+        app.set_tasks(grouped_tasks(&["n2"]));
+        assert_eq!(selected(&app), "n1");
+        let mut app = grouped_app();
+        app.select_task_id("n2");
+        app.set_tasks(grouped_tasks(&["n1", "n2"]));
+        assert_eq!(selected(&app), "t");
+        assert_eq!(app.list_state.selected(), Some(app.selected_task));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_after_external_edit_keeps_task_selected() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "Alpha\nBravo\nCharlie\n").unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+        let mut config = Config::default();
+        config.general.default_view = "all".to_string();
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), config);
+        app.refresh_tasks().await;
+        app.select_task_id("local:2");
+        let edited = app.get_selected_visible_task();
+        assert_eq!(edited.as_ref().map(|t| t.title.as_str()), Some("Bravo"));
+
+        // In the editor: lines added on top, Alpha checked off and Bravo
+        // made urgent. Bravo's old ID is now Yankee's line.
+        std::fs::write(&path, "Zulu\nYankee\nx 2025-03-01 Alpha\n(p1) Bravo\nCharlie\n")
+            .unwrap();
+        app.refresh_after_edit(edited).await;
+
+        let titles: Vec<&str> = app.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert!(!titles.contains(&"Alpha"));
+        let task = app.get_selected_visible_task().unwrap();
+        assert_eq!(task.title, "Bravo");
+        assert_eq!(task.id, "local:4");
+        assert_eq!(task.priority, Priority::High);
+    }
+
+    #[tokio::test]
+    async fn test_empty_message_explains_why() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "").unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+        let mut config = Config::default();
+        config.general.default_view = "today".to_string();
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), config);
+
+        app.refresh_tasks().await;
+        assert_eq!(app.empty_message(), "No tasks found. Press 'a' to add a task.");
+
+        // Tasks exist, just not in this view or for this search
+        std::fs::write(&path, "Someday task\n").unwrap();
+        app.refresh_tasks().await;
+        assert!(app.tasks.is_empty());
+        assert!(app.empty_message().starts_with("Nothing in Today (1 task in other views)"));
+
+        app.task_filter.search = Some("milk".to_string());
+        app.refresh_tasks().await;
+        assert!(app.empty_message().starts_with("No tasks in Today match \"milk\"."));
+        assert!(app.clear_search().await);
+        assert!(!app.clear_search().await);
+
+        // A failed fetch isn't an empty backend
+        std::fs::remove_file(&path).unwrap();
+        std::fs::create_dir(&path).unwrap();
+        app.refresh_tasks().await;
+        assert!(app.empty_message().contains("Press 'r' to retry."));
+    }
+
+    #[tokio::test]
+    async fn test_view_keys_keep_or_clear_search() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+        use crate::tui::keybindings::{Action, KeyBindings};
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let today = crate::clock::today();
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(
+            &path,
+            format!(
+                "Pay rent due:{}\nPay tax due:{}\nPay back Sam\nx {} Pay gym\n",
+                today,
+                today + chrono::Duration::days(5),
+                today
+            ),
+        )
+        .unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path });
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), Config::default());
+
+        let key = |c| KeyBindings::handle_normal(KeyEvent::from(KeyCode::Char(c)));
+        let titles = |app: &App| app.tasks.iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+
+        app.task_filter.search = Some("Pay".to_string());
+        let Some(Action::ShowView(view)) = key('2') else {
+            panic!("2 should pick a view");
+        };
+        app.show_view(view).await;
+        assert_eq!(app.current_view, View::Week);
+        assert_eq!(titles(&app), ["Pay rent", "Pay tax"]);
+
+        app.show_view(View::Today).await;
+        assert_eq!(titles(&app), ["Pay rent"]);
+        app.show_view(View::Done).await;
+        assert_eq!(titles(&app), ["Pay gym"]);
+        assert_eq!(app.task_filter.search.as_deref(), Some("Pay"));
+
+        // Esc drops the search but stays in the view
+        assert!(app.clear_search().await);
+        assert_eq!(app.current_view, View::Done);
+
+        app.task_filter.search = Some("rent".to_string());
+        assert_eq!(key('0'), Some(Action::ResetView));
+        app.reset_view().await;
+        assert_eq!(app.current_view, View::All);
+        assert!(!app.has_search());
+        assert_eq!(app.tasks.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_reload_keeps_previous_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let todo_path = dir.path().join("todo.txt");
+        let valid = format!(
+            "[general]\ntheme = \"dark\"\n\n[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.display()
+        );
+        std::fs::write(&config_path, &valid).unwrap();
+
+        let config = Config::load(Some(config_path.clone()), None, None).unwrap();
+        let manager = BackendManager::from_config(&config).unwrap();
+        let mut app = App::new(manager, config);
+
+        std::fs::write(&config_path, "[general\ntheme = \"light\"\n").unwrap();
+        app.reload_config().await;
+        assert_eq!(app.mode, AppMode::Alert);
+        assert!(app.alert_message.contains("Failed to parse config"));
+        assert_eq!(app.config.general.theme, "dark");
+
+        app.dismiss_alert();
+        std::fs::write(
+            &config_path,
+            valid.replace("theme = \"dark\"", "bang_priorities = [\"urgent\"]"),
+        )
+        .unwrap();
+        app.reload_config().await;
+        assert_eq!(app.mode, AppMode::Alert);
+        assert!(app.alert_message.contains("bang_priorities"));
+        assert_eq!(app.config.general.theme, "dark");
+
+        // Caught between an editor's truncate and its write
+        app.dismiss_alert();
+        std::fs::write(&config_path, "").unwrap();
+        app.reload_config().await;
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.config.general.theme, "dark");
+        assert_eq!(app.backend_manager.len(), 1);
+
+        std::fs::write(&config_path, valid.replace("dark", "light")).unwrap();
+        app.reload_config().await;
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.config.general.theme, "light");
+    }
+
+    #[tokio::test]
+    async fn test_board_columns_and_move() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        let today = Local::now().date_naive();
+        std::fs::write(
+            &path,
+            format!(
+                "Late due:{}\nNow due:{}\nSoon due:{}\nUndated\nx Finished due:{}\n",
+                today - Duration::days(2),
+                today,
+                today + Duration::days(3),
+                today
+            ),
+        )
+        .unwrap();
+
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), Config::default());
+        app.toggle_board().await;
+
+        let titles = |app: &App| -> Vec<Vec<String>> {
+            app.board_columns
+                .iter()
+                .map(|c| c.tasks.iter().map(|t| t.title.clone()).collect())
+                .collect()
+        };
+        assert_eq!(
+            titles(&app),
+            vec![vec!["Late"], vec!["Now"], vec!["Soon"], vec!["Undated"]]
+        );
+
+        // Overdue -> Today sets the due date to today
+        app.move_board_task(1).await;
+        assert_eq!(app.board_column, 1);
+        assert_eq!(
+            titles(&app),
+            vec![vec![], vec!["Late", "Now"], vec!["Soon"], vec!["Undated"]]
+        );
+        assert_eq!(app.get_selected_visible_task().unwrap().title, "Late");
+
+        // Today -> Upcoming sets it to tomorrow
+        app.move_board_task(1).await;
+        let moved = app.get_selected_visible_task().unwrap();
+        assert_eq!(moved.title, "Late");
+        assert_eq!(moved.due, Some(today + Duration::days(1)));
+
+        // Upcoming -> No date clears it
+        app.move_board_task(1).await;
+        assert_eq!(app.board_column, 3);
+        assert_eq!(titles(&app)[3], vec!["Late", "Undated"]);
+        assert_eq!(app.get_selected_visible_task().unwrap().due, None);
+        app.move_board_task(1).await;
+        assert_eq!(app.board_column, 3);
+
+        // Nothing may move back into Overdue
+        app.board_column = 1;
+        app.move_board_task(-1).await;
+        assert_eq!(titles(&app)[0], Vec::<String>::new());
+
+        app.toggle_board().await;
+        assert!(!app.board);
+    }
+
+    #[tokio::test]
+    async fn test_defer_and_reschedule_follow_task() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        let today = Local::now().date_naive();
+        std::fs::write(
+            &path,
+            format!("Alpha due:{}\nBravo due:{}\nCharlie\n", today, today),
+        )
+        .unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path });
+        let mut config = Config::default();
+        config.general.default_view = "all".to_string();
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), config);
+        app.refresh_tasks().await;
+        app.select_task_id("local:2");
+
+        // Deferring moves Bravo to the undated group, still selected
+        app.defer_selected_task().await;
+        let task = app.get_selected_visible_task().unwrap();
+        assert_eq!(task.title, "Bravo");
+        assert_eq!(task.due, None);
+        let group = &app.task_groups[app.selected_group];
+        assert_eq!((group.label.as_str(), group.date), ("Notes", None));
+        assert!(group.tasks.iter().any(|t| t.title == "Bravo"));
+
+        // A bad date keeps the prompt open; a good one moves the task back
+        app.start_reschedule();
+        app.input_buffer = "someday".to_string();
+        app.submit_input().await;
+        assert_eq!(app.input_mode, Some(InputMode::Reschedule("local:2".to_string())));
+        app.input_buffer = "tomorrow".to_string();
+        app.submit_input().await;
+        assert_eq!(app.mode, AppMode::Normal);
+        let task = app.get_selected_visible_task().unwrap();
+        assert_eq!(task.title, "Bravo");
+        assert_eq!(task.due, Some(today + Duration::days(1)));
+        assert_eq!(app.task_groups[app.selected_group].date, task.due);
+    }
+
+    #[tokio::test]
+    async fn test_quick_add_twice_is_already_added() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "").unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+        let manager = BackendManager::new(vec![Box::new(backend)])
+            .with_dedupe_window(Some(std::time::Duration::from_secs(60)));
+        let mut app = App::new(manager, Config::default());
+
+        for _ in 0..2 {
+            app.start_quick_add();
+            app.input_buffer = "Buy milk".to_string();
+            app.submit_input().await;
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        let (message, _) = app.status_message.clone().unwrap();
+        assert_eq!(message, "Already added: Buy milk");
+
+        // The same title with another due date is a different task
+        app.start_quick_add();
+        app.input_buffer = "Buy milk tomorrow".to_string();
+        app.submit_input().await;
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        // An Enter straight after the submit doesn't reach the list
+        assert!(app.just_submitted());
+        app.submitted_at = Some(Instant::now() - SUBMIT_GRACE);
+        assert!(!app.just_submitted());
+    }
+
+    #[tokio::test]
+    async fn test_completing_parent_asks_about_open_subtasks() {
+        use crate::backends::obsidian::{ObsidianBackend, ObsidianConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("Trip.md"),
+            "- [ ] Plan trip\n  - [ ] Flights\n  - [x] Visa\n  - [ ] Hotel\n- [ ] Pack\n  - [x] Charger\n",
+        )
+        .unwrap();
+        let mut table = toml::Table::new();
+        table.insert("vault_path".into(), dir.path().display().to_string().into());
+        let backend = ObsidianBackend::new(ObsidianConfig::from_table(&table).unwrap());
+        let mut config = Config::default();
+        config.general.default_view = "all".to_string();
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), config);
+        app.refresh_tasks().await;
+
+        // Only done children: completes straight away
+        let pack = app.tasks.iter().find(|t| t.title == "Pack").unwrap().id.clone();
+        app.select_task_id(&pack);
+        app.toggle_selected_task().await;
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.pending_confirm.is_none());
+
+        // Open children: asks, and nothing changes until confirmed
+        let plan = app.tasks.iter().find(|t| t.title == "Plan trip").unwrap().id.clone();
+        app.select_task_id(&plan);
+        app.toggle_selected_task().await;
+        assert_eq!(app.mode, AppMode::Confirm);
+        assert_eq!(app.confirm_message, "2 subtasks incomplete — complete anyway?");
+        app.cancel_confirm();
+        assert!(std::fs::read_to_string(dir.path().join("Trip.md")).unwrap().starts_with("- [ ] Plan trip"));
+
+        app.toggle_selected_task().await;
+        app.execute_confirm().await;
+        assert_eq!(app.mode, AppMode::Normal);
+        let task = app.tasks.iter().find(|t| t.id == plan).unwrap();
+        assert_eq!(task.status, TaskStatus::Done);
+    }
+
+    #[tokio::test]
+    async fn test_toggling_tasks_that_move_on_complete() {
+        use crate::backends::obsidian::{ObsidianBackend, ObsidianConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let note = dir.path().join("Work.md");
+        std::fs::write(&note, "- [ ] First\n- [ ] Second\n- [ ] Third\n").unwrap();
+        let mut table = toml::Table::new();
+        table.insert("vault_path".into(), dir.path().display().to_string().into());
+        table.insert("on_complete".into(), "move_to_done_section".into());
+        let backend = ObsidianBackend::new(ObsidianConfig::from_table(&table).unwrap());
+        let mut config = Config::default();
+        config.general.default_view = "all".to_string();
+        config.general.flash_on_complete = true;
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), config);
+        app.refresh_tasks().await;
+
+        // Back to back: completing First moved Second up a line, so the
+        // list is re-read at once rather than after the flash
+        for title in ["First", "Second"] {
+            let id = app
+                .tasks
+                .iter()
+                .find(|t| t.title == title)
+                .unwrap()
+                .id
+                .clone();
+            app.select_task_id(&id);
+            app.toggle_selected_task().await;
+            assert!(app.tasks.iter().all(|t| t.title != title));
+            assert!(app.recently_completed.is_empty());
+            assert!(app.refresh_at.is_none());
+        }
+        let content = std::fs::read_to_string(&note).unwrap();
+        assert!(content.starts_with("- [ ] Third\n"), "{}", content);
+        assert!(
+            content.contains("- [x] First") && content.contains("- [x] Second"),
+            "{}",
+            content
+        );
+
+        // An ID gone stale some other way is refused rather than hitting Third
+        let mut stale = app
+            .tasks
+            .iter()
+            .find(|t| t.title == "Third")
+            .unwrap()
+            .clone();
+        stale.title = "Fourth".to_string();
+        app.flip_status(stale).await;
+        assert_eq!(app.status_message.as_ref().unwrap().1, StatusLevel::Error);
+        assert!(std::fs::read_to_string(&note)
+            .unwrap()
+            .starts_with("- [ ] Third\n"));
+    }
+
+    #[tokio::test]
+    async fn test_quick_add_submit_creates_task() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), Config::default());
+
+        app.mode = AppMode::Input;
+        app.input_mode = Some(InputMode::QuickAdd);
+        app.input_buffer = "Buy milk".to_string();
+        app.submit_input().await;
+
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.input_mode.is_none());
+        assert!(std::fs::read_to_string(&path).unwrap().contains("Buy milk"));
+        assert_eq!(app.status_message.unwrap().0, "Created: Buy milk");
+    }
+
+    #[tokio::test]
+    async fn test_edit_keeps_time_words_in_title() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "Morning pages\n").unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path: path.clone() });
+        let mut config = Config::default();
+        config.general.default_view = "all".to_string();
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), config);
+        app.refresh_tasks().await;
+
+        app.select_task_id("local:1");
+        app.edit_selected_task();
+        assert_eq!(
+            app.input_mode,
+            Some(InputMode::EditTask("local:1".to_string()))
+        );
+        app.submit_input().await;
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Morning pages\n");
+    }
+
+    #[tokio::test]
+    async fn test_settings_write_through_and_reject_invalid() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let original = format!(
+            "# keep me\n[general]\ntheme = \"omarchy\"  # note\n\n[backends.local]\nenabled = true\npath = \"{}\"\n",
+            dir.path().join("todo.txt").display()
+        );
+        std::fs::write(&config_path, &original).unwrap();
+
+        let config = Config::load(Some(config_path.clone()), None, None).unwrap();
+        let manager = BackendManager::from_config(&config).unwrap();
+        let mut app = App::new(manager, config);
+
+        app.open_settings();
+        app.activate_setting().await;
+        assert_eq!(app.mode, AppMode::Settings);
+        assert_eq!(app.config.general.theme, "dark");
+        assert_eq!(
+            std::fs::read_to_string(&config_path).unwrap(),
+            original.replace("\"omarchy\"", "\"dark\"")
+        );
+
+        let before = std::fs::read_to_string(&config_path).unwrap();
+        app.settings_selected = SETTINGS
+            .iter()
+            .position(|s| s.key == "general.status_bar")
+            .unwrap();
+        app.activate_setting().await;
+        assert_eq!(app.mode, AppMode::Input);
+        app.input_buffer = "{pending} {bogus}".to_string();
+        app.submit_input().await;
+        assert_eq!(app.mode, AppMode::Alert);
+        assert!(app.alert_message.contains("{bogus}"));
+        assert!(app.config.general.status_bar.is_none());
+        assert_eq!(std::fs::read_to_string(&config_path).unwrap(), before);
+
+        // Parsed once on reload rather than on every draw
+        app.dismiss_alert();
+        app.open_settings();
+        app.settings_selected = SETTINGS
+            .iter()
+            .position(|s| s.key == "general.status_bar")
+            .unwrap();
+        app.activate_setting().await;
+        app.input_buffer = "{pending} open by {group}".to_string();
+        app.submit_input().await;
+        assert_eq!(app.mode, AppMode::Settings);
+        assert_eq!(app.status_bar, "{pending} open by {group}".parse().ok());
+    }
+
+    #[tokio::test]
+    async fn test_timer_switches_tasks_and_logs_time() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("todo.txt"), "Write report\nCall Bob\n").unwrap();
+        let mut config = Config {
+            config_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        config.general.default_view = "all".to_string();
+        let mut table = toml::Table::new();
+        table.insert("enabled".into(), true.into());
+        table.insert("path".into(), dir.path().join("todo.txt").display().to_string().into());
+        config.backends.local = Some(table);
+        let manager = BackendManager::from_config(&config).unwrap();
+        let mut app = App::new(manager, config);
+        app.refresh_tasks().await;
+
+        let id = |app: &App, title: &str| app.tasks.iter().find(|t| t.title == title).unwrap().id.clone();
+        let (report, call) = (id(&app, "Write report"), id(&app, "Call Bob"));
+        let timer_path = Timer::path(&app.config).unwrap();
+
+        app.select_task_id(&report);
+        app.toggle_timer();
+        assert!(app.is_timing(&report));
+        assert_eq!(Timer::load(&timer_path), app.timer);
+
+        // A second timer stops the first
+        app.select_task_id(&call);
+        app.toggle_timer();
+        assert!(app.is_timing(&call) && !app.is_timing(&report));
+        let log = timelog::read(&timelog::log_path(&app.config).unwrap());
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].task_id, report);
+
+        app.toggle_timer();
+        assert!(app.timer.is_none());
+        assert_eq!(Timer::load(&timer_path), None);
+        assert_eq!(timelog::read(&timelog::log_path(&app.config).unwrap()).len(), 2);
+    }
+}
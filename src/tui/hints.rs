@@ -0,0 +1,280 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::text::{Line, Span};
+
+use crate::backends::BackendCapabilities;
+use crate::tui::app::{App, AppMode, InputMode};
+use crate::tui::keybindings::{Action, KeyBindings};
+use crate::tui::theme::Theme;
+
+/// The key set in effect, mirroring how `handle_key` picks a handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintMode {
+    Normal,
+    Focus,
+    Board,
+    /// Text input; the label is what Enter does
+    Input(&'static str),
+    Help,
+    Confirm,
+    Alert,
+    Settings,
+}
+
+impl HintMode {
+    pub fn of(app: &App) -> Self {
+        match app.mode {
+            AppMode::Normal if app.focus => HintMode::Focus,
+            AppMode::Normal if app.board => HintMode::Board,
+            AppMode::Normal => HintMode::Normal,
+            AppMode::Input => HintMode::Input(match app.input_mode {
+                Some(InputMode::QuickAdd) => "add",
+                Some(InputMode::Search) => "search",
+                Some(InputMode::Reschedule(_)) => "schedule",
+                Some(InputMode::EditTask(_)) | Some(InputMode::EditSetting(_)) | None => "save",
+            }),
+            AppMode::Help => HintMode::Help,
+            AppMode::Confirm => HintMode::Confirm,
+            AppMode::Alert => HintMode::Alert,
+            AppMode::Settings => HintMode::Settings,
+        }
+    }
+
+    fn handler(self) -> fn(KeyEvent) -> Option<Action> {
+        match self {
+            HintMode::Normal => KeyBindings::handle_normal,
+            HintMode::Focus => KeyBindings::handle_focus,
+            HintMode::Board => KeyBindings::handle_board,
+            HintMode::Input(_) => KeyBindings::handle_input,
+            HintMode::Help => KeyBindings::handle_help,
+            HintMode::Confirm => KeyBindings::handle_confirm,
+            HintMode::Alert => KeyBindings::handle_alert,
+            HintMode::Settings => KeyBindings::handle_settings,
+        }
+    }
+}
+
+/// One "key label" pair in a hint line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub keys: String,
+    pub label: &'static str,
+}
+
+/// Keys tried, most preferred first, when looking up what triggers an action.
+fn candidate_keys() -> Vec<KeyCode> {
+    let mut keys: Vec<KeyCode> = ('a'..='z').chain('A'..='Z').map(KeyCode::Char).collect();
+    keys.extend("/?,|".chars().map(KeyCode::Char));
+    keys.extend([
+        KeyCode::Enter,
+        KeyCode::Esc,
+        KeyCode::Tab,
+        KeyCode::BackTab,
+        KeyCode::Down,
+        KeyCode::Up,
+        KeyCode::Left,
+        KeyCode::Right,
+    ]);
+    keys.extend((1..=12).map(KeyCode::F));
+    keys.push(KeyCode::Char(' '));
+    keys
+}
+
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "S-Tab".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
+
+/// The preferred key bound to `action` in `mode`, if any.
+fn key_for(mode: HintMode, action: Action) -> Option<String> {
+    let handler = mode.handler();
+    candidate_keys()
+        .into_iter()
+        .find(|&code| handler(KeyEvent::from(code)) == Some(action))
+        .map(key_name)
+}
+
+/// Key hints for `mode`, read from the bindings so they can't drift.
+/// `caps` is the selected task's backend; actions it can't do are left out.
+pub fn hints(mode: HintMode, caps: Option<BackendCapabilities>) -> Vec<Hint> {
+    let can_update = caps.map_or(true, |c| c.can_update);
+    let can_delete = caps.map_or(true, |c| c.can_delete);
+    let wanted: Vec<(&[Action], &'static str)> = match mode {
+        HintMode::Normal => {
+            let mut wanted: Vec<(&[Action], &'static str)> = vec![
+                (&[Action::MoveDown, Action::MoveUp], "navigate"),
+                (&[Action::NextView], "view"),
+                (&[Action::QuickAdd], "add"),
+            ];
+            if can_update {
+                wanted.push((&[Action::ToggleTask], "toggle"));
+            }
+            if can_delete {
+                wanted.push((&[Action::DeleteTask], "delete"));
+            }
+            wanted.extend([
+                (&[Action::Search][..], "search"),
+                (&[Action::Help][..], "help"),
+            ]);
+            wanted
+        }
+        HintMode::Focus if can_update => vec![
+            (&[Action::ToggleTask], "complete"),
+            (&[Action::Snooze], "snooze"),
+            (&[Action::FocusNext], "next"),
+            (&[Action::ToggleFocus], "exit focus"),
+        ],
+        HintMode::Focus => vec![
+            (&[Action::FocusNext], "next"),
+            (&[Action::ToggleFocus], "exit focus"),
+        ],
+        HintMode::Board if can_update => vec![
+            (&[Action::BoardLeft, Action::BoardRight], "column"),
+            (&[Action::MoveDown, Action::MoveUp], "navigate"),
+            (&[Action::MoveTaskLeft, Action::MoveTaskRight], "move"),
+            (&[Action::ToggleTask], "toggle"),
+            (&[Action::ToggleBoard], "exit board"),
+        ],
+        HintMode::Board => vec![
+            (&[Action::BoardLeft, Action::BoardRight], "column"),
+            (&[Action::MoveDown, Action::MoveUp], "navigate"),
+            (&[Action::ToggleBoard], "exit board"),
+        ],
+        HintMode::Input(submit) => vec![(&[Action::Submit], submit), (&[Action::Cancel], "cancel")],
+        HintMode::Confirm => vec![
+            (&[Action::Submit], "confirm"),
+            (&[Action::Cancel], "cancel"),
+        ],
+        HintMode::Settings => vec![
+            (&[Action::MoveDown, Action::MoveUp], "navigate"),
+            (&[Action::Submit], "change"),
+            (&[Action::Cancel], "close"),
+        ],
+        // Every key closes these
+        HintMode::Help | HintMode::Alert => {
+            return vec![Hint {
+                keys: "any key".to_string(),
+                label: "close",
+            }]
+        }
+    };
+
+    wanted
+        .into_iter()
+        .filter_map(|(actions, label)| {
+            let keys: Option<Vec<String>> = actions
+                .iter()
+                .map(|&action| key_for(mode, action))
+                .collect();
+            Some(Hint {
+                keys: keys?.join("/"),
+                label,
+            })
+        })
+        .collect()
+}
+
+/// Styled hints for `app`'s current mode and selected task.
+pub fn hint_line(app: &App, theme: &Theme) -> Line<'static> {
+    let mode = HintMode::of(app);
+    let task = match mode {
+        HintMode::Focus => app.focus_task(),
+        HintMode::Board => app.board_selected_task(),
+        _ => app.get_selected_visible_task(),
+    };
+    let caps = task.and_then(|task| app.backend_manager.capabilities(task.source));
+    let hints = hints(mode, caps);
+
+    let mut spans = Vec::new();
+    for (i, hint) in hints.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled("  ", theme.style_muted()));
+        }
+        spans.push(Span::styled(hint.keys, theme.style_accent()));
+        spans.push(Span::styled(
+            format!(" {}", hint.label),
+            theme.style_muted(),
+        ));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hint_text(hints: &[Hint]) -> String {
+        hints
+            .iter()
+            .map(|h| format!("{} {}", h.keys, h.label))
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    #[test]
+    fn test_hints_per_mode() {
+        let text = |mode| hint_text(&hints(mode, None));
+
+        assert_eq!(
+            text(HintMode::Normal),
+            "j/k navigate  v view  a add  x toggle  d delete  / search  ? help"
+        );
+        assert_eq!(
+            text(HintMode::Focus),
+            "x complete  s snooze  j next  f exit focus"
+        );
+        assert_eq!(
+            text(HintMode::Board),
+            "h/l column  j/k navigate  H/L move  x toggle  Esc exit board"
+        );
+        assert_eq!(text(HintMode::Input("add")), "Enter add  Esc cancel");
+        assert_eq!(text(HintMode::Confirm), "y confirm  n cancel");
+        assert_eq!(
+            text(HintMode::Settings),
+            "j/k navigate  Enter change  q close"
+        );
+        assert_eq!(text(HintMode::Help), "any key close");
+        assert_eq!(text(HintMode::Alert), "any key close");
+    }
+
+    #[test]
+    fn test_hints_follow_capabilities() {
+        let caps = BackendCapabilities {
+            can_create: true,
+            can_update: true,
+            can_delete: false,
+            supports_priority: true,
+            supports_due_time: false,
+            supports_description: false,
+            supports_recurrence: false,
+        };
+        let text = hint_text(&hints(HintMode::Normal, Some(caps)));
+        assert!(!text.contains("delete"));
+        assert!(text.contains("x toggle"));
+
+        let read_only = BackendCapabilities {
+            can_create: false,
+            can_update: false,
+            can_delete: false,
+            ..caps
+        };
+        let text = hint_text(&hints(HintMode::Normal, Some(read_only)));
+        assert!(!text.contains("toggle") && !text.contains("delete"));
+        assert_eq!(
+            hint_text(&hints(HintMode::Focus, Some(read_only))),
+            "j next  f exit focus"
+        );
+        assert!(!hint_text(&hints(HintMode::Board, Some(read_only))).contains("move"));
+    }
+}
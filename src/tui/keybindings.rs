@@ -1,5 +1,7 @@
 use crossterm::event::{KeyCode, KeyEvent};
 
+use crate::tui::app::View;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     Quit,
@@ -16,11 +18,28 @@ pub enum Action {
     DeleteTask,
     QuickAdd,
     Search,
+    ClearSearch,
     Refresh,
     Help,
     TogglePin,
     NextView,
     PreviousView,
+    ShowView(View),
+    ResetView,
+    ToggleFocus,
+    ToggleSplit,
+    ToggleBoard,
+    OpenSettings,
+    BoardLeft,
+    BoardRight,
+    MoveTaskLeft,
+    MoveTaskRight,
+    FocusNext,
+    Snooze,
+    Defer,
+    Reschedule,
+    ToggleTimer,
+    ReverseSort,
     Cancel,
     Submit,
     Backspace,
@@ -37,7 +56,9 @@ impl KeyBindings {
     pub fn handle_normal(key: KeyEvent) -> Option<Action> {
         match key.code {
             // Quit
-            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Char('q') => Some(Action::Quit),
+            // Clears a search first, quits otherwise
+            KeyCode::Esc => Some(Action::ClearSearch),
 
             // Navigation
             KeyCode::Char('j') | KeyCode::Down => Some(Action::MoveDown),
@@ -60,13 +81,62 @@ impl KeyBindings {
                 Some(Action::DeleteTask)
             }
             KeyCode::Char('p') => Some(Action::TogglePin),
+            KeyCode::Char('D') => Some(Action::Defer),
+            KeyCode::Char('s') => Some(Action::Reschedule),
+            KeyCode::Char('.') => Some(Action::ToggleTimer),
             KeyCode::Char('a') => Some(Action::QuickAdd),
             KeyCode::Char('/') => Some(Action::Search),
             KeyCode::Char('r') => Some(Action::Refresh),
+            KeyCode::Char('R') => Some(Action::ReverseSort),
             KeyCode::Char('v') => Some(Action::NextView),
             KeyCode::Char('V') => Some(Action::PreviousView),
+            KeyCode::Char('1') => Some(Action::ShowView(View::Today)),
+            KeyCode::Char('2') => Some(Action::ShowView(View::Week)),
+            KeyCode::Char('3') => Some(Action::ShowView(View::All)),
+            KeyCode::Char('4') => Some(Action::ShowView(View::Done)),
+            KeyCode::Char('0') => Some(Action::ResetView),
             KeyCode::Char('?') => Some(Action::Help),
+            KeyCode::Char('f') => Some(Action::ToggleFocus),
+            KeyCode::Char('|') => Some(Action::ToggleSplit),
+            KeyCode::F(4) => Some(Action::ToggleBoard),
+            KeyCode::Char(',') => Some(Action::OpenSettings),
+
+            _ => None,
+        }
+    }
 
+    pub fn handle_focus(key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('q') => Some(Action::Quit),
+            KeyCode::Char('f') | KeyCode::Esc => Some(Action::ToggleFocus),
+            KeyCode::Char('x') | KeyCode::Enter => Some(Action::ToggleTask),
+            KeyCode::Char('s') => Some(Action::Snooze),
+            KeyCode::Char('n') | KeyCode::Char('j') | KeyCode::Down => Some(Action::FocusNext),
+            KeyCode::Char('a') => Some(Action::QuickAdd),
+            KeyCode::Char('?') => Some(Action::Help),
+            _ => None,
+        }
+    }
+
+    pub fn handle_board(key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('q') => Some(Action::Quit),
+            KeyCode::F(4) | KeyCode::Esc => Some(Action::ToggleBoard),
+            KeyCode::Char('h') | KeyCode::Left => Some(Action::BoardLeft),
+            KeyCode::Char('l') | KeyCode::Right => Some(Action::BoardRight),
+            KeyCode::Char('j') | KeyCode::Down => Some(Action::MoveDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(Action::MoveUp),
+            KeyCode::Char('H') => Some(Action::MoveTaskLeft),
+            KeyCode::Char('L') => Some(Action::MoveTaskRight),
+            KeyCode::Char('x') | KeyCode::Enter => Some(Action::ToggleTask),
+            KeyCode::Char('e') => Some(Action::EditTask),
+            KeyCode::Char('o') => Some(Action::OpenInSource),
+            KeyCode::Char('d') => Some(Action::DeleteTask),
+            KeyCode::Char('p') => Some(Action::TogglePin),
+            KeyCode::Char('a') => Some(Action::QuickAdd),
+            KeyCode::Char('/') => Some(Action::Search),
+            KeyCode::Char('r') => Some(Action::Refresh),
+            KeyCode::Char('?') => Some(Action::Help),
             _ => None,
         }
     }
@@ -92,6 +162,20 @@ impl KeyBindings {
         }
     }
 
+    pub fn handle_settings(key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(Action::MoveDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(Action::MoveUp),
+            KeyCode::Enter | KeyCode::Char(' ') => Some(Action::Submit),
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char(',') => Some(Action::Cancel),
+            _ => None,
+        }
+    }
+
+    pub fn handle_alert(_key: KeyEvent) -> Option<Action> {
+        Some(Action::Cancel) // Any key dismisses
+    }
+
     pub fn handle_confirm(key: KeyEvent) -> Option<Action> {
         match key.code {
             KeyCode::Char('y') | KeyCode::Enter => Some(Action::Submit),
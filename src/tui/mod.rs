@@ -1,140 +1,107 @@
 use crossterm::{
-    event::{self, Event, KeyEvent},
+    event::{self, Event, KeyCode, KeyEvent},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use notify::{EventKind, Event as NotifyEvent, RecommendedWatcher, RecursiveMode, Watcher};
 use ratatui::{
-    backend::CrosstermBackend,
+    backend::{Backend, CrosstermBackend},
     Terminal,
 };
 use std::io;
-use std::sync::mpsc::{channel, Receiver};
+use std::sync::mpsc::{channel, Sender};
 use std::time::{Duration, Instant};
-
-use std::path::Path;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
 use crate::backends::BackendManager;
 use crate::tui::app::{App, AppMode};
 use crate::tui::keybindings::{Action, KeyBindings};
+use crate::tui::state::SessionState;
 use crate::tui::theme::{DynamicTheme, Theme};
+use crate::timelog;
+use crate::watch::{self, Debounce};
 
 pub mod app;
+pub mod hints;
 pub mod keybindings;
+pub mod settings;
+pub mod state;
+pub mod status_bar;
 pub mod theme;
 pub mod ui;
 pub mod views;
 
-fn setup_theme_watcher(theme: &Theme) -> crate::error::Result<(RecommendedWatcher, Receiver<NotifyEvent>)> {
-    let (tx, rx) = channel::<NotifyEvent>();
-    
-    let mut watcher = RecommendedWatcher::new(
-        move |res: Result<NotifyEvent, notify::Error>| {
-            if let Ok(event) = res {
-                let is_theme_event = event.paths.iter().any(|p| {
-                    p.to_string_lossy().contains("/theme/") || 
-                    p.file_name().map(|n| n == "theme").unwrap_or(false)
-                });
-                
-                if is_theme_event {
-                    // Only Create/Modify — Omarchy removes folder first, then recreates
-                    match event.kind {
-                        EventKind::Modify(_) | EventKind::Create(_) => {
-                            let _ = tx.send(event);
-                        }
-                        _ => {}
-                    }
-                }
-            }
-        },
-        notify::Config::default(),
-    )?;
-    
-    // Watch parent dir — Omarchy replaces the theme subfolder on switch
-    if let Some(path) = theme.watch_path() {
-        watcher.watch(&path, RecursiveMode::NonRecursive)?;
-    }
-    
-    Ok((watcher, rx))
+/// Something that wakes the event loop. Terminal input and every file
+/// watcher feed one channel, so the loop can sleep until any of them fires.
+#[derive(Clone)]
+enum Wakeup {
+    Input(Event),
+    Theme,
+    Vault,
+    Config,
 }
 
-fn setup_vault_watcher(config: &crate::config::Config) -> Option<(RecommendedWatcher, Receiver<NotifyEvent>)> {
-    let vault_path = config
-        .backends
-        .obsidian
-        .as_ref()
-        .filter(|t| t.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false))
-        .and_then(|t| t.get("vault_path").and_then(|v| v.as_str()))
-        .map(|s| shellexpand::tilde(s).into_owned())?;
-
-    let vault_path = Path::new(&vault_path).to_path_buf();
-    if !vault_path.exists() {
-        return None;
+/// A watcher callback that wakes the event loop with `wakeup`.
+fn waker(tx: &UnboundedSender<Wakeup>, wakeup: Wakeup) -> impl Fn() + Send + 'static {
+    let tx = tx.clone();
+    move || {
+        let _ = tx.send(wakeup.clone());
     }
+}
+
+/// Reads terminal events on its own thread and passes them on one at a
+/// time: after each it waits for `resume`, so nothing is read from the
+/// terminal while an editor has it.
+struct InputReader {
+    resume: Sender<()>,
+}
 
-    let (tx, rx) = channel::<NotifyEvent>();
-
-    let mut watcher = RecommendedWatcher::new(
-        move |res: Result<NotifyEvent, notify::Error>| {
-            if let Ok(event) = res {
-                let is_md_event = event.paths.iter().any(|p| {
-                    p.extension().and_then(|e| e.to_str()) == Some("md")
-                });
-
-                if is_md_event {
-                    match event.kind {
-                        EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => {
-                            let _ = tx.send(event);
-                        }
-                        _ => {}
+impl InputReader {
+    fn spawn(tx: UnboundedSender<Wakeup>) -> Self {
+        let (resume, resumed) = channel::<()>();
+        std::thread::spawn(move || loop {
+            match event::read() {
+                Ok(event) => {
+                    if tx.send(Wakeup::Input(event)).is_err() {
+                        break;
                     }
                 }
+                Err(e) => {
+                    tracing::warn!("Stopped reading terminal input: {}", e);
+                    break;
+                }
+            }
+            if resumed.recv().is_err() {
+                break;
             }
-        },
-        notify::Config::default(),
-    )
-    .ok()?;
+        });
+        Self { resume }
+    }
 
-    watcher
-        .watch(&vault_path, RecursiveMode::Recursive)
-        .ok()?;
+    /// The last event is dealt with; read the next one.
+    fn resume(&self) {
+        let _ = self.resume.send(());
+    }
+}
 
-    Some((watcher, rx))
+/// Handing the terminal to an external program and taking it back.
+trait Suspend {
+    fn suspend(&mut self) -> io::Result<()>;
+    fn resume(&mut self) -> io::Result<()>;
 }
 
-fn setup_config_watcher() -> Option<(RecommendedWatcher, Receiver<NotifyEvent>)> {
-    let config_path = crate::config::Config::default_config_path().ok()?;
-    let parent = config_path.parent()?.to_path_buf();
-    if !parent.exists() {
-        return None;
+impl Suspend for Terminal<CrosstermBackend<io::Stdout>> {
+    fn suspend(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        self.backend_mut().execute(LeaveAlternateScreen)?;
+        self.show_cursor()
     }
 
-    let (tx, rx) = channel::<NotifyEvent>();
-
-    let mut watcher = RecommendedWatcher::new(
-        move |res: Result<NotifyEvent, notify::Error>| {
-            if let Ok(event) = res {
-                let is_config_event = event.paths.iter().any(|p| {
-                    p.file_name().and_then(|n| n.to_str()) == Some("config.toml")
-                });
-
-                if is_config_event {
-                    match event.kind {
-                        EventKind::Modify(_) | EventKind::Create(_) => {
-                            let _ = tx.send(event);
-                        }
-                        _ => {}
-                    }
-                }
-            }
-        },
-        notify::Config::default(),
-    )
-    .ok()?;
-
-    watcher.watch(&parent, RecursiveMode::NonRecursive).ok()?;
-
-    Some((watcher, rx))
+    fn resume(&mut self) -> io::Result<()> {
+        enable_raw_mode()?;
+        self.backend_mut().execute(EnterAlternateScreen)?;
+        self.hide_cursor()?;
+        self.clear()
+    }
 }
 
 pub async fn run(backend_manager: BackendManager, config: crate::config::Config) -> crate::error::Result<()> {
@@ -144,163 +111,266 @@ pub async fn run(backend_manager: BackendManager, config: crate::config::Config)
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let initial_theme = Theme::load(&config.general.theme);
+    let initial_theme = Theme::load(&config.general.theme, config.dir().as_deref());
     let theme = DynamicTheme::new(initial_theme.clone());
     
-    // _watcher must stay alive for the duration of the event loop
-    let (_watcher, theme_rx) = match setup_theme_watcher(&initial_theme) {
-        Ok((watcher, rx)) => (Some(watcher), Some(rx)),
-        Err(_) => (None, None),
-    };
-
-    let (_vault_watcher, vault_rx) = match setup_vault_watcher(&config) {
-        Some((watcher, rx)) => (Some(watcher), Some(rx)),
-        None => (None, None),
-    };
-
-    let (_config_watcher, config_rx) = match setup_config_watcher() {
-        Some((watcher, rx)) => (Some(watcher), Some(rx)),
-        None => (None, None),
-    };
+    // The watchers must stay alive for the duration of the event loop
+    let (tx, mut rx) = unbounded_channel::<Wakeup>();
+    let _watcher = watch::theme(initial_theme.watch_path(), waker(&tx, Wakeup::Theme)).ok();
+    let _vault_watcher = watch::vault(&config, waker(&tx, Wakeup::Vault));
+    let _config_watcher = config
+        .config_path
+        .as_deref()
+        .and_then(|path| watch::file(path, waker(&tx, Wakeup::Config)));
+    let input = InputReader::spawn(tx);
+
+    let state_path = SessionState::path(&config);
+    let state = state_path
+        .as_deref()
+        .map(SessionState::load)
+        .unwrap_or_default();
+    let selected_task = state.selected_task.clone();
+
+    let timer = timelog::Timer::path(&config).and_then(|path| timelog::Timer::load(&path));
 
     let mut app = App::new(backend_manager, config);
+    app.restore_session(state);
+    app.timer = timer;
     app.refresh_tasks().await;
+    if let Some(ref id) = selected_task {
+        app.select_task_id(id);
+    }
+
+    let result = event_loop(&mut terminal, &mut app, &theme, &mut rx, &input).await;
 
-    let tick_rate = Duration::from_millis(250);
-    let mut last_tick = Instant::now();
-    let mut last_theme_change = Instant::now();
-    let mut last_vault_change = Instant::now();
-    let mut last_config_change = Instant::now();
+    disable_raw_mode()?;
+    terminal.backend_mut().execute(LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    if let Some(ref path) = state_path {
+        if let Err(e) = app.session_state().save(path) {
+            tracing::warn!("Failed to save TUI state to {}: {}", path.display(), e);
+        }
+    }
+
+    result
+}
+
+/// How long the loop may sleep before it next has to draw: a tick while a
+/// row is flashing or a refresh is pending, otherwise until the status bar
+/// or the running timer would change. `None` means until the next wakeup.
+fn wait_time(app: &App) -> Option<Duration> {
+    if !app.recently_completed.is_empty() || app.refresh_at.is_some() {
+        return Some(app.config.general.tick_rate());
+    }
+    match (status_bar::freshness_changes_in(app), status_bar::timer_changes_in(app)) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Draw, sleep until a key, a watched file or a timer needs attention,
+/// handle it, repeat. An idle TUI draws nothing.
+async fn event_loop<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    theme: &DynamicTheme,
+    rx: &mut UnboundedReceiver<Wakeup>,
+    input: &InputReader,
+) -> crate::error::Result<()>
+where
+    Terminal<B>: Suspend,
+{
+    let mut last_theme_change: Option<Instant> = None;
+    let mut last_vault_change: Option<Instant> = None;
+    // A config save is only read once the editor is done writing it
+    let mut config_change = Debounce::new(watch::CONFIG_QUIET);
+    // Other watcher events come in bursts; act on the first of each second
+    let debounced = |last: &mut Option<Instant>| {
+        if last.map_or(true, |at| at.elapsed() >= Duration::from_secs(1)) {
+            *last = Some(Instant::now());
+            true
+        } else {
+            false
+        }
+    };
 
     loop {
         let current_theme = theme.get();
-        terminal.draw(|f| ui::render(f, &mut app, &current_theme))?;
-
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-
-        let mut should_quit = false;
-        if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if let Some(action) = handle_key(key, &app) {
-                    // Actions that need to suspend the TUI for an external process
-                    let external_cmd = match action {
-                        Action::OpenInSource => {
-                            match get_open_command(&app) {
-                                Some(cmd) => Some(cmd),
-                                None => {
-                                    app.set_status(
-                                        "Set $EDITOR or install source app to open this task",
-                                        crate::tui::app::StatusLevel::Error,
-                                    );
-                                    None
-                                }
-                            }
-                        }
-                        Action::OpenConfig => get_config_command(),
-                        _ => None,
-                    };
-
-                    if let Some(cmd) = external_cmd {
-                        disable_raw_mode()?;
-                        terminal.backend_mut().execute(LeaveAlternateScreen)?;
-                        terminal.show_cursor()?;
-
-                        let status = std::process::Command::new(&cmd[0])
-                            .args(&cmd[1..])
-                            .status();
-
-                        enable_raw_mode()?;
-                        terminal.backend_mut().execute(EnterAlternateScreen)?;
-                        terminal.hide_cursor()?;
-                        terminal.clear()?;
-
-                        match status {
-                            Ok(s) if s.success() => {
-                                if action == Action::OpenConfig {
-                                    app.reload_config().await;
-                                } else {
-                                    app.refresh_tasks().await;
-                                }
-                            }
-                            Ok(s) => {
-                                app.set_status(
-                                    format!("Editor exited with code {}", s.code().unwrap_or(-1)),
-                                    crate::tui::app::StatusLevel::Warning,
-                                );
-                            }
-                            Err(e) => {
-                                app.set_status(
-                                    format!("Failed to open: {}", e),
-                                    crate::tui::app::StatusLevel::Error,
-                                );
-                            }
-                        }
-                    } else if action != Action::OpenInSource && action != Action::OpenConfig {
-                        if process_action(action, &mut app).await {
-                            should_quit = true;
-                        }
-                    }
+        app.update_details();
+        terminal.draw(|f| ui::render(f, app, &current_theme))?;
+
+        let timeout = match (wait_time(app), config_change.wait(Instant::now())) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let wakeup = match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, rx.recv()).await {
+                Ok(Some(wakeup)) => Some(wakeup),
+                Ok(None) => break,
+                Err(_) => None,
+            },
+            None => match rx.recv().await {
+                Some(wakeup) => Some(wakeup),
+                None => break,
+            },
+        };
+
+        match wakeup {
+            Some(Wakeup::Input(Event::Key(key))) => {
+                if handle_key_event(key, terminal, app).await? || app.should_quit {
+                    break;
                 }
+                input.resume();
+            }
+            // A resize only needs the redraw at the top of the loop
+            Some(Wakeup::Input(_)) => input.resume(),
+            Some(Wakeup::Theme) if debounced(&mut last_theme_change) => {
+                let new_theme =
+                    Theme::load(&app.config.general.theme, app.config.dir().as_deref());
+                theme.update(new_theme);
             }
+            Some(Wakeup::Vault) if debounced(&mut last_vault_change) => {
+                app.refresh_tasks().await;
+            }
+            Some(Wakeup::Config) => config_change.event(Instant::now()),
+            // Timed out, or more of a burst already acted on
+            _ => {}
         }
 
-        if let Some(ref rx) = theme_rx {
-            while let Ok(_event) = rx.try_recv() {
-                if last_theme_change.elapsed() >= Duration::from_secs(1) {
-                    let new_theme = Theme::load(&app.config.general.theme);
-                    theme.update(new_theme);
-                    last_theme_change = Instant::now();
-                }
-            }
+        if config_change.fire(Instant::now()) {
+            app.reload_config().await;
+            let new_theme =
+                Theme::load(&app.config.general.theme, app.config.dir().as_deref());
+            theme.update(new_theme);
         }
 
-        if let Some(ref rx) = vault_rx {
-            while let Ok(_event) = rx.try_recv() {
-                if last_vault_change.elapsed() >= Duration::from_secs(1) {
-                    app.refresh_tasks().await;
-                    last_vault_change = Instant::now();
-                }
-            }
+        app.refresh_if_due().await;
+
+        if app.should_quit {
+            break;
         }
+    }
 
-        if let Some(ref rx) = config_rx {
-            while let Ok(_event) = rx.try_recv() {
-                if last_config_change.elapsed() >= Duration::from_secs(1) {
-                    app.reload_config().await;
-                    let new_theme = Theme::load(&app.config.general.theme);
-                    theme.update(new_theme);
-                    last_config_change = Instant::now();
-                }
+    Ok(())
+}
+
+/// Handle one key press, suspending the TUI if it opens an editor.
+/// Returns whether to quit.
+async fn handle_key_event<B: Backend>(
+    key: KeyEvent,
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+) -> crate::error::Result<bool>
+where
+    Terminal<B>: Suspend,
+{
+    let Some(action) = handle_key(key, app) else {
+        return Ok(false);
+    };
+
+    // Actions that need to suspend the TUI for an external process
+    let external_cmd = match action {
+        Action::OpenInSource => get_open_command(app).await,
+        Action::OpenConfig => get_config_command(&app.config),
+        _ => None,
+    };
+    let external_cmd = match external_cmd {
+        Some(Ok(cmd)) => Some(cmd),
+        Some(Err(e)) => {
+            app.set_status(e, crate::tui::app::StatusLevel::Error);
+            None
+        }
+        None => {
+            if action == Action::OpenInSource {
+                app.set_status(
+                    "This task has no source file to open",
+                    crate::tui::app::StatusLevel::Error,
+                );
             }
+            None
         }
+    };
+
+    let Some(cmd) = external_cmd else {
+        let handled_above = action == Action::OpenInSource || action == Action::OpenConfig;
+        return Ok(!handled_above && process_action(action, app).await);
+    };
+
+    let edited = match action {
+        Action::OpenInSource => app.get_selected_visible_task(),
+        _ => None,
+    };
+    terminal.suspend()?;
+
+    let status = std::process::Command::new(&cmd.argv[0])
+        .args(&cmd.argv[1..])
+        .status();
 
-        if last_tick.elapsed() >= tick_rate {
-            last_tick = Instant::now();
+    terminal.resume()?;
+
+    // Local files have no watcher, and an editor that
+    // exits non-zero may still have saved
+    if action == Action::OpenConfig {
+        if matches!(status, Ok(s) if s.success()) {
+            app.reload_config().await;
         }
+    } else {
+        app.refresh_after_edit(edited).await;
+    }
 
-        if app.should_quit || should_quit {
-            break;
+    match status {
+        Ok(s) if s.success() => {}
+        Ok(s) => {
+            app.set_status(
+                format!("Editor exited with code {}", s.code().unwrap_or(-1)),
+                crate::tui::app::StatusLevel::Warning,
+            );
+        }
+        Err(e) => {
+            app.set_status(
+                format!("Failed to start '{}': {}", cmd.program, e),
+                crate::tui::app::StatusLevel::Error,
+            );
         }
     }
+    Ok(false)
+}
 
-    disable_raw_mode()?;
-    terminal.backend_mut().execute(LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+/// A process to run with the TUI suspended. `program` is what the user
+/// configured (e.g. "code --wait"), for error messages.
+struct ExternalCommand {
+    program: String,
+    argv: Vec<String>,
+}
 
-    Ok(())
+fn editor_command(
+    config: &crate::config::Config,
+    args: &[String],
+) -> Result<ExternalCommand, String> {
+    let program = crate::editor::resolve(&config.general);
+    let argv = crate::editor::command(&program, args).map_err(|e| e.to_string())?;
+    Ok(ExternalCommand { program, argv })
 }
 
-fn get_open_command(app: &App) -> Option<Vec<String>> {
+async fn get_open_command(app: &App) -> Option<Result<ExternalCommand, String>> {
     let task = app.get_selected_visible_task()?;
+    // The list may predate edits to the file; open where the task is now
+    let task = match app.backend_manager.relocate(&task).await {
+        Ok(current) => current,
+        Err(e) => {
+            tracing::debug!("Opening {} at its last known line: {}", task.id, e);
+            task
+        }
+    };
 
     if task.source == crate::model::BackendSource::Obsidian {
         if let Some(ref table) = app.config.backends.obsidian {
             if let Ok(obs_config) = crate::backends::obsidian::ObsidianConfig::from_table(table) {
                 let backend = crate::backends::obsidian::ObsidianBackend::new(obs_config);
-                if let Some(cmd) = backend.open_command(&task) {
-                    return Some(cmd);
+                if let Some(argv) = backend.open_command(&task) {
+                    let program = argv[0].clone();
+                    return Some(Ok(ExternalCommand { program, argv }));
                 }
             }
         }
@@ -308,26 +378,33 @@ fn get_open_command(app: &App) -> Option<Vec<String>> {
 
     let source_path = task.source_path.as_ref()?;
     let line_num = task.source_line.unwrap_or(1);
-
-    if let Ok(editor) = std::env::var("EDITOR") {
-        return Some(vec![editor, format!("+{}", line_num), source_path.clone()]);
-    }
-
-    None
+    Some(editor_command(
+        &app.config,
+        &[format!("+{}", line_num), source_path.clone()],
+    ))
 }
 
-fn get_config_command() -> Option<Vec<String>> {
-    let editor = std::env::var("EDITOR").ok()?;
-    let config_path = crate::config::Config::default_config_path().ok()?;
-    Some(vec![editor, config_path.to_string_lossy().into_owned()])
+fn get_config_command(config: &crate::config::Config) -> Option<Result<ExternalCommand, String>> {
+    let config_path = config.config_path.clone()?;
+    Some(editor_command(
+        config,
+        &[config_path.to_string_lossy().into_owned()],
+    ))
 }
 
 fn handle_key(key: KeyEvent, app: &App) -> Option<Action> {
+    if key.code == KeyCode::Enter && app.just_submitted() {
+        return None;
+    }
     match app.mode {
+        AppMode::Normal if app.focus => KeyBindings::handle_focus(key),
+        AppMode::Normal if app.board => KeyBindings::handle_board(key),
         AppMode::Normal => KeyBindings::handle_normal(key),
         AppMode::Input => KeyBindings::handle_input(key),
         AppMode::Help => KeyBindings::handle_help(key),
         AppMode::Confirm => KeyBindings::handle_confirm(key),
+        AppMode::Alert => KeyBindings::handle_alert(key),
+        AppMode::Settings => KeyBindings::handle_settings(key),
     }
 }
 
@@ -337,6 +414,18 @@ async fn process_action(action: Action, app: &mut App) -> bool {
             app.should_quit = true;
             return true;
         }
+        Action::MoveUp if app.mode == AppMode::Settings => {
+            app.settings_move(-1);
+        }
+        Action::MoveDown if app.mode == AppMode::Settings => {
+            app.settings_move(1);
+        }
+        Action::MoveUp if app.board => {
+            app.board_move_selection(-1);
+        }
+        Action::MoveDown if app.board => {
+            app.board_move_selection(1);
+        }
         Action::MoveUp => {
             app.move_selection_up();
         }
@@ -355,12 +444,57 @@ async fn process_action(action: Action, app: &mut App) -> bool {
         Action::ToggleAllGroups => {
             app.toggle_all_groups();
         }
+        Action::ToggleTask if app.focus => {
+            app.complete_focus_task().await;
+        }
         Action::ToggleTask => {
             app.toggle_selected_task().await;
         }
+        Action::ToggleFocus => {
+            app.toggle_focus();
+        }
+        Action::ToggleSplit => {
+            app.toggle_split();
+        }
+        Action::OpenSettings => {
+            app.open_settings();
+        }
+        Action::ToggleBoard => {
+            app.toggle_board().await;
+        }
+        Action::BoardLeft => {
+            app.board_focus_column(-1);
+        }
+        Action::BoardRight => {
+            app.board_focus_column(1);
+        }
+        Action::MoveTaskLeft => {
+            app.move_board_task(-1).await;
+        }
+        Action::MoveTaskRight => {
+            app.move_board_task(1).await;
+        }
+        Action::FocusNext => {
+            app.focus_next();
+        }
+        Action::Snooze => {
+            app.snooze_focus_task().await;
+        }
         Action::TogglePin => {
             app.toggle_pin().await;
         }
+        Action::Defer => {
+            app.defer_selected_task().await;
+        }
+        Action::Reschedule => {
+            app.start_reschedule();
+        }
+        Action::ToggleTimer => {
+            app.toggle_timer();
+        }
+        Action::ReverseSort => {
+            app.toggle_reverse();
+        }
         Action::EditTask => {
             app.edit_selected_task();
         }
@@ -374,6 +508,12 @@ async fn process_action(action: Action, app: &mut App) -> bool {
         Action::Search => {
             app.start_search();
         }
+        Action::ClearSearch => {
+            if !app.clear_search().await {
+                app.should_quit = true;
+                return true;
+            }
+        }
         Action::Refresh => {
             app.refresh_tasks().await;
             app.set_status("Tasks refreshed", crate::tui::app::StatusLevel::Info);
@@ -384,6 +524,12 @@ async fn process_action(action: Action, app: &mut App) -> bool {
         Action::PreviousView => {
             app.cycle_view_backward().await;
         }
+        Action::ShowView(view) => {
+            app.show_view(view).await;
+        }
+        Action::ResetView => {
+            app.reset_view().await;
+        }
         Action::Help => {
             app.toggle_help();
         }
@@ -391,12 +537,16 @@ async fn process_action(action: Action, app: &mut App) -> bool {
             match app.mode {
                 AppMode::Help => app.mode = AppMode::Normal,
                 AppMode::Confirm => app.cancel_confirm(),
+                AppMode::Alert => app.dismiss_alert(),
+                AppMode::Settings => app.close_settings(),
                 _ => app.cancel_input(),
             }
         }
         Action::Submit => {
             if app.mode == AppMode::Confirm {
                 app.execute_confirm().await;
+            } else if app.mode == AppMode::Settings {
+                app.activate_setting().await;
             } else {
                 app.submit_input().await;
             }
@@ -430,3 +580,111 @@ async fn process_action(action: Action, app: &mut App) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::{TestBackend, WindowSize};
+    use ratatui::buffer::Cell;
+    use ratatui::layout::{Position, Size};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// `TestBackend` that counts frames drawn.
+    struct CountingBackend {
+        inner: TestBackend,
+        draws: Arc<AtomicUsize>,
+    }
+
+    impl Backend for CountingBackend {
+        fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+        where
+            I: Iterator<Item = (u16, u16, &'a Cell)>,
+        {
+            self.draws.fetch_add(1, Ordering::SeqCst);
+            self.inner.draw(content)
+        }
+
+        fn hide_cursor(&mut self) -> io::Result<()> {
+            self.inner.hide_cursor()
+        }
+
+        fn show_cursor(&mut self) -> io::Result<()> {
+            self.inner.show_cursor()
+        }
+
+        fn get_cursor_position(&mut self) -> io::Result<Position> {
+            self.inner.get_cursor_position()
+        }
+
+        fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+            self.inner.set_cursor_position(position)
+        }
+
+        fn clear(&mut self) -> io::Result<()> {
+            self.inner.clear()
+        }
+
+        fn size(&self) -> io::Result<Size> {
+            self.inner.size()
+        }
+
+        fn window_size(&mut self) -> io::Result<WindowSize> {
+            self.inner.window_size()
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    impl Suspend for Terminal<CountingBackend> {
+        fn suspend(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn resume(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_loop_draws_nothing() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "Alpha\nBravo\n").unwrap();
+        let backend = LocalFileBackend::new(LocalFileConfig { path });
+        let mut config = crate::config::Config::default();
+        config.general.default_view = "all".to_string();
+        let mut app = App::new(BackendManager::new(vec![Box::new(backend)]), config);
+        app.refresh_tasks().await;
+
+        let draws = Arc::new(AtomicUsize::new(0));
+        let mut terminal = Terminal::new(CountingBackend {
+            inner: TestBackend::new(80, 24),
+            draws: Arc::clone(&draws),
+        })
+        .unwrap();
+        let theme = DynamicTheme::new(Theme::load("dark", None));
+        let (tx, mut rx) = unbounded_channel();
+        let input = InputReader { resume: channel().0 };
+
+        let keys = tokio::spawn(async move {
+            let key = |c| Wakeup::Input(Event::Key(KeyEvent::from(KeyCode::Char(c))));
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            tx.send(key('j')).unwrap();
+            tx.send(key('q')).unwrap();
+        });
+        event_loop(&mut terminal, &mut app, &theme, &mut rx, &input)
+            .await
+            .unwrap();
+        keys.await.unwrap();
+
+        // The first frame, then one for `j` after three idle seconds; `q`
+        // quits without drawing
+        assert_eq!(draws.load(Ordering::SeqCst), 2);
+        assert_eq!(app.selected_task, 1);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::config::Config;
+
+/// How a setting is changed from the settings popup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    /// Enter cycles through these values; "" means unset
+    Choice(&'static [&'static str]),
+    Toggle,
+    /// Enter edits the value inline; an empty value unsets it
+    Text,
+}
+
+/// A config option that can be changed without leaving the TUI.
+#[derive(Debug, Clone, Copy)]
+pub struct Setting {
+    /// Dotted path in config.toml, e.g. `general.theme`
+    pub key: &'static str,
+    pub label: &'static str,
+    pub kind: SettingKind,
+}
+
+pub const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "general.theme",
+        label: "Theme",
+        kind: SettingKind::Choice(&["omarchy", "dark", "light"]),
+    },
+    Setting {
+        key: "general.default_view",
+        label: "Default view",
+        kind: SettingKind::Choice(&["all", "today", "week", "upcoming", "notes", "done"]),
+    },
+    Setting {
+        key: "general.default_backend",
+        label: "Default backend",
+        kind: SettingKind::Choice(&["", "local", "obsidian"]),
+    },
+    Setting {
+        key: "general.layout",
+        label: "Layout",
+        kind: SettingKind::Choice(&["single", "split"]),
+    },
+    Setting {
+        key: "general.board_columns",
+        label: "Board columns",
+        kind: SettingKind::Choice(&["due", "priority"]),
+    },
+    Setting {
+        key: "general.flash_on_complete",
+        label: "Flash on complete",
+        kind: SettingKind::Toggle,
+    },
+    Setting {
+        key: "general.hash_tag_colors",
+        label: "Hash tag colors",
+        kind: SettingKind::Toggle,
+    },
+    Setting {
+        key: "general.status_bar",
+        label: "Status bar",
+        kind: SettingKind::Text,
+    },
+    Setting {
+        key: "waybar.tooltip_scope",
+        label: "Waybar tooltip",
+        kind: SettingKind::Choice(&["overdue_today", "all", "today_only"]),
+    },
+];
+
+impl Setting {
+    /// The value in effect (including defaults), or `None` if unset.
+    pub fn current(&self, config: &Config) -> Option<String> {
+        let (section, name) = self.key.split_once('.')?;
+        let value = toml::Value::try_from(config).ok()?;
+        match value.get(section)?.get(name)? {
+            toml::Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    /// What pressing Enter changes a `Choice` or `Toggle` to; `None` unsets.
+    pub fn next_value(&self, config: &Config) -> Option<toml_edit::Value> {
+        let current = self.current(config);
+        match self.kind {
+            SettingKind::Choice(options) => {
+                let current = current.unwrap_or_default();
+                let next = options
+                    .iter()
+                    .position(|o| *o == current)
+                    .map_or(0, |i| (i + 1) % options.len());
+                (!options[next].is_empty()).then(|| options[next].into())
+            }
+            SettingKind::Toggle => Some((current.as_deref() != Some("true")).into()),
+            SettingKind::Text => current.map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setting(key: &str) -> Setting {
+        *SETTINGS.iter().find(|s| s.key == key).unwrap()
+    }
+
+    #[test]
+    fn test_current_and_next_values() {
+        let mut config = Config::default();
+
+        let theme = setting("general.theme");
+        assert_eq!(theme.current(&config).as_deref(), Some("omarchy"));
+        assert_eq!(theme.next_value(&config).unwrap().as_str(), Some("dark"));
+        config.general.theme = "my-custom".to_string();
+        assert_eq!(theme.next_value(&config).unwrap().as_str(), Some("omarchy"));
+
+        let backend = setting("general.default_backend");
+        assert_eq!(backend.current(&config), None);
+        assert_eq!(backend.next_value(&config).unwrap().as_str(), Some("local"));
+        config.general.default_backend = Some("obsidian".to_string());
+        assert!(backend.next_value(&config).is_none());
+
+        let flash = setting("general.flash_on_complete");
+        assert_eq!(flash.current(&config).as_deref(), Some("true"));
+        assert_eq!(flash.next_value(&config).unwrap().as_bool(), Some(false));
+    }
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+
+/// What the TUI remembers between runs. Every field is optional so state
+/// files written by older versions still load.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    /// Active view, using the same names as `general.default_view`
+    pub view: Option<String>,
+    /// Collapsed flag per group, keyed by due date (`YYYY-MM-DD`) or `notes`
+    pub collapsed: HashMap<String, bool>,
+    pub selected_task: Option<String>,
+}
+
+impl SessionState {
+    pub fn path(config: &Config) -> Option<PathBuf> {
+        config.state_dir().map(|dir| dir.join("tui.json"))
+    }
+
+    /// Load saved state, starting fresh if the file is missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(_) => return Self::default(),
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring corrupt TUI state {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state").join("tui.json");
+
+        let state = SessionState {
+            view: Some("upcoming".to_string()),
+            collapsed: HashMap::from([
+                ("2025-03-15".to_string(), true),
+                ("notes".to_string(), false),
+            ]),
+            selected_task: Some("local:3".to_string()),
+        };
+        state.save(&path).unwrap();
+
+        assert_eq!(SessionState::load(&path), state);
+    }
+
+    #[test]
+    fn test_missing_or_corrupt_starts_fresh() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tui.json");
+        assert_eq!(SessionState::load(&path), SessionState::default());
+
+        fs::write(&path, "{ not json").unwrap();
+        assert_eq!(SessionState::load(&path), SessionState::default());
+    }
+
+    #[test]
+    fn test_partial_file_loads() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tui.json");
+        fs::write(&path, r#"{"view": "done"}"#).unwrap();
+
+        let state = SessionState::load(&path);
+        assert_eq!(state.view.as_deref(), Some("done"));
+        assert!(state.collapsed.is_empty());
+        assert!(state.selected_task.is_none());
+    }
+}
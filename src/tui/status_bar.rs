@@ -0,0 +1,280 @@
+use chrono::Local;
+use ratatui::{style::Style, text::Span};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::error::TasukiError;
+use crate::model::TaskStatus;
+use crate::output::{Segment, Template};
+use crate::timelog;
+use crate::tui::app::App;
+use crate::tui::theme::Theme;
+
+/// A `{placeholder}` in `general.status_bar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusField {
+    Pending,
+    Overdue,
+    Today,
+    Done,
+    Total,
+    Filter,
+    Search,
+    Group,
+    BackendCount,
+    Refreshed,
+}
+
+impl StatusField {
+    pub const NAMES: &'static [&'static str] = &[
+        "pending",
+        "overdue",
+        "today",
+        "done",
+        "total",
+        "filter",
+        "search",
+        "group",
+        "backend_count",
+        "refreshed",
+    ];
+
+    fn text(&self, app: &App) -> String {
+        let today = crate::clock::today();
+        let pending = || app.tasks.iter().filter(|t| t.status == TaskStatus::Pending);
+
+        match self {
+            StatusField::Pending => pending().count().to_string(),
+            StatusField::Overdue => pending()
+                .filter(|t| t.due.is_some_and(|d| d < today))
+                .count()
+                .to_string(),
+            StatusField::Today => pending()
+                .filter(|t| t.due == Some(today))
+                .count()
+                .to_string(),
+            StatusField::Done => app
+                .tasks
+                .iter()
+                .filter(|t| t.status == TaskStatus::Done)
+                .count()
+                .to_string(),
+            StatusField::Total => app.tasks.len().to_string(),
+            StatusField::Filter => match (&app.task_filter.search, &app.task_filter.tag) {
+                (Some(search), _) => format!("{} /{}", app.current_view.label(), search),
+                (None, Some(tag)) => format!("{} #{}", app.current_view.label(), tag),
+                (None, None) => app.current_view.label().to_string(),
+            },
+            StatusField::Search => match app.task_filter.tag {
+                Some(ref tag) => format!("#{}", tag),
+                None => app.task_filter.search.clone().unwrap_or_default(),
+            },
+            StatusField::Group => app.group_by.name().to_string(),
+            StatusField::BackendCount => app.backend_manager.len().to_string(),
+            StatusField::Refreshed => app
+                .last_refresh
+                .map(|at| at.format("%H:%M:%S").to_string())
+                .unwrap_or_else(|| "never".to_string()),
+        }
+    }
+
+    /// Counts that need attention are colored by severity; zero stays muted.
+    fn style(&self, text: &str, theme: &Theme) -> Style {
+        let nonzero = text != "0";
+        match self {
+            StatusField::Overdue if nonzero => theme.style_error(),
+            StatusField::Today if nonzero => theme.style_warning(),
+            StatusField::Pending if nonzero => theme.style_accent(),
+            StatusField::Done if nonzero => theme.style_success(),
+            StatusField::Pending
+            | StatusField::Overdue
+            | StatusField::Today
+            | StatusField::Done => theme.style_muted(),
+            _ => theme.style_default(),
+        }
+    }
+}
+
+impl FromStr for StatusField {
+    type Err = TasukiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(StatusField::Pending),
+            "overdue" => Ok(StatusField::Overdue),
+            "today" => Ok(StatusField::Today),
+            "done" => Ok(StatusField::Done),
+            "total" => Ok(StatusField::Total),
+            "filter" => Ok(StatusField::Filter),
+            "search" => Ok(StatusField::Search),
+            "group" => Ok(StatusField::Group),
+            "backend_count" => Ok(StatusField::BackendCount),
+            "refreshed" => Ok(StatusField::Refreshed),
+            _ => Err(TasukiError::Parse(format!(
+                "Unknown status bar placeholder '{{{}}}' (valid: {})",
+                s,
+                StatusField::NAMES.join(", ")
+            ))),
+        }
+    }
+}
+
+/// "refreshed 12s ago" for the status bar title, warning-colored once older
+/// than `general.stale_after_secs` or if a backend failed last time. A
+/// failed backend showing its last fetched tasks is marked "stale".
+pub fn freshness_span(app: &App, theme: &Theme) -> Span<'static> {
+    let Some(last) = app.last_refresh else {
+        return Span::styled(" not refreshed yet ", theme.style_warning());
+    };
+    let age = (Local::now() - last).num_seconds().max(0);
+    let failed: Vec<String> = app
+        .fetch_reports
+        .iter()
+        .filter(|r| r.result.is_err())
+        .map(|r| {
+            let state = if r.stale { "stale" } else { "failed" };
+            format!("{} {}", r.source.name(), state)
+        })
+        .collect();
+
+    // Under a minute the label doesn't tick, so an idle TUI needn't redraw
+    let mut text = if age < 60 {
+        " refreshed just now ".to_string()
+    } else {
+        format!(" refreshed {} ago ", format_age(age))
+    };
+    if !failed.is_empty() {
+        text.push_str(&format!("· {} ", failed.join(", ")));
+    }
+
+    let stale = age as u64 > app.config.general.stale_after_secs || !failed.is_empty();
+    let style = if stale {
+        theme.style_warning()
+    } else {
+        theme.style_muted()
+    };
+    Span::styled(text, style)
+}
+
+/// How long until `freshness_span` would read differently: the age label
+/// moves on, or the list turns stale. `None` if it never will.
+pub fn freshness_changes_in(app: &App) -> Option<Duration> {
+    let last = app.last_refresh?;
+    let age = (Local::now() - last).num_seconds().max(0) as u64;
+    let unit = match age {
+        a if a < 3600 => 60,
+        a if a < 86400 => 3600,
+        _ => 86400,
+    };
+    let mut next = (age / unit + 1) * unit;
+    let stale_at = app.config.general.stale_after_secs + 1;
+    if age < stale_at {
+        next = next.min(stale_at);
+    }
+    Some(Duration::from_secs(next - age))
+}
+
+/// " ▶ Write report 25m " while a task is being timed.
+pub fn timer_span(app: &App, theme: &Theme) -> Option<Span<'static>> {
+    let timer = app.timer.as_ref()?;
+    let elapsed = timer.elapsed(Local::now().naive_local());
+    Some(Span::styled(
+        format!(
+            " ▶ {} {} ",
+            crate::output::single_line(&timer.title),
+            timelog::format_duration(elapsed)
+        ),
+        theme.style_accent(),
+    ))
+}
+
+/// How long until `timer_span` shows another minute. `None` if no task is
+/// being timed.
+pub fn timer_changes_in(app: &App) -> Option<Duration> {
+    let timer = app.timer.as_ref()?;
+    let secs = timer
+        .elapsed(Local::now().naive_local())
+        .num_seconds()
+        .max(0) as u64;
+    Some(Duration::from_secs(60 - secs % 60))
+}
+
+/// Compact age: "12s", "3m", "2h", "4d".
+fn format_age(secs: i64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+/// Parsed `general.status_bar`, e.g. "{pending} pending · {overdue} overdue".
+pub type StatusBarTemplate = Template<StatusField>;
+
+impl StatusBarTemplate {
+    /// `general.status_bar`, if set. One that doesn't parse is left out
+    /// with a warning; `config check` reports it.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let template = config.general.status_bar.as_deref()?;
+        template
+            .parse()
+            .map_err(|e| tracing::warn!("general.status_bar: {}", e))
+            .ok()
+    }
+
+    pub fn spans(&self, app: &App, theme: &Theme) -> Vec<Span<'static>> {
+        self.segments()
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => Span::styled(text.clone(), theme.style_muted()),
+                Segment::Field(field) => {
+                    let text = field.text(app);
+                    let style = field.style(&text, theme);
+                    Span::styled(text, style)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        let template: StatusBarTemplate = "{pending} pending · {backend_count} backends"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            template.segments(),
+            vec![
+                Segment::Field(StatusField::Pending),
+                Segment::Literal(" pending · ".to_string()),
+                Segment::Field(StatusField::BackendCount),
+                Segment::Literal(" backends".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(0), "0s");
+        assert_eq!(format_age(59), "59s");
+        assert_eq!(format_age(60), "1m");
+        assert_eq!(format_age(3599), "59m");
+        assert_eq!(format_age(7200), "2h");
+        assert_eq!(format_age(86400 * 3), "3d");
+    }
+
+    #[test]
+    fn test_unknown_placeholder_rejected() {
+        let err = "{pending} {bogus}"
+            .parse::<StatusBarTemplate>()
+            .unwrap_err();
+        assert!(err.to_string().contains("{bogus}"));
+        assert!("{pending".parse::<StatusBarTemplate>().is_err());
+    }
+}
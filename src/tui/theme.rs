@@ -1,8 +1,10 @@
 use ratatui::style::{Color, Style};
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
+use crate::config::Config;
+
 #[derive(Debug, Clone)]
 pub struct Theme {
     pub background: Color,
@@ -19,19 +21,21 @@ pub struct Theme {
 
 impl Default for Theme {
     fn default() -> Self {
-        Self::load("omarchy")
+        Self::load("omarchy", None)
     }
 }
 
 impl Theme {
-    pub fn load(name: &str) -> Self {
+    /// Load a theme by name. Custom themes are read from `themes/<name>.toml`
+    /// under `config_dir` (the XDG config dir when `None`).
+    pub fn load(name: &str, config_dir: Option<&Path>) -> Self {
         match name {
             "omarchy" => Self::try_omarchy_tasuki()
                 .or_else(Self::try_omarchy_colors)
                 .unwrap_or_else(Self::dark),
             "dark" => Self::dark(),
             "light" => Self::light(),
-            custom => Self::try_custom(custom).unwrap_or_else(Self::dark),
+            custom => Self::try_custom(custom, config_dir).unwrap_or_else(Self::dark),
         }
     }
 
@@ -66,11 +70,11 @@ impl Theme {
         })
     }
 
-    fn try_custom(name: &str) -> Option<Self> {
-        let path = dirs::config_dir()?
-            .join("tasuki")
-            .join("themes")
-            .join(format!("{}.toml", name));
+    fn try_custom(name: &str, config_dir: Option<&Path>) -> Option<Self> {
+        let dir = config_dir
+            .map(Path::to_path_buf)
+            .or_else(crate::paths::config_dir)?;
+        let path = dir.join("themes").join(format!("{}.toml", name));
         if !path.exists() {
             return None;
         }
@@ -80,18 +84,18 @@ impl Theme {
     }
 
     pub fn watch_path(&self) -> Option<PathBuf> {
-        let home = std::env::var("HOME").ok()?;
-        Some(PathBuf::from(home).join(".config/omarchy/current"))
+        let home = crate::paths::home_dir()?;
+        Some(home.join(".config/omarchy/current"))
     }
 
     fn omarchy_theme_path() -> Option<PathBuf> {
-        let home = std::env::var("HOME").ok()?;
-        Some(PathBuf::from(home).join(".config/omarchy/current/theme/tasuki.toml"))
+        let home = crate::paths::home_dir()?;
+        Some(home.join(".config/omarchy/current/theme/tasuki.toml"))
     }
 
     fn omarchy_colors_path() -> Option<PathBuf> {
-        let home = std::env::var("HOME").ok()?;
-        Some(PathBuf::from(home).join(".config/omarchy/current/theme/colors.toml"))
+        let home = crate::paths::home_dir()?;
+        Some(home.join(".config/omarchy/current/theme/colors.toml"))
     }
 
     #[cfg(test)]
@@ -162,6 +166,49 @@ impl Theme {
     pub fn style_highlight(&self) -> Style {
         Style::default().fg(self.highlight)
     }
+
+    /// Style for a `#tag`: its `[tags.<name>]` color if set, else a color
+    /// hashed from the name when `hash_tag_colors` is on, else highlight.
+    /// Case is ignored, so `#Work` looks like `#work`.
+    pub fn style_tag(&self, tag: &str, config: &Config) -> Style {
+        // A nested tag without its own color takes its nearest parent's
+        let mut name = tag;
+        loop {
+            if let Some(color) = config
+                .tags
+                .get(name)
+                .or_else(|| {
+                    config
+                        .tags
+                        .iter()
+                        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                        .map(|(_, tag)| tag)
+                })
+                .and_then(|t| t.color.as_deref())
+                .and_then(hex_to_color)
+            {
+                return Style::default().fg(color);
+            }
+            match name.rsplit_once('/') {
+                Some((parent, _)) => name = parent,
+                None => break,
+            }
+        }
+
+        if config.general.hash_tag_colors {
+            // FNV-1a, so a tag keeps its color across runs and builds
+            let hash = tag
+                .to_lowercase()
+                .bytes()
+                .fold(0xcbf29ce484222325u64, |hash, b| {
+                    (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+                });
+            let palette = [self.accent, self.success, self.warning, self.highlight];
+            return Style::default().fg(palette[(hash % palette.len() as u64) as usize]);
+        }
+
+        self.style_highlight()
+    }
 }
 
 pub struct DynamicTheme {
@@ -263,7 +310,7 @@ struct OmarchyColorFile {
     color15: String,
 }
 
-fn hex_to_color(hex: &str) -> Option<Color> {
+pub fn hex_to_color(hex: &str) -> Option<Color> {
     let hex = hex.trim_start_matches('#');
     if hex.len() != 6 {
         return None;
@@ -295,13 +342,76 @@ mod tests {
         assert_ne!(theme.background, Color::Black);
     }
 
+    #[test]
+    fn test_custom_theme_from_config_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("themes")).unwrap();
+        std::fs::write(
+            dir.path().join("themes/portable.toml"),
+            r##"
+name = "portable"
+
+[colors]
+background = "#101010"
+foreground = "#eeeeee"
+accent = "#ff8800"
+success = "#00ff00"
+warning = "#ffff00"
+error = "#ff0000"
+muted = "#777777"
+highlight = "#ff00ff"
+selection_bg = "#333333"
+selection_fg = "#ffffff"
+"##,
+        )
+        .unwrap();
+
+        let theme = Theme::load("portable", Some(dir.path()));
+        assert_eq!(theme.background, Color::Rgb(0x10, 0x10, 0x10));
+        assert_eq!(theme.accent, Color::Rgb(0xff, 0x88, 0x00));
+
+        let missing = Theme::load("portable", Some(&dir.path().join("elsewhere")));
+        assert_eq!(missing.background, Theme::dark().background);
+    }
+
     #[test]
     fn test_watch_path() {
-        let theme = Theme::load("omarchy");
+        let theme = Theme::load("omarchy", None);
         let path = theme.watch_path();
         println!("Watch path: {:?}", path);
         if Theme::omarchy_available() {
             assert!(path.is_some());
         }
     }
+
+    #[test]
+    fn test_tag_style_lookup() {
+        let theme = Theme::dark();
+        let mut config = Config::default();
+        config.tags.insert(
+            "urgent".to_string(),
+            crate::config::TagConfig {
+                color: Some("#ff0000".to_string()),
+            },
+        );
+
+        assert_eq!(
+            theme.style_tag("urgent", &config),
+            Style::default().fg(Color::Rgb(255, 0, 0))
+        );
+        assert_eq!(
+            theme.style_tag("Urgent", &config),
+            Style::default().fg(Color::Rgb(255, 0, 0))
+        );
+        assert_eq!(theme.style_tag("work", &config), theme.style_highlight());
+
+        config.general.hash_tag_colors = true;
+        let hashed = theme.style_tag("work", &config);
+        assert_eq!(hashed, theme.style_tag("work", &config));
+        assert_eq!(hashed, theme.style_tag("Work", &config));
+        assert_eq!(
+            theme.style_tag("urgent", &config),
+            Style::default().fg(Color::Rgb(255, 0, 0))
+        );
+    }
 }
@@ -1,8 +1,14 @@
-use ratatui::Frame;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
 
 use crate::tui::app::{App, AppMode};
 use crate::tui::theme::Theme;
-use crate::tui::views::{confirm, quick_add, task_list};
+use crate::tui::views::{board, confirm, detail, focus, quick_add, settings, task_list};
+
+/// Narrower than this, the split layout falls back to a single list.
+const SPLIT_MIN_WIDTH: u16 = 100;
 
 pub fn render(f: &mut Frame, app: &mut App, theme: &Theme) {
     let area = f.area();
@@ -12,21 +18,55 @@ pub fn render(f: &mut Frame, app: &mut App, theme: &Theme) {
         area,
     );
 
-    match app.mode {
-        AppMode::Normal | AppMode::Input => {
-            task_list::draw_task_list(f, app, theme, area);
+    if app.focus && app.mode == AppMode::Normal {
+        focus::draw_focus(f, app, theme, area);
+        return;
+    }
+
+    draw_main(f, app, theme, area);
 
-            if app.mode == AppMode::Input {
-                quick_add::draw_input(f, app, theme, area);
-            }
+    match app.mode {
+        AppMode::Normal => {}
+        AppMode::Input => {
+            quick_add::draw_input(f, app, theme, area);
         }
         AppMode::Help => {
-            task_list::draw_task_list(f, app, theme, area);
-            task_list::draw_help(f, theme, area);
+            task_list::draw_help(f, app, theme, area);
         }
         AppMode::Confirm => {
-            task_list::draw_task_list(f, app, theme, area);
             confirm::draw_confirm(f, app, theme, area);
         }
+        AppMode::Alert => {
+            confirm::draw_alert(f, app, theme, area);
+        }
+        AppMode::Settings => {
+            settings::draw_settings(f, app, theme, area);
+        }
     }
 }
+
+/// The board, or the task list with its optional detail pane.
+fn draw_main(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    if app.board {
+        board::draw_board(f, app, theme, area);
+        return;
+    }
+
+    let list_area = draw_detail_pane(f, app, theme, area);
+    task_list::draw_task_list(f, app, theme, list_area);
+}
+
+/// Draw the detail pane on the right when the split layout is on and the
+/// terminal is wide enough; returns the area left for the task list.
+fn draw_detail_pane(f: &mut Frame, app: &App, theme: &Theme, area: Rect) -> Rect {
+    if !app.split || area.width < SPLIT_MIN_WIDTH {
+        return area;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(area);
+    detail::draw_detail(f, app, theme, chunks[1]);
+    chunks[0]
+}
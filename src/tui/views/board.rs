@@ -0,0 +1,81 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Modifier,
+    widgets::{Block, Borders, List, ListItem},
+    Frame,
+};
+
+use crate::tui::app::App;
+use crate::tui::theme::Theme;
+use crate::tui::views::task_list::{draw_status_bar, format_task_line};
+
+pub fn draw_board(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+
+    let count = app.board_columns.len() as u32;
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, count.max(1)); count as usize])
+        .split(chunks[0]);
+
+    let today = crate::clock::today();
+    let escalate_after = app.config.general.escalate_overdue_after_days;
+    let board = &app.board_columns;
+
+    for (index, (column, column_area)) in board.iter().zip(columns.iter()).enumerate() {
+        let focused = index == app.board_column;
+
+        let items: Vec<ListItem> = column
+            .tasks
+            .iter()
+            .map(|task| {
+                let flashing = app.is_flashing(&task.id);
+                let escalated = task.is_escalated(today, escalate_after);
+                let line = format_task_line(
+                    task,
+                    theme,
+                    &app.config,
+                    column_area.width,
+                    flashing,
+                    escalated,
+                    app.is_timing(&task.id),
+                );
+                ListItem::new(line)
+            })
+            .collect();
+
+        let border_style = if focused {
+            theme.style_accent()
+        } else {
+            theme.style_muted()
+        };
+        let highlight_style = if focused {
+            theme.style_selected().add_modifier(Modifier::BOLD)
+        } else {
+            theme.style_default()
+        };
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!(" {} ({}) ", column.label, column.tasks.len()))
+                    .borders(Borders::ALL)
+                    .border_style(border_style),
+            )
+            .highlight_style(highlight_style);
+
+        let state = &mut app.board_states[index];
+        if column.tasks.is_empty() {
+            state.select(None);
+        } else {
+            let selected = state.selected().unwrap_or(0).min(column.tasks.len() - 1);
+            state.select(Some(selected));
+        }
+        f.render_stateful_widget(list, *column_area, state);
+    }
+
+    draw_status_bar(f, app, theme, chunks[1]);
+}
@@ -1,10 +1,11 @@
 use ratatui::{
     layout::{Alignment, Rect},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
 use crate::tui::app::App;
+use crate::tui::hints;
 use crate::tui::theme::Theme;
 
 pub fn draw_confirm(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
@@ -22,9 +23,34 @@ pub fn draw_confirm(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
     f.render_widget(Clear, popup);
     f.render_widget(text, popup);
 
-    let hint = Paragraph::new("y / Enter = confirm    n / Esc = cancel")
-        .style(theme.style_muted())
-        .alignment(Alignment::Center);
+    let hint = Paragraph::new(hints::hint_line(app, theme)).alignment(Alignment::Center);
+
+    let hint_area = Rect {
+        x: popup.x,
+        y: popup.y + popup.height + 1,
+        width: popup.width,
+        height: 1,
+    };
+    f.render_widget(hint, hint_area);
+}
+
+/// Error overlay in the same style as the confirm dialog; any key closes it.
+pub fn draw_alert(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let block = Block::default()
+        .title(" Error ")
+        .borders(Borders::ALL)
+        .border_style(theme.style_error());
+
+    let text = Paragraph::new(app.alert_message.clone())
+        .block(block)
+        .style(theme.style_default())
+        .wrap(Wrap { trim: false });
+
+    let popup = super::centered_rect(70, 40, area);
+    f.render_widget(Clear, popup);
+    f.render_widget(text, popup);
+
+    let hint = Paragraph::new(hints::hint_line(app, theme)).alignment(Alignment::Center);
 
     let hint_area = Rect {
         x: popup.x,
@@ -0,0 +1,61 @@
+use ratatui::{
+    layout::Rect,
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::output::detail_rows;
+use crate::tui::app::App;
+use crate::tui::theme::Theme;
+
+pub fn draw_detail(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let block = Block::default()
+        .title(" Details ")
+        .borders(Borders::ALL)
+        .border_style(theme.style_muted());
+
+    let Some(task) = app.get_selected_visible_task() else {
+        let empty =
+            Paragraph::new(Span::styled("No task selected", theme.style_muted())).block(block);
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let today = crate::clock::today();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            task.title.clone(),
+            theme.style_default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for (name, value) in detail_rows(&task, &app.config, today) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<10}", name), theme.style_muted()),
+            Span::styled(value, theme.style_default()),
+        ]));
+    }
+
+    let details = app
+        .details
+        .as_ref()
+        .filter(|(id, _)| *id == task.id)
+        .map(|(_, lines)| lines.as_slice())
+        .unwrap_or_default();
+    if !details.is_empty() {
+        lines.push(Line::from(""));
+        for detail in details {
+            lines.push(Line::from(Span::styled(
+                detail.clone(),
+                theme.style_default(),
+            )));
+        }
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
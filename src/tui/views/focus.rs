@@ -0,0 +1,84 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::model::Priority;
+use crate::output::relative_date;
+use crate::tui::app::{App, StatusLevel};
+use crate::tui::hints;
+use crate::tui::theme::Theme;
+
+pub fn draw_focus(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let block = Block::default()
+        .title(" Focus ")
+        .borders(Borders::ALL)
+        .border_style(theme.style_muted());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(5),
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let body = match app.focus_task() {
+        Some(task) => {
+            let today = crate::clock::today();
+            let title_style = match task.priority {
+                Priority::High => theme.style_error(),
+                _ => theme.style_default(),
+            }
+            .add_modifier(Modifier::BOLD);
+
+            let mut details = Vec::new();
+            if let Some(due) = task.due {
+                details.push(format!("due {}", relative_date(due, today)));
+            }
+            for tag in &task.tags {
+                details.push(format!("#{}", tag));
+            }
+            details.push(format!("[{}]", task.source.name()));
+
+            vec![
+                Line::from(Span::styled(task.title.clone(), title_style)),
+                Line::from(""),
+                Line::from(Span::styled(details.join("  "), theme.style_muted())),
+            ]
+        }
+        None => vec![Line::from(Span::styled(
+            "Nothing to do. Press f to leave focus mode.",
+            theme.style_muted(),
+        ))],
+    };
+
+    let text = Paragraph::new(body)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(text, chunks[1]);
+
+    if let Some((msg, level)) = &app.status_message {
+        let style = match level {
+            StatusLevel::Info => theme.style_default(),
+            StatusLevel::Success => theme.style_success(),
+            StatusLevel::Warning => theme.style_warning(),
+            StatusLevel::Error => theme.style_error(),
+        };
+        let status = Paragraph::new(msg.clone())
+            .style(style)
+            .alignment(Alignment::Center);
+        f.render_widget(status, chunks[3]);
+    }
+
+    let hint = hints::hint_line(app, theme);
+    f.render_widget(Paragraph::new(hint).alignment(Alignment::Center), chunks[4]);
+}
@@ -1,5 +1,9 @@
+pub mod board;
 pub mod confirm;
+pub mod detail;
+pub mod focus;
 pub mod quick_add;
+pub mod settings;
 pub mod task_list;
 
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
@@ -1,9 +1,12 @@
 use ratatui::{
     layout::{Alignment, Rect},
+    style::Style,
+    text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
+use crate::nlp::{parse_quick_add_with, QuickAddOptions, TokenKind};
 use crate::tui::app::{App, InputMode};
 use crate::tui::theme::Theme;
 
@@ -12,6 +15,8 @@ pub fn draw_input(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
         Some(InputMode::QuickAdd) => " Quick Add ",
         Some(InputMode::Search) => " Search ",
         Some(InputMode::EditTask(_)) => " Edit Task ",
+        Some(InputMode::Reschedule(_)) => " Due Date ",
+        Some(InputMode::EditSetting(_)) => " Edit Setting ",
         None => " Input ",
     };
 
@@ -20,7 +25,17 @@ pub fn draw_input(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
         .borders(Borders::ALL)
         .border_style(theme.style_accent());
 
-    let input = Paragraph::new(app.input_buffer.clone())
+    let line = match &app.input_mode {
+        Some(InputMode::QuickAdd) | Some(InputMode::EditTask(_)) => preview_line(
+            &app.input_buffer,
+            &app.backend_manager.quick_add_options(&app.config),
+            app.config.general.date_format(),
+            theme,
+        ),
+        _ => Line::from(app.input_buffer.clone()),
+    };
+
+    let input = Paragraph::new(line)
         .block(input_block)
         .style(theme.style_default());
 
@@ -34,10 +49,14 @@ pub fn draw_input(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
 
     let hint_text = match &app.input_mode {
         Some(InputMode::QuickAdd) => {
-            "Supports: #tags @backends (p1/p2/p3) today/tomorrow/YYYY-MM-DD"
+            "Supports: #tags @backends p1/p2/p3 or !/!!/!!! today/tomorrow/YYYY-MM-DD eod/tonight"
         }
         Some(InputMode::Search) => "Type to filter tasks, Enter to confirm, Esc to cancel",
         Some(InputMode::EditTask(_)) => "Edit task and press Enter to save, Esc to cancel",
+        Some(InputMode::Reschedule(_)) => {
+            "today/tomorrow/a weekday/YYYY-MM-DD, empty to clear; Enter to save, Esc to cancel"
+        }
+        Some(InputMode::EditSetting(_)) => "Enter to save (empty to unset), Esc to cancel",
         None => "",
     };
 
@@ -53,3 +72,54 @@ pub fn draw_input(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
     };
     f.render_widget(hint, hint_area);
 }
+
+/// Colour each word of the input by how quick-add will interpret it. A
+/// time word like `eod` is followed by the day and time it resolved to.
+fn preview_line<'a>(
+    input: &'a str,
+    options: &QuickAddOptions,
+    date_format: &str,
+    theme: &Theme,
+) -> Line<'a> {
+    let Ok(parsed) = parse_quick_add_with(input, options) else {
+        return Line::from(input);
+    };
+
+    let style_for = |kind| match kind {
+        TokenKind::Title => theme.style_default(),
+        TokenKind::Tag => theme.style_accent(),
+        TokenKind::Priority => theme.style_error(),
+        TokenKind::Due => theme.style_warning(),
+        TokenKind::Backend => theme.style_muted(),
+        TokenKind::Context => theme.style_highlight(),
+    };
+
+    let mut spans = Vec::new();
+    let mut tokens = parsed.tokens.iter();
+    let mut rest = input;
+    while !rest.is_empty() {
+        let word_start = rest
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len());
+        if word_start > 0 {
+            spans.push(Span::raw(&rest[..word_start]));
+            rest = &rest[word_start..];
+            continue;
+        }
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let style = tokens
+            .next()
+            .map_or(Style::default(), |t| style_for(t.kind));
+        spans.push(Span::styled(&rest[..word_end], style));
+        rest = &rest[word_end..];
+    }
+
+    if let (Some(due), Some(time)) = (parsed.due, parsed.due_time) {
+        spans.push(Span::styled(
+            format!("  → {} {}", due.format(date_format), time.format("%H:%M")),
+            theme.style_muted(),
+        ));
+    }
+
+    Line::from(spans)
+}
@@ -0,0 +1,59 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::tui::app::App;
+use crate::tui::hints;
+use crate::tui::settings::{SettingKind, SETTINGS};
+use crate::tui::theme::Theme;
+
+pub fn draw_settings(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let items: Vec<ListItem> = SETTINGS
+        .iter()
+        .map(|setting| {
+            let value = setting
+                .current(&app.config)
+                .unwrap_or_else(|| "(unset)".to_string());
+            let hint = match setting.kind {
+                SettingKind::Choice(_) | SettingKind::Toggle => "",
+                SettingKind::Text => " ✎",
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<20}", setting.label), theme.style_default()),
+                Span::styled(value, theme.style_accent()),
+                Span::styled(hint, theme.style_muted()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(" Settings ")
+                .borders(Borders::ALL)
+                .border_style(theme.style_accent()),
+        )
+        .style(theme.style_default())
+        .highlight_style(theme.style_selected().add_modifier(Modifier::BOLD));
+
+    let popup = super::centered_rect(60, 50, area);
+    let mut state = ListState::default().with_selected(Some(app.settings_selected));
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut state);
+
+    let mut hint = hints::hint_line(app, theme);
+    hint.push_span(Span::styled(" · saved to config.toml", theme.style_muted()));
+    let hint = Paragraph::new(hint).alignment(Alignment::Center);
+
+    let hint_area = Rect {
+        x: popup.x,
+        y: popup.y + popup.height + 1,
+        width: popup.width,
+        height: 1,
+    };
+    f.render_widget(hint, hint_area);
+}
@@ -1,13 +1,21 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::Modifier,
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState,
+    },
     Frame,
 };
 
-use crate::model::{Priority, Task, TaskStatus};
+use crate::backends::BackendManager;
+use crate::config::Config;
+use crate::model::{BackendSource, Priority, Task, TaskStatus};
+use crate::output::{self, display_width};
 use crate::tui::app::App;
+use crate::tui::hints;
+use crate::tui::status_bar::{freshness_span, timer_span};
 use crate::tui::theme::Theme;
 
 pub fn draw_task_list(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
@@ -21,8 +29,10 @@ pub fn draw_task_list(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
 
     let mut items: Vec<ListItem> = Vec::new();
     let mut visible_idx = 0;
+    let today = crate::clock::today();
+    let escalate_after = app.config.general.escalate_overdue_after_days;
 
-    for (_group_idx, group) in app.task_groups.iter().enumerate() {
+    for group in app.task_groups.iter() {
         let is_selected = visible_idx == app.selected_task;
         let group_style = if is_selected {
             theme.style_selected().add_modifier(Modifier::BOLD)
@@ -42,13 +52,25 @@ pub fn draw_task_list(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
         if !group.collapsed {
             for task in &group.tasks {
                 let is_selected = visible_idx == app.selected_task;
-                let style = if is_selected {
+                let flashing = app.is_flashing(&task.id);
+                let style = if flashing {
+                    theme.style_success().add_modifier(Modifier::REVERSED)
+                } else if is_selected {
                     theme.style_selected().add_modifier(Modifier::BOLD)
                 } else {
                     theme.style_default()
                 };
 
-                let content = format_task_line(task, theme, task_area.width);
+                let escalated = task.is_escalated(today, escalate_after);
+                let content = format_task_line(
+                    task,
+                    theme,
+                    &app.config,
+                    task_area.width,
+                    flashing,
+                    escalated,
+                    app.is_timing(&task.id),
+                );
                 items.push(ListItem::new(content).style(style));
                 visible_idx += 1;
             }
@@ -56,23 +78,69 @@ pub fn draw_task_list(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
     }
 
     if items.is_empty() {
-        items.push(
-            ListItem::new("No tasks found. Press 'a' to add a task.").style(theme.style_muted()),
-        );
+        items.push(ListItem::new(app.empty_message()).style(theme.style_muted()));
     }
 
-    let tasks_block = Block::default()
+    let total_rows = items.len();
+    let viewport = task_area.height.saturating_sub(2) as usize;
+
+    let mut tasks_block = Block::default()
         .title(format!(
-            " {} ({}) ",
+            " Tasks · {} ({}){} ",
             app.current_view.label(),
-            app.tasks.len()
+            app.tasks.len(),
+            if app.reverse { " · reversed" } else { "" }
         ))
         .borders(Borders::ALL)
         .border_style(theme.style_muted());
+    if total_rows > viewport {
+        tasks_block = tasks_block.title_bottom(
+            Line::from(format!(" row {} of {} ", app.selected_task + 1, total_rows))
+                .right_aligned(),
+        );
+    }
 
     let list = List::new(items).block(tasks_block);
     f.render_stateful_widget(list, task_area, &mut app.list_state);
 
+    // Drawn over the right border, after the list has settled its offset
+    if let Some(mut state) = scroll_state(total_rows, app.list_state.offset(), viewport) {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .style(theme.style_muted());
+        f.render_stateful_widget(
+            scrollbar,
+            task_area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut state,
+        );
+    }
+
+    draw_status_bar(f, app, theme, status_area);
+}
+
+/// The task list's scrollbar with `total` rows scrolled down by `offset` and
+/// `viewport` rows on screen; `None` when every row fits.
+fn scroll_state(total: usize, offset: usize, viewport: usize) -> Option<ScrollbarState> {
+    if viewport == 0 || total <= viewport {
+        return None;
+    }
+    // One position per possible offset, so the thumb reaches the bottom
+    // exactly when the last row comes into view
+    let last_offset = total - viewport;
+    Some(
+        ScrollbarState::new(last_offset + 1)
+            .position(offset.min(last_offset))
+            .viewport_content_length(viewport),
+    )
+}
+
+/// Bottom bar: the current status message, else `general.status_bar` or the
+/// key hints for the current mode.
+pub fn draw_status_bar(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
     let status_text = if let Some((msg, level)) = &app.status_message {
         let style = match level {
             crate::tui::app::StatusLevel::Info => theme.style_default(),
@@ -81,32 +149,32 @@ pub fn draw_task_list(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
             crate::tui::app::StatusLevel::Error => theme.style_error(),
         };
         Line::from(vec![Span::styled(msg.clone(), style)])
+    } else if let Some(ref template) = app.status_bar {
+        Line::from(template.spans(app, theme))
     } else {
-        Line::from(vec![
-            Span::styled("↑/↓", theme.style_accent()),
-            Span::styled(" navigate  ", theme.style_muted()),
-            Span::styled("v", theme.style_accent()),
-            Span::styled(" view  ", theme.style_muted()),
-            Span::styled("a", theme.style_accent()),
-            Span::styled(" add  ", theme.style_muted()),
-            Span::styled("x", theme.style_accent()),
-            Span::styled(" toggle  ", theme.style_muted()),
-            Span::styled("/", theme.style_accent()),
-            Span::styled(" search  ", theme.style_muted()),
-            Span::styled("?", theme.style_accent()),
-            Span::styled(" help", theme.style_muted()),
-        ])
+        hints::hint_line(app, theme)
     };
 
-    let status_bar = Paragraph::new(Text::from(vec![status_text])).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(theme.style_muted()),
-    );
-    f.render_widget(status_bar, status_area);
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.style_muted())
+        .title(Line::from(freshness_span(app, theme)).right_aligned());
+    if let Some(span) = timer_span(app, theme) {
+        block = block.title(Line::from(span));
+    }
+    let status_bar = Paragraph::new(Text::from(vec![status_text])).block(block);
+    f.render_widget(status_bar, area);
 }
 
-fn format_task_line<'a>(task: &'a Task, theme: &'a Theme, width: u16) -> Line<'a> {
+pub fn format_task_line<'a>(
+    task: &'a Task,
+    theme: &'a Theme,
+    config: &Config,
+    width: u16,
+    flashing: bool,
+    escalated: bool,
+    timing: bool,
+) -> Line<'a> {
     let icon = match task.status {
         TaskStatus::Pending => "☐",
         TaskStatus::Done => "✓",
@@ -117,14 +185,22 @@ fn format_task_line<'a>(task: &'a Task, theme: &'a Theme, width: u16) -> Line<'a
         TaskStatus::Done => theme.style_success(),
     };
 
-    let priority_marker = match task.priority {
+    // Escalated tasks display as high priority with their own marker
+    let priority = if escalated {
+        Priority::High
+    } else {
+        task.priority
+    };
+
+    let priority_marker = match priority {
+        Priority::High if escalated => "[↑] ",
         Priority::High => "[!] ",
         Priority::Medium => "",
         Priority::Low => "",
         Priority::None => "",
     };
 
-    let priority_style = match task.priority {
+    let priority_style = match priority {
         Priority::High => theme.style_error(),
         Priority::Medium => theme.style_warning(),
         Priority::Low => theme.style_muted(),
@@ -132,28 +208,40 @@ fn format_task_line<'a>(task: &'a Task, theme: &'a Theme, width: u16) -> Line<'a
     };
 
     let source_label = format!("[{}]", task.source.name());
-    let is_pinned = task.tags.iter().any(|t| t == "pin");
+    let is_pinned = task.tags.iter().any(|t| t.eq_ignore_ascii_case("pin"));
 
     let mut tag_str = String::new();
     for tag in &task.tags {
         tag_str.push_str(&format!("#{} ", tag));
     }
 
+    let title = output::single_line(&task.title);
+
+    // Done late is flagged for review rather than hidden
+    let late_marker = if task.was_completed_late() {
+        "late "
+    } else {
+        ""
+    };
+
     let pin_len = if is_pinned { 3 } else { 0 };
+    let timer_len = if timing { 2 } else { 0 };
     let ctx_len = task
         .heading_context
         .as_ref()
-        .map(|c| c.len() + 3)
+        .map(|c| display_width(c) + 3)
         .unwrap_or(0);
     let left_len = 2
         + icon.len()
         + 1
+        + timer_len
         + pin_len
         + ctx_len
-        + priority_marker.len()
-        + task.title.len()
+        + priority_marker.chars().count()
+        + display_width(&title)
         + 1
-        + tag_str.len();
+        + late_marker.len()
+        + display_width(&tag_str);
     let right_len = source_label.len();
     let available = width.saturating_sub(2) as usize;
 
@@ -168,6 +256,10 @@ fn format_task_line<'a>(task: &'a Task, theme: &'a Theme, width: u16) -> Line<'a
         Span::styled(format!("{} ", icon), icon_style),
     ];
 
+    if timing {
+        spans.push(Span::styled("▶ ", theme.style_accent()));
+    }
+
     if is_pinned {
         spans.push(Span::styled("📌 ", theme.style_accent()));
     }
@@ -177,21 +269,73 @@ fn format_task_line<'a>(task: &'a Task, theme: &'a Theme, width: u16) -> Line<'a
     }
 
     spans.push(Span::styled(priority_marker.to_string(), priority_style));
-    spans.push(Span::styled(task.title.clone(), theme.style_default()));
+    spans.push(Span::styled(title, theme.style_default()));
     spans.push(Span::raw(" "));
 
+    if !late_marker.is_empty() {
+        spans.push(Span::styled(
+            late_marker,
+            theme.style_warning().add_modifier(Modifier::DIM),
+        ));
+    }
+
     for tag in &task.tags {
-        spans.push(Span::styled(format!("#{} ", tag), theme.style_highlight()));
+        spans.push(Span::styled(
+            format!("#{} ", tag),
+            theme.style_tag(tag, config),
+        ));
     }
 
     spans.push(Span::raw(" ".repeat(padding)));
     spans.push(Span::styled(source_label, theme.style_muted()));
 
+    if flashing {
+        for span in &mut spans {
+            span.style = span.style.patch(theme.style_success());
+        }
+    }
+
     Line::from(spans)
 }
 
-pub fn draw_help(f: &mut Frame, theme: &Theme, area: Rect) {
-    let help_text = vec![
+/// What the marks on a task line mean, for the help overlay: each enabled
+/// backend's label and icon (as its backend sets it), then the status and
+/// priority markers in use.
+pub fn legend(backends: &BackendManager, config: &Config) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = backends
+        .sources()
+        .into_iter()
+        .map(|source| {
+            let what = match source {
+                BackendSource::Obsidian => "Obsidian vault",
+                BackendSource::LocalFile => "todo.txt file",
+                BackendSource::ICal => "Calendar (read-only)",
+            };
+            (
+                format!("[{}] {}", source.name(), backends.icon(source)),
+                what.to_string(),
+            )
+        })
+        .collect();
+    entries.push(("☐ ✓".into(), "Pending, done".into()));
+    entries.push((
+        "[!]".into(),
+        "High priority; medium and low are colored".into(),
+    ));
+    if let Some(days) = config.general.escalate_overdue_after_days {
+        entries.push((
+            "[↑]".into(),
+            format!("Overdue {}+ days, raised to high", days),
+        ));
+    }
+    entries.push(("📌".into(), "Pinned to the Waybar tooltip".into()));
+    entries.push(("▶".into(), "Being timed (. to start/stop)".into()));
+    entries.push(("late".into(), "Done after its due date".into()));
+    entries
+}
+
+pub fn draw_help(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let mut help_text = vec![
         Line::from(vec![Span::styled(
             "Keybindings",
             theme.style_accent().add_modifier(Modifier::BOLD),
@@ -229,6 +373,18 @@ pub fn draw_help(f: &mut Frame, theme: &Theme, area: Rect) {
             Span::styled("p", theme.style_accent()),
             Span::styled("         Pin/unpin for waybar", theme.style_default()),
         ]),
+        Line::from(vec![
+            Span::styled("s", theme.style_accent()),
+            Span::styled("         Set due date", theme.style_default()),
+        ]),
+        Line::from(vec![
+            Span::styled(".", theme.style_accent()),
+            Span::styled("         Start/stop timer", theme.style_default()),
+        ]),
+        Line::from(vec![
+            Span::styled("D", theme.style_accent()),
+            Span::styled("         Defer: clear due date", theme.style_default()),
+        ]),
         Line::from(vec![
             Span::styled("e", theme.style_accent()),
             Span::styled("         Quick edit task", theme.style_default()),
@@ -249,32 +405,88 @@ pub fn draw_help(f: &mut Frame, theme: &Theme, area: Rect) {
             Span::styled("r", theme.style_accent()),
             Span::styled("         Refresh from backends", theme.style_default()),
         ]),
+        Line::from(vec![
+            Span::styled("R", theme.style_accent()),
+            Span::styled("         Reverse sort order", theme.style_default()),
+        ]),
         Line::from(vec![
             Span::styled("v/V", theme.style_accent()),
             Span::styled("       Next/previous view", theme.style_default()),
         ]),
+        Line::from(vec![
+            Span::styled("1-4", theme.style_accent()),
+            Span::styled(
+                "       Today/week/all/done view (0 clears filters)",
+                theme.style_default(),
+            ),
+        ]),
         Line::from(vec![
             Span::styled("d", theme.style_accent()),
             Span::styled("         Delete selected task", theme.style_default()),
         ]),
         Line::from(vec![
             Span::styled("c", theme.style_accent()),
-            Span::styled("         Open config in $EDITOR", theme.style_default()),
+            Span::styled("         Open config in editor", theme.style_default()),
+        ]),
+        Line::from(vec![
+            Span::styled("f", theme.style_accent()),
+            Span::styled(
+                "         Focus mode (x complete, s snooze, n next)",
+                theme.style_default(),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(",", theme.style_accent()),
+            Span::styled("         Settings", theme.style_default()),
+        ]),
+        Line::from(vec![
+            Span::styled("F4", theme.style_accent()),
+            Span::styled(
+                "        Board view (h/l columns, H/L move task)",
+                theme.style_default(),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("|", theme.style_accent()),
+            Span::styled("         Toggle detail pane", theme.style_default()),
         ]),
         Line::from(vec![
             Span::styled("?", theme.style_accent()),
             Span::styled("         Toggle this help", theme.style_default()),
         ]),
         Line::from(vec![
-            Span::styled("q, Esc", theme.style_accent()),
-            Span::styled("   Quit TUI", theme.style_default()),
+            Span::styled("Esc", theme.style_accent()),
+            Span::styled("       Clear search (quits if none)", theme.style_default()),
+        ]),
+        Line::from(vec![
+            Span::styled("q", theme.style_accent()),
+            Span::styled("         Quit TUI", theme.style_default()),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
-            "Quick-add supports: #tags @backends (p1/p2/p3) today/tomorrow/YYYY-MM-DD",
+            "Quick-add supports: #tags @backends p1/p2/p3 or !/!!/!!! today/tomorrow/YYYY-MM-DD",
             theme.style_muted(),
         )]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Legend",
+            theme.style_accent().add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
     ];
+    let legend = legend(&app.backend_manager, &app.config);
+    let width = legend
+        .iter()
+        .map(|(mark, _)| display_width(mark))
+        .max()
+        .unwrap_or(0);
+    for (mark, meaning) in legend {
+        let pad = " ".repeat(width - display_width(&mark) + 2);
+        help_text.push(Line::from(vec![
+            Span::styled(format!("{}{}", mark, pad), theme.style_accent()),
+            Span::styled(meaning, theme.style_default()),
+        ]));
+    }
 
     let help_paragraph = Paragraph::new(Text::from(help_text)).block(
         Block::default()
@@ -283,7 +495,83 @@ pub fn draw_help(f: &mut Frame, theme: &Theme, area: Rect) {
             .border_style(theme.style_accent()),
     );
 
-    let area = super::centered_rect(60, 70, area);
+    let area = super::centered_rect(60, 90, area);
     f.render_widget(Clear, area);
     f.render_widget(help_paragraph, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn done_task(completed: &str) -> Task {
+        let completed = NaiveDate::parse_from_str(completed, "%Y-%m-%d").unwrap();
+        Task::build("Report")
+            .with_status(TaskStatus::Done)
+            .with_due(NaiveDate::from_ymd_opt(2025, 3, 10))
+            .with_completed(completed.and_hms_opt(9, 0, 0))
+    }
+
+    fn rendered(task: &Task) -> String {
+        let theme = Theme::dark();
+        let line = format_task_line(task, &theme, &Config::default(), 80, false, false, false);
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_scroll_state() {
+        assert_eq!(scroll_state(10, 0, 10), None);
+        assert_eq!(scroll_state(3, 0, 0), None);
+
+        let expected = |content, position| {
+            Some(
+                ScrollbarState::new(content)
+                    .position(position)
+                    .viewport_content_length(10),
+            )
+        };
+        // 40 rows in 10 lines: offsets 0..=30
+        assert_eq!(scroll_state(40, 0, 10), expected(31, 0));
+        assert_eq!(scroll_state(40, 12, 10), expected(31, 12));
+        assert_eq!(scroll_state(40, 30, 10), expected(31, 30));
+        // A stale offset past the end pins the thumb to the bottom
+        assert_eq!(scroll_state(40, 35, 10), expected(31, 30));
+    }
+
+    #[test]
+    fn test_late_marker_only_when_completed_after_due() {
+        assert!(rendered(&done_task("2025-03-12")).contains("Report late "));
+        assert!(!rendered(&done_task("2025-03-10")).contains("late"));
+
+        let mut pending = done_task("2025-03-12");
+        pending.status = TaskStatus::Pending;
+        assert!(!rendered(&pending).contains("late"));
+    }
+
+    #[test]
+    fn test_legend_lists_enabled_backends() {
+        use crate::backends::localfile::{LocalFileBackend, LocalFileConfig};
+
+        let local = LocalFileBackend::new(LocalFileConfig {
+            path: "/tmp/tasuki-legend.txt".into(),
+        });
+        let manager = BackendManager::new(vec![Box::new(local)]);
+        let mut config = Config::default();
+
+        let entries = legend(&manager, &config);
+        let marks: Vec<&str> = entries.iter().map(|(mark, _)| mark.as_str()).collect();
+        assert!(marks.contains(&"[local] ■"));
+        assert!(!marks
+            .iter()
+            .any(|m| m.contains("[obsidian]") || m.contains("[↑]")));
+        assert!(marks.contains(&"[!]"));
+
+        config.general.escalate_overdue_after_days = Some(3);
+        let entries = legend(&manager, &config);
+        assert!(entries.contains(&(
+            "[↑]".to_string(),
+            "Overdue 3+ days, raised to high".to_string()
+        )));
+    }
+}
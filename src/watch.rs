@@ -0,0 +1,252 @@
+//! File watchers that wake the TUI, and the debounce that turns a burst of
+//! their events into one reload.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::config::Config;
+use crate::error::Result;
+
+/// How long config.toml must be left alone before it's reloaded. Editors
+/// save in steps (vim truncates, then writes), and the file in between
+/// isn't the config anyone meant.
+pub const CONFIG_QUIET: Duration = Duration::from_millis(300);
+
+/// Acts once events stop coming: each one pushes the deadline back by
+/// `quiet`.
+#[derive(Debug)]
+pub struct Debounce {
+    quiet: Duration,
+    last_event: Option<Instant>,
+}
+
+impl Debounce {
+    /// Fires `quiet` after the last event.
+    pub fn new(quiet: Duration) -> Self {
+        Self {
+            quiet,
+            last_event: None,
+        }
+    }
+
+    /// An event arrived at `now`.
+    pub fn event(&mut self, now: Instant) {
+        self.last_event = Some(now);
+    }
+
+    /// Time left until `fire` is due, if an event is waiting.
+    pub fn wait(&self, now: Instant) -> Option<Duration> {
+        self.last_event
+            .map(|at| (at + self.quiet).saturating_duration_since(now))
+    }
+
+    /// Whether the events have been quiet for long enough; if so they're
+    /// dealt with and it waits for the next.
+    pub fn fire(&mut self, now: Instant) -> bool {
+        match self.wait(now) {
+            Some(left) if left.is_zero() => {
+                self.last_event = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether the file at `path` is empty, as between the truncate and the
+/// write of a save: nothing worth loading yet. A file that isn't there is
+/// gone, not being saved.
+pub fn is_mid_save(path: &Path) -> bool {
+    match std::fs::metadata(path) {
+        Ok(metadata) => metadata.len() == 0,
+        Err(e) => e.kind() != std::io::ErrorKind::NotFound,
+    }
+}
+
+/// Watch the Omarchy theme folder's parent, `path` (the folder is removed
+/// and recreated on a theme switch). `on_change` runs on the watcher's
+/// thread.
+pub fn theme(
+    path: Option<PathBuf>,
+    on_change: impl Fn() + Send + 'static,
+) -> Result<RecommendedWatcher> {
+    let mut watcher = RecommendedWatcher::new(
+        move |res: std::result::Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let is_theme_event = event.paths.iter().any(|p| {
+                    p.to_string_lossy().contains("/theme/")
+                        || p.file_name().map(|n| n == "theme").unwrap_or(false)
+                });
+
+                if is_theme_event {
+                    // Only Create/Modify — Omarchy removes folder first, then recreates
+                    match event.kind {
+                        EventKind::Modify(_) | EventKind::Create(_) => on_change(),
+                        _ => {}
+                    }
+                }
+            }
+        },
+        notify::Config::default(),
+    )?;
+
+    if let Some(path) = path {
+        watcher.watch(&path, RecursiveMode::NonRecursive)?;
+    }
+
+    Ok(watcher)
+}
+
+/// Watch the enabled Obsidian vault's notes, if there is one.
+pub fn vault(config: &Config, on_change: impl Fn() + Send + 'static) -> Option<RecommendedWatcher> {
+    let vault_path = config
+        .backends
+        .obsidian
+        .as_ref()
+        .filter(|t| t.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false))
+        .and_then(|t| t.get("vault_path").and_then(|v| v.as_str()))
+        .and_then(|s| crate::paths::expand_path(s).ok())?;
+
+    if !vault_path.exists() {
+        return None;
+    }
+
+    let mut watcher = RecommendedWatcher::new(
+        move |res: std::result::Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let is_md_event = event
+                    .paths
+                    .iter()
+                    .any(|p| p.extension().and_then(|e| e.to_str()) == Some("md"));
+
+                if is_md_event {
+                    match event.kind {
+                        EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => {
+                            on_change()
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        },
+        notify::Config::default(),
+    )
+    .ok()?;
+
+    watcher.watch(&vault_path, RecursiveMode::Recursive).ok()?;
+
+    Some(watcher)
+}
+
+/// Watch the file at `path`, such as config.toml or todo.txt. Its folder is
+/// watched rather than the file, so a save that replaces the file is still
+/// seen.
+pub fn file(path: &Path, on_change: impl Fn() + Send + 'static) -> Option<RecommendedWatcher> {
+    let file_name = path.file_name()?.to_os_string();
+    let parent = path.parent()?.to_path_buf();
+    if !parent.exists() {
+        return None;
+    }
+
+    let mut watcher = RecommendedWatcher::new(
+        move |res: std::result::Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let is_config_event = event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == Some(file_name.as_os_str()));
+
+                if is_config_event {
+                    match event.kind {
+                        EventKind::Modify(_) | EventKind::Create(_) => on_change(),
+                        _ => {}
+                    }
+                }
+            }
+        },
+        notify::Config::default(),
+    )
+    .ok()?;
+
+    watcher.watch(&parent, RecursiveMode::NonRecursive).ok()?;
+
+    Some(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_debounce_waits_for_quiet() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut debounce = Debounce::new(Duration::from_millis(300));
+        assert_eq!(debounce.wait(start), None);
+        assert!(!debounce.fire(start));
+
+        debounce.event(ms(0));
+        debounce.event(ms(200));
+        assert!(!debounce.fire(ms(400)));
+        assert_eq!(debounce.wait(ms(400)), Some(Duration::from_millis(100)));
+        assert!(debounce.fire(ms(500)));
+        // Fired once per burst
+        assert!(!debounce.fire(ms(900)));
+        assert_eq!(debounce.wait(ms(900)), None);
+    }
+
+    #[test]
+    fn test_truncate_then_write_reloads_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[general]\ntheme = \"dark\"\n").unwrap();
+
+        let (tx, rx) = channel();
+        let _watcher = file(&path, move || {
+            let _ = tx.send(());
+        })
+        .unwrap();
+
+        // Events are stamped on the test's own clock, so the debounce is
+        // checked without waiting it out
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let recv = || rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        let mut debounce = Debounce::new(CONFIG_QUIET);
+
+        // vim's save: the file is truncated, then written 50ms later
+        std::fs::File::create(&path).unwrap();
+        recv();
+        debounce.event(ms(0));
+        assert!(is_mid_save(&path));
+
+        std::fs::write(&path, "[general]\ntheme = \"light\"\n").unwrap();
+        recv();
+        while rx.try_recv().is_ok() {}
+        debounce.event(ms(50));
+
+        // The truncate alone would have fired by now
+        assert!(!debounce.fire(ms(300)));
+        assert!(debounce.fire(ms(350)));
+        assert!(!is_mid_save(&path));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[general]\ntheme = \"light\"\n"
+        );
+        assert!(!debounce.fire(ms(1000)));
+    }
+
+    #[test]
+    fn test_is_mid_save() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        assert!(!is_mid_save(&path));
+        std::fs::write(&path, "").unwrap();
+        assert!(is_mid_save(&path));
+        std::fs::write(&path, "[general]\n").unwrap();
+        assert!(!is_mid_save(&path));
+    }
+}
@@ -1,110 +1,419 @@
 use chrono::{Local, NaiveDate};
 use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
 
+use crate::agenda;
+use crate::backends::localfile::LocalFileConfig;
 use crate::backends::BackendManager;
 use crate::config::Config;
-use crate::error::Result;
-use crate::model::{Task, TaskFilter, TaskStatus};
+use crate::error::{Result, TasukiError};
+use crate::model::{BackendSource, Task, TaskFilter, TaskStatus};
+use crate::output;
+use crate::streak::{self, Progress};
 
-pub async fn output(backend_manager: &BackendManager, config: &Config) -> Result<()> {
+/// Days ahead, counting from today, that the tooltip lists by day before
+/// "Later".
+const UPCOMING_DAYS: u32 = 7;
+
+/// Columns a title gets in the tooltip before it's cut with "…".
+const TOOLTIP_TITLE_WIDTH: usize = 60;
+
+/// How long `watch` waits after a task file changes before reading it.
+const CHANGE_SETTLE: Duration = Duration::from_millis(200);
+
+/// Overdue tasks that add the `overdue-3plus` class.
+const MANY_OVERDUE: usize = 3;
+
+/// Placeholders `[waybar] format` can use.
+pub const FORMAT_PLACEHOLDERS: &[&str] = &["count", "done_today", "goal", "streak"];
+
+/// Print the Waybar JSON, or with `plain` a single line like `2! 3 today`
+/// for bars that take a bare string.
+pub async fn output(backend_manager: &BackendManager, config: &Config, plain: bool) -> Result<()> {
+    let error_text = &config.waybar.error_text;
     if backend_manager.is_empty() {
-        let output = json!({
-            "text": "!",
-            "tooltip": "No backends configured.\n\nCreate ~/.config/tasuki/config.toml:\n\n[backends.local]\nenabled = true\n\nTasks stored in ~/.tasuki/todo.txt",
-            "class": "backend-error",
-            "alt": "error"
-        });
+        if plain {
+            println!("{}", error_text);
+            return Ok(());
+        }
+        let output = error_output(
+            error_text,
+            "No backends configured.\n\nCreate ~/.config/tasuki/config.toml:\n\n[backends.local]\nenabled = true\n\nTasks stored in ~/.local/share/tasuki/todo.txt",
+        );
         println!("{}", output);
         return Ok(());
     }
 
+    // Done tasks are only needed for the daily goal and `include_done`
+    let goal = config.general.daily_goal;
+    let include_done = config.waybar.include_done;
     let filter = TaskFilter {
-        status: Some(TaskStatus::Pending),
+        status: if goal.is_some() || include_done { None } else { Some(TaskStatus::Pending) },
         ..Default::default()
     };
 
+    let last_path = config.state_dir().map(|dir| dir.join(LAST_OUTPUT_FILE));
     let tasks = match backend_manager.all_tasks(&filter).await {
         Ok(t) => t,
         Err(e) => {
-            let output = json!({
-                "text": "!",
-                "tooltip": format!("Error: {}", e),
-                "class": "backend-error",
-                "alt": "error"
-            });
+            if plain {
+                eprintln!("Error: {}", e);
+                println!("{}", error_text);
+                return Ok(());
+            }
+            if let Some(stale) = fallback_output(&e, last_path.as_deref(), MAX_STALE_AGE) {
+                println!("{}", stale);
+                return Ok(());
+            }
+            let output = error_output(error_text, &format!("Error: {}", e));
             println!("{}", output);
             return Ok(());
         }
     };
 
-    let output = build_output(&tasks, &config.waybar.tooltip_scope);
+    let today = crate::clock::today();
+    let progress = goal.map(|goal| {
+        let history = streak::History::path(config);
+        streak::progress(history.as_deref(), &tasks, goal, today)
+    });
+    let (tasks, done_today) = split_done(tasks, today);
+
+    if plain {
+        println!("{}", plain_output(&tasks, &config.waybar.done_text));
+        return Ok(());
+    }
+
+    let mut output = build_output(&tasks, config, &|source| backend_manager.icon(source));
+    if include_done {
+        add_done_count(&mut output, done_today);
+    }
+    apply_format(&mut output, &config.waybar.format, progress.as_ref());
+    add_updated_footer(&mut output, &Local::now().format("%H:%M").to_string());
+    if let Some(ref path) = last_path {
+        save_last_output(path, &output);
+    }
     println!("{}", output);
     Ok(())
 }
 
-fn build_output(tasks: &[Task], tooltip_scope: &str) -> Value {
-    let today = Local::now().date_naive();
+/// `output` again every `interval`, and soon after a task file changes,
+/// until interrupted or `count` lines are out. Each output is one line, as
+/// Waybar's continuous modules read them.
+pub async fn watch(
+    backend_manager: &BackendManager,
+    config: &Config,
+    plain: bool,
+    interval: Duration,
+    count: Option<usize>,
+) -> Result<()> {
+    let (tx, mut changes) = tokio::sync::mpsc::unbounded_channel();
+    let on_change = move || {
+        let _ = tx.send(());
+    };
+    let local_path = config
+        .backends
+        .local
+        .as_ref()
+        .filter(|t| t.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false))
+        .and_then(|t| LocalFileConfig::resolve_path(t).ok());
+    let _local_watcher = local_path.and_then(|path| crate::watch::file(&path, on_change.clone()));
+    let _vault_watcher = crate::watch::vault(config, on_change);
 
-    let overdue: Vec<&Task> = tasks.iter()
-        .filter(|t| t.due.map_or(false, |d| d < today))
-        .collect();
+    // Created once, so a Ctrl-C while `output` runs isn't lost
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
 
-    let due_today: Vec<&Task> = tasks.iter()
-        .filter(|t| t.due.map_or(false, |d| d == today))
-        .collect();
+    let mut printed = 0;
+    loop {
+        output(backend_manager, config, plain).await?;
+        printed += 1;
+        if count.is_some_and(|count| printed >= count) {
+            return Ok(());
+        }
 
-    let due_tomorrow: Vec<&Task> = tasks.iter()
-        .filter(|t| t.due.map_or(false, |d| d == today + chrono::Duration::days(1)))
-        .collect();
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            Some(()) = changes.recv() => {
+                // Saves come in bursts; let this one finish
+                tokio::time::sleep(CHANGE_SETTLE).await;
+                while changes.try_recv().is_ok() {}
+            }
+            _ = &mut ctrl_c => return Ok(()),
+        }
+    }
+}
+
+/// The module when there's nothing to count: `text` (`error_text`) with
+/// the `backend-error` class.
+fn error_output(text: &str, tooltip: &str) -> Value {
+    json!({
+        "text": text,
+        "tooltip": tooltip,
+        "class": "backend-error",
+        "alt": "error"
+    })
+}
+
+/// The last JSON printed, in the state dir, shown (as stale) when a later
+/// fetch fails.
+const LAST_OUTPUT_FILE: &str = "waybar-last.json";
+
+/// How old the last output can be and still stand in for a failed fetch.
+const MAX_STALE_AGE: Duration = Duration::from_secs(30 * 60);
+
+/// The last output, marked stale, for an `error` that may clear up by
+/// itself (a vault on a network mount that's reconnecting): better the
+/// last badge than none. Other errors, or output older than `max_age`,
+/// get `None` so the error shows.
+fn fallback_output(error: &TasukiError, path: Option<&Path>, max_age: Duration) -> Option<Value> {
+    if !error.is_transient() {
+        return None;
+    }
+    let path = path?;
+    let age = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()?
+        .elapsed()
+        .ok()?;
+    if age >= max_age {
+        return None;
+    }
+    load_last_output(path).map(|last| stale_output(last, &error.to_string()))
+}
 
-    let mut upcoming_by_day: Vec<(NaiveDate, Vec<&Task>)> = Vec::new();
-    for day_offset in 2..=7 {
-        let date = today + chrono::Duration::days(day_offset);
-        let day_tasks: Vec<&Task> = tasks.iter()
-            .filter(|t| t.due == Some(date))
-            .collect();
-        if !day_tasks.is_empty() {
-            upcoming_by_day.push((date, day_tasks));
+fn load_last_output(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Best effort: without it a failed fetch just shows `!`.
+fn save_last_output(path: &Path, output: &Value) {
+    let saved = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(path, output.to_string()));
+    if let Err(e) = saved {
+        tracing::warn!("Could not save {}: {}", path.display(), e);
+    }
+}
+
+/// The last output again, with the `stale` class and the error after its
+/// "updated" footer.
+fn stale_output(mut last: Value, error: &str) -> Value {
+    add_class(&mut last, "stale");
+    if let Some(tooltip) = last["tooltip"].as_str() {
+        last["tooltip"] = Value::String(format!("{}\nstale: {}", tooltip, error));
+    }
+    last
+}
+
+/// Check `[waybar] format` for unknown placeholders and unclosed braces.
+pub fn check_format(format: &str) -> Result<()> {
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err(TasukiError::Parse(format!("Unclosed '{{' in {}", format)));
+        };
+        let name = &rest[start + 1..start + len];
+        if !FORMAT_PLACEHOLDERS.contains(&name) {
+            return Err(TasukiError::Parse(format!(
+                "Unknown placeholder '{{{}}}' (valid: {})",
+                name,
+                FORMAT_PLACEHOLDERS.join(", ")
+            )));
         }
+        rest = &rest[start + len + 1..];
     }
+    Ok(())
+}
 
-    let future: Vec<&Task> = tasks.iter()
-        .filter(|t| t.due.map_or(false, |d| d > today + chrono::Duration::days(7)))
-        .collect();
+/// Fill `format` into the module text, and add the `goal-met` class once
+/// today's goal is reached. Goal placeholders are empty without a goal;
+/// unknown ones are left as written (`config check` reports them). A
+/// hidden badge (empty `done_text`) keeps the whole module hidden.
+fn apply_format(output: &mut Value, format: &str, progress: Option<&Progress>) {
+    let count = output["text"].as_str().unwrap_or_default().to_string();
+    if count.is_empty() {
+        return;
+    }
+    let (done_today, goal, streak) = match progress {
+        Some(p) => (p.done_today.to_string(), p.goal.to_string(), p.streak.to_string()),
+        None => Default::default(),
+    };
+    output["text"] = Value::String(
+        format
+            .replace("{count}", &count)
+            .replace("{done_today}", &done_today)
+            .replace("{goal}", &goal)
+            .replace("{streak}", &streak),
+    );
 
-    let no_due: Vec<&Task> = tasks.iter()
-        .filter(|t| t.due.is_none())
-        .collect();
+    if progress.is_some_and(|p| p.met()) {
+        add_class(output, "goal-met");
+    }
+}
+
+/// Add `class` after the module's others, making a lone class an array.
+fn add_class(output: &mut Value, class: &str) {
+    output["class"] = match output["class"].take() {
+        Value::Array(mut classes) => {
+            classes.push(json!(class));
+            Value::Array(classes)
+        }
+        main => json!([main, class]),
+    };
+}
+
+/// The module's classes, the main one first: `has-escalated`,
+/// `has-overdue`, `has-tasks` or `all-done`. Then `overdue-3plus` from
+/// three overdue tasks, and `tag-<tag>-due` for each of `alert_tags` with a
+/// task due today or overdue (`work/clientA` gives `tag-work-clienta-due`).
+fn classes(buckets: &agenda::Buckets, config: &Config, today: NaiveDate) -> Vec<String> {
+    let escalate_after = config.general.escalate_overdue_after_days;
+    let dated = buckets.overdue.len()
+        + buckets.today.len()
+        + buckets.tomorrow.len()
+        + buckets.upcoming_count()
+        + buckets.later.len();
+
+    let main = if buckets.overdue.iter().any(|t| t.is_escalated(today, escalate_after)) {
+        "has-escalated"
+    } else if !buckets.overdue.is_empty() {
+        "has-overdue"
+    } else if dated > 0 {
+        "has-tasks"
+    } else {
+        "all-done"
+    };
+    let mut classes = vec![main.to_string()];
+
+    if buckets.overdue.len() >= MANY_OVERDUE {
+        classes.push(format!("overdue-{}plus", MANY_OVERDUE));
+    }
+    for tag in &config.waybar.alert_tags {
+        let due = buckets.overdue.iter().chain(&buckets.today).any(|task| {
+            task.tags.iter().any(|t| crate::model::tag_matches(t, tag))
+        });
+        if due {
+            let name: String = tag
+                .trim_start_matches('#')
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+                .collect();
+            classes.push(format!("tag-{}-due", name));
+        }
+    }
+    classes
+}
+
+/// The pending tasks the badge is built from, and how many tasks were
+/// completed `today`.
+fn split_done(tasks: Vec<Task>, today: NaiveDate) -> (Vec<Task>, usize) {
+    let done_today = streak::done_on(&tasks, today);
+    let pending = tasks.into_iter().filter(|t| t.status == TaskStatus::Pending).collect();
+    (pending, done_today)
+}
+
+/// For `include_done`: today's completions as `done_today` in the JSON and
+/// after the tooltip summary ("2 today · 5 done").
+fn add_done_count(output: &mut Value, done_today: usize) {
+    output["done_today"] = json!(done_today);
+    if let Some(tooltip) = output["tooltip"].as_str() {
+        output["tooltip"] = Value::String(format!("{} · {} done", tooltip, done_today));
+    }
+}
+
+/// End the tooltip with when it was generated, so a stalled module shows.
+fn add_updated_footer(output: &mut Value, time: &str) {
+    if let Some(tooltip) = output["tooltip"].as_str() {
+        output["tooltip"] = Value::String(format!("{}\nupdated {}", tooltip, time));
+    }
+}
+
+/// Overdue count with a `!`, then what's due today; `done_text` when neither.
+fn plain_output(tasks: &[Task], done_text: &str) -> String {
+    let today = crate::clock::today();
+    let overdue_count = tasks.iter().filter(|t| t.due.is_some_and(|d| d < today)).count();
+    let today_count = tasks.iter().filter(|t| t.due == Some(today)).count();
+
+    let mut parts = Vec::new();
+    if overdue_count > 0 {
+        parts.push(format!("{}!", overdue_count));
+    }
+    if today_count > 0 {
+        parts.push(format!("{} today", today_count));
+    }
+    if parts.is_empty() {
+        done_text.to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// A task's title as one tooltip line, so stray newlines, tabs and control
+/// characters can't break up or garble the tooltip.
+fn tooltip_title(task: &Task) -> String {
+    output::truncate_to_width(&output::single_line(&task.title), TOOLTIP_TITLE_WIDTH)
+}
+
+fn build_output<'a>(
+    tasks: &[Task],
+    config: &Config,
+    icon: &dyn Fn(BackendSource) -> &'a str,
+) -> Value {
+    let today = crate::clock::today();
+    let escalate_after = config.general.escalate_overdue_after_days;
+
+    let buckets = agenda::bucketize(tasks, today, UPCOMING_DAYS);
+    let upcoming_total = buckets.upcoming_count();
+    let classes = classes(&buckets, config, today);
+    let class = if config.waybar.class_style == "array" {
+        json!(classes)
+    } else {
+        json!(classes[0])
+    };
+    let agenda::Buckets {
+        overdue,
+        today: due_today,
+        tomorrow: due_tomorrow,
+        upcoming: upcoming_by_day,
+        later: future,
+        no_due,
+    } = buckets;
 
     let overdue_count = overdue.len();
     let today_count = due_today.len();
     let tomorrow_count = due_tomorrow.len();
-    let upcoming_total: usize = upcoming_by_day.iter().map(|(_, tasks)| tasks.len()).sum();
     let future_count = future.len();
     let no_due_count = no_due.len();
     let dated_total = tasks.len() - no_due_count;
 
-    let (display_text, class) = if overdue_count > 0 {
-        (overdue_count.to_string(), "has-overdue")
+    let display_text = if overdue_count > 0 {
+        overdue_count.to_string()
     } else if today_count > 0 {
-        (today_count.to_string(), "has-tasks")
+        today_count.to_string()
     } else if tomorrow_count > 0 {
-        (tomorrow_count.to_string(), "has-tasks")
+        tomorrow_count.to_string()
     } else if upcoming_total > 0 {
-        (upcoming_total.to_string(), "has-tasks")
+        upcoming_total.to_string()
     } else if dated_total > 0 {
-        (dated_total.to_string(), "has-tasks")
+        dated_total.to_string()
+    } else if no_due_count == 0 || config.waybar.show_zero {
+        config.waybar.done_text.clone()
     } else {
-        ("✓".to_string(), "all-done")
+        String::new()
     };
 
-    let scope = tooltip_scope;
+    let scope = config.waybar.tooltip_scope.as_str();
     let mut tooltip_lines = Vec::new();
 
     if scope != "today_only" && overdue_count > 0 {
         tooltip_lines.push(format!("Overdue ({}):", overdue_count));
         for task in overdue.iter().take(10) {
-            tooltip_lines.push(format!("  ☐ {} {}", task.title, task.source.icon()));
+            let marker = if task.is_escalated(today, escalate_after) { "↑ " } else { "" };
+            tooltip_lines.push(format!("  ☐ {}{} {}", marker, tooltip_title(task), icon(task.source)));
         }
         if overdue_count > 10 {
             tooltip_lines.push(format!("  ... and {} more", overdue_count - 10));
@@ -115,7 +424,7 @@ fn build_output(tasks: &[Task], tooltip_scope: &str) -> Value {
     if today_count > 0 {
         tooltip_lines.push(format!("Today ({}):", today_count));
         for task in due_today.iter().take(10) {
-            tooltip_lines.push(format!("  ☐ {} {}", task.title, task.source.icon()));
+            tooltip_lines.push(format!("  ☐ {} {}", tooltip_title(task), icon(task.source)));
         }
         if today_count > 10 {
             tooltip_lines.push(format!("  ... and {} more", today_count - 10));
@@ -127,7 +436,7 @@ fn build_output(tasks: &[Task], tooltip_scope: &str) -> Value {
         if tomorrow_count > 0 {
             tooltip_lines.push(format!("Tomorrow ({}):", tomorrow_count));
             for task in due_tomorrow.iter().take(5) {
-                tooltip_lines.push(format!("  ☐ {} {}", task.title, task.source.icon()));
+                tooltip_lines.push(format!("  ☐ {} {}", tooltip_title(task), icon(task.source)));
             }
             if tomorrow_count > 5 {
                 tooltip_lines.push(format!("  ... and {} more", tomorrow_count - 5));
@@ -136,10 +445,10 @@ fn build_output(tasks: &[Task], tooltip_scope: &str) -> Value {
         }
 
         for (date, day_tasks) in &upcoming_by_day {
-            let day_name = date.format("%A").to_string();
-            tooltip_lines.push(format!("{} {} ({}):", day_name, date, day_tasks.len()));
+            let label = output::due_label(Some(*date), config, today);
+            tooltip_lines.push(format!("{} ({}):", label, day_tasks.len()));
             for task in day_tasks.iter().take(3) {
-                tooltip_lines.push(format!("  ☐ {} {}", task.title, task.source.icon()));
+                tooltip_lines.push(format!("  ☐ {} {}", tooltip_title(task), icon(task.source)));
             }
             if day_tasks.len() > 3 {
                 tooltip_lines.push(format!("  ... and {} more", day_tasks.len() - 3));
@@ -150,8 +459,11 @@ fn build_output(tasks: &[Task], tooltip_scope: &str) -> Value {
         if future_count > 0 {
             tooltip_lines.push(format!("Later ({}):", future_count));
             for task in future.iter().take(3) {
-                let due_str = task.due.map(|d| d.to_string()).unwrap_or_default();
-                tooltip_lines.push(format!("  ☐ {} ({}) {}", task.title, task.source.icon(), due_str));
+                let due_str = task
+                    .due
+                    .map(|d| output::format_date(d, config, today))
+                    .unwrap_or_default();
+                tooltip_lines.push(format!("  ☐ {} ({}) {}", tooltip_title(task), icon(task.source), due_str));
             }
             if future_count > 3 {
                 tooltip_lines.push(format!("  ... and {} more", future_count - 3));
@@ -162,7 +474,7 @@ fn build_output(tasks: &[Task], tooltip_scope: &str) -> Value {
         if no_due_count > 0 {
             tooltip_lines.push(format!("Notes ({}):", no_due_count));
             for task in no_due.iter().take(5) {
-                tooltip_lines.push(format!("  📝 {} {}", task.title, task.source.icon()));
+                tooltip_lines.push(format!("  📝 {} {}", tooltip_title(task), icon(task.source)));
             }
             if no_due_count > 5 {
                 tooltip_lines.push(format!("  ... and {} more", no_due_count - 5));
@@ -171,13 +483,13 @@ fn build_output(tasks: &[Task], tooltip_scope: &str) -> Value {
     }
 
     let pinned: Vec<&Task> = tasks.iter()
-        .filter(|t| t.tags.iter().any(|tag| tag == "pin"))
+        .filter(|t| t.tags.iter().any(|tag| tag.eq_ignore_ascii_case("pin")))
         .collect();
 
     if !pinned.is_empty() {
         tooltip_lines.push(format!("Pinned ({}):", pinned.len()));
         for task in pinned.iter().take(5) {
-            tooltip_lines.push(format!("  📌 {} {}", task.title, task.source.icon()));
+            tooltip_lines.push(format!("  📌 {} {}", tooltip_title(task), icon(task.source)));
         }
         if pinned.len() > 5 {
             tooltip_lines.push(format!("  ... and {} more", pinned.len() - 5));
@@ -220,33 +532,32 @@ fn build_output(tasks: &[Task], tooltip_scope: &str) -> Value {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{BackendSource, Priority};
+    use crate::model::Priority;
     use chrono::Local;
 
+    /// `super::build_output` with each source's own icon.
+    fn build_output(tasks: &[Task], config: &Config) -> Value {
+        super::build_output(tasks, config, &|source| source.icon())
+    }
+
     fn make_task(title: &str, due: Option<NaiveDate>) -> Task {
-        Task {
-            id: format!("local:{}", title),
-            title: title.to_string(),
-            status: TaskStatus::Pending,
-            priority: Priority::None,
-            due,
-            tags: vec![],
-            source: BackendSource::LocalFile,
-            source_line: None,
-            source_path: None,
-            created_at: None,
-            completed_at: None,
-            heading_context: None,
-        }
+        Task::build(title).with_due(due)
     }
 
     fn today() -> NaiveDate {
         Local::now().date_naive()
     }
 
+    fn config_for(tooltip_scope: &str, escalate_after: Option<u32>) -> Config {
+        let mut config = Config::default();
+        config.waybar.tooltip_scope = tooltip_scope.to_string();
+        config.general.escalate_overdue_after_days = escalate_after;
+        config
+    }
+
     #[test]
     fn test_badge_no_tasks() {
-        let output = build_output(&[], "overdue_today");
+        let output = build_output(&[], &config_for("overdue_today", None));
         assert_eq!(output["text"], "✓");
         assert_eq!(output["class"], "all-done");
     }
@@ -258,7 +569,7 @@ mod tests {
             make_task("Overdue 1", Some(yesterday)),
             make_task("Overdue 2", Some(yesterday)),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         assert_eq!(output["text"], "2");
         assert_eq!(output["class"], "has-overdue");
     }
@@ -276,7 +587,7 @@ mod tests {
             make_task("No due 5", None),
             make_task("No due 6", None),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         // Badge shows overdue count, not total
         assert_eq!(output["text"], "2");
         assert_eq!(output["class"], "has-overdue");
@@ -289,7 +600,7 @@ mod tests {
             make_task("Today 2", Some(today())),
             make_task("Today 3", Some(today())),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         assert_eq!(output["text"], "3");
         assert_eq!(output["class"], "has-tasks");
     }
@@ -298,7 +609,7 @@ mod tests {
     fn test_badge_only_tomorrow() {
         let tomorrow = today() + chrono::Duration::days(1);
         let tasks = vec![make_task("Tomorrow 1", Some(tomorrow))];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         assert_eq!(output["text"], "1");
         assert_eq!(output["class"], "has-tasks");
     }
@@ -310,7 +621,7 @@ mod tests {
             make_task("Upcoming 1", Some(in_3_days)),
             make_task("Upcoming 2", Some(in_3_days)),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         assert_eq!(output["text"], "2");
         assert_eq!(output["class"], "has-tasks");
     }
@@ -319,7 +630,7 @@ mod tests {
     fn test_badge_only_future() {
         let in_30_days = today() + chrono::Duration::days(30);
         let tasks = vec![make_task("Future 1", Some(in_30_days))];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         assert_eq!(output["text"], "1");
         assert_eq!(output["class"], "has-tasks");
     }
@@ -331,11 +642,59 @@ mod tests {
             make_task("No due 2", None),
             make_task("No due 3", None),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         assert_eq!(output["text"], "✓");
         assert_eq!(output["class"], "all-done");
     }
 
+    #[test]
+    fn test_done_text_and_show_zero() {
+        let notes = vec![make_task("No due", None)];
+        let json = |tasks: &[Task], config: &Config| {
+            let mut output = build_output(tasks, config);
+            apply_format(&mut output, &config.waybar.format, None);
+            output.to_string()
+        };
+
+        let mut config = config_for("overdue_today", None);
+        config.waybar.done_text = "".to_string();
+        assert!(json(&[], &config).contains(r#""text":"""#));
+        assert!(json(&notes, &config).contains(r#""text":"""#));
+
+        let mut config = config_for("overdue_today", None);
+        config.waybar.show_zero = false;
+        assert!(json(&[], &config).contains(r#""text":"✓""#));
+        let hidden = json(&notes, &config);
+        assert!(hidden.contains(r#""text":"""#));
+        assert!(hidden.contains(r#""class":"all-done""#));
+
+        // A format around the badge doesn't bring a hidden module back
+        config.waybar.format = "[{count}]".to_string();
+        assert!(json(&notes, &config).contains(r#""text":"""#));
+        config.waybar.done_text = "🎉".to_string();
+        assert!(json(&[], &config).contains(r#""text":"[🎉]""#));
+        // Something due shows as usual
+        let due = vec![make_task("Now", Some(today()))];
+        assert!(json(&due, &config).contains(r#""text":"[1]""#));
+    }
+
+    #[test]
+    fn test_configured_state_texts() {
+        let config: Config = toml::from_str(
+            "[waybar]\nall_done_text = \"\u{f058}\"\nerror_text = \"\u{f071}\"\n",
+        )
+        .unwrap();
+        assert_eq!(build_output(&[], &config)["text"], "\u{f058}");
+
+        let error = error_output(&config.waybar.error_text, "Error: vault not found");
+        assert_eq!(error["text"], "\u{f071}");
+        assert_eq!(error["class"], "backend-error");
+
+        let defaults = Config::default();
+        assert_eq!(build_output(&[], &defaults)["text"], "✓");
+        assert_eq!(error_output(&defaults.waybar.error_text, "")["text"], "!");
+    }
+
     #[test]
     fn test_badge_cascading_today_beats_tomorrow() {
         let tomorrow = today() + chrono::Duration::days(1);
@@ -344,7 +703,7 @@ mod tests {
             make_task("Tomorrow 1", Some(tomorrow)),
             make_task("Tomorrow 2", Some(tomorrow)),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         assert_eq!(output["text"], "1");
     }
 
@@ -357,11 +716,50 @@ mod tests {
             make_task("Today 2", Some(today())),
             make_task("Today 3", Some(today())),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         assert_eq!(output["text"], "1");
         assert_eq!(output["class"], "has-overdue");
     }
 
+    #[test]
+    fn test_badge_escalated_overdue() {
+        let long_ago = today() - chrono::Duration::days(10);
+        let yesterday = today() - chrono::Duration::days(1);
+        let tasks = vec![
+            make_task("Stale", Some(long_ago)),
+            make_task("Recent", Some(yesterday)),
+            make_task("Today 1", Some(today())),
+        ];
+
+        // Count is still every overdue task; only the class changes
+        let output = build_output(&tasks, &config_for("overdue_today", Some(7)));
+        assert_eq!(output["text"], "2");
+        assert_eq!(output["class"], "has-escalated");
+        let tooltip = output["tooltip"].as_str().unwrap();
+        assert!(tooltip.contains("☐ ↑ Stale"));
+        assert!(tooltip.contains("☐ Recent"));
+
+        let output = build_output(&tasks, &config_for("overdue_today", None));
+        assert_eq!(output["class"], "has-overdue");
+    }
+
+    #[test]
+    fn test_badge_escalation_threshold_not_reached() {
+        let week_ago = today() - chrono::Duration::days(7);
+        let tasks = vec![make_task("Week old", Some(week_ago))];
+        let output = build_output(&tasks, &config_for("overdue_today", Some(7)));
+        assert_eq!(output["class"], "has-overdue");
+    }
+
+    #[test]
+    fn test_badge_already_high_not_escalated() {
+        let long_ago = today() - chrono::Duration::days(10);
+        let mut task = make_task("Urgent", Some(long_ago));
+        task.priority = Priority::High;
+        let output = build_output(&[task], &config_for("overdue_today", Some(7)));
+        assert_eq!(output["class"], "has-overdue");
+    }
+
     // -- Tooltip scope tests --
 
     #[test]
@@ -372,7 +770,7 @@ mod tests {
             make_task("No due 1", None),
             make_task("No due 2", None),
         ];
-        let output = build_output(&tasks, "all");
+        let output = build_output(&tasks, &config_for("all", None));
         let tooltip = output["tooltip"].as_str().unwrap();
         assert!(tooltip.contains("Overdue (1):"));
         assert!(tooltip.contains("Notes (2):"));
@@ -386,7 +784,7 @@ mod tests {
         let tasks = vec![
             make_task("Tomorrow 1", Some(tomorrow)),
         ];
-        let output = build_output(&tasks, "all");
+        let output = build_output(&tasks, &config_for("all", None));
         let tooltip = output["tooltip"].as_str().unwrap();
         assert!(tooltip.contains("Tomorrow (1):"));
         assert!(tooltip.contains("Tomorrow 1"));
@@ -398,19 +796,58 @@ mod tests {
         let tasks = vec![
             make_task("Future 1", Some(in_30_days)),
         ];
-        let output = build_output(&tasks, "all");
+        let output = build_output(&tasks, &config_for("all", None));
         let tooltip = output["tooltip"].as_str().unwrap();
         assert!(tooltip.contains("Later (1):"));
         assert!(tooltip.contains("Future 1"));
     }
 
+    #[test]
+    fn test_tooltip_titles_stay_on_one_line() {
+        let tasks = vec![
+            make_task("Call\n\tSam\r\nback\u{200B}", Some(today())),
+            make_task(&"long ".repeat(40), Some(today())),
+        ];
+        let output = build_output(&tasks, &config_for("overdue_today", None));
+        let tooltip = output["tooltip"].as_str().unwrap();
+        assert!(tooltip.contains("  ☐ Call Sam back ■\n"), "{}", tooltip);
+        assert!(!tooltip.contains('\t') && !tooltip.contains('\r') && !tooltip.contains('\u{200B}'));
+        let long = tooltip.lines().find(|l| l.contains("long")).unwrap();
+        assert!(long.ends_with("… ■"), "{}", long);
+    }
+
+    #[test]
+    fn test_tooltip_uses_date_format() {
+        let in_3_days = today() + chrono::Duration::days(3);
+        let in_30_days = today() + chrono::Duration::days(30);
+        let tasks = vec![
+            make_task("Soon", Some(in_3_days)),
+            make_task("Future 1", Some(in_30_days)),
+        ];
+        let mut config = config_for("all", None);
+        config.general.date_format = Some("%d/%m/%Y".to_string());
+        let output = build_output(&tasks, &config);
+        let tooltip = output["tooltip"].as_str().unwrap();
+        assert!(tooltip.contains(&format!(
+            "{} {} (1):",
+            in_3_days.format("%A"),
+            in_3_days.format("%d/%m/%Y")
+        )));
+        assert!(tooltip.contains(&in_30_days.format("%d/%m/%Y").to_string()));
+
+        config.general.relative_dates = true;
+        let output = build_output(&tasks, &config);
+        let tooltip = output["tooltip"].as_str().unwrap();
+        assert!(tooltip.contains(&format!("{} (in 3 days) (1):", in_3_days.format("%A"))));
+    }
+
     #[test]
     fn test_tooltip_scope_overdue_today_hides_no_due() {
         let tasks = vec![
             make_task("Today 1", Some(today())),
             make_task("No due 1", None),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         let tooltip = output["tooltip"].as_str().unwrap();
         assert!(tooltip.contains("Today (1):"));
         assert!(!tooltip.contains("No due date"));
@@ -423,7 +860,7 @@ mod tests {
             make_task("Today 1", Some(today())),
             make_task("Tomorrow 1", Some(tomorrow)),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         let tooltip = output["tooltip"].as_str().unwrap();
         assert!(tooltip.contains("Today (1):"));
         assert!(!tooltip.contains("Tomorrow"));
@@ -436,7 +873,7 @@ mod tests {
             make_task("Overdue 1", Some(yesterday)),
             make_task("Today 1", Some(today())),
         ];
-        let output = build_output(&tasks, "today_only");
+        let output = build_output(&tasks, &config_for("today_only", None));
         let tooltip = output["tooltip"].as_str().unwrap();
         assert!(!tooltip.contains("Overdue"));
         assert!(tooltip.contains("Today (1):"));
@@ -449,15 +886,205 @@ mod tests {
             make_task("Overdue 1", Some(yesterday)),
             make_task("Today 1", Some(today())),
         ];
-        let output = build_output(&tasks, "overdue_today");
+        let output = build_output(&tasks, &config_for("overdue_today", None));
         let tooltip = output["tooltip"].as_str().unwrap();
         assert!(tooltip.contains("1 overdue · 1 today"));
     }
 
     #[test]
     fn test_tooltip_summary_all_done() {
-        let output = build_output(&[], "overdue_today");
+        let output = build_output(&[], &config_for("overdue_today", None));
         let tooltip = output["tooltip"].as_str().unwrap();
         assert!(tooltip.contains("All done!"));
     }
+
+    #[test]
+    fn test_done_tasks_count_but_not_in_badge() {
+        let today = today();
+        let done = |title: &str, on: NaiveDate| {
+            let mut task = make_task(title, Some(today));
+            task.status = TaskStatus::Done;
+            task.completed_at = on.and_hms_opt(9, 0, 0);
+            task
+        };
+        let tasks = vec![
+            make_task("Now", Some(today)),
+            make_task("Also now", Some(today)),
+            done("Shipped", today),
+            done("Filed", today),
+            done("Old", today - chrono::Duration::days(1)),
+        ];
+
+        let (pending, done_today) = split_done(tasks, today);
+        assert_eq!(done_today, 2);
+        let mut output = build_output(&pending, &config_for("overdue_today", None));
+        add_done_count(&mut output, done_today);
+        assert_eq!(output["text"], "2");
+        assert_eq!(output["done_today"], 2);
+        let tooltip = output["tooltip"].as_str().unwrap();
+        assert!(tooltip.ends_with("\n2 today · 2 done"));
+        assert!(!tooltip.contains("Shipped"));
+    }
+
+    #[test]
+    fn test_plain_output() {
+        let today = today();
+        let overdue = today - chrono::Duration::days(2);
+        let tasks = vec![
+            make_task("Late 1", Some(overdue)),
+            make_task("Late 2", Some(overdue)),
+            make_task("Now", Some(today)),
+            make_task("Note", None),
+        ];
+        assert_eq!(plain_output(&tasks, "✓"), "2! 1 today");
+        assert_eq!(plain_output(&tasks[2..], "✓"), "1 today");
+        assert_eq!(plain_output(&tasks[..2], "✓"), "2!");
+        assert_eq!(plain_output(&tasks[3..], "✓"), "✓");
+    }
+
+    #[test]
+    fn test_tooltip_updated_footer() {
+        let mut output = build_output(&[], &config_for("overdue_today", None));
+        add_updated_footer(&mut output, "14:32");
+        let tooltip = output["tooltip"].as_str().unwrap();
+        assert!(tooltip.ends_with("All done! ✓\nupdated 14:32"));
+    }
+
+    #[test]
+    fn test_stale_output_after_failed_fetch() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("state").join(LAST_OUTPUT_FILE);
+        assert!(load_last_output(&path).is_none());
+
+        let mut output = build_output(&[make_task("Now", Some(today()))], &config_for("all", None));
+        add_updated_footer(&mut output, "14:32");
+        save_last_output(&path, &output);
+
+        let stale = stale_output(load_last_output(&path).unwrap(), "Input/output error (os error 5)");
+        assert_eq!(stale["text"], output["text"]);
+        assert_eq!(stale["class"], json!(["has-tasks", "stale"]));
+        let tooltip = stale["tooltip"].as_str().unwrap();
+        assert!(tooltip.ends_with("updated 14:32\nstale: Input/output error (os error 5)"));
+
+        // Already a list, e.g. with `goal-met`
+        let stale = stale_output(json!({"class": ["has-tasks", "goal-met"]}), "gone");
+        assert_eq!(stale["class"], json!(["has-tasks", "goal-met", "stale"]));
+    }
+
+    #[test]
+    fn test_fallback_only_for_transient_errors_and_recent_output() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(LAST_OUTPUT_FILE);
+        save_last_output(
+            &path,
+            &json!({"text": "3", "class": "has-tasks", "tooltip": "updated 14:32"}),
+        );
+        let eio = TasukiError::Unavailable {
+            backend: "obsidian".to_string(),
+            source: std::io::Error::from_raw_os_error(5),
+        };
+        let hour = Duration::from_secs(3600);
+
+        let stale = fallback_output(&eio, Some(&path), hour).unwrap();
+        assert_eq!(stale["text"], "3");
+        assert_eq!(stale["class"], json!(["has-tasks", "stale"]));
+
+        // Too old, nothing saved, or an error that won't clear up by itself
+        assert!(fallback_output(&eio, Some(&path), Duration::ZERO).is_none());
+        assert!(fallback_output(&eio, Some(&dir.path().join("none.json")), hour).is_none());
+        assert!(fallback_output(&eio, None, hour).is_none());
+        let gone = TasukiError::Backend {
+            backend: "local".to_string(),
+            message: "Is a directory (os error 21)".to_string(),
+        };
+        assert!(fallback_output(&gone, Some(&path), hour).is_none());
+    }
+
+    #[test]
+    fn test_classes_from_buckets() {
+        let today = today();
+        let yesterday = today - chrono::Duration::days(1);
+        let tagged = |title: &str, due, tag: &str| {
+            let mut task = make_task(title, due);
+            task.tags = vec![tag.to_string()];
+            task
+        };
+        let mut config = config_for("overdue_today", None);
+        config.waybar.alert_tags = vec!["work".to_string(), "#Home/Garden".to_string(), "errands".to_string()];
+
+        let tasks = vec![
+            make_task("Late 1", Some(yesterday)),
+            make_task("Late 2", Some(yesterday)),
+            tagged("Standup", Some(today), "work/daily"),
+            tagged("Weed", Some(yesterday), "home/garden"),
+            tagged("Groceries", Some(today + chrono::Duration::days(1)), "errands"),
+        ];
+        let buckets = agenda::bucketize(&tasks, today, UPCOMING_DAYS);
+        assert_eq!(
+            classes(&buckets, &config, today),
+            vec!["has-overdue", "overdue-3plus", "tag-work-due", "tag-home-garden-due"]
+        );
+
+        // Two overdue isn't many; an errand due tomorrow isn't due yet
+        let buckets = agenda::bucketize(&tasks[2..], today, UPCOMING_DAYS);
+        assert_eq!(
+            classes(&buckets, &config, today),
+            vec!["has-overdue", "tag-work-due", "tag-home-garden-due"]
+        );
+        let buckets = agenda::bucketize(&tasks[4..], today, UPCOMING_DAYS);
+        assert_eq!(classes(&buckets, &config, today), vec!["has-tasks"]);
+        let buckets = agenda::bucketize(&[], today, UPCOMING_DAYS);
+        assert_eq!(classes(&buckets, &config, today), vec!["all-done"]);
+    }
+
+    #[test]
+    fn test_class_style_array() {
+        let yesterday = today() - chrono::Duration::days(1);
+        let tasks: Vec<Task> = (0..3).map(|i| make_task(&format!("Late {}", i), Some(yesterday))).collect();
+
+        // The default keeps the main class alone
+        let mut config = config_for("overdue_today", None);
+        assert_eq!(build_output(&tasks, &config)["class"], "has-overdue");
+
+        config.waybar.class_style = "array".to_string();
+        let mut output = build_output(&tasks, &config);
+        assert_eq!(output["class"], json!(["has-overdue", "overdue-3plus"]));
+        add_class(&mut output, "goal-met");
+        assert_eq!(output["class"], json!(["has-overdue", "overdue-3plus", "goal-met"]));
+        assert_eq!(build_output(&[], &config)["class"], json!(["all-done"]));
+    }
+
+    #[test]
+    fn test_format_daily_goal() {
+        let tasks = vec![make_task("Today 1", Some(today()))];
+        let progress = |done_today| Progress {
+            done_today,
+            goal: 3,
+            streak: 4,
+            best_streak: 6,
+        };
+
+        let mut output = build_output(&tasks, &config_for("overdue_today", None));
+        apply_format(&mut output, "{count} · {done_today}/{goal} 🔥{streak}", Some(&progress(2)));
+        assert_eq!(output["text"], "1 · 2/3 🔥4");
+        assert_eq!(output["class"], "has-tasks");
+
+        let mut output = build_output(&tasks, &config_for("overdue_today", None));
+        apply_format(&mut output, "{done_today}/{goal}", Some(&progress(3)));
+        assert_eq!(output["text"], "3/3");
+        assert_eq!(output["class"], json!(["has-tasks", "goal-met"]));
+
+        let mut output = build_output(&tasks, &config_for("overdue_today", None));
+        apply_format(&mut output, "{count} {done_today}", None);
+        assert_eq!(output["text"], "1 ");
+    }
+
+    #[test]
+    fn test_check_format() {
+        assert!(check_format("{count}").is_ok());
+        assert!(check_format("{count} {done_today}/{goal} ({streak})").is_ok());
+        let err = check_format("{count} {bogus}").unwrap_err();
+        assert!(err.to_string().contains("{bogus}"));
+        assert!(check_format("{count").is_err());
+    }
 }
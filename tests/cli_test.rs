@@ -3,6 +3,24 @@ use predicates::prelude::*;
 use std::fs;
 use tempfile::TempDir;
 
+/// The tasuki binary with `$HOME` in `dir` and no XDG overrides, so tests
+/// never touch the real user's files, such as the numbers `list` saves.
+/// `$COLUMNS` is unset too, so piped output isn't wrapped.
+fn tasuki(dir: &TempDir) -> assert_cmd::Command {
+    let mut cmd = cargo_bin_cmd!("tasuki");
+    cmd.env("HOME", dir.path());
+    for var in [
+        "XDG_CONFIG_HOME",
+        "XDG_DATA_HOME",
+        "XDG_STATE_HOME",
+        "XDG_CACHE_HOME",
+        "COLUMNS",
+    ] {
+        cmd.env_remove(var);
+    }
+    cmd
+}
+
 #[test]
 fn test_waybar_outputs_valid_json() {
     let temp_dir = TempDir::new().unwrap();
@@ -10,7 +28,7 @@ fn test_waybar_outputs_valid_json() {
 
     fs::write(&config_path, "").unwrap();
 
-    let mut cmd = cargo_bin_cmd!("tasuki");
+    let mut cmd = tasuki(&temp_dir);
     cmd.arg("waybar").arg("--config").arg(&config_path);
 
     cmd.assert()
@@ -26,7 +44,7 @@ fn test_config_command() {
 
     fs::write(&config_path, "[general]\ndefault_view = \"upcoming\"\n").unwrap();
 
-    let mut cmd = cargo_bin_cmd!("tasuki");
+    let mut cmd = tasuki(&temp_dir);
     cmd.arg("config").arg("--config").arg(&config_path);
 
     cmd.assert()
@@ -49,7 +67,7 @@ fn test_list_command_empty() {
     )
     .unwrap();
 
-    let mut cmd = cargo_bin_cmd!("tasuki");
+    let mut cmd = tasuki(&temp_dir);
     cmd.arg("list").arg("all").arg("--config").arg(&config_path);
 
     cmd.assert()
@@ -73,7 +91,7 @@ fn test_list_command_with_tasks() {
     )
     .unwrap();
 
-    let mut cmd = cargo_bin_cmd!("tasuki");
+    let mut cmd = tasuki(&temp_dir);
     cmd.arg("list").arg("all").arg("--config").arg(&config_path);
 
     cmd.assert()
@@ -82,6 +100,94 @@ fn test_list_command_with_tasks() {
         .stdout(predicate::str::contains("Test task 2"));
 }
 
+#[test]
+fn test_list_limit_and_offset() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(&todo_path, "Task A\nTask B\nTask C\nTask D\n").unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    // Piped output is never paged, whatever $PAGER says
+    let mut cmd = tasuki(&temp_dir);
+    cmd.args(["list", "all", "--limit", "2", "--offset", "1", "--config"])
+        .arg(&config_path)
+        .env("PAGER", "/nonexistent/pager");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("  1 ☐ Task B"))
+        .stdout(predicate::str::contains("  2 ☐ Task C"))
+        .stdout(predicate::str::contains("Task A").not())
+        .stdout(predicate::str::contains("Task D").not());
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.args(["list", "all", "--offset", "9", "--no-pager", "--config"])
+        .arg(&config_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks found"));
+}
+
+#[test]
+fn test_list_reverse_flips_first_and_last() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(
+        &todo_path,
+        "Soon due:2025-03-02\nLater due:2025-03-20\nFirst due:2025-03-01\n",
+    )
+    .unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let list = |extra: &[&str]| {
+        let mut cmd = tasuki(&temp_dir);
+        cmd.env("TASUKI_TODAY", "2025-03-01")
+            .args(["list", "all"])
+            .args(extra)
+            .arg("--config")
+            .arg(&config_path);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        String::from_utf8(output).unwrap()
+    };
+    let titles = |text: &str| -> Vec<String> {
+        text.lines()
+            .filter_map(|l| {
+                l.split_once("☐ ")
+                    .map(|(_, rest)| rest.split(" (").next().unwrap().to_string())
+            })
+            .collect()
+    };
+
+    assert_eq!(titles(&list(&[])), vec!["First", "Soon", "Later"]);
+    assert_eq!(
+        titles(&list(&["--reverse"])),
+        vec!["Later", "Soon", "First"]
+    );
+
+    // Sections run the other way too, and numbering still starts at the top
+    let grouped = list(&["--reverse", "--group", "due"]);
+    assert_eq!(titles(&grouped), vec!["Later", "Soon", "First"]);
+    assert!(grouped.starts_with("Thursday"));
+    assert!(grouped.contains("  1 ☐ Later"));
+}
+
 #[test]
 fn test_list_command_json_format() {
     let temp_dir = TempDir::new().unwrap();
@@ -98,7 +204,7 @@ fn test_list_command_json_format() {
     )
     .unwrap();
 
-    let mut cmd = cargo_bin_cmd!("tasuki");
+    let mut cmd = tasuki(&temp_dir);
     cmd.arg("list")
         .arg("all")
         .arg("--format")
@@ -118,11 +224,9 @@ fn test_waybar_with_todo_txt() {
     let config_path = temp_dir.path().join("config.toml");
     let todo_path = temp_dir.path().join("todo.txt");
 
-    // Use future date to avoid overdue
-    let future_date = chrono::Local::now().date_naive() + chrono::Duration::days(30);
     fs::write(
         &todo_path,
-        format!("(p1) Test task 1\n(p2) Test task 2 due:{}\n", future_date),
+        "(p1) Test task 1\n(p2) Test task 2 due:2025-03-31\n",
     )
     .unwrap();
 
@@ -132,8 +236,12 @@ fn test_waybar_with_todo_txt() {
     );
     fs::write(&config_path, config).unwrap();
 
-    let mut cmd = cargo_bin_cmd!("tasuki");
-    cmd.arg("waybar").arg("--config").arg(&config_path);
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("waybar")
+        .arg("--today")
+        .arg("2025-03-01")
+        .arg("--config")
+        .arg(&config_path);
 
     cmd.assert()
         .success()
@@ -141,6 +249,120 @@ fn test_waybar_with_todo_txt() {
         .stdout(predicate::str::contains("has-tasks"));
 }
 
+#[test]
+fn test_waybar_watch_prints_a_line_per_interval() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(&todo_path, "Pay rent due:2025-03-01\n").unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.args([
+        "waybar",
+        "--interval",
+        "0.05",
+        "--count",
+        "2",
+        "--today",
+        "2025-03-01",
+        "--config",
+    ])
+    .arg(&config_path)
+    .timeout(std::time::Duration::from_secs(10));
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let json: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(json["text"], "1");
+    }
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.args(["waybar", "--interval", "0", "--config"])
+        .arg(&config_path);
+    cmd.assert().failure().code(5);
+}
+
+#[test]
+fn test_waybar_plain_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(
+        &todo_path,
+        "Late one due:2025-02-26\nLate two due:2025-02-26\nToday A due:2025-03-01\n\
+         Today B due:2025-03-01\nToday C due:2025-03-01\nSomeday\n",
+    )
+    .unwrap();
+
+    let config = format!(
+        "[backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("TASUKI_TODAY", "2025-03-01")
+        .arg("waybar")
+        .arg("--plain")
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert().success().stdout("2! 3 today\n");
+}
+
+#[test]
+fn test_backends_reports_counts_and_failures() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(&todo_path, "One\nTwo\nx Three\n").unwrap();
+
+    let config = format!(
+        "[backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(&config_path, &config).unwrap();
+
+    tasuki(&temp_dir)
+        .arg("backends")
+        .arg("--config")
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^local\s+3 tasks\s+\d+ms\n").unwrap())
+        .stdout(predicate::str::contains("updated "));
+
+    let missing = temp_dir.path().join("no-vault");
+    fs::write(
+        &config_path,
+        format!(
+            "{}\n[backends.obsidian]\nenabled = true\nvault_path = \"{}\"\n",
+            config,
+            missing.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    tasuki(&temp_dir)
+        .arg("backends")
+        .arg("--config")
+        .arg(&config_path)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("obsidian   error:"));
+}
+
 #[test]
 fn test_help_command() {
     let mut cmd = cargo_bin_cmd!("tasuki");
@@ -162,10 +384,1353 @@ fn test_no_backends_error() {
 
     fs::write(&config_path, "").unwrap();
 
-    let mut cmd = cargo_bin_cmd!("tasuki");
+    let mut cmd = tasuki(&temp_dir);
     cmd.arg("list").arg("--config").arg(&config_path);
 
     cmd.assert()
         .failure()
         .stderr(predicate::str::contains("No backends enabled"));
 }
+
+#[test]
+fn test_local_backend_without_home_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+
+    fs::write(&config_path, "[backends.local]\nenabled = true\n").unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env_remove("HOME")
+        .env_remove("XDG_DATA_HOME")
+        .current_dir(temp_dir.path())
+        .arg("list")
+        .arg("all")
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Could not find a home directory"));
+
+    assert!(!temp_dir.path().join("~").exists());
+}
+
+#[test]
+fn test_list_json_envelope_and_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(&todo_path, "Test task #work\n").unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("list")
+        .arg("all")
+        .arg("--format")
+        .arg("json")
+        .arg("--fields")
+        .arg("id,title,tags")
+        .arg("--config")
+        .arg(&config_path);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(value["version"], 1);
+    assert_eq!(
+        value["tasks"][0],
+        serde_json::json!({"id": "local:1", "title": "Test task", "tags": ["work"]})
+    );
+}
+
+#[test]
+fn test_show_command() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(&todo_path, "(p1) Call dentist #health\nBuy milk\n").unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("show")
+        .arg("dentist")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Call dentist"))
+        .stdout(predicate::str::contains("local:1"))
+        .stdout(predicate::str::contains("#health"));
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("show")
+        .arg("local:2")
+        .arg("--format")
+        .arg("json")
+        .arg("--config")
+        .arg(&config_path);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(value["task"]["title"], "Buy milk");
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("show")
+        .arg("nothing like this")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert().code(4);
+}
+
+#[test]
+fn test_exit_codes() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+
+    // No backends enabled: config error
+    fs::write(&config_path, "").unwrap();
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("list").arg("--config").arg(&config_path);
+    cmd.assert().code(2);
+
+    // todo.txt path points at a directory: backend error
+    let bad_path = temp_dir.path().join("not-a-file");
+    fs::create_dir(&bad_path).unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            bad_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("list").arg("all").arg("--config").arg(&config_path);
+    cmd.assert().code(3);
+
+    // Unknown task ID: not found
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(&todo_path, "Only task\n").unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("show")
+        .arg("local:99")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert().code(4);
+
+    // Unknown --fields entry: parse error
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("list")
+        .arg("all")
+        .arg("--format")
+        .arg("json")
+        .arg("--fields")
+        .arg("bogus")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert().code(5);
+}
+
+#[test]
+fn test_local_default_path_follows_xdg() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().join("home");
+    let data_home = temp_dir.path().join("data");
+    let config_path = temp_dir.path().join("config.toml");
+    fs::create_dir_all(&home).unwrap();
+
+    fs::write(&config_path, "[backends.local]\nenabled = true\n").unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("HOME", &home)
+        .env("XDG_DATA_HOME", &data_home)
+        .arg("add")
+        .arg("XDG task")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert().success();
+
+    let content = fs::read_to_string(data_home.join("tasuki/todo.txt")).unwrap();
+    assert!(content.contains("XDG task"));
+    assert!(!home.join(".tasuki").exists());
+}
+
+#[test]
+fn test_local_default_path_keeps_legacy_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().join("home");
+    let data_home = temp_dir.path().join("data");
+    let config_path = temp_dir.path().join("config.toml");
+    fs::create_dir_all(home.join(".tasuki")).unwrap();
+    fs::write(home.join(".tasuki/todo.txt"), "Legacy task\n").unwrap();
+
+    fs::write(&config_path, "[backends.local]\nenabled = true\n").unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("HOME", &home)
+        .env("XDG_DATA_HOME", &data_home)
+        .arg("list")
+        .arg("all")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Legacy task"));
+
+    assert!(!data_home.join("tasuki/todo.txt").exists());
+}
+
+#[test]
+fn test_delete_requires_yes_when_not_interactive() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(&todo_path, "Keep me\nDelete me\n").unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("delete")
+        .arg("Delete me")
+        .arg("--config")
+        .arg(&config_path)
+        .write_stdin("y\n");
+    cmd.assert()
+        .code(6)
+        .stderr(predicate::str::contains("--yes"));
+    assert!(fs::read_to_string(&todo_path)
+        .unwrap()
+        .contains("Delete me"));
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("delete")
+        .arg("Delete me")
+        .arg("--yes")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted task: Delete me"));
+
+    let content = fs::read_to_string(&todo_path).unwrap();
+    assert!(content.contains("Keep me"));
+    assert!(!content.contains("Delete me"));
+}
+
+#[test]
+fn test_config_check() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("sub/todo.txt");
+
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("config").arg("check").arg(&config_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("is valid"));
+
+    // Checking must not create the todo.txt directory
+    assert!(!temp_dir.path().join("sub").exists());
+
+    fs::write(&config_path, "[general\ntheme = \"dark\"\n").unwrap();
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("config")
+        .arg("check")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("Failed to parse config"));
+
+    fs::write(
+        &config_path,
+        "[general]\ndefault_view = \"someday\"\n\n[backends.obsidian]\nenabled = true\nvault_path = \"/nonexistent/vault\"\n",
+    )
+    .unwrap();
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("config").arg("check").arg(&config_path);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("warning: general.default_view"))
+        .stderr(predicate::str::contains(
+            "error: backends.obsidian.vault_path",
+        ));
+}
+
+#[test]
+fn test_add_to_disabled_backend_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("add")
+        .arg("Vault thing")
+        .args(["--backend", "obsidian"])
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert()
+        .code(3)
+        .stderr(predicate::str::contains("'obsidian'"))
+        .stderr(predicate::str::contains("enabled: local"));
+    assert!(!todo_path.exists());
+}
+
+#[test]
+fn test_add_with_allow_fallback() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(
+        &config_path,
+        format!(
+            "[backends]\nallow_fallback = true\n\n[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("add")
+        .arg("Vault thing")
+        .args(["--backend", "obsidian"])
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("in local ("))
+        .stdout(predicate::str::contains("— obsidian is not enabled"));
+    assert!(fs::read_to_string(&todo_path)
+        .unwrap()
+        .contains("Vault thing"));
+}
+
+#[test]
+fn test_add_output_modes() {
+    let temp_dir = TempDir::new().unwrap();
+    let vault_path = temp_dir.path().join("vault");
+    fs::create_dir_all(&vault_path).unwrap();
+    fs::write(
+        temp_dir.path().join("config.toml"),
+        format!(
+            "[backends.obsidian]\nenabled = true\nvault_path = \"{}\"\ninbox_file = \"Notes/Inbox.md\"\n",
+            vault_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+    fs::create_dir_all(vault_path.join("Notes")).unwrap();
+    let add = |args: &[&str]| {
+        let mut cmd = tasuki(&temp_dir);
+        cmd.arg("--config-dir")
+            .arg(temp_dir.path())
+            .arg("add")
+            .args(args);
+        cmd.assert()
+    };
+
+    add(&["Water plants"]).success().stdout(
+        "✓ Created task: Water plants (ID: obsidian:Notes/Inbox.md:1) in obsidian (Notes/Inbox.md)\n",
+    );
+
+    add(&["--quiet", "Feed cat"])
+        .success()
+        .stdout("obsidian:Notes/Inbox.md:2\n");
+
+    let output = add(&["--json", "Pay rent 2025-03-01 #home"]).success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["task"]["id"], "obsidian:Notes/Inbox.md:3");
+    assert_eq!(json["task"]["title"], "Pay rent");
+    assert_eq!(json["task"]["due"], "2025-03-01");
+    assert_eq!(json["task"]["tags"], serde_json::json!(["home"]));
+
+    // A failed add prints nothing for a pipeline to pick up
+    add(&["--quiet", "--backend", "local", "Elsewhere"])
+        .failure()
+        .stdout("");
+}
+
+#[test]
+fn test_config_dir_flag() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("config.toml"),
+        "[general]\ntheme = \"portable\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("config").arg("--config-dir").arg(temp_dir.path());
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("theme = \"portable\""));
+}
+
+#[test]
+fn test_add_routes_to_default_backend() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    let vault_path = temp_dir.path().join("vault");
+    fs::create_dir_all(&vault_path).unwrap();
+
+    fs::write(
+        &config_path,
+        format!(
+            "[general]\ndefault_backend = \"obsidian\"\n\n[backends.local]\nenabled = true\npath = \"{}\"\n\n[backends.obsidian]\nenabled = true\nvault_path = \"{}\"\n",
+            todo_path.to_string_lossy(),
+            vault_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("add")
+        .arg("Untagged task")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("ID: obsidian:"));
+
+    let inbox = fs::read_to_string(vault_path.join("Inbox.md")).unwrap();
+    assert!(inbox.contains("- [ ] Untagged task"));
+    assert!(!todo_path.exists());
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("add")
+        .arg("Tagged task @local")
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert().success();
+    assert!(fs::read_to_string(&todo_path)
+        .unwrap()
+        .contains("Tagged task"));
+}
+
+#[test]
+fn test_invalid_default_backend() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(
+        &config_path,
+        format!(
+            "[general]\ndefault_backend = \"caldav\"\n\n[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("config").arg("check").arg(&config_path);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "general.default_backend \"caldav\" is not one of local, obsidian, ical",
+    ));
+
+    // Commands that load the backends say the same
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("list").arg("--config").arg(&config_path);
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "is not one of local, obsidian, ical",
+    ));
+}
+
+#[test]
+fn test_sync_obsidian_to_local() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    let vault_path = temp_dir.path().join("vault");
+    fs::create_dir_all(&vault_path).unwrap();
+    fs::write(
+        vault_path.join("Inbox.md"),
+        "- [ ] Call mom #phone\n- [ ] Write report\n",
+    )
+    .unwrap();
+
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n\n[backends.obsidian]\nenabled = true\nvault_path = \"{}\"\n",
+            todo_path.to_string_lossy(),
+            vault_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let sync = || {
+        let mut cmd = tasuki(&temp_dir);
+        cmd.env("XDG_DATA_HOME", temp_dir.path().join("data"))
+            .args([
+                "sync", "--from", "obsidian", "--to", "local", "--tag", "phone",
+            ])
+            .arg("--config")
+            .arg(&config_path);
+        cmd
+    };
+
+    sync()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 created"));
+    let todo = fs::read_to_string(&todo_path).unwrap();
+    assert!(todo.contains("Call mom"));
+    assert!(!todo.contains("Write report"));
+    assert!(temp_dir.path().join("data/tasuki/sync.json").exists());
+
+    // Complete on the "phone" side; the vault follows
+    fs::write(&todo_path, format!("x {}", todo)).unwrap();
+    sync()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 created, 1 completed"));
+    let inbox = fs::read_to_string(vault_path.join("Inbox.md")).unwrap();
+    assert!(inbox.contains("- [x] Call mom"));
+}
+
+#[test]
+fn test_list_week_grouped_by_due() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    let today = chrono::Local::now().date_naive();
+    let day = |n: i64| today + chrono::Duration::days(n);
+
+    fs::write(
+        &todo_path,
+        format!(
+            "Overdue thing due:{}\nToday thing due:{}\nSoon thing due:{}\nLater thing due:{}\nUndated thing\n",
+            day(-2),
+            today,
+            day(3),
+            day(10)
+        ),
+    )
+    .unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.args(["list", "week", "--group", "due"])
+        .arg("--config")
+        .arg(&config_path);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+
+    let expected = format!(
+        "Overdue - {} (1)\n    1 ☐ Overdue thing (due {})\n\nToday (1)\n    2 ☐ Today thing (due {})\n\n{} (1)\n    3 ☐ Soon thing (due {})\n",
+        day(-2),
+        day(-2),
+        today,
+        day(3).format("%A %Y-%m-%d"),
+        day(3)
+    );
+    assert_eq!(stdout, expected);
+}
+
+#[test]
+fn test_list_overdue_and_week_filters() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    let today = chrono::Local::now().date_naive();
+    let day = |n: i64| today + chrono::Duration::days(n);
+
+    fs::write(
+        &todo_path,
+        format!(
+            "Old thing due:{}\nYesterday thing due:{}\nToday thing due:{}\nSoon thing due:{}\nLater thing due:{}\nx Done thing due:{}\nUndated thing\n",
+            day(-9),
+            day(-1),
+            today,
+            day(6),
+            day(8),
+            day(-3)
+        ),
+    )
+    .unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let titles = |filter: &str| -> Vec<String> {
+        let mut cmd = tasuki(&temp_dir);
+        cmd.args(["list", filter, "--output-template", "{title}"])
+            .arg("--config")
+            .arg(&config_path);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    };
+
+    assert_eq!(titles("overdue"), ["Old thing", "Yesterday thing"]);
+    assert_eq!(
+        titles("week"),
+        ["Old thing", "Yesterday thing", "Today thing", "Soon thing"]
+    );
+}
+
+#[test]
+fn test_status_bar_unknown_placeholder_fails_check() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    let write_config = |status_bar: &str| {
+        fs::write(
+            &config_path,
+            format!(
+                "[general]\nstatus_bar = \"{}\"\n\n[backends.local]\nenabled = true\npath = \"{}\"\n",
+                status_bar,
+                todo_path.to_string_lossy()
+            ),
+        )
+        .unwrap();
+    };
+
+    write_config("{pending} pending · {overdue} overdue · {refreshed}");
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("config").arg("check").arg(&config_path);
+    cmd.assert().success();
+
+    write_config("{pending} pending · {overdu}");
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("config").arg("check").arg(&config_path);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("general.status_bar"))
+        .stderr(predicate::str::contains("{overdu}"));
+}
+
+#[test]
+fn test_edit_config_validates_after_editor() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("edit-config")
+        .arg("--config")
+        .arg(&config_path)
+        .env("EDITOR", "true");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("is valid"));
+
+    // Still reachable when the file no longer parses
+    fs::write(&config_path, "[general\ntheme = \"dark\"\n").unwrap();
+    let mut cmd = tasuki(&temp_dir);
+    cmd.args(["config", "edit"])
+        .arg("--config")
+        .arg(&config_path)
+        .env("EDITOR", "true");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("Failed to parse config"));
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.arg("edit-config")
+        .arg("--config")
+        .arg(&config_path)
+        .env("EDITOR", "false");
+    cmd.assert().code(6);
+}
+
+#[test]
+fn test_serve_stdio_answers_each_line() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(&todo_path, "Water plants\n").unwrap();
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    tasuki(&temp_dir)
+        .arg("serve")
+        .arg("--stdio")
+        .arg("--config")
+        .arg(&config_path)
+        .write_stdin(
+            "not json\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"tasks/complete\",\"params\":{\"id\":\"local:1\"}}\n",
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"code\":-32700"))
+        .stdout(predicate::str::contains("\"status\":\"Done\""));
+
+    assert!(fs::read_to_string(&todo_path).unwrap().starts_with("x "));
+}
+
+#[test]
+fn test_add_stdin_imports_checklist() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(
+        &config_path,
+        format!(
+            "[backends.local]\nenabled = true\npath = \"{}\"\n",
+            todo_path.to_string_lossy()
+        ),
+    )
+    .unwrap();
+    let checklist = "- [ ] Send notes #team\n- [x] Book room\n- [?] maybe\n";
+
+    tasuki(&temp_dir)
+        .args(["add", "--stdin", "--dry-run", "--config"])
+        .arg(&config_path)
+        .write_stdin(checklist)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "would create  Send notes in local",
+        ));
+    assert!(!todo_path.exists() || fs::read_to_string(&todo_path).unwrap().is_empty());
+
+    // Without --stdin there's nothing to dry-run
+    tasuki(&temp_dir)
+        .args(["add", "Buy milk", "--dry-run", "--config"])
+        .arg(&config_path)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--stdin"));
+    assert!(!todo_path.exists() || fs::read_to_string(&todo_path).unwrap().is_empty());
+
+    tasuki(&temp_dir)
+        .args(["add", "--stdin", "--config"])
+        .arg(&config_path)
+        .write_stdin(checklist)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "   1  created       Send notes (local:1)",
+        ))
+        .stdout(predicate::str::contains(
+            "   2  skipped-done  - [x] Book room",
+        ))
+        .stdout(predicate::str::contains("   3  parse-failed  - [?] maybe"));
+    assert_eq!(fs::read_to_string(&todo_path).unwrap().lines().count(), 1);
+}
+
+#[test]
+fn test_stats_and_waybar_track_daily_goal() {
+    let temp_dir = TempDir::new().unwrap();
+    let todo_path = temp_dir.path().join("todo.txt");
+
+    fs::write(
+        &todo_path,
+        "x 2025-02-27 Done A\nx 2025-02-28 Done B\nx 2025-02-28 Done C\n\
+         x 2025-03-01 Done D\nx 2025-03-01 Done E\nToday due:2025-03-01\nLate due:2025-02-20\n",
+    )
+    .unwrap();
+
+    let config = format!(
+        "[general]\ndaily_goal = 2\n\n[waybar]\nformat = \"{{count}} {{done_today}}/{{goal}}\"\n\n\
+         [backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(temp_dir.path().join("config.toml"), config).unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("TASUKI_TODAY", "2025-03-01")
+        .arg("stats")
+        .arg("--config-dir")
+        .arg(temp_dir.path());
+
+    cmd.assert().success().stdout(
+        "pending     2 (1 overdue, 1 due today)\n\
+         done today  2/2  ✓\n\
+         streak      2 days (best 2)\n\
+         tracked     0m this week\n",
+    );
+    assert!(temp_dir.path().join("state").join("streak.json").exists());
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("TASUKI_TODAY", "2025-03-01")
+        .arg("waybar")
+        .arg("--config-dir")
+        .arg(temp_dir.path());
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"text\":\"1 2/2\""))
+        .stdout(predicate::str::contains("\"goal-met\""));
+}
+
+#[test]
+fn test_stats_per_tag_and_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(
+        &todo_path,
+        "x 2025-02-28 Send report #work #urgent\nSlides #work\nGroceries #home\n",
+    )
+    .unwrap();
+    let config = format!(
+        "[backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(temp_dir.path().join("config.toml"), config).unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("TASUKI_TODAY", "2025-03-01")
+        .args(["stats", "--config-dir"])
+        .arg(temp_dir.path());
+    cmd.assert().success().stdout(predicate::str::ends_with(
+        "\nTags\n  #home    0/1 done\n  #urgent  1/1 done\n  #work    1/2 done\n",
+    ));
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("TASUKI_TODAY", "2025-03-01")
+        .args(["stats", "--format", "json", "--config-dir"])
+        .arg(temp_dir.path());
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["pending"], 2);
+    assert_eq!(
+        json["tags"]["work"],
+        serde_json::json!({"done": 1, "total": 2})
+    );
+    assert_eq!(
+        json["tags"]["urgent"],
+        serde_json::json!({"done": 1, "total": 1})
+    );
+    assert!(json["goal"].is_null());
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.args(["stats", "--format", "csv", "--config-dir"])
+        .arg(temp_dir.path());
+    cmd.assert().code(5);
+}
+
+#[test]
+fn test_timelog_totals_per_day_and_week() {
+    let temp_dir = TempDir::new().unwrap();
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(&todo_path, "Write report\n").unwrap();
+    let config = format!(
+        "[backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(temp_dir.path().join("config.toml"), config).unwrap();
+
+    let data_dir = temp_dir.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::write(
+        data_dir.join("timelog.jsonl"),
+        "{\"task_id\":\"local:1\",\"title\":\"Write report\",\"start\":\"2025-02-21T09:00:00\",\"end\":\"2025-02-21T10:00:00\"}\n\
+         {\"task_id\":\"local:1\",\"title\":\"Write report\",\"start\":\"2025-02-24T09:00:00\",\"end\":\"2025-02-24T09:40:00\"}\n\
+         {\"task_id\":\"local:2\",\"title\":\"Call Bob\",\"start\":\"2025-02-24T11:00:00\",\"end\":\"2025-02-24T11:15:00\"}\n\
+         {\"task_id\":\"local:1\",\"title\":\"Write report\",\"start\":\"2025-02-25T14:00:00\",\"end\":\"2025-02-25T14:30:00\"}\n",
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("TASUKI_TODAY", "2025-02-26")
+        .args(["timelog", "--since", "monday", "--config-dir"])
+        .arg(temp_dir.path());
+    cmd.assert().success().stdout(
+        "Monday 2025-02-24\n      40m  Write report\n      15m  Call Bob\n\n\
+         Tuesday 2025-02-25\n      30m  Write report\n\n\
+         total 1h 25m\n",
+    );
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.args(["timelog", "--task", "report", "--config-dir"])
+        .arg(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Friday 2025-02-21\n   1h 00m  Write report\n",
+        ))
+        .stdout(predicate::str::contains("Call Bob").not())
+        .stdout(predicate::str::ends_with("total 2h 10m\n"));
+
+    // Only this week, from Monday
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("TASUKI_TODAY", "2025-02-26")
+        .args(["stats", "--config-dir"])
+        .arg(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("tracked     1h 25m this week\n"));
+}
+
+#[test]
+fn test_done_by_list_number() {
+    let temp_dir = TempDir::new().unwrap();
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(
+        &todo_path,
+        "Water plants due:2025-03-02\nPay rent due:2025-03-01\n2025 taxes\n",
+    )
+    .unwrap();
+    let config = format!(
+        "[backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(temp_dir.path().join("config.toml"), config).unwrap();
+
+    let tasuki = |args: &[&str]| {
+        let mut cmd = tasuki(&temp_dir);
+        cmd.env("TASUKI_TODAY", "2025-03-01")
+            .args(args)
+            .arg("--config-dir")
+            .arg(temp_dir.path());
+        cmd
+    };
+
+    tasuki(&["list", "all"]).assert().success().stdout(
+        "  1 ☐ Pay rent (due 2025-03-01)\n  2 ☐ Water plants (due 2025-03-02)\n  3 ☐ 2025 taxes\n",
+    );
+    // Piped output wraps to `$COLUMNS` when it's set
+    tasuki(&["list", "all"])
+        .env("COLUMNS", "30")
+        .assert()
+        .success()
+        .stdout(
+            "  1 ☐ Pay\n      rent (due 2025-03-01)\n  2 ☐ Water\n      plants (due 2025-03-02)\n  3 ☐ 2025 taxes\n",
+        );
+
+    tasuki(&["done", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Completed: Water plants"));
+    assert!(fs::read_to_string(&todo_path)
+        .unwrap()
+        .starts_with("x 2025-03-01 Water plants"));
+
+    tasuki(&["done", "4"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("No task #4"));
+
+    // Other formats expire the numbers; a bare number is a title query again
+    tasuki(&["list", "all", "--format", "json"])
+        .assert()
+        .success();
+    tasuki(&["show", "2025"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("2025 taxes\n"));
+}
+
+#[test]
+fn test_task_commands_by_exact_id() {
+    let temp_dir = TempDir::new().unwrap();
+    let vault = temp_dir.path().join("vault");
+    fs::create_dir_all(vault.join(".obsidian")).unwrap();
+    fs::create_dir_all(vault.join("Daily Notes")).unwrap();
+    let note = vault.join("Daily Notes").join("2025-02-25.md");
+    fs::write(
+        &note,
+        "# Tuesday\n\n- [ ] Call: the bank\n- [ ] Call the bank\n",
+    )
+    .unwrap();
+    let config = format!(
+        "[backends.obsidian]\nenabled = true\nvault_path = \"{}\"\n",
+        vault.to_string_lossy()
+    );
+    fs::write(temp_dir.path().join("config.toml"), config).unwrap();
+
+    let tasuki = |args: &[&str]| {
+        let mut cmd = tasuki(&temp_dir);
+        cmd.args(args).arg("--config-dir").arg(temp_dir.path());
+        cmd
+    };
+
+    // Spaces and colons are all part of the one argument
+    tasuki(&["show", "--id", "obsidian:Daily Notes/2025-02-25.md:4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Call the bank\n"));
+    tasuki(&["done", "--id=obsidian:Daily Notes/2025-02-25.md:3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "✓ Completed: Call: the bank (ID: obsidian:Daily Notes/2025-02-25.md:3)",
+        ));
+    assert_eq!(
+        fs::read_to_string(&note).unwrap().lines().nth(3),
+        Some("- [ ] Call the bank")
+    );
+
+    // The line moved: an error, not whichever task is there now
+    fs::write(&note, "- [ ] Call the bank\n").unwrap();
+    tasuki(&[
+        "delete",
+        "--yes",
+        "--id",
+        "obsidian:Daily Notes/2025-02-25.md:4",
+    ])
+    .assert()
+    .code(2)
+    .stderr(predicate::str::contains(
+        "Error: Stale task ID: No task with ID 'obsidian:Daily Notes/2025-02-25.md:4'",
+    ));
+    assert_eq!(fs::read_to_string(&note).unwrap(), "- [ ] Call the bank\n");
+
+    // Another task moved onto a listed line: checked against the listing
+    tasuki(&["list", "all"]).assert().success();
+    fs::write(&note, "- [ ] Pay rent\n- [ ] Call the bank\n").unwrap();
+    tasuki(&["done", "--id", "obsidian:Daily Notes/2025-02-25.md:1"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "is now \"Pay rent\", not \"Call the bank\"",
+        ));
+    tasuki(&["done", "1"]).assert().code(2);
+    assert_eq!(
+        fs::read_to_string(&note).unwrap(),
+        "- [ ] Pay rent\n- [ ] Call the bank\n"
+    );
+    fs::write(&note, "- [ ] Call the bank\n").unwrap();
+
+    tasuki(&["done", "--id", "local:1"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("enabled: obsidian"));
+    tasuki(&[
+        "done",
+        "--id",
+        "obsidian:Daily Notes/2025-02-25.md:1",
+        "Call",
+    ])
+    .assert()
+    .code(2);
+
+    tasuki(&[
+        "delete",
+        "--yes",
+        "--id",
+        "obsidian:Daily Notes/2025-02-25.md:1",
+    ])
+    .assert()
+    .success();
+    assert_eq!(fs::read_to_string(&note).unwrap(), "");
+}
+
+#[test]
+fn test_agenda_command() {
+    let temp_dir = TempDir::new().unwrap();
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(
+        &todo_path,
+        "Water plants due:2025-03-03\nPay rent due:2025-02-28\nx 2025-03-01 Old chore due:2025-03-01\nRead book\n",
+    )
+    .unwrap();
+    let config = format!(
+        "[backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(temp_dir.path().join("config.toml"), config).unwrap();
+
+    tasuki(&temp_dir)
+        .env("TASUKI_TODAY", "2025-03-01")
+        .args(["agenda", "--days", "3", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(
+            "Overdue (1)\n  ☐ Pay rent (2025-02-28)\n\nMonday 2025-03-03 (1)\n  ☐ Water plants\n\nNo due date (1)\n  ☐ Read book\n",
+        );
+
+    // Sunday's agenda, as Markdown for a daily note
+    tasuki(&temp_dir)
+        .env("TASUKI_TODAY", "2025-03-01")
+        .args(["agenda", "--date", "tomorrow", "--format", "markdown", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(
+            "# Agenda for Sunday 2025-03-02\n\n## Overdue (1)\n\n- [ ] Pay rent (2025-02-28)\n\n## Monday 2025-03-03 (1)\n\n- [ ] Water plants\n\n## No due date (1)\n\n- [ ] Read book\n",
+        );
+
+    tasuki(&temp_dir)
+        .args(["agenda", "--format", "html", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("<!DOCTYPE html>"))
+        .stdout(predicate::str::contains("<style>"));
+
+    tasuki(&temp_dir)
+        .args(["agenda", "--format", "pdf", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .code(5);
+    tasuki(&temp_dir)
+        .args(["agenda", "--date", "someday", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .code(5);
+}
+
+#[test]
+fn test_waybar_shows_error_when_fetch_fails_for_good() {
+    let temp_dir = TempDir::new().unwrap();
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(&todo_path, "Call Sam due:2025-03-01\n").unwrap();
+    let config = format!(
+        "[backends]\nretry_attempts = 1\n\n[backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(temp_dir.path().join("config.toml"), config).unwrap();
+    let waybar = || {
+        let mut cmd = tasuki(&temp_dir);
+        cmd.env("TASUKI_TODAY", "2025-03-01")
+            .arg("waybar")
+            .arg("--config-dir")
+            .arg(temp_dir.path());
+        cmd
+    };
+
+    waybar()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"text\":\"1\""));
+
+    // Not a network hiccup but a lasting failure: the error, not the last
+    // output (a transient one is covered by the waybar unit tests)
+    fs::remove_file(&todo_path).unwrap();
+    fs::create_dir(&todo_path).unwrap();
+    waybar()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"backend-error\""))
+        .stdout(predicate::str::contains("\"stale\"").not());
+}
+
+#[test]
+fn test_waybar_error_text() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("config.toml"),
+        "[waybar]\nerror_text = \"E\"\n",
+    )
+    .unwrap();
+
+    tasuki(&temp_dir)
+        .arg("waybar")
+        .arg("--config-dir")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"text\":\"E\""))
+        .stdout(predicate::str::contains("backend-error"));
+
+    tasuki(&temp_dir)
+        .args(["waybar", "--plain", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout("E\n");
+}
+
+#[test]
+fn test_report_command() {
+    let temp_dir = TempDir::new().unwrap();
+    let todo_path = temp_dir.path().join("todo.txt");
+    fs::write(
+        &todo_path,
+        "x 2025-02-23 Sunday chore #work\nx 2025-02-24 Review PR #work\nx 2025-02-26 Water plants\nx 2025-03-01 Ship release #work\nPay rent due:2025-02-27\n",
+    )
+    .unwrap();
+    let config = format!(
+        "[backends.local]\nenabled = true\npath = \"{}\"\n",
+        todo_path.to_string_lossy()
+    );
+    fs::write(temp_dir.path().join("config.toml"), config).unwrap();
+
+    // Saturday; "monday" is the one just gone, and both ends are included
+    tasuki(&temp_dir)
+        .env("TASUKI_TODAY", "2025-03-01")
+        .args(["report", "--since", "monday", "--tag", "work", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(
+            "Done Monday 2025-02-24 – Saturday 2025-03-01: 2 completed, 0 overdue\n\nMonday 2025-02-24 (1)\n  ✓ Review PR\n\nSaturday 2025-03-01 (1)\n  ✓ Ship release\n",
+        );
+
+    tasuki(&temp_dir)
+        .env("TASUKI_TODAY", "2025-03-01")
+        .args(["report", "--since", "2025-02-26", "--until", "friday", "--format", "markdown", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(
+            "# Done Wednesday 2025-02-26 – Friday 2025-02-28\n\n1 completed, 1 overdue.\n\n## Wednesday 2025-02-26\n\n- [x] Water plants\n\n## Still overdue\n\n- [ ] Pay rent (2025-02-27)\n",
+        );
+
+    tasuki(&temp_dir)
+        .env("TASUKI_TODAY", "2025-03-01")
+        .args(["report", "--format", "json", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"completed\": 3"));
+
+    tasuki(&temp_dir)
+        .args(["report", "--since", "someday", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .code(5);
+}
+
+#[test]
+fn test_profile_selects_config_and_state() {
+    let temp_dir = TempDir::new().unwrap();
+    let profiles = temp_dir.path().join("profiles");
+    fs::create_dir_all(&profiles).unwrap();
+    for (name, task) in [("config", "Home chore"), ("profiles/work", "Work ticket")] {
+        let todo_path = temp_dir
+            .path()
+            .join(format!("{}.txt", name.replace('/', "-")));
+        fs::write(&todo_path, format!("{}\n", task)).unwrap();
+        fs::write(
+            temp_dir.path().join(format!("{}.toml", name)),
+            format!(
+                "[backends.local]\nenabled = true\npath = \"{}\"\n",
+                todo_path.to_string_lossy()
+            ),
+        )
+        .unwrap();
+    }
+
+    tasuki(&temp_dir)
+        .args(["list", "all", "--profile", "work", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Work ticket"))
+        .stdout(predicate::str::contains("Home chore").not());
+    // Numbered-list state is kept per profile
+    assert!(temp_dir
+        .path()
+        .join("state/profiles/work/last-list.json")
+        .exists());
+    assert!(!temp_dir.path().join("state/last-list.json").exists());
+
+    // An explicit --config wins over the profile's file
+    tasuki(&temp_dir)
+        .args(["list", "all", "--profile", "work", "--config"])
+        .arg(temp_dir.path().join("config.toml"))
+        .arg("--config-dir")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Home chore"))
+        .stdout(predicate::str::contains("Work ticket").not());
+
+    tasuki(&temp_dir)
+        .args(["list", "all", "--profile", "personal", "--config-dir"])
+        .arg(temp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Profile 'personal' not found"));
+}
+
+#[test]
+fn test_backends_env_override_skips_config_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let todo_path = temp_dir.path().join("fixture.txt");
+    fs::write(&todo_path, "Fixture task\n").unwrap();
+
+    // A config home whose file would fail to parse if it were read
+    let config_home = temp_dir.path().join("config");
+    fs::create_dir_all(config_home.join("tasuki")).unwrap();
+    fs::write(
+        config_home.join("tasuki").join("config.toml"),
+        "not = [valid",
+    )
+    .unwrap();
+
+    let mut cmd = tasuki(&temp_dir);
+    cmd.env("TASUKI_BACKENDS", format!("local={}", todo_path.display()))
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("XDG_STATE_HOME", temp_dir.path().join("state"))
+        .args(["list", "all"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Fixture task"));
+}